@@ -0,0 +1,14 @@
+//! `cargo bench` 入口：以 `--bench` 模式运行 `benches/workloads` 下的所有工作负载，
+//! 对比本编译器与 `gcc -O0` 生成代码的编译时间和运行时间。
+
+use std::process::Command;
+
+fn main() {
+    let workloads = concat!(env!("CARGO_MANIFEST_DIR"), "/benches/workloads");
+    let status = Command::new(env!("CARGO_BIN_EXE_ccompiler"))
+        .arg(workloads)
+        .arg("--bench")
+        .status()
+        .expect("无法运行 ccompiler");
+    std::process::exit(status.code().unwrap_or(1));
+}
@@ -0,0 +1,22 @@
+/* Collatz 序列长度：测量循环、分支和除法 */
+int collatz_len(int n) {
+    int steps = 0;
+    while (n != 1) {
+        if (n % 2 == 0)
+            n = n / 2;
+        else
+            n = 3 * n + 1;
+        steps = steps + 1;
+    }
+    return steps;
+}
+
+int main(void) {
+    int best = 0;
+    for (int i = 1; i < 100000; i = i + 1) {
+        int len = collatz_len(i);
+        if (len > best)
+            best = len;
+    }
+    return best % 256;
+}
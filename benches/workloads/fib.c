@@ -0,0 +1,10 @@
+/* 递归斐波那契：主要测量函数调用开销 */
+int fib(int n) {
+    if (n < 2)
+        return n;
+    return fib(n - 1) + fib(n - 2);
+}
+
+int main(void) {
+    return fib(30) % 256;
+}
@@ -0,0 +1,391 @@
+/* 大量长标识符：主要测量编译器前端和中端处理标识符的开销 */
+int combine_intermediate_results_0(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 0;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_1(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 1;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_2(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 2;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_3(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 3;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_4(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 4;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_5(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 5;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_6(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 6;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_7(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 7;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_8(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 8;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_9(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 9;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_10(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 10;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_11(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 11;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_12(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 12;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_13(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 13;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_14(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 14;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_15(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 15;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_16(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 16;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_17(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 17;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_18(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 18;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_19(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 19;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_20(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 20;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_21(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 21;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_22(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 22;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int combine_intermediate_results_23(int first_operand_value, int second_operand_value) {
+    int intermediate_partial_result_0 = first_operand_value + 23;
+    int intermediate_partial_result_1 = intermediate_partial_result_0 * 3 + second_operand_value % 3;
+    int intermediate_partial_result_2 = intermediate_partial_result_1 * 3 + second_operand_value % 4;
+    int intermediate_partial_result_3 = intermediate_partial_result_2 * 3 + second_operand_value % 5;
+    int intermediate_partial_result_4 = intermediate_partial_result_3 * 3 + second_operand_value % 6;
+    int intermediate_partial_result_5 = intermediate_partial_result_4 * 3 + second_operand_value % 7;
+    int intermediate_partial_result_6 = intermediate_partial_result_5 * 3 + second_operand_value % 8;
+    int intermediate_partial_result_7 = intermediate_partial_result_6 * 3 + second_operand_value % 9;
+    for (int loop_iteration_counter = 0; loop_iteration_counter < 4; loop_iteration_counter = loop_iteration_counter + 1) {
+        intermediate_partial_result_7 = (intermediate_partial_result_7 + intermediate_partial_result_3 - loop_iteration_counter) % 1000;
+    }
+    return intermediate_partial_result_7;
+}
+
+int main(void) {
+    int running_total_of_all_results = 0;
+    for (int outer_round_number = 0; outer_round_number < 20000; outer_round_number = outer_round_number + 1) {
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_0(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_1(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_2(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_3(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_4(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_5(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_6(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_7(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_8(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_9(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_10(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_11(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_12(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_13(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_14(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_15(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_16(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_17(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_18(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_19(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_20(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_21(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_22(outer_round_number, running_total_of_all_results)) % 100000;
+        running_total_of_all_results = (running_total_of_all_results + combine_intermediate_results_23(outer_round_number, running_total_of_all_results)) % 100000;
+    }
+    return running_total_of_all_results % 256;
+}
@@ -0,0 +1,23 @@
+/* 2x2 矩阵在循环中反复相乘（取模防止溢出）。
+ * 目前还不支持数组，所以矩阵元素用标量表示。 */
+int main(void) {
+    int a = 1;
+    int b = 1;
+    int c = 1;
+    int d = 0;
+    int r00 = 1;
+    int r01 = 0;
+    int r10 = 0;
+    int r11 = 1;
+    for (int i = 0; i < 2000000; i = i + 1) {
+        int t00 = (r00 * a + r01 * c) % 10007;
+        int t01 = (r00 * b + r01 * d) % 10007;
+        int t10 = (r10 * a + r11 * c) % 10007;
+        int t11 = (r10 * b + r11 * d) % 10007;
+        r00 = t00;
+        r01 = t01;
+        r10 = t10;
+        r11 = t11;
+    }
+    return r01 % 256;
+}
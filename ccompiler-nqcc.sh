@@ -0,0 +1,6 @@
+#!/bin/bash
+
+# 供《Writing a C Compiler》官方测试套件 (test_compiler) 调用的编译器入口：
+# 原样转发测试套件传入的参数，并打开 --nqcc-compat 兼容模式。
+
+exec "$(dirname "$0")/target/debug/ccompiler" --nqcc-compat "$@"
@@ -0,0 +1,245 @@
+// src/backend/asm_verify.rs
+
+//! **汇编一致性检查 (Internal Consistency Checker)**
+//!
+//! 在发射汇编代码之前，对最终的 `assembly_ast::Program` 做一次检查，确认
+//! `patch_instructions` 之后的每条指令都满足 x86-64 的机器约束。
+//!
+//! 违反约束说明编译器内部有 bug。与其让 gcc 的汇编器报出难以理解的错误，
+//! 不如在这里给出指向具体函数和指令的内部编译器错误 (ICE)。
+//!
+//! 检查的约束:
+//! -   不允许残留伪寄存器
+//! -   `mov`/`cmp`/`add`/`sub` 不允许两个内存操作数
+//! -   目标操作数不能是立即数 (`cmp` 的第二个操作数同理)
+//! -   `idiv` 的操作数不能是立即数
+//! -   `imul` 和 `movzbl` 的目标必须是寄存器，`movzbl` 的源不能是立即数
+//! -   `setcc` 的操作数必须可按字节寻址
+//! -   4 字节访问的栈偏移必须 4 字节对齐，栈调整量必须是 8 的正整数倍
+//! -   立即数必须能用指令的操作数宽度编码（4 字节为 32 位有符号数，1 字节为 8 位）
+//! -   `call` 之后不能在重新写入之前读取被破坏的调用者保存寄存器（`%rax` 除外，它保存返回值）
+//! -   省略栈帧的函数不能访问栈；不调整 `%rsp` 而把局部变量放在红区中的函数必须是叶子函数，
+//!     不能移动 `%rsp`，局部变量区不能超过 128 字节
+
+use crate::backend::assembly_ast::{BinaryOp, Function, Instruction, Operand, Program, Reg};
+use crate::backend::assembly_ast_gen::RED_ZONE_SIZE;
+use crate::i18n::tr;
+
+/// 检查整个程序，返回遇到的第一个违反约束的错误。
+pub fn verify_program(program: &Program) -> Result<(), String> {
+    program.functions.iter().try_for_each(verify_function)
+}
+
+fn verify_function(function: &Function) -> Result<(), String> {
+    verify_frame(function).map_err(|reason| {
+        tr!(
+            "内部编译器错误 (ICE): 函数 '{}' 的栈帧违反约束: {}",
+            function.name,
+            reason
+        )
+    })?;
+    // 上一次 call 之后被破坏、且尚未重新写入的寄存器
+    let mut clobbered: Vec<Reg> = Vec::new();
+    for (index, instruction) in function.instructions.iter().enumerate() {
+        verify_instruction(instruction)
+            .and_then(|()| check_clobbers(instruction, &mut clobbered))
+            .map_err(|reason| {
+                tr!(
+                    "内部编译器错误 (ICE): 函数 '{}' 的第 {} 条指令 `{}` 违反机器约束: {}",
+                    function.name,
+                    index,
+                    format!("{:?}", instruction),
+                    reason
+                )
+            })?;
+    }
+    Ok(())
+}
+
+/// 检查栈帧布局：省略栈帧和使用红区的函数不能做需要完整栈帧的事。
+fn verify_frame(function: &Function) -> Result<(), String> {
+    let instructions = &function.instructions;
+    if !function.frame_pointer
+        && instructions.iter().any(|ins| {
+            ins.operands()
+                .iter()
+                .any(|op| matches!(op, Operand::Stack(_)))
+        })
+    {
+        return Err(tr!("省略了栈帧，却通过 %rbp 访问栈"));
+    }
+    let allocated = matches!(instructions.first(), Some(Instruction::AllocateStack(_)));
+    if function.stack_size == 0 || allocated {
+        return Ok(());
+    }
+    // 局部变量位于 %rsp 下方的红区中
+    if function.stack_size > RED_ZONE_SIZE {
+        return Err(tr!(
+            "局部变量区 {} 字节超出了 {} 字节的红区",
+            function.stack_size,
+            RED_ZONE_SIZE
+        ));
+    }
+    if instructions.iter().any(|ins| {
+        matches!(
+            ins,
+            Instruction::Call(_) | Instruction::Push(_) | Instruction::AllocateStack(_)
+        )
+    }) {
+        return Err(tr!("使用红区的函数不能调用其他函数或移动 %rsp"));
+    }
+    Ok(())
+}
+
+/// 按指令顺序跟踪被 `call` 破坏的寄存器。
+///
+/// 这是一个线性扫描：遇到标签时无法知道从哪条路径到达，保守地清空状态。
+fn check_clobbers(instruction: &Instruction, clobbered: &mut Vec<Reg>) -> Result<(), String> {
+    if let Some(reg) = instruction
+        .reads()
+        .into_iter()
+        .find(|r| clobbered.contains(r))
+    {
+        return Err(tr!(
+            "读取了被前面的 call 破坏的寄存器 {}",
+            format!("{:?}", reg)
+        ));
+    }
+    match instruction {
+        Instruction::Label(_) => clobbered.clear(),
+        // %rax 中是返回值，其余调用者保存的寄存器都被破坏
+        Instruction::Call(_) => {
+            *clobbered = Reg::CALLER_SAVED
+                .into_iter()
+                .filter(|r| *r != Reg::AX)
+                .collect();
+        }
+        _ => clobbered.retain(|r| !instruction.writes().contains(r)),
+    }
+    Ok(())
+}
+
+fn verify_instruction(instruction: &Instruction) -> Result<(), String> {
+    match instruction {
+        Instruction::Mov { src, dst } => {
+            check_operand(src, 4)?;
+            check_operand(dst, 4)?;
+            check_not_mem_mem(src, dst)?;
+            check_writable(dst)
+        }
+        Instruction::MovByte { src, dst } => {
+            check_operand(src, 1)?;
+            check_operand(dst, 1)?;
+            check_not_mem_mem(src, dst)?;
+            check_writable(dst)
+        }
+        Instruction::MovZeroExtend { src, dst } => {
+            check_operand(src, 1)?;
+            check_operand(dst, 4)?;
+            if matches!(src, Operand::Imm(_)) {
+                return Err(tr!("movzbl 的源操作数不能是立即数"));
+            }
+            check_register(dst, "movzbl 的目标操作数必须是寄存器")
+        }
+        Instruction::Unary { operand, .. } => {
+            check_operand(operand, 4)?;
+            check_writable(operand)
+        }
+        Instruction::Binary {
+            op,
+            left_operand,
+            right_operand,
+        } => {
+            check_operand(left_operand, 4)?;
+            check_operand(right_operand, 4)?;
+            check_writable(right_operand)?;
+            match op {
+                BinaryOp::Multiply => {
+                    check_register(right_operand, "imul 的目标操作数必须是寄存器")
+                }
+                BinaryOp::Add | BinaryOp::Subtract => {
+                    check_not_mem_mem(left_operand, right_operand)
+                }
+            }
+        }
+        Instruction::Cmp { operand1, operand2 } => {
+            check_operand(operand1, 4)?;
+            check_operand(operand2, 4)?;
+            check_not_mem_mem(operand1, operand2)?;
+            if matches!(operand2, Operand::Imm(_)) {
+                return Err(tr!("cmp 的第二个操作数不能是立即数"));
+            }
+            Ok(())
+        }
+        Instruction::Idiv(operand) => {
+            check_operand(operand, 4)?;
+            if matches!(operand, Operand::Imm(_)) {
+                return Err(tr!("idiv 的操作数不能是立即数"));
+            }
+            Ok(())
+        }
+        Instruction::SetCC { operand, .. } => {
+            check_operand(operand, 1)?;
+            match operand {
+                Operand::Register(_) | Operand::Stack(_) => Ok(()),
+                _ => Err(tr!("setcc 的操作数必须可按字节寻址")),
+            }
+        }
+        Instruction::Push(operand) => check_operand(operand, 4),
+        Instruction::AllocateStack(size) | Instruction::DeallocateStack(size) => {
+            if *size <= 0 || size % 8 != 0 {
+                return Err(tr!("栈调整量 {} 不是 8 的正整数倍", size));
+            }
+            Ok(())
+        }
+        Instruction::Cdq
+        | Instruction::Jmp(_)
+        | Instruction::JmpCC { .. }
+        | Instruction::Label(_)
+        | Instruction::Call(_)
+        | Instruction::Ret => Ok(()),
+    }
+}
+
+/// 检查单个操作数：不能残留伪寄存器，栈偏移必须按访问宽度对齐，立即数必须在范围内。
+fn check_operand(operand: &Operand, width: i64) -> Result<(), String> {
+    match operand {
+        Operand::Pseudo(name) => Err(tr!("残留伪寄存器 '{}'", name)),
+        Operand::Imm(value) if !imm_fits(*value, width) => {
+            Err(tr!("立即数 {} 无法编码为 {} 字节操作数", value, width))
+        }
+        Operand::Stack(offset) if offset % width != 0 => {
+            Err(tr!("栈偏移 {} 未按 {} 字节对齐", offset, width))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn imm_fits(value: i64, width: i64) -> bool {
+    match width {
+        // movb 既接受有符号也接受无符号的字节值
+        1 => (-128..=255).contains(&value),
+        _ => i32::try_from(value).is_ok(),
+    }
+}
+
+fn check_not_mem_mem(a: &Operand, b: &Operand) -> Result<(), String> {
+    if matches!((a, b), (Operand::Stack(_), Operand::Stack(_))) {
+        return Err(tr!("不允许两个内存操作数"));
+    }
+    Ok(())
+}
+
+fn check_writable(dst: &Operand) -> Result<(), String> {
+    if matches!(dst, Operand::Imm(_)) {
+        return Err(tr!("目标操作数不能是立即数"));
+    }
+    Ok(())
+}
+
+fn check_register(operand: &Operand, reason: &str) -> Result<(), String> {
+    match operand {
+        Operand::Register(_) => Ok(()),
+        _ => Err(reason.to_string()),
+    }
+}
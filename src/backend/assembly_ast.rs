@@ -1,24 +1,50 @@
-use crate::common::{AstNode, PrettyPrinter};
+use crate::backend::ir_json;
+use crate::common::{AstNode, PrettyPrinter, Symbol};
+use crate::i18n::tr;
+use serde::Serialize;
 
 // src/backend/assembly_ast.rs
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Program {
     pub functions: Vec<Function>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Function {
-    pub name: String,
+    pub name: Symbol,
     pub instructions: Vec<Instruction>,
-    // pub stack_size: i64,
+    /// 栈布局后局部变量区的大小（已按 16 字节对齐，不含返回地址和保存的 %rbp）
+    pub stack_size: i64,
+    /// 被分配到栈槽的伪寄存器数量（目前没有寄存器分配，所有伪寄存器都溢出到栈上）
+    #[serde(skip)]
+    pub spills: usize,
+    /// 是否需要建立栈帧（`push %rbp; mov %rsp, %rbp`）。不调用其他函数、也不访问栈的
+    /// 叶子函数省略栈帧
+    #[serde(skip)]
+    pub frame_pointer: bool,
+    /// 与 `instructions` 一一对应：每条汇编指令来源的 TACKY 指令在函数体中的下标。
+    /// 函数序言、参数搬移和尾声等没有对应 TACKY 指令的为 `None`。
+    #[serde(skip)]
+    pub origins: Vec<Option<usize>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum Instruction {
     Mov {
         src: Operand,
         dst: Operand,
     },
+    /// 1 字节的移动 (`movb`)，用于写入 `_Bool` 对象
+    MovByte {
+        src: Operand,
+        dst: Operand,
+    },
+    /// 从 1 字节零扩展到 32 位 (`movzbl`)，用于读取 `_Bool` 对象
+    MovZeroExtend {
+        src: Operand,
+        dst: Operand,
+    },
     Unary {
         op: UnaryOp,
         operand: Operand,
@@ -32,25 +58,34 @@ pub enum Instruction {
         operand1: Operand,
         operand2: Operand,
     },
+    #[serde(serialize_with = "ir_json::operand")]
     Idiv(Operand),
     Cdq,
+    #[serde(serialize_with = "ir_json::target")]
     Jmp(String),
+    #[serde(rename = "jmp_cc")]
     JmpCC {
-        condtion: ConditionCode,
+        condition: ConditionCode,
         target: String,
     },
+    #[serde(rename = "set_cc")]
     SetCC {
-        conditin: ConditionCode,
+        condition: ConditionCode,
         operand: Operand,
     },
+    #[serde(serialize_with = "ir_json::name")]
     Label(String),
+    #[serde(serialize_with = "ir_json::bytes")]
     AllocateStack(i64),
+    #[serde(serialize_with = "ir_json::bytes")]
     DeallocateStack(i64),
+    #[serde(serialize_with = "ir_json::operand")]
     Push(Operand),
-    Call(String),
+    #[serde(serialize_with = "ir_json::name")]
+    Call(Symbol),
     Ret,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum ConditionCode {
     E,
     NE,
@@ -59,26 +94,28 @@ pub enum ConditionCode {
     L,
     LE,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum BinaryOp {
     Add,
     Subtract,
     Multiply,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum UnaryOp {
     Complement, //按位取反
     Neg,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Operand {
     Imm(i64),
+    #[serde(rename = "reg")]
     Register(Reg),
-    Pseudo(String),
+    Pseudo(Symbol),
     Stack(i64),
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum Reg {
     AX,
     CX,
@@ -90,6 +127,136 @@ pub enum Reg {
     R10,
     R11,
 }
+
+impl Operand {
+    /// 构造一个 32 位立即数操作数。`int` 指令只能编码 32 位立即数，超出范围时返回错误，
+    /// 而不是留给 gcc 的汇编器报出难以理解的错误。
+    pub fn imm32(value: i64) -> Result<Operand, String> {
+        if i32::try_from(value).is_ok() {
+            Ok(Operand::Imm(value))
+        } else {
+            Err(tr!(
+                "整数常量 {} 超出 32 位立即数的范围 [{}, {}]",
+                value,
+                i32::MIN,
+                i32::MAX
+            ))
+        }
+    }
+}
+
+impl Reg {
+    /// System V 调用约定中由调用者保存的寄存器：被调函数可以随意修改它们，
+    /// 所以 `call` 之后这些寄存器中原有的值都不再可用（`%rax` 中是返回值）。
+    pub const CALLER_SAVED: [Reg; 9] = [
+        Reg::AX,
+        Reg::CX,
+        Reg::DX,
+        Reg::DI,
+        Reg::SI,
+        Reg::R8,
+        Reg::R9,
+        Reg::R10,
+        Reg::R11,
+    ];
+}
+
+impl Instruction {
+    /// 指令读取的寄存器。
+    pub fn reads(&self) -> Vec<Reg> {
+        let regs = |ops: &[&Operand]| -> Vec<Reg> {
+            ops.iter()
+                .filter_map(|op| match op {
+                    Operand::Register(r) => Some(r.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+        match self {
+            Instruction::Mov { src, .. }
+            | Instruction::MovByte { src, .. }
+            | Instruction::MovZeroExtend { src, .. } => regs(&[src]),
+            Instruction::Unary { operand, .. } | Instruction::Push(operand) => regs(&[operand]),
+            Instruction::Binary {
+                left_operand,
+                right_operand,
+                ..
+            } => regs(&[left_operand, right_operand]),
+            Instruction::Cmp { operand1, operand2 } => regs(&[operand1, operand2]),
+            // idiv 以 %edx:%eax 为被除数
+            Instruction::Idiv(operand) => {
+                let mut r = regs(&[operand]);
+                r.extend([Reg::AX, Reg::DX]);
+                r
+            }
+            Instruction::Cdq => vec![Reg::AX],
+            Instruction::Ret => vec![Reg::AX],
+            // 参数寄存器的读取取决于被调函数的参数个数，这里不做建模
+            Instruction::Call(_)
+            | Instruction::SetCC { .. }
+            | Instruction::Jmp(_)
+            | Instruction::JmpCC { .. }
+            | Instruction::Label(_)
+            | Instruction::AllocateStack(_)
+            | Instruction::DeallocateStack(_) => Vec::new(),
+        }
+    }
+
+    /// 指令的所有显式操作数。
+    pub fn operands(&self) -> Vec<&Operand> {
+        match self {
+            Instruction::Mov { src, dst }
+            | Instruction::MovByte { src, dst }
+            | Instruction::MovZeroExtend { src, dst } => vec![src, dst],
+            Instruction::Unary { operand, .. }
+            | Instruction::SetCC { operand, .. }
+            | Instruction::Idiv(operand)
+            | Instruction::Push(operand) => vec![operand],
+            Instruction::Binary {
+                left_operand,
+                right_operand,
+                ..
+            } => vec![left_operand, right_operand],
+            Instruction::Cmp { operand1, operand2 } => vec![operand1, operand2],
+            Instruction::Cdq
+            | Instruction::Ret
+            | Instruction::Call(_)
+            | Instruction::Jmp(_)
+            | Instruction::JmpCC { .. }
+            | Instruction::Label(_)
+            | Instruction::AllocateStack(_)
+            | Instruction::DeallocateStack(_) => Vec::new(),
+        }
+    }
+
+    /// 指令写入（或破坏）的寄存器。
+    pub fn writes(&self) -> Vec<Reg> {
+        let reg = |op: &Operand| match op {
+            Operand::Register(r) => vec![r.clone()],
+            _ => Vec::new(),
+        };
+        match self {
+            Instruction::Mov { dst, .. }
+            | Instruction::MovByte { dst, .. }
+            | Instruction::MovZeroExtend { dst, .. } => reg(dst),
+            Instruction::Unary { operand, .. } | Instruction::SetCC { operand, .. } => reg(operand),
+            Instruction::Binary { right_operand, .. } => reg(right_operand),
+            Instruction::Idiv(_) => vec![Reg::AX, Reg::DX],
+            Instruction::Cdq => vec![Reg::DX],
+            // call 破坏所有调用者保存的寄存器
+            Instruction::Call(_) => Reg::CALLER_SAVED.to_vec(),
+            Instruction::Cmp { .. }
+            | Instruction::Push(_)
+            | Instruction::Ret
+            | Instruction::Jmp(_)
+            | Instruction::JmpCC { .. }
+            | Instruction::Label(_)
+            | Instruction::AllocateStack(_)
+            | Instruction::DeallocateStack(_) => Vec::new(),
+        }
+    }
+}
+
 //--------------打印逻辑
 
 impl AstNode for Program {
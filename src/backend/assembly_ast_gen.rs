@@ -7,9 +7,44 @@ use crate::backend::assembly_ast::{
     BinaryOp, ConditionCode, Function, Instruction, Operand, Program, Reg, UnaryOp,
 };
 use crate::backend::tacky_ir;
+use crate::common::{DataLayout, Symbol};
+use crate::frontend::type_checking::{CType, SymbolTable};
+
+/// `INT_MIN / -1` 和 `INT_MIN % -1` 的处理方式（`--int-div-overflow`）。
+///
+/// 有符号 `/` 和 `%` 向零截断：`-7 / 2 == -3`，`-7 % 2 == -1`，余数的符号与被除数相同，
+/// 并且总满足 `(a / b) * b + a % b == a`。这正是 `cdq; idivl` 的行为。
+///
+/// 唯一的例外是 `INT_MIN / -1`：商 `2^31` 无法用 `int` 表示，C 标准规定其行为未定义，
+/// `idivl` 会触发除法异常（Linux 上进程收到 `SIGFPE`）。`INT_MIN % -1` 在数学上为 0，
+/// 但同样由 `idivl` 计算，因此也会触发异常。除以零在两种模式下都会触发异常。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum IntDivOverflow {
+    /// 直接使用 `idivl`，溢出时触发 `SIGFPE`（与 gcc 一致）
+    #[default]
+    Trap,
+    /// 除数为 `-1` 时不执行 `idivl`：商为被除数取负（`INT_MIN / -1 == INT_MIN`），余数为 0
+    Wrap,
+}
 
 /// 负责将 IR AST 转换为汇编 AST。
-pub struct AssemblyGenerator {}
+pub struct AssemblyGenerator<'a> {
+    /// 符号表，用于确定每个伪寄存器对应对象的大小。
+    tables: &'a SymbolTable,
+    /// 当前函数唯一的尾声（epilogue）标签，所有 `return` 都跳转到这里。
+    return_label: String,
+    /// 有符号除法溢出的处理方式
+    div_overflow: IntDivOverflow,
+    /// 是否允许叶子函数使用红区（`-mred-zone`）
+    red_zone: bool,
+    /// 叶子函数是否把寄存器传入的参数留在寄存器中（`--disable-pass params-in-registers` 关闭）
+    params_in_registers: bool,
+    /// 目标平台的数据模型，决定栈槽和栈上参数的大小
+    layout: DataLayout,
+    /// 当前函数名和其中已生成的内部标签数，用于生成唯一的标签名
+    function_name: Symbol,
+    label_count: usize,
+}
 
 // 为 Instruction 添加一个辅助方法，用于遍历和映射其所有操作数。
 impl Instruction {
@@ -21,6 +56,14 @@ impl Instruction {
                 src: f(src),
                 dst: f(dst),
             },
+            Instruction::MovByte { src, dst } => Instruction::MovByte {
+                src: f(src),
+                dst: f(dst),
+            },
+            Instruction::MovZeroExtend { src, dst } => Instruction::MovZeroExtend {
+                src: f(src),
+                dst: f(dst),
+            },
             Instruction::Unary { op, operand } => Instruction::Unary {
                 op: op.clone(),
                 operand: f(operand),
@@ -35,8 +78,8 @@ impl Instruction {
                 right_operand: f(right_operand),
             },
             Instruction::Idiv(operand) => Instruction::Idiv(f(operand)),
-            Instruction::SetCC { conditin, operand } => Instruction::SetCC {
-                conditin: conditin.clone(),
+            Instruction::SetCC { condition, operand } => Instruction::SetCC {
+                condition: condition.clone(),
                 operand: f(operand),
             },
             Instruction::Cmp { operand1, operand2 } => Instruction::Cmp {
@@ -50,16 +93,61 @@ impl Instruction {
     }
 }
 
-impl AssemblyGenerator {
-    pub fn new() -> Self {
-        AssemblyGenerator {}
+/// System V ABI 中 %rsp 下方可以直接使用的红区大小（字节）。
+pub const RED_ZONE_SIZE: i64 = 128;
+
+/// 第 `index` 个参数的传入寄存器（System V 调用约定）；第 7 个及以后的参数通过栈传递。
+fn param_register(index: usize) -> Option<Reg> {
+    match index {
+        0 => Some(Reg::DI),
+        1 => Some(Reg::SI),
+        2 => Some(Reg::DX),
+        3 => Some(Reg::CX),
+        4 => Some(Reg::R8),
+        5 => Some(Reg::R9),
+        _ => None,
     }
+}
 
-    pub fn generate(&mut self, ir_program: tacky_ir::Program) -> Result<Program, String> {
+impl<'a> AssemblyGenerator<'a> {
+    pub fn new(tables: &'a SymbolTable) -> Self {
+        AssemblyGenerator {
+            tables,
+            return_label: String::new(),
+            div_overflow: IntDivOverflow::default(),
+            red_zone: true,
+            params_in_registers: true,
+            layout: DataLayout::default(),
+            function_name: Symbol::intern(""),
+            label_count: 0,
+        }
+    }
+
+    pub fn with_div_overflow(mut self, mode: IntDivOverflow) -> Self {
+        self.div_overflow = mode;
+        self
+    }
+
+    pub fn with_red_zone(mut self, enabled: bool) -> Self {
+        self.red_zone = enabled;
+        self
+    }
+
+    pub fn with_params_in_registers(mut self, enabled: bool) -> Self {
+        self.params_in_registers = enabled;
+        self
+    }
+
+    pub fn with_layout(mut self, layout: DataLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn generate(&mut self, ir_program: &tacky_ir::Program) -> Result<Program, String> {
         let functions = ir_program
             .functions
-            .into_iter()
-            .map(|ir_func| self.process_function(&ir_func))
+            .iter()
+            .map(|ir_func| self.process_function(ir_func))
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(Program { functions })
@@ -67,81 +155,180 @@ impl AssemblyGenerator {
 
     fn process_function(&mut self, ir_func: &tacky_ir::Function) -> Result<Function, String> {
         // 第 1 步：将 IR 转换为初始汇编指令
-        let mut initial_instructions = Vec::new();
-        let ins_helper = self.generate_function_helper(ir_func)?;
-        initial_instructions.extend(ins_helper);
-        let ins = self.generate_initial_instructions(ir_func)?;
+        self.return_label = format!("{}.return", ir_func.name);
+        self.function_name = ir_func.name;
+        self.label_count = 0;
+        // 同时记录每条汇编指令来源于哪条 TACKY 指令（参数搬移和尾声没有来源）
+        let mut initial_instructions = self.generate_function_helper(ir_func)?;
+        let mut origins = vec![None; initial_instructions.len()];
+        let (ins, ins_origins) = self.generate_initial_instructions(ir_func)?;
         initial_instructions.extend(ins);
+        origins.extend(ins_origins);
+        if self.params_in_registers {
+            self.keep_params_in_registers(ir_func, &mut initial_instructions, &mut origins);
+        }
+        // 统一的函数尾声：如果最后一条指令就是跳到尾声，直接落入即可
+        if matches!(initial_instructions.last(), Some(Instruction::Jmp(t)) if *t == self.return_label)
+        {
+            initial_instructions.pop();
+            origins.pop();
+        }
+        initial_instructions.push(Instruction::Label(self.return_label.clone()));
+        initial_instructions.push(Instruction::Ret);
+        origins.extend([None, None]);
 
         // 第 2 步：替换伪寄存器并计算栈大小
-        let (instructions_with_stack, stack_size) =
+        let (instructions_with_stack, stack_size, spills) =
             self.allocate_stack_slots(&initial_instructions);
 
         // 第 3 步：修复无效指令 (例如内存到内存的移动)
-        let mut final_instructions = self.patch_instructions(&instructions_with_stack);
+        let (mut final_instructions, mut origins) =
+            self.patch_instructions(&instructions_with_stack, &origins);
 
         // 第 4 步：插入栈分配指令
-        if stack_size > 0 {
-            // x86-64 要求栈是 16 字节对齐的
-            let aligned_stack_size = (stack_size + 15) & !15;
+        // x86-64 要求栈是 16 字节对齐的
+        let aligned_stack_size = (stack_size + 15) & !15;
+        // System V ABI 保证 %rsp 下方 128 字节的红区不会被信号处理函数等破坏，
+        // 叶子函数的局部变量放得下时不必调整 %rsp
+        let in_red_zone = self.red_zone
+            && aligned_stack_size <= RED_ZONE_SIZE
+            && !final_instructions
+                .iter()
+                .any(|ins| matches!(ins, Instruction::Call(_)));
+        if aligned_stack_size > 0 && !in_red_zone {
             final_instructions.insert(0, Instruction::AllocateStack(aligned_stack_size));
+            origins.insert(0, None);
         }
 
+        // 第 5 步：不调用其他函数、也不访问栈的函数不需要建立栈帧
+        let frame_pointer = final_instructions.iter().any(|ins| {
+            matches!(ins, Instruction::Call(_))
+                || ins
+                    .operands()
+                    .iter()
+                    .any(|op| matches!(op, Operand::Stack(_)))
+        });
+
         Ok(Function {
-            name: ir_func.name.clone(),
+            name: ir_func.name,
             instructions: final_instructions,
-            // stack_size,
+            stack_size: aligned_stack_size,
+            spills,
+            frame_pointer,
+            origins,
         })
     }
+
+    /// 叶子函数（不含 `call`）中，通过寄存器传入的参数不必复制到栈槽：
+    /// 只要函数体不会写入该寄存器，就直接用它代替参数的伪寄存器。
+    ///
+    /// `_Bool` 参数的高位没有定义，仍然截断后复制到栈槽。
+    fn keep_params_in_registers(
+        &self,
+        ir_func: &tacky_ir::Function,
+        instructions: &mut Vec<Instruction>,
+        origins: &mut Vec<Option<usize>>,
+    ) {
+        if instructions
+            .iter()
+            .any(|ins| matches!(ins, Instruction::Call(_)))
+        {
+            return;
+        }
+        // 参数搬移是函数开头的前 params.len() 条指令，它们只写伪寄存器
+        let clobbered: Vec<Reg> = instructions[ir_func.params.len()..]
+            .iter()
+            .flat_map(Instruction::writes)
+            .collect();
+        let kept: HashMap<Symbol, Reg> = ir_func
+            .params
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &param)| Some((param, param_register(i)?)))
+            .filter(|(param, reg)| {
+                self.pseudo_type(*param) != CType::Bool && !clobbered.contains(reg)
+            })
+            .collect();
+        if kept.is_empty() {
+            return;
+        }
+
+        let moves = ir_func.params.len();
+        (*instructions, *origins) = std::mem::take(instructions)
+            .into_iter()
+            .zip(std::mem::take(origins))
+            .enumerate()
+            .filter(|(i, (ins, _))| {
+                !(*i < moves
+                    && matches!(ins, Instruction::Mov { dst: Operand::Pseudo(name), .. } if kept.contains_key(name)))
+            })
+            .map(|(_, pair)| pair)
+            .unzip();
+        for ins in instructions.iter_mut() {
+            *ins = ins.map_operands(|op| match op {
+                Operand::Pseudo(name) if kept.contains_key(name) => {
+                    Operand::Register(kept[name].clone())
+                }
+                _ => op.clone(),
+            });
+        }
+    }
     fn generate_function_helper(
         &mut self,
         ir_func: &tacky_ir::Function,
     ) -> Result<Vec<Instruction>, String> {
         let mut ins = Vec::new();
 
-        for (i, param) in ir_func.params.iter().enumerate() {
-            let destination = Operand::Pseudo(param.clone());
-            let source = if i < 6 {
+        for (i, &param) in ir_func.params.iter().enumerate() {
+            let destination = Operand::Pseudo(param);
+            let source = if let Some(register) = param_register(i) {
                 // --- 情况1: 前6个参数，通过寄存器传递 ---
-                // 使用 match 将索引映射到正确的寄存器
-                let register = match i {
-                    0 => Reg::DI,
-                    1 => Reg::SI,
-                    2 => Reg::DX,
-                    3 => Reg::CX,
-                    4 => Reg::R8,
-                    5 => Reg::R9,
-                    // 这个分支理论上不可能到达，因为我们有 i < 6 的检查
-                    _ => unreachable!(),
-                };
                 Operand::Register(register)
             } else {
                 // --- 情况2: 第7个及以后的参数，通过栈传递 ---
-                // 计算相对于基址指针 %rbp 的偏移量
-                // 第7个参数 (i=6) 的偏移量是 16
-                // 第8个参数 (i=7) 的偏移量是 24 (16 + 8)
-                // ...
-                let offset = 16 + ((i - 6) * 8) as i64;
+                // 计算相对于基址指针 %rbp 的偏移量：跳过保存的 %rbp 和返回地址，
+                // 每个参数占一个指针大小的栈槽。LP64 中第7个参数 (i=6) 的偏移量是 16，
+                // 第8个参数 (i=7) 的偏移量是 24 (16 + 8)，依此类推
+                let slot = self.layout.pointer_size;
+                let offset = 2 * slot + (i - 6) as i64 * slot;
                 Operand::Stack(offset)
             };
-            ins.push(Instruction::Mov {
-                src: source,
-                dst: destination,
-            });
+            if self.pseudo_type(param) == CType::Bool {
+                // `_Bool` 参数只有低 8 位有效
+                ins.push(Instruction::MovByte {
+                    src: source,
+                    dst: destination,
+                });
+            } else {
+                ins.push(Instruction::Mov {
+                    src: source,
+                    dst: destination,
+                });
+            }
         }
         Ok(ins)
     }
 
+    fn pseudo_type(&self, name: Symbol) -> CType {
+        self.tables
+            .get(name)
+            .map(|s| s.tpye.clone())
+            .unwrap_or(CType::Int)
+    }
+
+    /// 翻译函数体，返回汇编指令以及每条指令对应的 TACKY 指令下标。
     fn generate_initial_instructions(
-        &self,
+        &mut self,
         ir_func: &tacky_ir::Function,
-    ) -> Result<Vec<Instruction>, String> {
-        ir_func
-            .body
-            .iter()
-            .map(|ins| self.generate_instruction(ins))
-            .collect::<Result<Vec<_>, _>>()
-            .map(|vecs| vecs.into_iter().flatten().collect())
+    ) -> Result<(Vec<Instruction>, Vec<Option<usize>>), String> {
+        let mut instructions = Vec::new();
+        let mut origins = Vec::new();
+        for (index, ins) in ir_func.body.iter().enumerate() {
+            let generated = self.generate_instruction(ins)?;
+            origins.extend(std::iter::repeat_n(Some(index), generated.len()));
+            instructions.extend(generated);
+        }
+        Ok((instructions, origins))
     }
 
     /// (重构后的辅助函数) 为关系运算符和逻辑 NOT 生成指令序列。
@@ -161,7 +348,7 @@ impl AssemblyGenerator {
             },
             // 2. 根据条件设置字节大小的 AL 寄存器
             Instruction::SetCC {
-                conditin: cc,
+                condition: cc,
                 operand: Operand::Register(Reg::AX), // SetCC 将使用8位的 %al 部分
             },
             // 3. 将字节从 %al 移动到完整的 %eax 寄存器，并进行零扩展。
@@ -181,18 +368,19 @@ impl AssemblyGenerator {
 
     /// 从单个 ir instruction 生成一个或多个汇编指令。
     fn generate_instruction(
-        &self,
+        &mut self,
         ir_incs: &tacky_ir::Instruction,
     ) -> Result<Vec<Instruction>, String> {
         match ir_incs {
             tacky_ir::Instruction::Return(val) => {
+                // 返回值放入 %eax，然后跳到函数唯一的尾声
                 let return_operand = self.generate_expression(val)?;
                 Ok(vec![
                     Instruction::Mov {
                         src: return_operand,
                         dst: Operand::Register(Reg::AX),
                     },
-                    Instruction::Ret,
+                    Instruction::Jmp(self.return_label.clone()),
                 ])
             }
             tacky_ir::Instruction::Unary { op, src, dst } => {
@@ -238,30 +426,9 @@ impl AssemblyGenerator {
 
                 match op {
                     // 除法和取余的特殊情况
-                    tacky_ir::BinaryOp::Divide => Ok(vec![
-                        Instruction::Mov {
-                            src: src1_operand,
-                            dst: Operand::Register(Reg::AX),
-                        },
-                        Instruction::Cdq,
-                        Instruction::Idiv(src2_operand),
-                        Instruction::Mov {
-                            src: Operand::Register(Reg::AX),
-                            dst: dst_operand,
-                        },
-                    ]),
-                    tacky_ir::BinaryOp::Remainder => Ok(vec![
-                        Instruction::Mov {
-                            src: src1_operand,
-                            dst: Operand::Register(Reg::AX),
-                        },
-                        Instruction::Cdq,
-                        Instruction::Idiv(src2_operand),
-                        Instruction::Mov {
-                            src: Operand::Register(Reg::DX),
-                            dst: dst_operand,
-                        },
-                    ]),
+                    tacky_ir::BinaryOp::Divide | tacky_ir::BinaryOp::Remainder => {
+                        Ok(self.generate_division(op, src1_operand, src2_operand, dst_operand))
+                    }
                     // 关系运算符现在使用辅助函数
                     tacky_ir::BinaryOp::EqualEqual
                     | tacky_ir::BinaryOp::BangEqual
@@ -316,7 +483,7 @@ impl AssemblyGenerator {
                         operand2: condition_value,
                     },
                     Instruction::JmpCC {
-                        condtion: ConditionCode::E,
+                        condition: ConditionCode::E,
                         target: target.clone(),
                     },
                 ])
@@ -329,7 +496,7 @@ impl AssemblyGenerator {
                         operand2: condition_value,
                     },
                     Instruction::JmpCC {
-                        condtion: ConditionCode::NE,
+                        condition: ConditionCode::NE,
                         target: target.clone(),
                     },
                 ])
@@ -343,11 +510,24 @@ impl AssemblyGenerator {
                 }])
             }
             tacky_ir::Instruction::Label(t) => Ok(vec![Instruction::Label(t.clone())]),
+            tacky_ir::Instruction::ZeroExtend { src, dst } => {
+                Ok(vec![Instruction::MovZeroExtend {
+                    src: self.generate_expression(src)?,
+                    dst: self.generate_expression(dst)?,
+                }])
+            }
+            tacky_ir::Instruction::Truncate { src, dst } => Ok(vec![Instruction::MovByte {
+                src: self.generate_expression(src)?,
+                dst: self.generate_expression(dst)?,
+            }]),
             tacky_ir::Instruction::FunctionCall { name, args, dst } => {
                 let mut ins = Vec::new();
                 //对齐
                 let num_stack_args = if args.len() > 6 { args.len() - 6 } else { 0 };
-                let stack_padding = if num_stack_args % 2 != 0 { 8 } else { 0 };
+                // 调用时栈必须 16 字节对齐：栈帧的大小已经是 16 的倍数，
+                // 压入奇数个 8 字节的栈参数之前先填充 8 字节
+                let slot = self.layout.pointer_size;
+                let stack_padding = (16 - num_stack_args as i64 * slot % 16) % 16;
                 if stack_padding != 0 {
                     ins.push(Instruction::AllocateStack(stack_padding));
                 }
@@ -382,36 +562,110 @@ impl AssemblyGenerator {
                     }
                 }
                 // // 发出 call 指令
-                ins.push(Instruction::Call(name.clone()));
+                ins.push(Instruction::Call(*name));
                 // 调整栈指针
                 let stack_args_len_i64 = stack_args.len() as i64;
-                let bytes_to_remove: i64 = 8 * stack_args_len_i64 + stack_padding;
+                let bytes_to_remove: i64 = slot * stack_args_len_i64 + stack_padding;
                 if bytes_to_remove > 0 {
                     ins.push(Instruction::DeallocateStack(bytes_to_remove));
                 }
-                // 获取返回值
-                let assembly_dst = self.generate_expression(dst)?;
-                ins.push(Instruction::Mov {
-                    src: Operand::Register(Reg::AX),
-                    dst: assembly_dst,
-                });
+                // 获取返回值（被丢弃时不需要）
+                if let Some(dst) = dst {
+                    let assembly_dst = self.generate_expression(dst)?;
+                    ins.push(Instruction::Mov {
+                        src: Operand::Register(Reg::AX),
+                        dst: assembly_dst,
+                    });
+                }
 
                 Ok(ins)
             }
         }
     }
 
+    /// 生成有符号除法或取余：商在 `%eax` 中，余数在 `%edx` 中。
+    ///
+    /// `IntDivOverflow::Wrap` 模式下，除数为 `-1` 时跳过 `idivl`，避免 `INT_MIN / -1`
+    /// 触发异常；除数是常量时直接选择其中一条路径。
+    fn generate_division(
+        &mut self,
+        op: &tacky_ir::BinaryOp,
+        dividend: Operand,
+        divisor: Operand,
+        dst: Operand,
+    ) -> Vec<Instruction> {
+        let is_divide = matches!(op, tacky_ir::BinaryOp::Divide);
+        let result = if is_divide { Reg::AX } else { Reg::DX };
+        let idiv = [Instruction::Cdq, Instruction::Idiv(divisor.clone())];
+        // x / -1 == -x（INT_MIN 取负后仍为 INT_MIN），x % -1 == 0
+        let minus_one = if is_divide {
+            Instruction::Unary {
+                op: UnaryOp::Neg,
+                operand: Operand::Register(Reg::AX),
+            }
+        } else {
+            Instruction::Mov {
+                src: Operand::Imm(0),
+                dst: Operand::Register(Reg::DX),
+            }
+        };
+
+        let mut ins = vec![Instruction::Mov {
+            src: dividend,
+            dst: Operand::Register(Reg::AX),
+        }];
+        match (self.div_overflow, &divisor) {
+            (IntDivOverflow::Trap, _) => ins.extend(idiv),
+            (IntDivOverflow::Wrap, Operand::Imm(-1)) => ins.push(minus_one),
+            (IntDivOverflow::Wrap, Operand::Imm(_)) => ins.extend(idiv),
+            (IntDivOverflow::Wrap, _) => {
+                let idiv_label = self.new_label("idiv");
+                let done_label = self.new_label("idiv_done");
+                ins.push(Instruction::Cmp {
+                    operand1: Operand::Imm(-1),
+                    operand2: divisor.clone(),
+                });
+                ins.push(Instruction::JmpCC {
+                    condition: ConditionCode::NE,
+                    target: idiv_label.clone(),
+                });
+                ins.push(minus_one);
+                ins.push(Instruction::Jmp(done_label.clone()));
+                ins.push(Instruction::Label(idiv_label));
+                ins.extend(idiv);
+                ins.push(Instruction::Label(done_label));
+            }
+        }
+        ins.push(Instruction::Mov {
+            src: Operand::Register(result),
+            dst,
+        });
+        ins
+    }
+
+    /// 生成当前函数内唯一的标签名。
+    fn new_label(&mut self, name: &str) -> String {
+        self.label_count += 1;
+        format!("{}.{}.{}", self.function_name, name, self.label_count)
+    }
+
     fn generate_expression(&self, v: &tacky_ir::Value) -> Result<Operand, String> {
         match v {
-            tacky_ir::Value::Constant(i) => Ok(Operand::Imm(*i)),
-            tacky_ir::Value::Var(name) => Ok(Operand::Pseudo(name.clone())),
+            tacky_ir::Value::Constant(i) => Operand::imm32(*i),
+            tacky_ir::Value::Var(name) => Ok(Operand::Pseudo(*name)),
         }
     }
 
-    fn patch_instructions(&self, instructions: &[Instruction]) -> Vec<Instruction> {
+    /// 修复无效指令。一条指令可能被展开成多条，展开后的指令沿用原指令的来源。
+    fn patch_instructions(
+        &self,
+        instructions: &[Instruction],
+        origins: &[Option<usize>],
+    ) -> (Vec<Instruction>, Vec<Option<usize>>) {
         let mut new_ins = Vec::with_capacity(instructions.len());
+        let mut new_origins = Vec::with_capacity(origins.len());
 
-        for item in instructions {
+        for (item, origin) in instructions.iter().zip(origins) {
             match item {
                 // 修复内存到内存的 mov
                 Instruction::Mov {
@@ -427,6 +681,41 @@ impl AssemblyGenerator {
                         dst: Operand::Stack(*d_off),
                     });
                 }
+                Instruction::MovByte {
+                    src: Operand::Stack(s_off),
+                    dst: Operand::Stack(d_off),
+                } => {
+                    new_ins.push(Instruction::MovByte {
+                        src: Operand::Stack(*s_off),
+                        dst: Operand::Register(Reg::R10),
+                    });
+                    new_ins.push(Instruction::MovByte {
+                        src: Operand::Register(Reg::R10),
+                        dst: Operand::Stack(*d_off),
+                    });
+                }
+                // movzbl 不接受立即数源操作数：立即数直接截断后移动
+                Instruction::MovZeroExtend {
+                    src: Operand::Imm(i),
+                    dst,
+                } => new_ins.push(Instruction::Mov {
+                    src: Operand::Imm(*i & 0xff),
+                    dst: dst.clone(),
+                }),
+                // movzbl 的目标必须是寄存器
+                Instruction::MovZeroExtend {
+                    src,
+                    dst: Operand::Stack(d_off),
+                } => {
+                    new_ins.push(Instruction::MovZeroExtend {
+                        src: src.clone(),
+                        dst: Operand::Register(Reg::R11),
+                    });
+                    new_ins.push(Instruction::Mov {
+                        src: Operand::Register(Reg::R11),
+                        dst: Operand::Stack(*d_off),
+                    });
+                }
                 // 修复 idiv 的立即数操作数
                 Instruction::Idiv(Operand::Imm(val)) => {
                     new_ins.push(Instruction::Mov {
@@ -506,21 +795,24 @@ impl AssemblyGenerator {
                 // 其他所有指令都是有效的
                 _ => new_ins.push(item.clone()),
             }
+            new_origins.resize(new_ins.len(), *origin);
         }
-        new_ins
+        (new_ins, new_origins)
     }
 
-    /// 它接受一个指令列表，返回一个新的、替换好伪寄存器的列表和栈大小
-    fn allocate_stack_slots(&self, instructions: &[Instruction]) -> (Vec<Instruction>, i64) {
-        let mut pseudo_map: HashMap<String, i64> = HashMap::new();
-        let mut next_stack_offset = -4; // 第一个变量在 -4(%rbp)
+    /// 它接受一个指令列表，返回一个新的、替换好伪寄存器的列表、栈大小和使用的栈槽数
+    fn allocate_stack_slots(&self, instructions: &[Instruction]) -> (Vec<Instruction>, i64, usize) {
+        let mut pseudo_map: HashMap<Symbol, i64> = HashMap::new();
+        // 已使用的栈空间，第一个 int 变量在 -4(%rbp)
+        let mut stack_size = 0;
 
         let mut map_operand_logic = |operand: &Operand| {
             if let Operand::Pseudo(name) = operand {
-                let offset = *pseudo_map.entry(name.clone()).or_insert_with(|| {
-                    let offset = next_stack_offset;
-                    next_stack_offset -= 4;
-                    offset
+                let offset = *pseudo_map.entry(*name).or_insert_with(|| {
+                    // 每个对象按自身大小对齐（int 为 4 字节，_Bool 为 1 字节）
+                    let size = self.pseudo_type(*name).size(&self.layout).max(1);
+                    stack_size = (stack_size + size + size - 1) / size * size;
+                    -stack_size
                 });
                 Operand::Stack(offset)
             } else {
@@ -533,8 +825,6 @@ impl AssemblyGenerator {
             .map(|inst| inst.map_operands(&mut map_operand_logic))
             .collect();
 
-        // 栈大小是分配的变量数 * 4
-        let stack_size = pseudo_map.len() as i64 * 4;
-        (new_instructions, stack_size)
+        (new_instructions, stack_size, pseudo_map.len())
     }
 }
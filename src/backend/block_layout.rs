@@ -0,0 +1,311 @@
+// src/backend/block_layout.rs
+
+//! **基本块布局 (`-fblock-layout`)**
+//!
+//! 把函数的 TACKY 指令序列切分成基本块（从标签处或者跳转、`return` 之后开始），重新排列
+//! 这些块，让尽可能多的控制流转移直接落入下一个块，然后重新线性化。
+//!
+//! 排列从入口块开始贪心地进行。一个块放好之后，依次尝试把下面的块放在它后面：
+//! 1.  它无条件跳转的目标，这条 `Jump` 随之删除；
+//! 2.  原来紧跟在它后面、控制流会落入的块；
+//! 3.  它条件跳转的目标，跳转条件随之反转，跳到原来的落入块。
+//!
+//! 把跳转目标拉上来的前提是目标块原来的前一个块不会落入它（或者那个块已经放在别处），
+//! 否则只是把一条跳转挪到了另一个块里。都不行时按原来的顺序取下一个还没放的块。
+//!
+//! 线性化时，落入块不再紧跟在后面的块末尾补一条 `Jump`（必要时为落入块生成标签），
+//! 最后删除没有跳转指向的标签。
+
+use std::collections::{HashMap, HashSet};
+
+use crate::backend::dead_code;
+use crate::backend::tacky_ir::{Function, Instruction, Value};
+
+/// 一个基本块：开头的标签（如果有）和其余的指令。
+struct Block {
+    label: Option<String>,
+    body: Vec<Instruction>,
+}
+
+impl Block {
+    fn last(&self) -> Option<&Instruction> {
+        self.body.last()
+    }
+}
+
+/// 重排函数的基本块，返回删除的跳转指令数量。
+pub fn layout_function(function: &mut Function) -> usize {
+    let jumps_before = count_jumps(&function.body);
+    let mut blocks = split_blocks(std::mem::take(&mut function.body));
+    let order = place_blocks(&blocks);
+    function.body = linearize(&function.name.to_string(), &mut blocks, &order);
+    jumps_before.saturating_sub(count_jumps(&function.body))
+}
+
+fn count_jumps(body: &[Instruction]) -> usize {
+    body.iter()
+        .filter(|ins| {
+            matches!(
+                ins,
+                Instruction::Jump(_)
+                    | Instruction::JumpIfZero { .. }
+                    | Instruction::JumpIfNotZero { .. }
+            )
+        })
+        .count()
+}
+
+fn split_blocks(body: Vec<Instruction>) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut current = Block {
+        label: None,
+        body: Vec::new(),
+    };
+    for ins in body {
+        if let Instruction::Label(label) = ins {
+            if current.label.is_some() || !current.body.is_empty() {
+                blocks.push(current);
+            }
+            current = Block {
+                label: Some(label),
+                body: Vec::new(),
+            };
+            continue;
+        }
+        let ends_block = matches!(
+            ins,
+            Instruction::Jump(_)
+                | Instruction::JumpIfZero { .. }
+                | Instruction::JumpIfNotZero { .. }
+                | Instruction::Return(_)
+        );
+        current.body.push(ins);
+        if ends_block {
+            blocks.push(std::mem::replace(
+                &mut current,
+                Block {
+                    label: None,
+                    body: Vec::new(),
+                },
+            ));
+        }
+    }
+    if current.label.is_some() || !current.body.is_empty() {
+        blocks.push(current);
+    }
+    blocks
+}
+
+/// 控制流从块 `b` 的末尾落入的块（按原来的顺序）。
+fn fallthrough(blocks: &[Block], b: usize) -> Option<usize> {
+    match blocks[b].last() {
+        Some(Instruction::Jump(_) | Instruction::Return(_)) => None,
+        _ => (b + 1 < blocks.len()).then_some(b + 1),
+    }
+}
+
+/// 决定块的顺序。
+fn place_blocks(blocks: &[Block]) -> Vec<usize> {
+    let index: HashMap<&str, usize> = blocks
+        .iter()
+        .enumerate()
+        .filter_map(|(i, b)| b.label.as_deref().map(|l| (l, i)))
+        .collect();
+    let mut placed = vec![false; blocks.len()];
+    let mut order = Vec::with_capacity(blocks.len());
+    if blocks.is_empty() {
+        return order;
+    }
+    placed[0] = true;
+    order.push(0);
+
+    while order.len() < blocks.len() {
+        let last = order[order.len() - 1];
+        let free = |t: usize, placed: &[bool]| !placed[t];
+        // 目标块原来的前一个块不落入它时，把它拉上来不会让别的块多一条跳转
+        let can_pull = |t: usize, placed: &[bool]| {
+            free(t, placed) && (fallthrough(blocks, t - 1) != Some(t) || placed[t - 1])
+        };
+        let target = match blocks[last].last() {
+            Some(
+                Instruction::Jump(target)
+                | Instruction::JumpIfZero { target, .. }
+                | Instruction::JumpIfNotZero { target, .. },
+            ) => index.get(target.as_str()).copied().filter(|&t| t != 0),
+            _ => None,
+        };
+        let unconditional = matches!(blocks[last].last(), Some(Instruction::Jump(_)));
+        let next = target
+            .filter(|&t| unconditional && can_pull(t, &placed))
+            .or(fallthrough(blocks, last).filter(|&f| free(f, &placed)))
+            .or(target.filter(|&t| can_pull(t, &placed)))
+            .unwrap_or_else(|| (0..blocks.len()).find(|&b| !placed[b]).unwrap());
+        placed[next] = true;
+        order.push(next);
+    }
+    order
+}
+
+fn linearize(function_name: &str, blocks: &mut [Block], order: &[usize]) -> Vec<Instruction> {
+    let existing: HashSet<String> = blocks.iter().filter_map(|b| b.label.clone()).collect();
+    let mut counter = 0;
+    let mut label_of = |blocks: &mut [Block], b: usize| -> String {
+        if let Some(label) = &blocks[b].label {
+            return label.clone();
+        }
+        let label = loop {
+            let candidate = format!("{}.layout.{}", function_name, counter);
+            counter += 1;
+            if !existing.contains(&candidate) {
+                break candidate;
+            }
+        };
+        blocks[b].label = Some(label.clone());
+        label
+    };
+    let label_index = |blocks: &[Block], label: &str| {
+        blocks
+            .iter()
+            .position(|b| b.label.as_deref() == Some(label))
+    };
+
+    let falls: Vec<Option<usize>> = (0..blocks.len()).map(|b| fallthrough(blocks, b)).collect();
+    for (k, &b) in order.iter().enumerate() {
+        let next = order.get(k + 1).copied();
+        // 落入块不再紧跟在后面时需要补一条跳转
+        let mut jump_to = falls[b].filter(|&f| next != Some(f));
+        match blocks[b].body.pop().map(split_branch) {
+            Some(Err(Instruction::Jump(target)))
+                if next.is_none() || label_index(blocks, &target) != next =>
+            {
+                blocks[b].body.push(Instruction::Jump(target));
+            }
+            // 跳转目标紧跟在后面，删除这条跳转
+            Some(Err(Instruction::Jump(_))) => {}
+            Some(Ok((if_zero, condition, target))) => match jump_to {
+                Some(f) if next.is_some() && label_index(blocks, &target) == next => {
+                    // 跳转目标紧跟在后面：反转条件，跳到原来的落入块
+                    let target = label_of(blocks, f);
+                    blocks[b].body.push(branch(!if_zero, condition, target));
+                    jump_to = None;
+                }
+                _ => blocks[b].body.push(branch(if_zero, condition, target)),
+            },
+            Some(Err(other)) => blocks[b].body.push(other),
+            None => {}
+        }
+        if let Some(f) = jump_to {
+            let target = label_of(blocks, f);
+            blocks[b].body.push(Instruction::Jump(target));
+        }
+    }
+
+    let mut body = Vec::new();
+    for &b in order {
+        if let Some(label) = blocks[b].label.take() {
+            body.push(Instruction::Label(label));
+        }
+        body.append(&mut blocks[b].body);
+    }
+    // 删除不再有跳转指向的标签
+    dead_code::remove_unreachable(body, |_| false)
+}
+
+/// 把条件跳转拆成（条件为零时跳转？, 条件, 目标）；其他指令原样返回。
+fn split_branch(ins: Instruction) -> Result<(bool, Value, String), Instruction> {
+    match ins {
+        Instruction::JumpIfZero { condition, target } => Ok((true, condition, target)),
+        Instruction::JumpIfNotZero { condition, target } => Ok((false, condition, target)),
+        other => Err(other),
+    }
+}
+
+fn branch(if_zero: bool, condition: Value, target: String) -> Instruction {
+    if if_zero {
+        Instruction::JumpIfZero { condition, target }
+    } else {
+        Instruction::JumpIfNotZero { condition, target }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UniqueNameGenerator;
+    use crate::backend::assembly_ast_gen::IntDivOverflow;
+    use crate::backend::interpreter;
+    use crate::backend::tacky_gen::TackyGenerator;
+    use crate::common::Symbol;
+    use crate::frontend::loop_labeling::LoopLabeling;
+    use crate::frontend::parse_program;
+    use crate::frontend::resolve_ident::IdentifierResolver;
+    use crate::frontend::type_checking::TypeChecker;
+
+    #[test]
+    fn block_layout_turns_jumps_into_fallthroughs() {
+        let x = Value::Var(Symbol::intern("x"));
+        let mut function = Function {
+            name: Symbol::intern("f"),
+            params: Vec::new(),
+            body: vec![
+                Instruction::Copy {
+                    src: Value::Constant(1),
+                    dst: x.clone(),
+                },
+                Instruction::Jump("second".to_string()),
+                Instruction::Label("third".to_string()),
+                Instruction::Return(x.clone()),
+                Instruction::Label("second".to_string()),
+                Instruction::Copy {
+                    src: Value::Constant(2),
+                    dst: x.clone(),
+                },
+                Instruction::Jump("third".to_string()),
+            ],
+        };
+        assert_eq!(layout_function(&mut function), 2);
+        let body: Vec<String> = function.body.iter().map(|ins| ins.to_string()).collect();
+        assert_eq!(body.len(), 3, "{:?}", body);
+        assert!(matches!(function.body[2], Instruction::Return(_)));
+
+        // 重排前后程序的结果相同
+        let ast = parse_program(
+            "\
+    int f(int a, int b) {
+        int r = 0;
+        for (int i = 0; i < a; i = i + 1) {
+            if (i == b) continue;
+            if (i > 20) break;
+            r = r + (i % 3 == 0 ? i : -i);
+            int j = 0;
+            do { j = j + 1; if (j == 2 && i > 5) break; r = r + j; } while (j < 3);
+        }
+        return r && a || b ? r : -1;
+    }
+    int main(void) {
+        int acc = 0;
+        for (int k = -3; k < 30; k = k + 1) acc = acc * 7 + f(k, k / 2);
+        return acc % 256;
+    }
+    ",
+        );
+        let mut names = UniqueNameGenerator::new();
+        let (resolved, _) = IdentifierResolver::new(&mut names)
+            .resolve_program(&ast)
+            .unwrap();
+        let labeled = LoopLabeling::new(&mut names)
+            .label_loops_in_program(&resolved)
+            .unwrap();
+        let (typed, symbols, _) = TypeChecker::new().typecheck_program(&labeled).unwrap();
+        let (mut program, _) = TackyGenerator::new(&mut names, &symbols)
+            .generate_tacky(&typed)
+            .unwrap();
+        let expected = interpreter::run(&program, &symbols, IntDivOverflow::Trap).unwrap();
+        for function in &mut program.functions {
+            layout_function(function);
+        }
+        crate::backend::tacky_ir::verify(&program, &symbols).unwrap();
+        let actual = interpreter::run(&program, &symbols, IntDivOverflow::Trap).unwrap();
+        assert_eq!(actual, expected);
+    }
+}
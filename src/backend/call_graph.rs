@@ -0,0 +1,84 @@
+// src/backend/call_graph.rs
+
+//! **调用图与无用函数消除 (`--gc-functions`)**
+//!
+//! 在 TACKY 程序上构建调用图，并删除从根函数（`main` 以及显式标记为 `extern` 的函数）
+//! 出发不可达的函数，以减小生成的汇编代码体积。
+//!
+//! 这是一种整程序假设：其他翻译单元调用的非 `main` 函数需要显式声明为 `extern`，
+//! 否则会被删除。因此它只在用户显式传入 `--gc-functions` 时运行。
+
+use std::collections::{HashMap, HashSet};
+
+use crate::backend::tacky_ir::{Instruction, Program};
+use crate::common::Symbol;
+use crate::i18n::tr;
+
+/// 调用图：函数名 -> 它直接调用的函数名集合。
+#[derive(Debug, Default)]
+pub struct CallGraph {
+    edges: HashMap<Symbol, HashSet<Symbol>>,
+}
+
+impl CallGraph {
+    pub fn build(program: &Program) -> Self {
+        let edges = program
+            .functions
+            .iter()
+            .map(|f| {
+                let callees = f
+                    .body
+                    .iter()
+                    .filter_map(|i| match i {
+                        Instruction::FunctionCall { name, .. } => Some(*name),
+                        _ => None,
+                    })
+                    .collect();
+                (f.name, callees)
+            })
+            .collect();
+        CallGraph { edges }
+    }
+
+    /// 返回从 `roots` 出发可达的所有函数（包括根本身）。
+    pub fn reachable_from(&self, roots: impl IntoIterator<Item = Symbol>) -> HashSet<Symbol> {
+        let mut reachable = HashSet::new();
+        let mut worklist: Vec<Symbol> = roots.into_iter().collect();
+        while let Some(name) = worklist.pop() {
+            if !reachable.insert(name) {
+                continue;
+            }
+            if let Some(callees) = self.edges.get(&name) {
+                worklist.extend(callees.iter().copied());
+            }
+        }
+        reachable
+    }
+}
+
+/// 删除从 `main` 和 `exported` 出发不可达的函数，返回被删除的函数名。
+///
+/// 程序中没有定义 `main` 时（例如只编译一个库文件）无法确定根，返回错误，不做任何删除。
+pub fn gc_functions(
+    program: &mut Program,
+    exported: &HashSet<Symbol>,
+) -> Result<Vec<Symbol>, String> {
+    if !program.functions.iter().any(|f| f.name == "main") {
+        return Err(tr!(
+            "--gc-functions 需要以 main 作为根，但程序中没有定义 main"
+        ));
+    }
+    let graph = CallGraph::build(program);
+    let roots = std::iter::once(Symbol::intern("main")).chain(exported.iter().copied());
+    let reachable = graph.reachable_from(roots);
+
+    let mut removed = Vec::new();
+    program.functions.retain(|f| {
+        let keep = reachable.contains(&f.name);
+        if !keep {
+            removed.push(f.name);
+        }
+        keep
+    });
+    Ok(removed)
+}
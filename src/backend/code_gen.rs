@@ -3,8 +3,10 @@
 use crate::backend::assembly_ast::{
     BinaryOp, ConditionCode, Function, Instruction, Operand, Program, Reg, UnaryOp,
 };
-use crate::frontend::type_checking::SymbolInfo;
-use std::collections::HashMap;
+use crate::backend::tacky_ir;
+use crate::common::Symbol;
+use crate::frontend::type_checking::{IdentifierAttrs, SymbolTable};
+use crate::i18n::tr;
 use std::fs::File;
 use std::io::{self, BufWriter, Write};
 
@@ -19,95 +21,241 @@ pub enum InstructionSuffix {
     Q,    //64
 }
 
+/// 汇编输出语法（`--masm`）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum AsmSyntax {
+    /// AT&T 语法（gcc 默认）：`movl $1, -4(%rbp)`
+    #[default]
+    Att,
+    /// Intel 语法：`mov DWORD PTR [rbp-4], 1`
+    Intel,
+}
+
+/// 发射一个函数时写出的汇编代码量（`--stats`）。
+#[derive(Debug, Clone)]
+pub struct EmittedFunction {
+    pub name: Symbol,
+    /// 写出的行数（包括伪指令、标签和注释）
+    pub lines: usize,
+    pub bytes: usize,
+}
+
+/// 统计写入的字节数和行数的 `Write` 包装。
+struct CountingWriter<'w, W: Write> {
+    inner: &'w mut W,
+    lines: usize,
+    bytes: usize,
+}
+
+impl<W: Write> Write for CountingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.bytes += written;
+        self.lines += buf[..written].iter().filter(|&&b| b == b'\n').count();
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 pub struct CodeGenerator<'a> {
-    tables: &'a HashMap<String, SymbolInfo>,
+    tables: &'a SymbolTable,
+    /// 设置后，在每段汇编指令前以注释形式打印其来源的 TACKY 指令 (`--annotate-asm`)
+    annotations: Option<&'a tacky_ir::Program>,
+    syntax: AsmSyntax,
 }
 
 impl<'a> CodeGenerator<'a> {
-    pub fn new(tables: &'a HashMap<String, SymbolInfo>) -> Self {
-        CodeGenerator { tables }
+    pub fn new(tables: &'a SymbolTable) -> Self {
+        CodeGenerator {
+            tables,
+            annotations: None,
+            syntax: AsmSyntax::default(),
+        }
     }
 
+    /// 选择输出的汇编语法。
+    pub fn with_syntax(mut self, syntax: AsmSyntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+
+    /// 使用生成该汇编程序的 TACKY 程序为输出添加 `# tacky: ...` 注释。
+    pub fn with_tacky_annotations(mut self, ir_program: &'a tacky_ir::Program) -> Self {
+        self.annotations = Some(ir_program);
+        self
+    }
+
+    /// 将汇编代码写入指定路径的文件（`generate_program` 的薄封装）。
     pub fn generate_program_to_file(
         &self,
         program: &Program,
         file_name: &str,
-    ) -> Result<(), String> {
-        let file = File::create(file_name).map_err(|e| format!("无法创建文件: {}", e))?;
+    ) -> Result<Vec<EmittedFunction>, String> {
+        let file = File::create(file_name).map_err(|e| tr!("无法创建文件: {}", e))?;
         let mut writer = BufWriter::new(file);
-        self.emit_program(program, &mut writer)
-            .map_err(|e| e.to_string())
+        let emitted = self.generate_program(program, &mut writer)?;
+        writer.flush().map_err(|e| e.to_string())?;
+        Ok(emitted)
+    }
+
+    /// 将汇编代码写入任意 `io::Write`（文件、stdout 或内存缓冲区），
+    /// 返回每个函数写出的代码量。
+    pub fn generate_program<W: Write>(
+        &self,
+        program: &Program,
+        w: &mut W,
+    ) -> Result<Vec<EmittedFunction>, String> {
+        self.emit_program(program, w).map_err(|e| e.to_string())
     }
 
-    fn emit_program(&self, program: &Program, writer: &mut impl Write) -> io::Result<()> {
+    fn emit_program(
+        &self,
+        program: &Program,
+        writer: &mut impl Write,
+    ) -> io::Result<Vec<EmittedFunction>> {
+        if self.syntax == AsmSyntax::Intel {
+            writeln!(writer, "    .intel_syntax noprefix")?;
+        }
+        let mut emitted = Vec::with_capacity(program.functions.len());
         for function in &program.functions {
-            self.emit_function(function, writer)?;
+            let mut counter = CountingWriter {
+                inner: writer,
+                lines: 0,
+                bytes: 0,
+            };
+            self.emit_function(function, &mut counter)?;
+            emitted.push(EmittedFunction {
+                name: function.name,
+                lines: counter.lines,
+                bytes: counter.bytes,
+            });
             writeln!(writer)?; // 函数之间添加空行以提高可读性
         }
         // 这个指令告诉链接器栈是不可执行的，这是一个好的安全实践。
         writeln!(writer, "    .section .note.GNU-stack,\"\",@progbits")?;
-        Ok(())
+        Ok(emitted)
     }
 
-    fn emit_function(&self, function: &Function, writer: &mut impl Write) -> io::Result<()> {
+    /// 把一个函数发射为汇编文本行，每行附带其来源的 TACKY 指令在函数体中的下标
+    /// （`--explain` 用来交叉高亮）。
+    pub fn function_listing(&self, function: &Function) -> Vec<(String, Option<usize>)> {
+        let text = |buf: Vec<u8>| String::from_utf8(buf).expect("汇编文本是 UTF-8");
+        let mut header = Vec::new();
+        self.emit_function_header(function, &mut header)
+            .expect("写入内存缓冲区不会失败");
+        let mut listing: Vec<_> = text(header)
+            .lines()
+            .map(|line| (line.to_string(), None))
+            .collect();
+        for (i, instruction) in function.instructions.iter().enumerate() {
+            let mut buf = Vec::new();
+            self.emit_body_instruction(function, instruction, &mut buf)
+                .expect("写入内存缓冲区不会失败");
+            let origin = function.origins.get(i).copied().flatten();
+            listing.extend(text(buf).lines().map(|line| (line.to_string(), origin)));
+        }
+        listing
+    }
+
+    /// 函数元信息和序言。
+    fn emit_function_header(&self, function: &Function, writer: &mut impl Write) -> io::Result<()> {
         // --- 函数元信息 ---
-        writeln!(writer, "    .globl {}", function.name)?;
+        // static 函数具有内部链接，不导出符号
+        if self.is_global(function.name) {
+            writeln!(writer, "    .globl {}", function.name)?;
+        }
         writeln!(writer, "{}:", function.name)?;
 
         // --- 函数序言 ---
-        self.emit_indented("pushq %rbp", writer)?;
-        self.emit_indented("movq %rsp, %rbp", writer)?;
+        if function.frame_pointer {
+            self.emit_frame_pointer_op("pushq %rbp", "push rbp", writer)?;
+            self.emit_frame_pointer_op("movq %rsp, %rbp", "mov rbp, rsp", writer)?;
+        }
+        Ok(())
+    }
+
+    fn emit_function(&self, function: &Function, writer: &mut impl Write) -> io::Result<()> {
+        self.emit_function_header(function, writer)?;
 
         // --- 函数体 ---
-        for instruction in &function.instructions {
-            self.emit_instruction(instruction, writer)?;
+        let tacky_body = self.annotations.and_then(|p| {
+            p.functions
+                .iter()
+                .find(|f| f.name == function.name)
+                .map(|f| &f.body)
+        });
+        let mut last_origin = None;
+        for (i, instruction) in function.instructions.iter().enumerate() {
+            if let Some(body) = tacky_body {
+                // 同一条 TACKY 指令展开出的连续汇编指令只注释一次
+                let origin = function.origins.get(i).copied().flatten();
+                if let Some(index) = origin
+                    && origin != last_origin
+                {
+                    writeln!(writer, "    # tacky: {}", body[index])?;
+                }
+                last_origin = origin;
+            }
+            self.emit_body_instruction(function, instruction, writer)?;
         }
 
         Ok(())
     }
 
+    fn emit_body_instruction(
+        &self,
+        function: &Function,
+        instruction: &Instruction,
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        match instruction {
+            // 没有栈帧时尾声只有 ret
+            Instruction::Ret if !function.frame_pointer => self.emit_indented("ret", writer),
+            _ => self.emit_instruction(instruction, writer),
+        }
+    }
+
     fn emit_instruction(
         &self,
         instruction: &Instruction,
         writer: &mut impl Write,
     ) -> io::Result<()> {
+        use InstructionSuffix::{Byte, Long, Q};
         match instruction {
             Instruction::Mov { src, dst } => {
                 // 特殊情况：movzbl %al, %eax
                 // 这是我们将字节零扩展为长整型的方式。
                 if let (Operand::Register(Reg::AX), Operand::Register(Reg::AX)) = (src, dst) {
-                    self.emit_indented("movzbl %al, %eax", writer)
+                    self.emit_op("movzbl", "movzx", &[(src, Byte), (dst, Long)], writer)
                 } else {
                     // movl 用于32位（Long）操作数。
-                    let line = format!(
-                        "movl {}, {}",
-                        self.format_operand(src, InstructionSuffix::Long),
-                        self.format_operand(dst, InstructionSuffix::Long)
-                    );
-                    self.emit_indented(&line, writer)
+                    self.emit_op("movl", "mov", &[(src, Long), (dst, Long)], writer)
                 }
             }
+            Instruction::MovByte { src, dst } => {
+                self.emit_op("movb", "mov", &[(src, Byte), (dst, Byte)], writer)
+            }
+            Instruction::MovZeroExtend { src, dst } => {
+                self.emit_op("movzbl", "movzx", &[(src, Byte), (dst, Long)], writer)
+            }
             Instruction::Unary { op, operand } => {
-                let (mnemonic, suffix) = match op {
-                    UnaryOp::Neg => ("neg", "l"),
-                    UnaryOp::Complement => ("not", "l"),
+                let (att, intel) = match op {
+                    UnaryOp::Neg => ("negl", "neg"),
+                    UnaryOp::Complement => ("notl", "not"),
                 };
-                let line = format!(
-                    "{}{} {}",
-                    mnemonic,
-                    suffix,
-                    self.format_operand(operand, InstructionSuffix::Long)
-                );
-                self.emit_indented(&line, writer)
+                self.emit_op(att, intel, &[(operand, Long)], writer)
             }
             Instruction::AllocateStack(size) => {
                 // 栈分配/释放使用64位（Quad）寄存器。
-                self.emit_indented(&format!("subq ${}, %rsp", size), writer)
+                self.emit_stack_pointer_op("subq", "sub", *size, writer)
             }
             Instruction::Ret => {
                 // 这是函数尾声
-                self.emit_indented("movq %rbp, %rsp", writer)?;
-                self.emit_indented("popq %rbp", writer)?;
+                self.emit_frame_pointer_op("movq %rbp, %rsp", "mov rsp, rbp", writer)?;
+                self.emit_frame_pointer_op("popq %rbp", "pop rbp", writer)?;
                 self.emit_indented("ret", writer)
             }
             Instruction::Binary {
@@ -115,59 +263,50 @@ impl<'a> CodeGenerator<'a> {
                 left_operand,
                 right_operand,
             } => {
-                let (mnemonic, suffix) = match op {
-                    BinaryOp::Add => ("add", "l"),
-                    BinaryOp::Subtract => ("sub", "l"),
-                    BinaryOp::Multiply => ("imul", "l"),
+                let (att, intel) = match op {
+                    BinaryOp::Add => ("addl", "add"),
+                    BinaryOp::Subtract => ("subl", "sub"),
+                    BinaryOp::Multiply => ("imull", "imul"),
                 };
-                let src = self.format_operand(left_operand, InstructionSuffix::Long);
-                let dst = self.format_operand(right_operand, InstructionSuffix::Long);
-                self.emit_indented(&format!("{}{} {}, {}", mnemonic, suffix, src, dst), writer)
-            }
-            Instruction::Idiv(operand) => {
-                let opr = self.format_operand(operand, InstructionSuffix::Long);
-                self.emit_indented(&format!("idivl {}", opr), writer)
+                self.emit_op(
+                    att,
+                    intel,
+                    &[(left_operand, Long), (right_operand, Long)],
+                    writer,
+                )
             }
+            Instruction::Idiv(operand) => self.emit_op("idivl", "idiv", &[(operand, Long)], writer),
             Instruction::Cdq => self.emit_indented("cdq", writer),
             Instruction::Cmp { operand1, operand2 } => {
-                let opr1 = self.format_operand(operand1, InstructionSuffix::Long);
-                let opr2 = self.format_operand(operand2, InstructionSuffix::Long);
-                self.emit_indented(&format!("cmpl {}, {}", opr1, opr2), writer)
+                self.emit_op("cmpl", "cmp", &[(operand1, Long), (operand2, Long)], writer)
             }
             Instruction::Jmp(name) => {
                 self.emit_indented(&format!("jmp {}{}", LOCAL_LABEL_PREFIX, name), writer)
             }
-            Instruction::JmpCC { condtion, target } => {
-                let c = self.format_condition(condtion);
+            Instruction::JmpCC { condition, target } => {
+                let c = self.format_condition(condition);
                 self.emit_indented(&format!("j{} {}{}", c, LOCAL_LABEL_PREFIX, target), writer)
             }
-            Instruction::SetCC { conditin, operand } => {
+            Instruction::SetCC { condition, operand } => {
                 // SetCC 现在只对寄存器的字节形式进行操作。
-                let c = self.format_condition(conditin);
-                let opr = self.format_operand(operand, InstructionSuffix::Byte);
-                self.emit_indented(&format!("set{} {}", c, opr), writer)
+                let c = self.format_condition(condition);
+                let mnemonic = format!("set{}", c);
+                self.emit_op(&mnemonic, &mnemonic, &[(operand, Byte)], writer)
             }
             Instruction::Label(t) => {
                 // 标签不缩进。
                 writeln!(writer, "{}{}:", LOCAL_LABEL_PREFIX, t)
             }
             Instruction::DeallocateStack(i) => {
-                self.emit_indented(&format!("addq ${} ,%rsp", i), writer)
-            }
-            Instruction::Push(operand) => {
-                let opr = self.format_operand(operand, InstructionSuffix::Q);
-                self.emit_indented(&format!("pushq {} ", opr), writer)
+                self.emit_stack_pointer_op("addq", "add", *i, writer)
             }
+            Instruction::Push(operand) => self.emit_op("pushq", "push", &[(operand, Q)], writer),
             Instruction::Call(name) => {
-                if self.tables.contains_key(name) {
-                    // let r = self.tables.get(name).unwrap();
-                    // if r.defined {
-                    //     self.emit_indented(&format!("call {} ", name), writer)
-                    // } else {
-                    //     self.emit_indented(&format!("call {}@PLT", name), writer)
-                    // }
-                    self.emit_indented(&format!("call {} ", name), writer)
+                if self.is_defined(*name) {
+                    self.emit_indented(&format!("call {}", name), writer)
                 } else {
+                    // 在别的编译单元（例如 libc）中定义的函数可能位于共享库里，
+                    // 通过 PLT 调用，位置无关的可执行文件才能正确链接。
                     self.emit_indented(&format!("call {}@PLT", name), writer)
                 }
             }
@@ -176,18 +315,97 @@ impl<'a> CodeGenerator<'a> {
 
     // --- 辅助函数 ---
 
+    /// 符号是否具有外部链接。符号表中没有记录的函数（例如手工构造的测试程序）视为全局。
+    fn is_global(&self, name: Symbol) -> bool {
+        match self.tables.get(name).map(|s| &s.identifier_attrs) {
+            Some(IdentifierAttrs::FunAttr { global, .. })
+            | Some(IdentifierAttrs::StaticAttr { global, .. }) => *global,
+            _ => true,
+        }
+    }
+
+    /// 函数是否在本编译单元中定义。
+    fn is_defined(&self, name: Symbol) -> bool {
+        matches!(
+            self.tables.get(name).map(|s| &s.identifier_attrs),
+            Some(IdentifierAttrs::FunAttr { defined: true, .. })
+        )
+    }
+
     /// 写入带标准缩进的一行。
     fn emit_indented(&self, line: &str, writer: &mut impl Write) -> io::Result<()> {
         writeln!(writer, "    {}", line)
     }
 
+    /// 发射一条带操作数的指令。
+    ///
+    /// `operands` 按 AT&T 的顺序（源操作数在前，目的操作数在后）给出，并带上各自的大小；
+    /// Intel 语法下操作数顺序在这里统一反转。
+    fn emit_op(
+        &self,
+        att_mnemonic: &str,
+        intel_mnemonic: &str,
+        operands: &[(&Operand, InstructionSuffix)],
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        let mut formatted: Vec<String> = operands
+            .iter()
+            .map(|(operand, size)| self.format_operand(operand, *size))
+            .collect();
+        let mnemonic = match self.syntax {
+            AsmSyntax::Att => att_mnemonic,
+            AsmSyntax::Intel => {
+                formatted.reverse();
+                intel_mnemonic
+            }
+        };
+        self.emit_indented(&format!("{} {}", mnemonic, formatted.join(", ")), writer)
+    }
+
+    /// 发射调整 `%rsp` 的指令（`subq`/`addq`）。
+    fn emit_stack_pointer_op(
+        &self,
+        att_mnemonic: &str,
+        intel_mnemonic: &str,
+        bytes: i64,
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        let line = match self.syntax {
+            AsmSyntax::Att => format!("{} ${}, %rsp", att_mnemonic, bytes),
+            AsmSyntax::Intel => format!("{} rsp, {}", intel_mnemonic, bytes),
+        };
+        self.emit_indented(&line, writer)
+    }
+
+    /// 发射函数序言/尾声中操作 `%rbp`/`%rsp` 的固定指令。
+    fn emit_frame_pointer_op(
+        &self,
+        att: &str,
+        intel: &str,
+        writer: &mut impl Write,
+    ) -> io::Result<()> {
+        match self.syntax {
+            AsmSyntax::Att => self.emit_indented(att, writer),
+            AsmSyntax::Intel => self.emit_indented(intel, writer),
+        }
+    }
+
     /// 格式化操作数以用于汇编输出。
     fn format_operand(&self, operand: &Operand, size: InstructionSuffix) -> String {
-        match operand {
-            Operand::Imm(val) => format!("${}", val),
-            Operand::Register(reg) => self.format_reg(reg, size),
-            Operand::Stack(offset) => format!("{}(%rbp)", offset),
-            Operand::Pseudo(_) => {
+        match (operand, self.syntax) {
+            (Operand::Imm(val), AsmSyntax::Att) => format!("${}", val),
+            (Operand::Imm(val), AsmSyntax::Intel) => val.to_string(),
+            (Operand::Register(reg), _) => self.format_reg(reg, size),
+            (Operand::Stack(offset), AsmSyntax::Att) => format!("{}(%rbp)", offset),
+            (Operand::Stack(offset), AsmSyntax::Intel) => {
+                let ptr = match size {
+                    InstructionSuffix::Byte => "BYTE",
+                    InstructionSuffix::Long => "DWORD",
+                    InstructionSuffix::Q => "QWORD",
+                };
+                format!("{} PTR [rbp{:+}]", ptr, offset)
+            }
+            (Operand::Pseudo(_), _) => {
                 panic!("伪寄存器不应出现在最终代码生成阶段");
             }
         }
@@ -250,6 +468,10 @@ impl<'a> CodeGenerator<'a> {
             //     reg, size
             // ),
         };
-        name.to_string()
+        match self.syntax {
+            AsmSyntax::Att => name.to_string(),
+            // Intel 语法 (noprefix) 的寄存器名不带 `%`
+            AsmSyntax::Intel => name.trim_start_matches('%').to_string(),
+        }
     }
 }
@@ -0,0 +1,171 @@
+// src/backend/constexpr_lite.rs
+
+//! **编译期求值纯函数调用 (`-fconstexpr-lite`)**
+//!
+//! 如果被调用的函数足够小（不超过 [`MAX_CALLEE_INSTRUCTIONS`] 条 TACKY 指令）、是纯函数
+//! （不读写静态存储期变量和 `volatile` 变量，也不调用任何函数），并且调用的所有参数都是常量，就用 TACKY
+//! 解释器在编译期执行这次调用，把调用指令替换为把结果复制到目标的 `Copy`。
+//!
+//! 被调用的函数可能有死循环，所以编译期执行的指令数有上限 [`MAX_STEPS`]。执行出错
+//! （例如除以零）或者超过上限时保留原来的调用，错误在运行时照常发生。
+
+use std::collections::HashSet;
+
+use crate::backend::assembly_ast_gen::IntDivOverflow;
+use crate::backend::interpreter;
+use crate::backend::tacky_ir::{Function, Instruction, Program, Value};
+use crate::common::Symbol;
+use crate::frontend::type_checking::{IdentifierAttrs, SymbolTable};
+
+/// 可以在编译期求值的函数的最大指令数
+pub const MAX_CALLEE_INSTRUCTIONS: usize = 64;
+/// 编译期求值一次调用最多执行的指令数
+pub const MAX_STEPS: usize = 10_000;
+
+/// 求值所有可以在编译期求值的调用，返回被替换的调用数量。
+pub fn fold_pure_calls(
+    program: &mut Program,
+    symbols: &SymbolTable,
+    div_overflow: IntDivOverflow,
+) -> usize {
+    let pure: HashSet<Symbol> = program
+        .functions
+        .iter()
+        .filter(|f| f.body.len() <= MAX_CALLEE_INSTRUCTIONS && is_pure(f, symbols))
+        .map(|f| f.name)
+        .collect();
+
+    // 先在不可变的程序上求值，再统一替换
+    let mut results = Vec::new();
+    for (f, function) in program.functions.iter().enumerate() {
+        for (i, ins) in function.body.iter().enumerate() {
+            let Instruction::FunctionCall { name, args, .. } = ins else {
+                continue;
+            };
+            if !pure.contains(name) {
+                continue;
+            }
+            let Some(args) = constant_args(args) else {
+                continue;
+            };
+            if let Ok(result) =
+                interpreter::evaluate_call(program, symbols, div_overflow, *name, args, MAX_STEPS)
+            {
+                results.push((f, i, result));
+            }
+        }
+    }
+
+    // 从后往前替换，删除返回值被丢弃的调用不影响前面的下标
+    for &(f, i, result) in results.iter().rev() {
+        let body = &mut program.functions[f].body;
+        match &body[i] {
+            Instruction::FunctionCall { dst: Some(dst), .. } => {
+                body[i] = Instruction::Copy {
+                    src: Value::Constant(result as i64),
+                    dst: dst.clone(),
+                };
+            }
+            // 纯函数的调用没有副作用，结果不用时整条指令都可以删除
+            _ => {
+                body.remove(i);
+            }
+        }
+    }
+    results.len()
+}
+
+fn constant_args(args: &[Value]) -> Option<Vec<i32>> {
+    args.iter()
+        .map(|a| match a {
+            Value::Constant(c) => Some(*c as i32),
+            Value::Var(_) => None,
+        })
+        .collect()
+}
+
+/// 函数不调用其他函数，也不读写静态存储期变量和 `volatile` 变量。
+fn is_pure(function: &Function, symbols: &SymbolTable) -> bool {
+    let is_static = |v: &Value| match v {
+        Value::Var(name) => {
+            symbols.is_volatile(*name)
+                || symbols.get(*name).is_some_and(|s| {
+                    matches!(s.identifier_attrs, IdentifierAttrs::StaticAttr { .. })
+                })
+        }
+        Value::Constant(_) => false,
+    };
+    function.body.iter().all(|ins| match ins {
+        Instruction::FunctionCall { .. } => false,
+        Instruction::Return(v) => !is_static(v),
+        Instruction::Unary { src, dst, .. }
+        | Instruction::Copy { src, dst }
+        | Instruction::ZeroExtend { src, dst }
+        | Instruction::Truncate { src, dst } => !is_static(src) && !is_static(dst),
+        Instruction::Binary {
+            src1, src2, dst, ..
+        } => !is_static(src1) && !is_static(src2) && !is_static(dst),
+        Instruction::JumpIfZero { condition, .. }
+        | Instruction::JumpIfNotZero { condition, .. } => !is_static(condition),
+        Instruction::Jump(_) | Instruction::Label(_) => true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UniqueNameGenerator;
+    use crate::backend::tacky_gen::TackyGenerator;
+    use crate::frontend::loop_labeling::LoopLabeling;
+    use crate::frontend::parse_program;
+    use crate::frontend::resolve_ident::IdentifierResolver;
+    use crate::frontend::type_checking::TypeChecker;
+
+    #[test]
+    fn pure_calls_with_constant_arguments_are_evaluated() {
+        let ast = parse_program(
+            "\
+    static int counter;
+    int square(int x) { return x * x; }
+    int spin(int n) { while (n) {} return 0; }
+    int bump(int x) { counter = counter + x; return counter; }
+    int main(void) { return square(7) + spin(1) + bump(3); }
+    ",
+        );
+        let mut names = UniqueNameGenerator::new();
+        let (resolved, _) = IdentifierResolver::new(&mut names)
+            .resolve_program(&ast)
+            .unwrap();
+        let labeled = LoopLabeling::new(&mut names)
+            .label_loops_in_program(&resolved)
+            .unwrap();
+        let (typed, symbols, _) = TypeChecker::new().typecheck_program(&labeled).unwrap();
+        let (mut program, _) = TackyGenerator::new(&mut names, &symbols)
+            .generate_tacky(&typed)
+            .unwrap();
+        let folded = fold_pure_calls(&mut program, &symbols, IntDivOverflow::Trap);
+        assert_eq!(folded, 1);
+        let main = program.functions.iter().find(|f| f.name == "main").unwrap();
+        assert!(
+            main.body.iter().any(|ins| matches!(
+                ins,
+                Instruction::Copy {
+                    src: Value::Constant(49),
+                    ..
+                }
+            )),
+            "{:?}",
+            main.body
+        );
+        // 死循环超过指令上限，读写静态变量的函数不是纯函数
+        let calls: Vec<&str> = main
+            .body
+            .iter()
+            .filter_map(|ins| match ins {
+                Instruction::FunctionCall { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(calls, ["spin", "bump"]);
+    }
+}
@@ -0,0 +1,71 @@
+// src/backend/dead_code.rs
+
+//! **不可达代码消除**
+//!
+//! 在单个函数的 TACKY 指令序列上删除永远不会执行的指令：
+//! -   `Return`、无条件 `Jump` 以及对 noreturn 函数（`_Noreturn` /
+//!     `__attribute__((noreturn))`）的调用之后，直到下一个标签之前的指令都不可达；
+//! -   没有任何跳转指向的标签被删除，于是它后面的代码也可能变得不可达。
+//!
+//! 两步交替进行，直到不再有变化。
+
+use std::collections::HashSet;
+
+use crate::backend::tacky_ir::Instruction;
+use crate::common::Symbol;
+
+/// 删除不可达的指令和不再被引用的标签。`is_noreturn` 判断一个函数是否不会返回。
+pub fn remove_unreachable(
+    mut body: Vec<Instruction>,
+    is_noreturn: impl Fn(Symbol) -> bool,
+) -> Vec<Instruction> {
+    loop {
+        let before = body.len();
+
+        let mut reachable = true;
+        body.retain(|ins| {
+            if matches!(ins, Instruction::Label(_)) {
+                reachable = true;
+            }
+            let keep = reachable;
+            if is_terminator(ins, &is_noreturn) {
+                reachable = false;
+            }
+            keep
+        });
+
+        let targets: HashSet<String> = body.iter().filter_map(jump_target).collect();
+        body.retain(|ins| match ins {
+            Instruction::Label(l) => targets.contains(l),
+            _ => true,
+        });
+
+        if body.len() == before {
+            return body;
+        }
+    }
+}
+
+/// 控制流能否从指令序列的末尾落出去（例如函数体末尾缺少 `return`）。
+pub fn falls_through(body: &[Instruction], is_noreturn: impl Fn(Symbol) -> bool) -> bool {
+    !body
+        .last()
+        .is_some_and(|ins| is_terminator(ins, &is_noreturn))
+}
+
+fn is_terminator(ins: &Instruction, is_noreturn: &impl Fn(Symbol) -> bool) -> bool {
+    match ins {
+        Instruction::Return(_) | Instruction::Jump(_) => true,
+        Instruction::FunctionCall { name, .. } => is_noreturn(*name),
+        _ => false,
+    }
+}
+
+fn jump_target(ins: &Instruction) -> Option<String> {
+    match ins {
+        Instruction::Jump(t)
+        | Instruction::JumpIfZero { target: t, .. }
+        | Instruction::JumpIfNotZero { target: t, .. } => Some(t.clone()),
+        _ => None,
+    }
+}
@@ -0,0 +1,300 @@
+// src/backend/interpreter.rs
+
+//! **TACKY 解释器 (`--interpret`)**
+//!
+//! 直接执行 TACKY IR，不需要汇编器和链接器。适合没有完整工具链的 CI 环境，
+//! 也可以用来对照本地代码生成的结果，验证 IR 本身的语义。
+//!
+//! 支持的子集：
+//! -   只有 `int` 和 `_Bool`，运算按 32 位二进制补码回绕，与生成的汇编一致
+//! -   只能调用程序中定义的函数；调用外部函数（例如 libc）是运行时错误
+//! -   静态存储期变量按符号表中的初始值初始化（没有初始值的为 0）
+//! -   除以零是运行时错误；`INT_MIN / -1` 按 `--int-div-overflow` 的设置处理
+//! -   读取未初始化的局部变量得到 0
+//!
+//! 函数调用深度有上限，超出时报错而不是让解释器自身栈溢出：`call` 和 `execute` 互相递归，
+//! 解释器在一个栈足够 [`MAX_CALL_DEPTH`] 层调用的线程上运行。
+//!
+//! [`evaluate_call`] 在编译期执行单次调用（`-fconstexpr-lite`），此时执行的指令数也有上限。
+
+use std::collections::HashMap;
+
+use crate::backend::assembly_ast_gen::IntDivOverflow;
+use crate::backend::tacky_ir::{BinaryOp, Function, Instruction, Program, UnaryOp, Value};
+use crate::common::Symbol;
+use crate::frontend::type_checking::{CType, IdentifierAttrs, InitValue, SymbolTable};
+use crate::i18n::{self, tr};
+
+/// 允许的最大函数调用深度。
+const MAX_CALL_DEPTH: usize = 10_000;
+
+/// 每层调用预留的栈空间。未优化构建中 `call` 加 `execute` 的栈帧不到 4 KiB，这里留出余量。
+const STACK_PER_CALL: usize = 16 * 1024;
+
+/// 执行程序的 `main` 函数，返回它的返回值。
+pub fn run(
+    program: &Program,
+    symbols: &SymbolTable,
+    div_overflow: IntDivOverflow,
+) -> Result<i32, String> {
+    on_interpreter_stack(|| {
+        Interpreter::new(program, symbols, div_overflow).call(Symbol::intern("main"), Vec::new())
+    })
+}
+
+/// 以常量参数执行一次函数调用，最多执行 `max_steps` 条指令。
+pub fn evaluate_call(
+    program: &Program,
+    symbols: &SymbolTable,
+    div_overflow: IntDivOverflow,
+    name: Symbol,
+    args: Vec<i32>,
+    max_steps: usize,
+) -> Result<i32, String> {
+    on_interpreter_stack(|| {
+        let mut interpreter = Interpreter::new(program, symbols, div_overflow);
+        interpreter.steps_left = Some(max_steps);
+        interpreter.call(name, args)
+    })
+}
+
+/// 在栈大小足够 [`MAX_CALL_DEPTH`] 层调用的线程上运行解释器，调用线程的栈大小不受我们控制。
+fn on_interpreter_stack(
+    interpret: impl FnOnce() -> Result<i32, String> + Send,
+) -> Result<i32, String> {
+    let lang = i18n::lang();
+    std::thread::scope(|scope| {
+        let thread = std::thread::Builder::new()
+            .name("interpreter".into())
+            .stack_size(MAX_CALL_DEPTH * STACK_PER_CALL)
+            .spawn_scoped(scope, move || {
+                // 诊断语言是线程局部的
+                if let Some(lang) = lang {
+                    i18n::set_lang(lang);
+                }
+                interpret()
+            })
+            .map_err(|e| tr!("无法创建解释器线程: {}", e))?;
+        thread
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+struct Interpreter<'a> {
+    functions: HashMap<Symbol, &'a Function>,
+    symbols: &'a SymbolTable,
+    /// 静态存储期变量的当前值
+    globals: HashMap<Symbol, i32>,
+    div_overflow: IntDivOverflow,
+    depth: usize,
+    /// 还允许执行的指令数；`None` 表示没有上限
+    steps_left: Option<usize>,
+}
+
+/// 一次函数调用的局部变量。
+struct Frame {
+    locals: HashMap<Symbol, i32>,
+}
+
+impl<'a> Interpreter<'a> {
+    fn new(program: &'a Program, symbols: &'a SymbolTable, div_overflow: IntDivOverflow) -> Self {
+        let functions = program.functions.iter().map(|f| (f.name, f)).collect();
+        let globals = symbols
+            .iter()
+            .filter_map(|(name, info)| match &info.identifier_attrs {
+                IdentifierAttrs::StaticAttr { init_value, .. } => {
+                    let value = match init_value {
+                        InitValue::Initial(v) => *v as i32,
+                        InitValue::Tentative | InitValue::NoInitalizer => 0,
+                    };
+                    Some((name, value))
+                }
+                _ => None,
+            })
+            .collect();
+        Interpreter {
+            functions,
+            symbols,
+            globals,
+            div_overflow,
+            depth: 0,
+            steps_left: None,
+        }
+    }
+
+    fn call(&mut self, name: Symbol, args: Vec<i32>) -> Result<i32, String> {
+        let Some(&function) = self.functions.get(&name) else {
+            return Err(tr!("--interpret 不支持调用外部函数 '{}'", name));
+        };
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(tr!(
+                "运行时错误: 函数调用深度超过 {} 层（无限递归？）",
+                MAX_CALL_DEPTH
+            ));
+        }
+        if args.len() != function.params.len() {
+            return Err(tr!(
+                "运行时错误: 函数 '{}' 需要 {} 个参数，但调用时传入了 {} 个",
+                name,
+                function.params.len(),
+                args.len()
+            ));
+        }
+
+        let mut frame = Frame {
+            locals: HashMap::new(),
+        };
+        for (&param, arg) in function.params.iter().zip(args) {
+            // `_Bool` 参数只有低 8 位有效
+            let arg = if self.is_bool(param) { arg & 0xff } else { arg };
+            frame.locals.insert(param, arg);
+        }
+        let labels: HashMap<&str, usize> = function
+            .body
+            .iter()
+            .enumerate()
+            .filter_map(|(i, ins)| match ins {
+                Instruction::Label(l) => Some((l.as_str(), i)),
+                _ => None,
+            })
+            .collect();
+
+        self.depth += 1;
+        let result = self.execute(function, &labels, &mut frame);
+        self.depth -= 1;
+        result
+    }
+
+    fn execute(
+        &mut self,
+        function: &Function,
+        labels: &HashMap<&str, usize>,
+        frame: &mut Frame,
+    ) -> Result<i32, String> {
+        let jump = |target: &str| {
+            labels
+                .get(target)
+                .copied()
+                .ok_or_else(|| tr!("运行时错误: 跳转目标 '{}' 不存在", target))
+        };
+        let mut pc = 0;
+        while let Some(ins) = function.body.get(pc) {
+            pc += 1;
+            if let Some(steps) = &mut self.steps_left {
+                *steps = steps
+                    .checked_sub(1)
+                    .ok_or_else(|| tr!("运行时错误: 执行的指令数超过上限"))?;
+            }
+            match ins {
+                Instruction::Return(v) => return Ok(self.read(frame, v)),
+                Instruction::Unary { op, src, dst } => {
+                    let v = self.read(frame, src);
+                    let result = match op {
+                        UnaryOp::Complement => !v,
+                        UnaryOp::Negate => v.wrapping_neg(),
+                        UnaryOp::Not => (v == 0) as i32,
+                    };
+                    self.write(frame, dst, result);
+                }
+                Instruction::Binary {
+                    op,
+                    src1,
+                    src2,
+                    dst,
+                } => {
+                    let l = self.read(frame, src1);
+                    let r = self.read(frame, src2);
+                    let result = self.binary(op, l, r)?;
+                    self.write(frame, dst, result);
+                }
+                Instruction::Copy { src, dst } => {
+                    let v = self.read(frame, src);
+                    self.write(frame, dst, v);
+                }
+                Instruction::ZeroExtend { src, dst } | Instruction::Truncate { src, dst } => {
+                    let v = self.read(frame, src) & 0xff;
+                    self.write(frame, dst, v);
+                }
+                Instruction::Jump(target) => pc = jump(target)?,
+                Instruction::JumpIfZero { condition, target } => {
+                    if self.read(frame, condition) == 0 {
+                        pc = jump(target)?;
+                    }
+                }
+                Instruction::JumpIfNotZero { condition, target } => {
+                    if self.read(frame, condition) != 0 {
+                        pc = jump(target)?;
+                    }
+                }
+                Instruction::Label(_) => {}
+                Instruction::FunctionCall { name, args, dst } => {
+                    let args = args.iter().map(|a| self.read(frame, a)).collect();
+                    let result = self.call(*name, args)?;
+                    if let Some(dst) = dst {
+                        self.write(frame, dst, result);
+                    }
+                }
+            }
+        }
+        // TACKY 生成保证每个函数都以 return 结束
+        Err(tr!("运行时错误: 控制流到达函数 '{}' 的末尾", function.name))
+    }
+
+    fn binary(&self, op: &BinaryOp, l: i32, r: i32) -> Result<i32, String> {
+        Ok(match op {
+            BinaryOp::Add => l.wrapping_add(r),
+            BinaryOp::Subtract => l.wrapping_sub(r),
+            BinaryOp::Multiply => l.wrapping_mul(r),
+            BinaryOp::Divide | BinaryOp::Remainder => {
+                if r == 0 {
+                    return Err(tr!("运行时错误: 除以零"));
+                }
+                if l == i32::MIN && r == -1 && self.div_overflow == IntDivOverflow::Trap {
+                    return Err(tr!("运行时错误: 有符号除法溢出 (INT_MIN / -1)"));
+                }
+                if matches!(op, BinaryOp::Divide) {
+                    l.wrapping_div(r)
+                } else {
+                    l.wrapping_rem(r)
+                }
+            }
+            BinaryOp::EqualEqual => (l == r) as i32,
+            BinaryOp::BangEqual => (l != r) as i32,
+            BinaryOp::Greater => (l > r) as i32,
+            BinaryOp::GreaterEqual => (l >= r) as i32,
+            BinaryOp::Less => (l < r) as i32,
+            BinaryOp::LessEqual => (l <= r) as i32,
+        })
+    }
+
+    fn read(&self, frame: &Frame, value: &Value) -> i32 {
+        match value {
+            Value::Constant(c) => *c as i32,
+            Value::Var(name) => frame
+                .locals
+                .get(name)
+                .or_else(|| self.globals.get(name))
+                .copied()
+                .unwrap_or(0),
+        }
+    }
+
+    fn write(&mut self, frame: &mut Frame, dst: &Value, value: i32) {
+        let Value::Var(name) = dst else {
+            return;
+        };
+        match self.globals.get_mut(name) {
+            Some(slot) => *slot = value,
+            None => {
+                frame.locals.insert(*name, value);
+            }
+        }
+    }
+
+    fn is_bool(&self, name: Symbol) -> bool {
+        self.symbols
+            .get(name)
+            .is_some_and(|s| s.tpye == CType::Bool)
+    }
+}
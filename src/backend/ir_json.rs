@@ -0,0 +1,94 @@
+// src/backend/ir_json.rs
+
+//! **中间表示的 JSON 序列化 (`--emit-ir-json`)**
+//!
+//! 把 TACKY IR 和汇编 AST 序列化为 JSON，供评分脚本、可视化工具和差分测试等外部工具
+//! 直接读取，而不必解析 `pretty()` 的文本输出。
+//!
+//! 序列化由 IR 类型上派生的 `Serialize` 完成。每个函数是一个对象，指令是带 `"kind"` 字段的
+//! 对象，字段名与 IR 中的字段名一致，例如：
+//!
+//! ```json
+//! {"kind": "binary", "op": "add", "src1": {"var": "main.x.0"}, "src2": {"constant": 1}, "dst": {"var": "main.tmp1"}}
+//! ```
+//!
+//! 没有字段名的元组变体（例如 `Jump(target)`）用下面的函数包装成只有一个字段的对象。
+
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+
+use crate::backend::{assembly_ast as asm, tacky_ir as tacky};
+
+/// 把 TACKY IR 序列化为 JSON。
+pub fn tacky_program(program: &tacky::Program) -> String {
+    to_json(program)
+}
+
+/// 把汇编 AST 序列化为 JSON。
+pub fn asm_program(program: &asm::Program) -> String {
+    to_json(program)
+}
+
+fn to_json(program: &impl Serialize) -> String {
+    let mut json = serde_json::to_string_pretty(program).expect("IR 总能序列化为 JSON");
+    json.push('\n');
+    json
+}
+
+/// 为元组变体生成 `serialize_with` 函数：把唯一的字段写成名为 `$field` 的字段。
+macro_rules! single_field {
+    ($($name:ident => $field:literal),* $(,)?) => {$(
+        pub(crate) fn $name<T: Serialize, S: Serializer>(
+            value: &T,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            let mut object = serializer.serialize_struct($field, 1)?;
+            object.serialize_field($field, value)?;
+            object.end()
+        }
+    )*};
+}
+
+single_field! {
+    value => "value",
+    target => "target",
+    name => "name",
+    operand => "operand",
+    bytes => "bytes",
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::tacky_ir::{Function, Instruction, Program, Value};
+
+    #[test]
+    fn labels_and_names_are_escaped() {
+        let program = Program {
+            functions: vec![Function {
+                name: "main".into(),
+                params: vec!["a\"b".into()],
+                body: vec![
+                    Instruction::Label("L\\1".to_string()),
+                    Instruction::Jump("L\\1".to_string()),
+                    Instruction::Return(Value::Var("a\"b".into())),
+                ],
+            }],
+        };
+        let json: serde_json::Value = serde_json::from_str(&tacky_program(&program)).unwrap();
+        let function = &json["functions"][0];
+        assert_eq!(function["params"][0], "a\"b");
+        assert_eq!(
+            function["body"][0],
+            serde_json::json!({"kind": "label", "name": "L\\1"})
+        );
+        assert_eq!(
+            function["body"][1],
+            serde_json::json!({"kind": "jump", "target": "L\\1"})
+        );
+        assert_eq!(
+            function["body"][2],
+            serde_json::json!({"kind": "return", "value": {"var": "a\"b"}})
+        );
+    }
+}
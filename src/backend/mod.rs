@@ -1,5 +1,21 @@
+pub mod asm_verify;
 pub mod assembly_ast;
 pub mod assembly_ast_gen;
+pub mod block_layout;
+pub mod call_graph;
 pub mod code_gen;
+pub mod constexpr_lite;
+pub mod dead_code;
+pub mod interpreter;
+pub mod ir_json;
+pub mod pass_manager;
+pub mod stack_usage;
+pub mod stats;
 pub mod tacky_gen;
 pub mod tacky_ir;
+pub mod temp_reuse;
+
+#[cfg(test)]
+mod snapshot_tests;
+#[cfg(test)]
+mod tacky_gen_tests;
@@ -0,0 +1,192 @@
+// src/backend/pass_manager.rs
+
+//! **优化遍的管理 (`--disable-pass`)**
+//!
+//! 所有可以开关的遍都登记在 [`PASSES`] 中，每个遍有一个名字和必须在它之前运行的遍。
+//! 遍分两种：
+//! -   **程序级遍**：生成 TACKY IR 之后在整个程序上运行，按 `after` 约束排定顺序；
+//! -   **内置遍**：在 TACKY / 汇编生成器内部逐个函数运行，只能开启或关闭。
+//!
+//! 怀疑某个遍导致错误编译时，逐个用 `--disable-pass <名字>` 关闭，就能找到出问题的那一个。
+
+use std::collections::HashSet;
+
+use crate::backend::assembly_ast_gen::IntDivOverflow;
+use crate::backend::block_layout;
+use crate::backend::call_graph;
+use crate::backend::constexpr_lite;
+use crate::backend::tacky_ir::Program;
+use crate::common::Symbol;
+use crate::frontend::type_checking::SymbolTable;
+use crate::i18n::tr;
+
+/// 程序级遍运行时需要的信息。
+pub struct PassContext<'a> {
+    pub symbols: &'a SymbolTable,
+    pub div_overflow: IntDivOverflow,
+    /// 显式声明为 extern 的函数，可能被其他翻译单元调用
+    pub exported: HashSet<Symbol>,
+}
+
+pub enum PassKind {
+    /// 在整个 TACKY 程序上运行：`title` 是进度信息中的标题，`run` 返回结果摘要
+    Program {
+        title: fn() -> String,
+        run: fn(&mut Program, &PassContext) -> String,
+    },
+    /// 在生成器内部运行，由生成器的 `with_*` 选项开关
+    Builtin,
+}
+
+pub struct Pass {
+    pub name: &'static str,
+    /// 同时开启时必须先运行的遍
+    pub after: &'static [&'static str],
+    /// 没有显式开启时是否运行
+    pub default_enabled: bool,
+    pub kind: PassKind,
+}
+
+/// 所有登记的遍。
+pub const PASSES: &[Pass] = &[
+    Pass {
+        name: "reuse-temps",
+        after: &[],
+        default_enabled: true,
+        kind: PassKind::Builtin,
+    },
+    Pass {
+        name: "constexpr-lite",
+        after: &[],
+        default_enabled: false,
+        kind: PassKind::Program {
+            title: || tr!("编译期求值纯函数调用"),
+            run: run_constexpr_lite,
+        },
+    },
+    // 求值掉的调用可能是某个函数唯一的调用者
+    Pass {
+        name: "gc-functions",
+        after: &["constexpr-lite"],
+        default_enabled: false,
+        kind: PassKind::Program {
+            title: || tr!("删除不可达函数"),
+            run: run_gc_functions,
+        },
+    },
+    Pass {
+        name: "block-layout",
+        after: &["constexpr-lite", "gc-functions"],
+        default_enabled: false,
+        kind: PassKind::Program {
+            title: || tr!("重排基本块"),
+            run: run_block_layout,
+        },
+    },
+    Pass {
+        name: "params-in-registers",
+        after: &[],
+        default_enabled: true,
+        kind: PassKind::Builtin,
+    },
+];
+
+fn find(name: &str) -> Option<&'static Pass> {
+    PASSES.iter().find(|p| p.name == name)
+}
+
+/// `--disable-pass` 的参数解析：必须是登记过的遍的名字。
+///
+/// 命令行解析时还没有设置诊断语言，错误信息不经过翻译。
+pub fn parse_name(name: &str) -> Result<&'static str, String> {
+    find(name).map(|p| p.name).ok_or_else(|| {
+        let names: Vec<&str> = PASSES.iter().map(|p| p.name).collect();
+        format!(
+            "unknown pass '{}' (known passes: {})",
+            name,
+            names.join(", ")
+        )
+    })
+}
+
+/// 记录每个遍是否开启，并为开启的程序级遍排定顺序。
+pub struct PassManager {
+    enabled: HashSet<&'static str>,
+}
+
+impl PassManager {
+    /// 只开启默认运行的遍。
+    pub fn new() -> Self {
+        PassManager {
+            enabled: PASSES
+                .iter()
+                .filter(|p| p.default_enabled)
+                .map(|p| p.name)
+                .collect(),
+        }
+    }
+
+    pub fn enable(&mut self, name: &str) {
+        let pass = find(name).expect("只能开启登记过的遍");
+        self.enabled.insert(pass.name);
+    }
+
+    pub fn disable(&mut self, name: &str) {
+        self.enabled.remove(name);
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.enabled.contains(name)
+    }
+
+    /// 开启的程序级遍的运行顺序：满足 `after` 约束，其余按登记的顺序。
+    pub fn schedule(&self) -> Vec<&'static Pass> {
+        let mut pending: Vec<&'static Pass> = PASSES
+            .iter()
+            .filter(|p| matches!(p.kind, PassKind::Program { .. }) && self.is_enabled(p.name))
+            .collect();
+        let mut order: Vec<&'static Pass> = Vec::with_capacity(pending.len());
+        while !pending.is_empty() {
+            let ready = pending
+                .iter()
+                .position(|p| {
+                    p.after
+                        .iter()
+                        .all(|a| !pending.iter().any(|q| q.name == *a))
+                })
+                .expect("遍的顺序约束存在环");
+            order.push(pending.remove(ready));
+        }
+        order
+    }
+}
+
+fn run_constexpr_lite(program: &mut Program, cx: &PassContext) -> String {
+    let folded = constexpr_lite::fold_pure_calls(program, cx.symbols, cx.div_overflow);
+    tr!("   ✅ 在编译期求值了 {} 个调用", folded)
+}
+
+fn run_gc_functions(program: &mut Program, cx: &PassContext) -> String {
+    match call_graph::gc_functions(program, &cx.exported) {
+        Ok(removed) if removed.is_empty() => tr!("   ✅ 没有不可达的函数。"),
+        Ok(removed) => tr!(
+            "   ✅ 已删除 {} 个函数: {}",
+            removed.len(),
+            removed
+                .iter()
+                .map(|name| name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Err(e) => tr!("   警告: {}，跳过。", e),
+    }
+}
+
+fn run_block_layout(program: &mut Program, _cx: &PassContext) -> String {
+    let removed: usize = program
+        .functions
+        .iter_mut()
+        .map(block_layout::layout_function)
+        .sum();
+    tr!("   ✅ 删除了 {} 条跳转指令", removed)
+}
@@ -0,0 +1,410 @@
+// src/backend/snapshot_tests.rs
+
+//! 后端快照测试：每个测试手工构造一个小的 TACKY 程序，直接送入
+//! `AssemblyGenerator` + `CodeGenerator`，并将输出的汇编文本与
+//! `snapshots/` 目录下保存的快照比较。
+//!
+//! 修改后端（例如指令修复规则）后如果快照发生变化，用 `cargo insta review`
+//! 检查差异并确认。
+
+use crate::backend::asm_verify;
+use crate::backend::assembly_ast_gen::{AssemblyGenerator, IntDivOverflow};
+use crate::backend::code_gen::{AsmSyntax, CodeGenerator};
+use crate::backend::tacky_ir::{self, BinaryOp, Function, Instruction, Program, UnaryOp, Value};
+use crate::frontend::type_checking::{CType, IdentifierAttrs, SymbolInfo, SymbolTable};
+
+fn var(name: &str) -> Value {
+    Value::Var(name.into())
+}
+
+/// 把单个函数编译成汇编文本。
+fn emit_with_tables(params: &[&str], body: Vec<Instruction>, tables: &SymbolTable) -> String {
+    emit_with_syntax(params, body, tables, AsmSyntax::Att)
+}
+
+fn emit_with_syntax(
+    params: &[&str],
+    body: Vec<Instruction>,
+    tables: &SymbolTable,
+    syntax: AsmSyntax,
+) -> String {
+    emit_with_generator(params, body, AssemblyGenerator::new(tables), tables, syntax)
+}
+
+fn emit_with_generator(
+    params: &[&str],
+    body: Vec<Instruction>,
+    mut generator: AssemblyGenerator,
+    tables: &SymbolTable,
+    syntax: AsmSyntax,
+) -> String {
+    let program = Program {
+        functions: vec![Function {
+            name: "f".into(),
+            params: params.iter().map(|&p| p.into()).collect(),
+            body,
+        }],
+    };
+    let asm = generator.generate(&program).expect("汇编生成失败");
+    asm_verify::verify_program(&asm).expect("汇编一致性检查失败");
+    let mut out = Vec::new();
+    CodeGenerator::new(tables)
+        .with_syntax(syntax)
+        .generate_program(&asm, &mut out)
+        .expect("汇编发射失败");
+    String::from_utf8(out).unwrap()
+}
+
+fn emit(params: &[&str], body: Vec<Instruction>) -> String {
+    emit_with_tables(params, body, &SymbolTable::new())
+}
+
+fn binary(op: BinaryOp) -> String {
+    emit(
+        &["a", "b"],
+        vec![
+            Instruction::Binary {
+                op,
+                src1: var("a"),
+                src2: var("b"),
+                dst: var("t"),
+            },
+            Instruction::Return(var("t")),
+        ],
+    )
+}
+
+#[test]
+fn return_constant() {
+    insta::assert_snapshot!(emit(&[], vec![Instruction::Return(Value::Constant(42))]));
+}
+
+#[test]
+fn copy() {
+    insta::assert_snapshot!(emit(
+        &["a"],
+        vec![
+            Instruction::Copy {
+                src: var("a"),
+                dst: var("b"),
+            },
+            Instruction::Return(var("b")),
+        ]
+    ));
+}
+
+#[test]
+fn unary_ops() {
+    let body = [UnaryOp::Negate, UnaryOp::Complement, UnaryOp::Not]
+        .into_iter()
+        .enumerate()
+        .map(|(i, op)| Instruction::Unary {
+            op,
+            src: var("a"),
+            dst: var(&format!("t{}", i)),
+        })
+        .chain([Instruction::Return(var("t0"))])
+        .collect();
+    insta::assert_snapshot!(emit(&["a"], body));
+}
+
+#[test]
+fn binary_add() {
+    insta::assert_snapshot!(binary(BinaryOp::Add));
+}
+
+#[test]
+fn binary_subtract() {
+    insta::assert_snapshot!(binary(BinaryOp::Subtract));
+}
+
+#[test]
+fn binary_multiply() {
+    insta::assert_snapshot!(binary(BinaryOp::Multiply));
+}
+
+#[test]
+fn binary_divide() {
+    insta::assert_snapshot!(binary(BinaryOp::Divide));
+}
+
+#[test]
+fn binary_remainder() {
+    insta::assert_snapshot!(binary(BinaryOp::Remainder));
+}
+
+#[test]
+fn divide_by_constant() {
+    insta::assert_snapshot!(emit(
+        &["a"],
+        vec![
+            Instruction::Binary {
+                op: BinaryOp::Divide,
+                src1: var("a"),
+                src2: Value::Constant(3),
+                dst: var("t"),
+            },
+            Instruction::Return(var("t")),
+        ]
+    ));
+}
+
+/// `--int-div-overflow wrap` 下的除法和取余。
+fn wrapping_division(op: BinaryOp, divisor: Value) -> String {
+    let tables = SymbolTable::new();
+    emit_with_generator(
+        &["a", "b"],
+        vec![
+            Instruction::Binary {
+                op,
+                src1: var("a"),
+                src2: divisor,
+                dst: var("t"),
+            },
+            Instruction::Return(var("t")),
+        ],
+        AssemblyGenerator::new(&tables).with_div_overflow(IntDivOverflow::Wrap),
+        &tables,
+        AsmSyntax::Att,
+    )
+}
+
+#[test]
+fn wrapping_divide() {
+    insta::assert_snapshot!(wrapping_division(BinaryOp::Divide, var("b")));
+}
+
+#[test]
+fn wrapping_remainder() {
+    insta::assert_snapshot!(wrapping_division(BinaryOp::Remainder, var("b")));
+}
+
+#[test]
+fn wrapping_divide_by_minus_one() {
+    // 除数是常量时不需要运行时检查
+    insta::assert_snapshot!(wrapping_division(BinaryOp::Divide, Value::Constant(-1)));
+}
+
+#[test]
+fn no_red_zone() {
+    // `-mno-red-zone`：叶子函数也用 subq 分配局部变量
+    let tables = SymbolTable::new();
+    insta::assert_snapshot!(emit_with_generator(
+        &["a"],
+        vec![
+            Instruction::Unary {
+                op: UnaryOp::Negate,
+                src: var("a"),
+                dst: var("t"),
+            },
+            Instruction::Return(var("t")),
+        ],
+        AssemblyGenerator::new(&tables).with_red_zone(false),
+        &tables,
+        AsmSyntax::Att,
+    ));
+}
+
+#[test]
+fn stack_slots_in_first_use_order() {
+    // 栈槽按伪寄存器第一次出现的顺序分配，而不是按名字排序：`tmp10` 在 `tmp2` 之前
+    insta::assert_snapshot!(emit(
+        &[],
+        vec![
+            Instruction::FunctionCall {
+                name: "g".into(),
+                args: Vec::new(),
+                dst: Some(var("tmp10")),
+            },
+            Instruction::FunctionCall {
+                name: "g".into(),
+                args: Vec::new(),
+                dst: Some(var("tmp2")),
+            },
+            Instruction::Binary {
+                op: BinaryOp::Subtract,
+                src1: var("tmp10"),
+                src2: var("tmp2"),
+                dst: var("t"),
+            },
+            Instruction::Return(var("t")),
+        ]
+    ));
+}
+
+#[test]
+fn binary_relational() {
+    let body = [
+        BinaryOp::EqualEqual,
+        BinaryOp::BangEqual,
+        BinaryOp::Greater,
+        BinaryOp::GreaterEqual,
+        BinaryOp::Less,
+        BinaryOp::LessEqual,
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, op)| Instruction::Binary {
+        op,
+        src1: var("a"),
+        src2: var("b"),
+        dst: var(&format!("t{}", i)),
+    })
+    .chain([Instruction::Return(var("t0"))])
+    .collect();
+    insta::assert_snapshot!(emit(&["a", "b"], body));
+}
+
+#[test]
+fn jumps() {
+    insta::assert_snapshot!(emit(
+        &["a"],
+        vec![
+            Instruction::JumpIfZero {
+                condition: var("a"),
+                target: "else.0".to_string(),
+            },
+            Instruction::JumpIfNotZero {
+                condition: var("a"),
+                target: "end.1".to_string(),
+            },
+            Instruction::Jump("end.1".to_string()),
+            Instruction::Label("else.0".to_string()),
+            Instruction::Return(Value::Constant(0)),
+            Instruction::Label("end.1".to_string()),
+            Instruction::Return(Value::Constant(1)),
+        ]
+    ));
+}
+
+#[test]
+fn call_register_args() {
+    insta::assert_snapshot!(emit(
+        &["a"],
+        vec![
+            Instruction::FunctionCall {
+                name: "g".into(),
+                args: vec![var("a"), Value::Constant(2)],
+                dst: Some(var("t")),
+            },
+            Instruction::Return(var("t")),
+        ]
+    ));
+}
+
+#[test]
+fn call_stack_args() {
+    // 7 个参数：第 7 个通过栈传递，并需要 8 字节的对齐填充
+    let args = (0..6).map(Value::Constant).chain([var("a")]).collect();
+    insta::assert_snapshot!(emit(
+        &["a"],
+        vec![
+            Instruction::FunctionCall {
+                name: "g".into(),
+                args,
+                dst: Some(var("t")),
+            },
+            Instruction::Return(var("t")),
+        ]
+    ));
+}
+
+#[test]
+fn stack_params() {
+    let params = ["p0", "p1", "p2", "p3", "p4", "p5", "p6", "p7"];
+    insta::assert_snapshot!(emit(&params, vec![Instruction::Return(var("p7"))]));
+}
+
+#[test]
+fn bool_extend_and_truncate() {
+    let local = |tpye| SymbolInfo {
+        tpye,
+        identifier_attrs: IdentifierAttrs::LocalAttr,
+        span: None,
+        volatile: false,
+    };
+    let tables = SymbolTable::from([
+        ("b".into(), local(CType::Bool)),
+        ("i".into(), local(CType::Int)),
+    ]);
+    insta::assert_snapshot!(emit_with_tables(
+        &["b"],
+        vec![
+            Instruction::ZeroExtend {
+                src: var("b"),
+                dst: var("i"),
+            },
+            Instruction::Truncate {
+                src: Value::Constant(1),
+                dst: var("b"),
+            },
+            Instruction::Return(var("i")),
+        ],
+        &tables
+    ));
+}
+
+#[test]
+fn intel_syntax() {
+    // 覆盖内存/寄存器/立即数操作数、字节操作数、栈参数和栈指针调整
+    let args = (0..6).map(Value::Constant).chain([var("a")]).collect();
+    insta::assert_snapshot!(emit_with_syntax(
+        &["a", "b"],
+        vec![
+            Instruction::Binary {
+                op: BinaryOp::Less,
+                src1: var("a"),
+                src2: var("b"),
+                dst: var("c"),
+            },
+            Instruction::FunctionCall {
+                name: "g".into(),
+                args,
+                dst: Some(var("t")),
+            },
+            Instruction::Return(var("t")),
+        ],
+        &SymbolTable::new(),
+        AsmSyntax::Intel
+    ));
+}
+
+#[test]
+fn tacky_verifier_rejects_malformed_functions() {
+    let verify = |body: Vec<Instruction>| {
+        let program = Program {
+            functions: vec![Function {
+                name: "f".into(),
+                params: vec!["a".into()],
+                body,
+            }],
+        };
+        tacky_ir::verify(&program, &SymbolTable::new())
+    };
+    assert_eq!(
+        verify(vec![
+            Instruction::Copy {
+                src: var("a"),
+                dst: var("t"),
+            },
+            Instruction::Return(var("t")),
+        ]),
+        Ok(())
+    );
+    let errors = [
+        verify(vec![
+            Instruction::Label("l".to_string()),
+            Instruction::Label("l".to_string()),
+            Instruction::Return(var("a")),
+        ]),
+        verify(vec![Instruction::Jump("missing".to_string())]),
+        verify(vec![Instruction::Return(var("undefined"))]),
+        verify(vec![
+            Instruction::Return(var("a")),
+            Instruction::Return(Value::Constant(0)),
+        ]),
+    ];
+    for error in errors {
+        assert!(error.is_err_and(|e| e.contains("ICE")));
+    }
+}
@@ -0,0 +1,157 @@
+// src/backend/stack_usage.rs
+
+//! **栈使用分析 (`--report-stack-usage`)**
+//!
+//! 在栈布局完成后，计算每个函数的栈帧大小，并基于调用图估算从该函数开始的
+//! 最坏情况栈深度。适合需要预估栈空间的嵌入式风格用户。
+//!
+//! -   **帧大小** = 返回地址和保存的 `%rbp` (16 字节) + 局部变量区 + 调用时最多额外占用的
+//!     空间（对齐填充和通过栈传递的参数）。
+//! -   **最坏情况** = 自身帧大小 + 所有被调函数中最坏情况的最大值。
+//!     调用图中存在环（直接或间接递归）时，最坏情况是无界的。
+//!     调用本程序中未定义的（外部）函数时，估算值不包含外部函数的栈使用。
+
+use std::collections::{HashMap, HashSet};
+
+use crate::backend::assembly_ast::{Function, Instruction, Program};
+use crate::common::Symbol;
+use crate::i18n::tr;
+
+/// 返回地址 (8 字节)
+const RETURN_ADDRESS: i64 = 8;
+/// 函数序言中保存的 `%rbp`（省略栈帧的函数没有）
+const SAVED_FRAME_POINTER: i64 = 8;
+
+/// 单个函数的栈使用情况。
+#[derive(Debug)]
+pub struct StackUsage {
+    pub name: Symbol,
+    pub frame_size: i64,
+    /// 最坏情况栈深度；`None` 表示因递归而无界
+    pub worst_case: Option<i64>,
+    /// 调用链上是否有外部函数（其栈使用未计入）
+    pub calls_external: bool,
+}
+
+/// 分析整个程序的栈使用情况，结果按函数在程序中的顺序排列。
+pub fn analyze(program: &Program) -> Vec<StackUsage> {
+    let functions: HashMap<Symbol, &Function> =
+        program.functions.iter().map(|f| (f.name, f)).collect();
+    let mut analyzer = Analyzer {
+        functions,
+        memo: HashMap::new(),
+        visiting: HashSet::new(),
+    };
+    program
+        .functions
+        .iter()
+        .map(|f| {
+            let (worst_case, calls_external) = analyzer.worst_case(f.name);
+            StackUsage {
+                name: f.name,
+                frame_size: frame_size(f),
+                worst_case,
+                calls_external,
+            }
+        })
+        .collect()
+}
+
+/// 打印每个函数的栈使用表。
+pub fn print_report(usages: &[StackUsage]) {
+    println!("{}", tr!("--- 栈使用报告 ---"));
+    println!(
+        "{:<20} {:>14} {:>16}",
+        "function", "frame(bytes)", "worst-case(bytes)"
+    );
+    for u in usages {
+        let worst = match u.worst_case {
+            None => tr!("无界 (递归)"),
+            Some(w) if u.calls_external => tr!("{} (不含外部函数)", w),
+            Some(w) => w.to_string(),
+        };
+        println!("{:<20} {:>14} {:>16}", u.name, u.frame_size, worst);
+    }
+}
+
+/// 计算函数自身的栈帧大小。
+fn frame_size(function: &Function) -> i64 {
+    // 跟踪函数调用时临时压栈的空间（对齐填充和栈参数），取最大值
+    let mut current: i64 = 0;
+    let mut max_outgoing: i64 = 0;
+    for (i, instruction) in function.instructions.iter().enumerate() {
+        match instruction {
+            // 函数开头的 AllocateStack 是局部变量区，已记录在 stack_size 中
+            Instruction::AllocateStack(_) if i == 0 => {}
+            Instruction::AllocateStack(n) => current += n,
+            Instruction::Push(_) => current += 8,
+            Instruction::DeallocateStack(n) => current -= n,
+            _ => {}
+        }
+        max_outgoing = max_outgoing.max(current);
+    }
+    let saved_rbp = if function.frame_pointer {
+        SAVED_FRAME_POINTER
+    } else {
+        0
+    };
+    RETURN_ADDRESS + saved_rbp + function.stack_size + max_outgoing
+}
+
+fn callees(function: &Function) -> Vec<Symbol> {
+    let mut names: Vec<Symbol> = function
+        .instructions
+        .iter()
+        .filter_map(|i| match i {
+            Instruction::Call(name) => Some(*name),
+            _ => None,
+        })
+        .collect();
+    names.sort_unstable_by_key(|name| name.as_str());
+    names.dedup();
+    names
+}
+
+struct Analyzer<'a> {
+    functions: HashMap<Symbol, &'a Function>,
+    /// 已计算完成的结果
+    memo: HashMap<Symbol, (Option<i64>, bool)>,
+    /// 当前 DFS 路径上的函数，用于检测递归
+    visiting: HashSet<Symbol>,
+}
+
+impl<'a> Analyzer<'a> {
+    /// 返回 (最坏情况栈深度, 是否调用了外部函数)。
+    fn worst_case(&mut self, name: Symbol) -> (Option<i64>, bool) {
+        if let Some(result) = self.memo.get(&name) {
+            return *result;
+        }
+        let Some(function) = self.functions.get(&name).copied() else {
+            // 外部函数：栈使用未知
+            return (Some(0), true);
+        };
+        if !self.visiting.insert(name) {
+            // 回到了 DFS 路径上的函数：存在递归
+            return (None, false);
+        }
+
+        let mut deepest_callee = Some(0);
+        let mut calls_external = false;
+        for callee in callees(function) {
+            let (worst, external) = self.worst_case(callee);
+            calls_external |= external;
+            deepest_callee = match (deepest_callee, worst) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                _ => None,
+            };
+        }
+
+        self.visiting.remove(&name);
+        let result = (
+            deepest_callee.map(|d| frame_size(function) + d),
+            calls_external,
+        );
+        self.memo.insert(name, result);
+        result
+    }
+}
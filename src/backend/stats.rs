@@ -0,0 +1,98 @@
+// src/backend/stats.rs
+
+//! **代码生成统计 (`--stats`)**
+//!
+//! 汇编代码发射完成后，为每个函数打印一行统计：TACKY 指令数、汇编指令数、写出的
+//! 汇编行数和字节数、栈帧大小、溢出到栈上的伪寄存器数以及标签数。长期记录这些数字
+//! 可以跟踪代码生成质量的变化。
+
+use crate::backend::assembly_ast::{self, Instruction};
+use crate::backend::code_gen::EmittedFunction;
+use crate::backend::stack_usage;
+use crate::backend::tacky_ir;
+use crate::common::Symbol;
+use crate::i18n::tr;
+
+/// 单个函数的统计数据。
+#[derive(Debug)]
+pub struct FunctionStats {
+    pub name: Symbol,
+    pub tacky_instructions: usize,
+    /// 汇编 AST 中的指令数（不含标签，也不含发射时加上的序言和尾声）
+    pub asm_instructions: usize,
+    pub asm_lines: usize,
+    pub asm_bytes: usize,
+    /// 栈帧大小，与 `--report-stack-usage` 的口径一致
+    pub frame_size: i64,
+    pub spills: usize,
+    pub labels: usize,
+}
+
+/// 汇总 TACKY IR、汇编 AST 和发射结果中每个函数的统计数据，按汇编程序中的顺序排列。
+pub fn collect(
+    ir_program: &tacky_ir::Program,
+    asm_program: &assembly_ast::Program,
+    emitted: &[EmittedFunction],
+) -> Vec<FunctionStats> {
+    let usages = stack_usage::analyze(asm_program);
+    asm_program
+        .functions
+        .iter()
+        .zip(usages)
+        .map(|(f, usage)| {
+            let labels = f
+                .instructions
+                .iter()
+                .filter(|ins| matches!(ins, Instruction::Label(_)))
+                .count();
+            let emitted = emitted.iter().find(|e| e.name == f.name);
+            FunctionStats {
+                name: f.name,
+                tacky_instructions: ir_program
+                    .functions
+                    .iter()
+                    .find(|t| t.name == f.name)
+                    .map_or(0, |t| t.body.len()),
+                asm_instructions: f.instructions.len() - labels,
+                asm_lines: emitted.map_or(0, |e| e.lines),
+                asm_bytes: emitted.map_or(0, |e| e.bytes),
+                frame_size: usage.frame_size,
+                spills: f.spills,
+                labels,
+            }
+        })
+        .collect()
+}
+
+/// 打印统计表，最后一行是所有函数的合计。
+pub fn print_report(stats: &[FunctionStats]) {
+    println!("{}", tr!("--- 代码生成统计 ---"));
+    println!(
+        "{:<20} {:>8} {:>8} {:>8} {:>10} {:>8} {:>8} {:>8}",
+        "function", "tacky", "asm", "lines", "bytes", "frame", "spills", "labels"
+    );
+    for s in stats {
+        println!(
+            "{:<20} {:>8} {:>8} {:>8} {:>10} {:>8} {:>8} {:>8}",
+            s.name,
+            s.tacky_instructions,
+            s.asm_instructions,
+            s.asm_lines,
+            s.asm_bytes,
+            s.frame_size,
+            s.spills,
+            s.labels
+        );
+    }
+    println!(
+        "{:<20} {:>8} {:>8} {:>8} {:>10} {:>8} {:>8} {:>8}",
+        "total",
+        stats.iter().map(|s| s.tacky_instructions).sum::<usize>(),
+        stats.iter().map(|s| s.asm_instructions).sum::<usize>(),
+        stats.iter().map(|s| s.asm_lines).sum::<usize>(),
+        stats.iter().map(|s| s.asm_bytes).sum::<usize>(),
+        stats.iter().map(|s| s.frame_size).sum::<i64>(),
+        stats.iter().map(|s| s.spills).sum::<usize>(),
+        stats.iter().map(|s| s.labels).sum::<usize>()
+    );
+}
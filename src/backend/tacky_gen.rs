@@ -1,58 +1,176 @@
+use std::collections::HashSet;
+
 use crate::UniqueNameGenerator;
+use crate::backend::dead_code;
 use crate::backend::tacky_ir::*;
-use crate::frontend::c_ast::{self, BlockItem};
-const CONTINUE_LABEL: &str = "continue.";
-const BREAK_LABEL: &str = "break.";
+use crate::backend::temp_reuse;
+use crate::common::{ResourceLimits, Symbol};
+use crate::diagnostics::Diagnostic;
+use crate::frontend::c_ast::{self, AstArena, BlockItem, ExprId, LoopLabels, StmtId};
+use crate::frontend::conversions;
+use crate::frontend::type_checking::{CType, IdentifierAttrs, SymbolTable, TypedProgram};
+use crate::i18n::tr;
 
 #[derive(Debug)]
 pub struct TackyGenerator<'a> {
     name_gen: &'a mut UniqueNameGenerator,
-}
-
-// A helper enum to make the short-circuiting logic more readable.
-enum ShortCircuitJump {
-    OnZero,
-    OnNotZero,
+    /// 类型检查阶段产生的符号表，用于查询变量和函数的类型。
+    symbols: &'a SymbolTable,
+    /// 当前正在生成的函数的返回类型。
+    current_ret_type: CType,
+    /// 每个函数允许生成的 TACKY 指令数量上限。
+    max_instructions: usize,
+    /// 是否报告控制流到达非 `main` 函数末尾的警告 (`-Wreturn-type`)
+    warn_return_type: bool,
+    warnings: Vec<Diagnostic>,
+    /// 是否合并活跃区间不重叠的临时变量（`--no-reuse-temps` 关闭）
+    reuse_temps: bool,
+    /// 当前函数中生成的临时变量
+    temps: HashSet<Symbol>,
+    /// 正在翻译的程序的 AST 节点，由 `generate_tacky` 设置
+    arena: Option<&'a AstArena>,
 }
 
 impl<'a> TackyGenerator<'a> {
-    pub fn new(g: &'a mut UniqueNameGenerator) -> Self {
-        TackyGenerator { name_gen: g }
+    pub fn new(g: &'a mut UniqueNameGenerator, symbols: &'a SymbolTable) -> Self {
+        TackyGenerator {
+            name_gen: g,
+            symbols,
+            current_ret_type: CType::Int,
+            max_instructions: ResourceLimits::default().max_tacky_instructions,
+            warn_return_type: false,
+            warnings: Vec::new(),
+            reuse_temps: true,
+            temps: HashSet::new(),
+            arena: None,
+        }
+    }
+
+    pub fn with_max_instructions(mut self, max_instructions: usize) -> Self {
+        self.max_instructions = max_instructions;
+        self
+    }
+
+    pub fn with_temp_reuse(mut self, enabled: bool) -> Self {
+        self.reuse_temps = enabled;
+        self
+    }
+
+    pub fn with_return_type_warnings(mut self, enabled: bool) -> Self {
+        self.warn_return_type = enabled;
+        self
+    }
+
+    pub fn generate_tacky(
+        &mut self,
+        c_ast: &'a TypedProgram,
+    ) -> Result<(Program, Vec<Diagnostic>), Diagnostic> {
+        let mut tacky_functions = Vec::new();
+        self.arena = Some(&c_ast.arena);
+
+        // 遍历所有顶层声明
+        for decl in &c_ast.declarations {
+            // 关键：只处理有函数体的函数定义
+            if let c_ast::Declaration::Fun(func_decl) = decl
+                && let Some(body_block) = &func_decl.body
+            {
+                self.current_ret_type = CType::from(func_decl.ret_type);
+                // 1. 生成函数体的所有指令
+                self.name_gen.enter_function(func_decl.name);
+                let instructions = self.generate_block(body_block)?;
+                self.name_gen.exit_function();
+
+                // 2. 删除 return、跳转和 noreturn 函数调用之后的不可达代码
+                let is_noreturn = |name| self.is_noreturn(name);
+                let mut instructions = dead_code::remove_unreachable(instructions, is_noreturn);
+
+                // 3. 确保函数总有返回值
+                // 控制流能到达函数末尾时，添加 return 0。对 main 这是标准规定的行为
+                // (C99 5.1.2.2.3)，`int main(void) {}` 返回 0；对其他函数，只有调用者使用返回值
+                // 时才是未定义行为 (C11 6.9.1p12)，同样返回 0 让生成的代码总是良定义的，
+                // 是否提示由 -Wreturn-type 决定。
+                if dead_code::falls_through(&instructions, is_noreturn) {
+                    // noreturn 函数本来就不应返回
+                    if self.warn_return_type
+                        && func_decl.name != "main"
+                        && !self.is_noreturn(func_decl.name)
+                    {
+                        self.warnings.push(
+                            Diagnostic::warning(tr!(
+                                "控制流到达非 void 函数 '{}' 的末尾",
+                                func_decl.name
+                            ))
+                            .with_code("return-type")
+                            .with_span(func_decl.span),
+                        );
+                    }
+                    instructions.push(Instruction::Return(Value::Constant(0)));
+                }
+                if self.reuse_temps {
+                    temp_reuse::reuse_temps(&mut instructions, &self.temps);
+                }
+                self.temps.clear();
+                if instructions.len() > self.max_instructions {
+                    return Err(Diagnostic::error(tr!(
+                        "超出资源限制: 函数 '{}' 生成了 {} 条 TACKY 指令，超过上限 {} (--max-tacky-instructions)",
+                        func_decl.name,
+                        instructions.len(),
+                        self.max_instructions
+                    ))
+                    .with_code("limit-exceeded")
+                    .with_span(func_decl.span));
+                }
+
+                // 4. 构建 TACKY Function
+                tacky_functions.push(Function {
+                    name: func_decl.name,
+                    params: func_decl.parameters.clone(),
+                    body: instructions,
+                });
+            }
+            // 函数声明（原型）和文件作用域变量不产生函数体，直接忽略。
+        }
+
+        Ok((
+            Program {
+                functions: tacky_functions,
+            },
+            std::mem::take(&mut self.warnings),
+        ))
+    }
+
+    /// 生成一个临时变量，并记录下来供临时变量复用使用。
+    fn new_temp_var(&mut self, hint: &str) -> Symbol {
+        let name = self.name_gen.new_temp_var(hint);
+        self.temps.insert(name);
+        name
+    }
+
+    fn ast(&self) -> &'a AstArena {
+        self.arena.expect("只能在 generate_tacky 中访问 AST 节点")
+    }
+
+    fn exp(&self, id: ExprId) -> &'a c_ast::Expression {
+        &self.ast()[id]
+    }
+
+    fn stmt(&self, id: StmtId) -> &'a c_ast::Statement {
+        &self.ast()[id]
+    }
+
+    /// 表达式是否有副作用；读 `volatile` 变量也算副作用，不能省略。
+    fn has_side_effects(&self, exp: ExprId) -> bool {
+        let symbols = self.symbols;
+        self.ast()
+            .has_side_effects(exp, &|name| symbols.is_volatile(name))
     }
 
-    pub fn generate_tacky(&mut self, c_ast: &c_ast::Program) -> Result<Program, String> {
-        // let mut tacky_functions = Vec::new();
-
-        // // 遍历所有顶层声明
-        // for func_decl in &c_ast.declarations {
-        //     // 关键：只处理有函数体的函数定义
-        //     if let Some(body_block) = &func_decl.body {
-        //         // 这是一个函数定义，我们为它生成 TACKY
-
-        //         // 1. 生成函数体的所有指令
-        //         let mut instructions = self.generate_block(body_block)?;
-
-        //         // 2. 确保函数总有返回值
-        //         // 检查最后一条指令是不是 return，如果不是，就添加 return 0
-        //         if !matches!(instructions.last(), Some(Instruction::Return(_))) {
-        //             instructions.push(Instruction::Return(Value::Constant(0)));
-        //         }
-
-        //         // 3. 构建 TACKY Function
-        //         let tacky_func = Function {
-        //             name: func_decl.name.clone(),
-        //             params: func_decl.parameters.clone(),
-        //             body: instructions,
-        //         };
-        //         tacky_functions.push(tacky_func);
-        //     }
-        //     // 如果 func_decl.body 是 None，则它是一个函数声明，我们直接忽略它。
-        // }
-
-        // Ok(Program {
-        //     functions: tacky_functions,
-        // })
-        panic!()
+    /// 被调用的函数是否声明为 `_Noreturn` / `__attribute__((noreturn))`。
+    fn is_noreturn(&self, name: Symbol) -> bool {
+        matches!(
+            self.symbols.get(name).map(|s| &s.identifier_attrs),
+            Some(IdentifierAttrs::FunAttr { noreturn: true, .. })
+        )
     }
 
     // 职责：将一个 AST 块转换成一个扁平的指令列表
@@ -68,7 +186,7 @@ impl<'a> TackyGenerator<'a> {
     fn generate_block_item(&mut self, item: &c_ast::BlockItem) -> Result<Vec<Instruction>, String> {
         match item {
             BlockItem::D(d) => self.generate_tacky_decl(d),
-            BlockItem::S(s) => self.generate_tacky_statement(s),
+            BlockItem::S(s) => self.generate_tacky_statement(*s),
         }
     }
     fn generate_tacky_decl(&mut self, d: &c_ast::Declaration) -> Result<Vec<Instruction>, String> {
@@ -79,27 +197,31 @@ impl<'a> TackyGenerator<'a> {
             c_ast::Declaration::Variable(v) => self.generate_var_tacky(v),
         }
     }
+    /// 块作用域变量声明：初始化表达式的全部指令之后才存入变量，和前后的语句按源码顺序排列。
     fn generate_var_tacky(&mut self, v: &c_ast::VarDecl) -> Result<Vec<Instruction>, String> {
-        if let Some(init_exp) = &v.init {
+        if let Some(init_exp) = v.init {
             // 这是一个带初始化的声明，如 `int x = 5;`
             let (mut instructions, result_value) = self.generate_tacky_exp(init_exp)?;
-            instructions.push(Instruction::Copy {
-                src: result_value,
-                dst: Value::Var(v.name.clone()),
-            });
+            self.emit_store(v.name, init_exp, result_value, &mut instructions);
             Ok(instructions)
         } else {
             // 这是一个无初始化的声明，如 `int x;`，它不产生任何 TACKY 指令。
+            // 遮蔽外层变量的声明在标识符解析时已经改了名，不会影响外层的变量。
             Ok(Vec::new())
         }
     }
     fn generate_forinit(&mut self, init: &c_ast::ForInit) -> Result<Vec<Instruction>, String> {
         match init {
-            c_ast::ForInit::InitDecl(d) => Ok(self.generate_var_tacky(d)?),
+            c_ast::ForInit::InitDecl(decls) => {
+                let mut instructions = Vec::new();
+                for d in decls {
+                    instructions.extend(self.generate_var_tacky(d)?);
+                }
+                Ok(instructions)
+            }
             c_ast::ForInit::InitExp(e) => {
                 if let Some(item) = e {
-                    let (ins, _) = self.generate_tacky_exp(item)?; //？
-                    Ok(ins)
+                    self.generate_tacky_exp_for_effect(*item)
                 } else {
                     Ok(Vec::new())
                 }
@@ -107,13 +229,14 @@ impl<'a> TackyGenerator<'a> {
         }
     }
 
-    fn generate_tacky_statement(
-        &mut self,
-        c_stat: &c_ast::Statement,
-    ) -> Result<Vec<Instruction>, String> {
-        match c_stat {
+    fn generate_tacky_statement(&mut self, c_stat: StmtId) -> Result<Vec<Instruction>, String> {
+        match self.stmt(c_stat) {
             c_ast::Statement::Return(exp) => {
-                let (mut instructions, result_value) = self.generate_tacky_exp(exp)?;
+                let (mut instructions, result_value) = self.generate_tacky_exp(*exp)?;
+                let result_value = match self.current_ret_type {
+                    CType::Bool => self.convert_to_bool(*exp, result_value, &mut instructions),
+                    _ => result_value,
+                };
                 instructions.push(Instruction::Return(result_value));
                 Ok(instructions)
             }
@@ -123,8 +246,7 @@ impl<'a> TackyGenerator<'a> {
             }
             c_ast::Statement::Expression(e) => {
                 //丢弃表达式的值
-                let (instructions, _) = self.generate_tacky_exp(e)?;
-                Ok(instructions)
+                self.generate_tacky_exp_for_effect(*e)
             }
             c_ast::Statement::Compound(b) => Ok(self.generate_block(b)?),
             c_ast::Statement::If {
@@ -137,12 +259,8 @@ impl<'a> TackyGenerator<'a> {
 
                 let mut instructions = Vec::new();
 
-                // --- 1. 条件部分 (公共逻辑) ---
-                // 首先，且只生成并执行【条件】表达式的指令。
-                let (cond_instrs, cond_val) = self.generate_tacky_exp(condition)?;
-                instructions.extend(cond_instrs);
-
-                // --- 2. 根据是否存在 else 分支，构建不同的控制流 ---
+                // --- 根据是否存在 else 分支，构建不同的控制流 ---
+                // 条件部分直接生成为跳转代码（见 `generate_branch`），不计算 0/1 值。
                 match else_stmt {
                     // Case 1: if (condition) { then_stmt }
                     None => {
@@ -150,13 +268,10 @@ impl<'a> TackyGenerator<'a> {
                         let end_label = self.name_gen.new_label("end");
 
                         // 如果条件为假(0)，则跳过整个 then 块。
-                        instructions.push(Instruction::JumpIfZero {
-                            condition: cond_val,
-                            target: end_label.clone(),
-                        });
+                        instructions.extend(self.generate_branch(*condition, false, &end_label)?);
 
                         // 生成并添加 then 块的指令。
-                        let then_instrs = self.generate_tacky_statement(then_stmt)?;
+                        let then_instrs = self.generate_tacky_statement(*then_stmt)?;
                         instructions.extend(then_instrs);
 
                         // 放置结束标签。
@@ -170,14 +285,15 @@ impl<'a> TackyGenerator<'a> {
                         let end_label = self.name_gen.new_label("end");
 
                         // 如果条件为假(0)，则跳转到 else 块。
-                        instructions.push(Instruction::JumpIfZero {
-                            condition: cond_val,
-                            target: else_label.clone(),
-                        });
+                        instructions.extend(self.generate_branch(
+                            *condition,
+                            false,
+                            &else_label,
+                        )?);
 
                         // [Then 分支]
                         // 生成并添加 then 块的指令。
-                        let then_instrs = self.generate_tacky_statement(then_stmt)?;
+                        let then_instrs = self.generate_tacky_statement(*then_stmt)?;
                         instructions.extend(then_instrs);
                         // then 块执行完毕后，必须无条件跳过 else 块。
                         instructions.push(Instruction::Jump(end_label.clone()));
@@ -186,7 +302,7 @@ impl<'a> TackyGenerator<'a> {
                         // 放置 else 块的入口标签。
                         instructions.push(Instruction::Label(else_label));
                         // 生成并添加 else 块的指令。
-                        let else_instrs = self.generate_tacky_statement(else_s)?;
+                        let else_instrs = self.generate_tacky_statement(*else_s)?;
                         instructions.extend(else_instrs);
 
                         // [结尾]
@@ -196,31 +312,44 @@ impl<'a> TackyGenerator<'a> {
                 }
                 Ok(instructions)
             }
-            c_ast::Statement::Break(n) => {
-                Ok(vec![Instruction::Jump(format!("{}{}", BREAK_LABEL, n))])
+            c_ast::Statement::Break(label) => Ok(vec![Instruction::Jump(
+                loop_labels(label)?.break_label.clone(),
+            )]),
+            c_ast::Statement::Continue(label) => Ok(vec![Instruction::Jump(
+                loop_labels(label)?.continue_label.clone(),
+            )]),
+            // 循环标签解析已经把标签改成了全局唯一的名字
+            c_ast::Statement::Labeled { label, stmt } => {
+                let mut instructions = vec![Instruction::Label(label.to_string())];
+                instructions.extend(self.generate_tacky_statement(*stmt)?);
+                Ok(instructions)
             }
-            c_ast::Statement::Continue(n) => {
-                Ok(vec![Instruction::Jump(format!("{}{}", CONTINUE_LABEL, n))])
+            c_ast::Statement::Case { stmt, label, .. }
+            | c_ast::Statement::Default { stmt, label } => {
+                let label = label.clone().ok_or_else(|| {
+                    tr!("内部编译器错误 (ICE): case 或 default 语句没有跳转标签，它可能不在 switch 语句中")
+                })?;
+                let mut instructions = vec![Instruction::Label(label)];
+                instructions.extend(self.generate_tacky_statement(*stmt)?);
+                Ok(instructions)
             }
             c_ast::Statement::DoWhile {
                 body,
                 condition,
                 label,
             } => {
-                let start_label = self.name_gen.new_label("start");
-                let continue_label = format!("{}{}", CONTINUE_LABEL, label.clone().unwrap());
-                let break_label = format!("{}{}", BREAK_LABEL, label.clone().unwrap());
+                let LoopLabels {
+                    start_label,
+                    continue_label,
+                    break_label,
+                    ..
+                } = loop_labels(label)?.clone();
                 let mut instructions = Vec::new();
                 instructions.push(Instruction::Label(start_label.clone()));
-                let body_instrs = self.generate_tacky_statement(&body)?;
+                let body_instrs = self.generate_tacky_statement(*body)?;
                 instructions.extend(body_instrs);
                 instructions.push(Instruction::Label(continue_label));
-                let (cond_instrs, cond_val) = self.generate_tacky_exp(condition)?;
-                instructions.extend(cond_instrs);
-                instructions.push(Instruction::JumpIfNotZero {
-                    condition: cond_val,
-                    target: start_label,
-                });
+                instructions.extend(self.generate_branch(*condition, true, &start_label)?);
                 instructions.push(Instruction::Label(break_label));
 
                 Ok(instructions)
@@ -230,17 +359,15 @@ impl<'a> TackyGenerator<'a> {
                 body,
                 label,
             } => {
-                let continue_label = format!("{}{}", CONTINUE_LABEL, label.clone().unwrap());
-                let break_label = format!("{}{}", BREAK_LABEL, label.clone().unwrap());
+                let LoopLabels {
+                    continue_label,
+                    break_label,
+                    ..
+                } = loop_labels(label)?.clone();
                 let mut instructions = Vec::new();
                 instructions.push(Instruction::Label(continue_label.clone()));
-                let (cond_instrs, cond_val) = self.generate_tacky_exp(condition)?;
-                instructions.extend(cond_instrs);
-                instructions.push(Instruction::JumpIfZero {
-                    condition: cond_val,
-                    target: break_label.clone(),
-                });
-                let body_instrs = self.generate_tacky_statement(&body)?;
+                instructions.extend(self.generate_branch(*condition, false, &break_label)?);
+                let body_instrs = self.generate_tacky_statement(*body)?;
                 instructions.extend(body_instrs);
                 instructions.push(Instruction::Jump(continue_label));
                 instructions.push(Instruction::Label(break_label));
@@ -253,26 +380,25 @@ impl<'a> TackyGenerator<'a> {
                 body,
                 label,
             } => {
-                let start_label = self.name_gen.new_label("start");
-                let continue_label = format!("{}{}", CONTINUE_LABEL, label.clone().unwrap());
-                let break_label = format!("{}{}", BREAK_LABEL, label.clone().unwrap());
+                let LoopLabels {
+                    start_label,
+                    continue_label,
+                    break_label,
+                    ..
+                } = loop_labels(label)?.clone();
                 let mut instructions = Vec::new();
                 let init_instrs = self.generate_forinit(init)?;
                 instructions.extend(init_instrs);
                 instructions.push(Instruction::Label(start_label.clone()));
                 if let Some(c) = condition {
-                    let (cond_instrs, cond_val) = self.generate_tacky_exp(c)?;
-                    instructions.extend(cond_instrs);
-                    instructions.push(Instruction::JumpIfZero {
-                        condition: cond_val,
-                        target: break_label.clone(),
-                    });
+                    instructions.extend(self.generate_branch(*c, false, &break_label)?);
                 }
-                let body_instrs = self.generate_tacky_statement(&body)?;
+                let body_instrs = self.generate_tacky_statement(*body)?;
                 instructions.extend(body_instrs);
+                // `continue` 跳到这里：先执行 post 表达式，再回到条件判断
                 instructions.push(Instruction::Label(continue_label));
                 if let Some(p) = post {
-                    let (post_instrs, _) = self.generate_tacky_exp(p)?;
+                    let post_instrs = self.generate_tacky_exp_for_effect(*p)?;
                     instructions.extend(post_instrs);
                 }
                 instructions.push(Instruction::Jump(start_label));
@@ -282,86 +408,259 @@ impl<'a> TackyGenerator<'a> {
         }
     }
 
-    /// Generates TACKY IR for short-circuiting binary operators like `&&` and `||`.
+    /// 为值被丢弃的表达式（表达式语句、`for` 的初始化表达式和 post 表达式）生成指令。
+    ///
+    /// `&&`、`||` 和 `?:` 只生成产生副作用所需的控制流，不再为结果创建 0/1 临时变量
+    /// 和对应的赋值、标签。例如 `a && f();` 只生成“`a` 为 0 时跳过调用”。
+    /// 没有副作用的表达式（例如 `a + b;`）不生成任何指令。
+    fn generate_tacky_exp_for_effect(&mut self, exp: ExprId) -> Result<Vec<Instruction>, String> {
+        if !self.has_side_effects(exp) {
+            return Ok(Vec::new());
+        }
+        match self.exp(exp) {
+            c_ast::Expression::Binary {
+                op: op @ (c_ast::BinaryOp::And | c_ast::BinaryOp::Or),
+                left,
+                right,
+            } => {
+                // `&&` 在左边为 0 时跳过右边，`||` 在左边非 0 时跳过右边
+                let skip_if = matches!(op, c_ast::BinaryOp::Or);
+                // 左边是常量时右边要么一定求值，要么一定不求值，不需要跳转
+                if let Some(l) = self.constant_truth(*left) {
+                    return if l == skip_if {
+                        Ok(Vec::new())
+                    } else {
+                        self.generate_tacky_exp_for_effect(*right)
+                    };
+                }
+                // 右边没有副作用（例如 `f() || 1;`）时只需要求值左边
+                if !self.has_side_effects(*right) {
+                    return self.generate_tacky_exp_for_effect(*left);
+                }
+                let end_label = self.name_gen.new_label("end");
+                let mut instructions = self.generate_branch(*left, skip_if, &end_label)?;
+                instructions.extend(self.generate_tacky_exp_for_effect(*right)?);
+                instructions.push(Instruction::Label(end_label));
+                Ok(instructions)
+            }
+            c_ast::Expression::Conditional {
+                condition,
+                left,
+                right,
+            } => {
+                let false_label = self.name_gen.new_label("false");
+                let end_label = self.name_gen.new_label("end");
+                let mut instructions = self.generate_branch(*condition, false, &false_label)?;
+                instructions.extend(self.generate_tacky_exp_for_effect(*left)?);
+                instructions.push(Instruction::Jump(end_label.clone()));
+                instructions.push(Instruction::Label(false_label));
+                instructions.extend(self.generate_tacky_exp_for_effect(*right)?);
+                instructions.push(Instruction::Label(end_label));
+                Ok(instructions)
+            }
+            c_ast::Expression::Assignment { left, right } => {
+                let (instructions, _) = self.generate_assignment(*left, *right, false)?;
+                Ok(instructions)
+            }
+            // 返回值被丢弃的调用不分配临时变量
+            c_ast::Expression::FuncCall { name, args } => {
+                let (mut instructions, arg_values) = self.generate_call_args(*name, args)?;
+                instructions.push(Instruction::FunctionCall {
+                    name: *name,
+                    args: arg_values,
+                    dst: None,
+                });
+                Ok(instructions)
+            }
+            // 单独的 `volatile` 变量：生成一次读取
+            c_ast::Expression::Var(name) => {
+                let dst = Value::Var(self.new_temp_var("volatile"));
+                Ok(vec![Instruction::Copy {
+                    src: Value::Var(*name),
+                    dst,
+                }])
+            }
+            _ => {
+                let (instructions, _) = self.generate_tacky_exp(exp)?;
+                Ok(instructions)
+            }
+        }
+    }
+
+    /// 为 `&&` 和 `||` 生成求值为 0/1 的指令。
     ///
-    /// # Arguments
-    /// * `left`, `right` - The left and right hand side expressions.
-    /// * `jump_type` - The condition on which to short-circuit.
-    /// * `short_circuit_val` - The value to assign to the result if we short-circuit.
-    /// * `fall_through_val` - The value to assign to the result if we don't short-circuit.
+    /// 整个 `&&`/`||` 链只分配一个 false 标签和一个 end 标签：条件部分由 `generate_branch`
+    /// 生成跳转代码，条件为假时跳到 false 标签，否则落入结果为 1 的分支。
     fn generate_short_circuit_op(
         &mut self,
-        left: &c_ast::Expression,
-        right: &c_ast::Expression,
-        jump_type: ShortCircuitJump,
-        short_circuit_val: i64,
-        fall_through_val: i64,
+        exp: ExprId,
+        hint: &str,
     ) -> Result<(Vec<Instruction>, Value), String> {
-        // 1. Evaluate left expression
-        let (mut instructions, v1) = self.generate_tacky_exp(left)?;
-
-        // 2. Generate labels
-        let short_circuit_label = self.name_gen.new_label("");
+        if let Some((instructions, value)) = self.fold_logical(exp)? {
+            return Ok((instructions, Value::Constant(value as i64)));
+        }
+        let false_label = self.name_gen.new_label("false");
         let end_label = self.name_gen.new_label("end");
+        let mut instructions = self.generate_branch(exp, false, &false_label)?;
 
-        // 3. Helper function to create the correct jump instruction
-        let make_jump = |condition, target| match jump_type {
-            ShortCircuitJump::OnZero => Instruction::JumpIfZero { condition, target },
-            ShortCircuitJump::OnNotZero => Instruction::JumpIfNotZero { condition, target },
-        };
-
-        // 4. Conditional jump for left expression
-        instructions.push(make_jump(v1, short_circuit_label.clone()));
-
-        // 5. Evaluate right expression
-        let (instrs2, v2) = self.generate_tacky_exp(right)?;
-        instructions.extend(instrs2);
-
-        // 6. Conditional jump for right expression
-        instructions.push(make_jump(v2, short_circuit_label.clone()));
-
-        // 7. Create result variable
-        let result_var = self.name_gen.new_temp_var();
-        let result = Value::Var(result_var);
-
-        // 8. Fall-through case (no short-circuit happened)
+        let result = Value::Var(self.new_temp_var(hint));
         instructions.push(Instruction::Copy {
-            src: Value::Constant(fall_through_val),
+            src: Value::Constant(1),
             dst: result.clone(),
         });
         instructions.push(Instruction::Jump(end_label.clone()));
-
-        // 9. Short-circuit case
-        instructions.push(Instruction::Label(short_circuit_label));
+        instructions.push(Instruction::Label(false_label));
         instructions.push(Instruction::Copy {
-            src: Value::Constant(short_circuit_val),
+            src: Value::Constant(0),
             dst: result.clone(),
         });
-
-        // 10. End label
         instructions.push(Instruction::Label(end_label));
 
         Ok((instructions, result))
     }
 
+    /// 常量表达式的真假；不是常量时返回 `None`。
+    fn constant_truth(&self, exp: ExprId) -> Option<bool> {
+        match self.exp(exp) {
+            c_ast::Expression::Constant(i) => Some(*i != 0),
+            _ => None,
+        }
+    }
+
+    /// 结果在编译期就能确定的 `&&`/`||`，例如 `0 && f()`、`1 || g()` 和 `f() && 0`。
+    ///
+    /// 返回求值必须保留的副作用（`f() && 0` 仍然要调用 `f`）和确定的结果；
+    /// 结果取决于运行时的值时返回 `None`。
+    fn fold_logical(&mut self, exp: ExprId) -> Result<Option<(Vec<Instruction>, bool)>, String> {
+        let c_ast::Expression::Binary {
+            op: op @ (c_ast::BinaryOp::And | c_ast::BinaryOp::Or),
+            left,
+            right,
+        } = self.exp(exp)
+        else {
+            return Ok(None);
+        };
+        // 短路时整个表达式的值：`&&` 为 0，`||` 为 1
+        let short_circuit_value = matches!(op, c_ast::BinaryOp::Or);
+        match (self.constant_truth(*left), self.constant_truth(*right)) {
+            (Some(l), _) if l == short_circuit_value => Ok(Some((Vec::new(), l))),
+            (Some(_), Some(r)) => Ok(Some((Vec::new(), r))),
+            (None, Some(r)) if r == short_circuit_value => {
+                Ok(Some((self.generate_tacky_exp_for_effect(*left)?, r)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// 把 `exp` 作为条件生成“跳转代码”：当 `exp` 的真假等于 `jump_if` 时跳到 `target`，
+    /// 否则落入后续指令。
+    ///
+    /// `&&`、`||`、`!` 和 `?:` 不计算中间的 0/1 值，而是把跳转目标沿着表达式树向下传递，
+    /// 所以 `a || b || c || ...` 这样的长链每个操作数只产生一条条件跳转，
+    /// 整条链最多再需要一个标签。
+    fn generate_branch(
+        &mut self,
+        exp: ExprId,
+        jump_if: bool,
+        target: &str,
+    ) -> Result<Vec<Instruction>, String> {
+        match self.exp(exp) {
+            c_ast::Expression::Binary {
+                op: op @ (c_ast::BinaryOp::And | c_ast::BinaryOp::Or),
+                left,
+                right,
+            } => {
+                if let Some((mut instructions, value)) = self.fold_logical(exp)? {
+                    if value == jump_if {
+                        instructions.push(Instruction::Jump(target.to_string()));
+                    }
+                    return Ok(instructions);
+                }
+                // `&&` 为假（`||` 为真）当且仅当任一操作数为假（真）：两个操作数直接跳到 target
+                let short_circuit_on = matches!(op, c_ast::BinaryOp::Or);
+                // 一边是不会短路的常量时，结果就是另一边的真假
+                if self.constant_truth(*left) == Some(!short_circuit_on) {
+                    return self.generate_branch(*right, jump_if, target);
+                }
+                if self.constant_truth(*right) == Some(!short_circuit_on) {
+                    return self.generate_branch(*left, jump_if, target);
+                }
+                if jump_if == short_circuit_on {
+                    let mut instructions = self.generate_branch(*left, jump_if, target)?;
+                    instructions.extend(self.generate_branch(*right, jump_if, target)?);
+                    Ok(instructions)
+                } else {
+                    // 否则左操作数短路时整个表达式的结果已经确定，跳过右操作数
+                    let skip_label = self.name_gen.new_label("skip");
+                    let mut instructions =
+                        self.generate_branch(*left, short_circuit_on, &skip_label)?;
+                    instructions.extend(self.generate_branch(*right, jump_if, target)?);
+                    instructions.push(Instruction::Label(skip_label));
+                    Ok(instructions)
+                }
+            }
+            c_ast::Expression::Unary {
+                op: c_ast::UnaryOp::Not,
+                exp,
+            } => self.generate_branch(*exp, !jump_if, target),
+            // 两个分支各自作为条件直接跳到 target，不需要保存 `?:` 的结果
+            c_ast::Expression::Conditional {
+                condition,
+                left,
+                right,
+            } => {
+                let else_label = self.name_gen.new_label("else");
+                let end_label = self.name_gen.new_label("end");
+                let mut instructions = self.generate_branch(*condition, false, &else_label)?;
+                instructions.extend(self.generate_branch(*left, jump_if, target)?);
+                instructions.push(Instruction::Jump(end_label.clone()));
+                instructions.push(Instruction::Label(else_label));
+                instructions.extend(self.generate_branch(*right, jump_if, target)?);
+                instructions.push(Instruction::Label(end_label));
+                Ok(instructions)
+            }
+            c_ast::Expression::Constant(i) => Ok(if (*i != 0) == jump_if {
+                vec![Instruction::Jump(target.to_string())]
+            } else {
+                Vec::new()
+            }),
+            _ => {
+                let (mut instructions, value) = self.generate_tacky_exp(exp)?;
+                let target = target.to_string();
+                instructions.push(if jump_if {
+                    Instruction::JumpIfNotZero {
+                        condition: value,
+                        target,
+                    }
+                } else {
+                    Instruction::JumpIfZero {
+                        condition: value,
+                        target,
+                    }
+                });
+                Ok(instructions)
+            }
+        }
+    }
+
     /// 修改后的核心函数
     /// 返回: (生成的指令列表, 表达式结果存放的 Value)
-    fn generate_tacky_exp(
-        &mut self,
-        exp: &c_ast::Expression,
-    ) -> Result<(Vec<Instruction>, Value), String> {
-        match exp {
+    fn generate_tacky_exp(&mut self, exp: ExprId) -> Result<(Vec<Instruction>, Value), String> {
+        match self.exp(exp) {
             c_ast::Expression::Constant(i) => Ok((Vec::new(), Value::Constant(*i))),
 
             c_ast::Expression::Unary { op, exp } => {
-                let (mut instructions, src_value) = self.generate_tacky_exp(exp)?;
-                let dst_var_name = self.name_gen.new_temp_var();
-                let dst_value = Value::Var(dst_var_name);
+                let (mut instructions, src_value) = self.generate_tacky_exp(*exp)?;
                 let tacky_op = match op {
                     c_ast::UnaryOp::Complement => UnaryOp::Complement,
                     c_ast::UnaryOp::Negate => UnaryOp::Negate,
                     c_ast::UnaryOp::Not => UnaryOp::Not,
+                    // 一元加只做整数提升。读取 `_Bool` 变量时已经零扩展为 int，
+                    // 所以操作数的值就是结果。
+                    // 有了带类型的 AST 之后，应把提升建模为显式插入的类型转换节点。
+                    c_ast::UnaryOp::Plus => return Ok((instructions, src_value)),
                 };
+                let dst_value = Value::Var(self.new_temp_var(tacky_op.name()));
                 instructions.push(Instruction::Unary {
                     op: tacky_op,
                     src: src_value,
@@ -370,26 +669,12 @@ impl<'a> TackyGenerator<'a> {
                 Ok((instructions, dst_value))
             }
             c_ast::Expression::Binary { op, left, right } => match op {
-                c_ast::BinaryOp::And => self.generate_short_circuit_op(
-                    left,
-                    right,
-                    ShortCircuitJump::OnZero, // For &&, we short-circuit if a value is 0
-                    0,                        // The result is 0 if we short-circuit
-                    1,                        // The result is 1 if we don't (fall-through)
-                ),
-                c_ast::BinaryOp::Or => self.generate_short_circuit_op(
-                    left,
-                    right,
-                    ShortCircuitJump::OnNotZero, // For ||, we short-circuit if a value is not 0
-                    1,                           // The result is 1 if we short-circuit
-                    0,                           // The result is 0 if we don't (fall-through)
-                ),
+                c_ast::BinaryOp::And => self.generate_short_circuit_op(exp, "and"),
+                c_ast::BinaryOp::Or => self.generate_short_circuit_op(exp, "or"),
                 _ => {
                     // All other binary operators that don't short-circuit
-                    let (mut instructions1, src1_value) = self.generate_tacky_exp(left)?;
-                    let (instructions2, src2_value) = self.generate_tacky_exp(right)?;
-                    let dst_var_name = self.name_gen.new_temp_var();
-                    let dst_value = Value::Var(dst_var_name);
+                    let (mut instructions1, src1_value) = self.generate_tacky_exp(*left)?;
+                    let (instructions2, src2_value) = self.generate_tacky_exp(*right)?;
                     let tacky_op = match op {
                         c_ast::BinaryOp::Add => BinaryOp::Add,
                         c_ast::BinaryOp::Subtract => BinaryOp::Subtract,
@@ -402,8 +687,13 @@ impl<'a> TackyGenerator<'a> {
                         c_ast::BinaryOp::GreaterEqual => BinaryOp::GreaterEqual,
                         c_ast::BinaryOp::Less => BinaryOp::Less,
                         c_ast::BinaryOp::LessEqual => BinaryOp::LessEqual,
-                        _ => unreachable!("Handled by short-circuiting logic"),
+                        c_ast::BinaryOp::And | c_ast::BinaryOp::Or => {
+                            return Err(tr!(
+                                "内部编译器错误 (ICE): 短路运算符没有按短路求值生成代码"
+                            ));
+                        }
                     };
+                    let dst_value = Value::Var(self.new_temp_var(tacky_op.name()));
                     instructions1.extend(instructions2);
                     instructions1.push(Instruction::Binary {
                         op: tacky_op,
@@ -415,46 +705,21 @@ impl<'a> TackyGenerator<'a> {
                 }
             },
             c_ast::Expression::Assignment { left, right } => {
-                // 左侧必须是变量，获取其名称
-                let dest_var_name = if let c_ast::Expression::Var(name) = &**left {
-                    name.clone()
-                } else {
-                    // 在此简化模型中，我们只支持赋值给简单变量
-                    return Err("Assignment to non-variable is not supported.".to_string());
-                };
-                let dest_value = Value::Var(dest_var_name);
-
-                // [优化点] 检查右侧是否是函数调用
-                if let c_ast::Expression::FuncCall { name, args } = &**right {
-                    // 如果是 `var = func(...)`，生成一步到位的 FunCall 指令
-                    let mut all_instructions = Vec::new();
-                    let mut arg_values = Vec::new();
-                    for arg in args {
-                        let (arg_instrs, arg_val) = self.generate_tacky_exp(arg)?;
-                        all_instructions.extend(arg_instrs);
-                        arg_values.push(arg_val);
-                    }
-
-                    all_instructions.push(Instruction::FunctionCall {
-                        name: name.clone(),
-                        args: arg_values,
-                        dst: dest_value.clone(), //直接将结果存入目标变量
-                    });
-
-                    // 赋值表达式的值就是被赋的值
-                    Ok((all_instructions, dest_value))
+                self.generate_assignment(*left, *right, true)
+            }
+            c_ast::Expression::Var(id) => {
+                if is_promoted(&self.var_type(*id)) {
+                    // `_Bool` 在参与运算前提升为 int
+                    let dst = Value::Var(self.new_temp_var("zext"));
+                    let instructions = vec![Instruction::ZeroExtend {
+                        src: Value::Var(*id),
+                        dst: dst.clone(),
+                    }];
+                    Ok((instructions, dst))
                 } else {
-                    // 对于其他赋值，如 a = b + c，使用通用逻辑
-                    let (src_instrs, src_value) = self.generate_tacky_exp(right)?;
-                    let mut instructions = src_instrs;
-                    instructions.push(Instruction::Copy {
-                        src: src_value,
-                        dst: dest_value.clone(),
-                    });
-                    Ok((instructions, dest_value))
+                    Ok((Vec::new(), Value::Var(*id)))
                 }
             }
-            c_ast::Expression::Var(id) => Ok((Vec::new(), Value::Var(id.clone()))),
             c_ast::Expression::Conditional {
                 condition,
                 left,
@@ -466,7 +731,7 @@ impl<'a> TackyGenerator<'a> {
                 // --- 1. 准备阶段 ---
                 // 创建整个表达式所需的共享资源：最终结果的临时变量和跳转标签。
                 // 这部分可以安全地提前完成。
-                let result_val = Value::Var(self.name_gen.new_temp_var());
+                let result_val = Value::Var(self.new_temp_var("cond"));
                 let false_label = self.name_gen.new_label("false");
                 let end_label = self.name_gen.new_label("end");
 
@@ -474,19 +739,13 @@ impl<'a> TackyGenerator<'a> {
 
                 // --- 2. 条件部分 ---
                 // 首先，且只生成并执行【条件】表达式的指令。
-                let (cond_instrs, cond_val) = self.generate_tacky_exp(condition)?;
-                instructions.extend(cond_instrs);
-
-                // 根据条件结果进行跳转。如果为假(0)，则跳过 "then" 分支。
-                instructions.push(Instruction::JumpIfZero {
-                    condition: cond_val,
-                    target: false_label.clone(),
-                });
+                // 如果为假(0)，则跳过 "then" 分支。
+                instructions.extend(self.generate_branch(*condition, false, &false_label)?);
 
                 // --- 3. Then 分支 (当条件为真时执行) ---
                 // 只有在确定要执行 "then" 分支时，才为其生成指令。
                 // 这保证了 `left` 表达式的副作用只在条件为真时发生。
-                let (then_instrs, then_val) = self.generate_tacky_exp(left)?;
+                let (then_instrs, then_val) = self.generate_tacky_exp(*left)?;
                 instructions.extend(then_instrs);
                 instructions.push(Instruction::Copy {
                     src: then_val,
@@ -501,7 +760,7 @@ impl<'a> TackyGenerator<'a> {
 
                 // 只有在确定要执行 "else" 分支时，才为其生成指令。
                 // 这保证了 `right` 表达式的副作用只在条件为假时发生。
-                let (else_instrs, else_val) = self.generate_tacky_exp(right)?;
+                let (else_instrs, else_val) = self.generate_tacky_exp(*right)?;
                 instructions.extend(else_instrs);
                 instructions.push(Instruction::Copy {
                     src: else_val,
@@ -515,26 +774,233 @@ impl<'a> TackyGenerator<'a> {
                 Ok((instructions, result_val))
             }
             c_ast::Expression::FuncCall { name, args } => {
-                // 这个分支现在只处理不作为赋值右值的函数调用
-                // (例如，在表达式语句 `foo();` 中，或者像 `a + foo()` 这样的复杂表达式中)
-                let mut all_instructions = Vec::new();
-                let mut arg_values = Vec::new();
-                for arg in args {
-                    let (arg_instrs, arg_val) = self.generate_tacky_exp(arg)?;
-                    all_instructions.extend(arg_instrs);
-                    arg_values.push(arg_val);
-                }
+                // 这个分支只处理值被使用的函数调用（例如 `a + foo()`），
+                // 表达式语句 `foo();` 和 `x = foo();` 在只求副作用时另行处理
+                let (mut all_instructions, arg_values) = self.generate_call_args(*name, args)?;
 
                 // 结果必须存入一个新的临时变量
-                let dst_temp = Value::Var(self.name_gen.new_temp_var());
+                let dst_temp = Value::Var(self.new_temp_var("call"));
                 all_instructions.push(Instruction::FunctionCall {
-                    name: name.clone(),
+                    name: *name,
                     args: arg_values,
-                    dst: dst_temp.clone(),
+                    dst: Some(dst_temp.clone()),
                 });
 
+                if is_promoted(&self.return_type(*name)) {
+                    // ABI 只保证 `_Bool` 返回值的低 8 位有效
+                    let extended = Value::Var(self.new_temp_var("zext"));
+                    all_instructions.push(Instruction::ZeroExtend {
+                        src: dst_temp,
+                        dst: extended.clone(),
+                    });
+                    return Ok((all_instructions, extended));
+                }
                 Ok((all_instructions, dst_temp))
             }
         }
     }
+
+    /// 从左到右依次求值调用参数，并按被调函数的参数类型做转换。
+    ///
+    /// 每个参数都先落到独立的临时变量中：如果参数值直接引用一个具名变量（如 `f(a, g())`），
+    /// 后面参数的求值（例如一次修改 `a` 的调用）会改变它，因此这种情况下先复制一份。
+    /// 已经是本次求值新建的临时变量（例如 `_Bool` 变量提升或转换的结果）时不再复制。
+    /// 所有参数求值完成后，汇编生成阶段才把它们依次移入参数寄存器。
+    fn generate_call_args(
+        &mut self,
+        name: Symbol,
+        args: &[ExprId],
+    ) -> Result<(Vec<Instruction>, Vec<Value>), String> {
+        let param_types = match self.symbols.get(name).map(|s| &s.tpye) {
+            Some(CType::FunType { params, .. }) => params.clone(),
+            _ => Vec::new(),
+        };
+        let mut instructions = Vec::new();
+        let mut arg_values = Vec::new();
+        for (i, &arg) in args.iter().enumerate() {
+            let (arg_instrs, arg_val) = self.generate_tacky_exp(arg)?;
+            instructions.extend(arg_instrs);
+            let arg_val = match param_types.get(i) {
+                Some(CType::Bool) => self.convert_to_bool(arg, arg_val, &mut instructions),
+                _ => arg_val,
+            };
+            let arg_val = match arg_val {
+                Value::Var(name) if !self.temps.contains(&name) => {
+                    let tmp = Value::Var(self.new_temp_var("arg"));
+                    instructions.push(Instruction::Copy {
+                        src: Value::Var(name),
+                        dst: tmp.clone(),
+                    });
+                    tmp
+                }
+                value => value,
+            };
+            arg_values.push(arg_val);
+        }
+        Ok((instructions, arg_values))
+    }
+
+    /// 为赋值表达式 `left = right` 生成指令。
+    ///
+    /// 赋值表达式的值是转换到左边类型之后存入的值，而不是左边的变量 (C11 6.5.16p3)：
+    /// `want_value` 为真时返回一个常量或临时变量，表达式中之后对左边变量的修改不会影响它，
+    /// 也不会再读一次 `volatile` 变量。只求副作用时不生成这个临时变量。
+    fn generate_assignment(
+        &mut self,
+        left: ExprId,
+        right: ExprId,
+        want_value: bool,
+    ) -> Result<(Vec<Instruction>, Value), String> {
+        // 左侧必须是变量，获取其名称
+        let dest_var_name = if let c_ast::Expression::Var(name) = self.exp(left) {
+            *name
+        } else {
+            // 在此简化模型中，我们只支持赋值给简单变量
+            return Err(tr!("Assignment to non-variable is not supported."));
+        };
+
+        // [优化点] 只求副作用的 `var = func(...)`：目标和返回值都是 int 时不需要类型转换，
+        // 生成一步到位的 FunCall 指令，返回值直接存入目标变量
+        if !want_value
+            && let c_ast::Expression::FuncCall { name, args } = self.exp(right)
+            && self.var_type(dest_var_name) == CType::Int
+            && self.return_type(*name) == CType::Int
+        {
+            let (mut instructions, arg_values) = self.generate_call_args(*name, args)?;
+            let dst = Value::Var(dest_var_name);
+            instructions.push(Instruction::FunctionCall {
+                name: *name,
+                args: arg_values,
+                dst: Some(dst.clone()),
+            });
+            return Ok((instructions, dst));
+        }
+
+        let (mut instructions, src_value) = self.generate_tacky_exp(right)?;
+        // 右边是变量时先复制到临时变量，表达式的值不能随着它一起改变
+        let src_value = match src_value {
+            Value::Var(name) if want_value && !self.temps.contains(&name) => {
+                let tmp = Value::Var(self.new_temp_var("assign"));
+                instructions.push(Instruction::Copy {
+                    src: Value::Var(name),
+                    dst: tmp.clone(),
+                });
+                tmp
+            }
+            value => value,
+        };
+        let result = self.emit_store(dest_var_name, right, src_value, &mut instructions);
+        Ok((instructions, result))
+    }
+
+    /// 把 `value` 存入变量 `name`，按变量类型做必要的转换。
+    /// 返回转换后存入的值。
+    fn emit_store(
+        &mut self,
+        name: Symbol,
+        exp: ExprId,
+        value: Value,
+        instructions: &mut Vec<Instruction>,
+    ) -> Value {
+        let dst = Value::Var(name);
+        match self.var_type(name) {
+            CType::Bool => {
+                let converted = self.convert_to_bool(exp, value, instructions);
+                instructions.push(Instruction::Truncate {
+                    src: converted.clone(),
+                    dst,
+                });
+                converted
+            }
+            _ => {
+                instructions.push(Instruction::Copy {
+                    src: value.clone(),
+                    dst,
+                });
+                value
+            }
+        }
+    }
+
+    /// 把一个 int 值转换为 `_Bool` 的取值：非零变为 1，零保持为 0。
+    ///
+    /// 比较、逻辑运算等表达式的结果本来就只能是 0 或 1，这时直接复用原值，
+    /// 不再额外生成 `!= 0` 比较（也就是后端的 `setcc`）。
+    fn convert_to_bool(
+        &mut self,
+        exp: ExprId,
+        value: Value,
+        instructions: &mut Vec<Instruction>,
+    ) -> Value {
+        if let Value::Constant(i) = value {
+            return Value::Constant((i != 0) as i64);
+        }
+        if self.is_boolean_valued(exp) {
+            return value;
+        }
+        let dst = Value::Var(self.new_temp_var("bool"));
+        instructions.push(Instruction::Binary {
+            op: BinaryOp::BangEqual,
+            src1: value,
+            src2: Value::Constant(0),
+            dst: dst.clone(),
+        });
+        dst
+    }
+
+    /// 表达式的值是否保证只能是 0 或 1。
+    fn is_boolean_valued(&self, exp: ExprId) -> bool {
+        match self.exp(exp) {
+            c_ast::Expression::Constant(i) => *i == 0 || *i == 1,
+            c_ast::Expression::Unary {
+                op: c_ast::UnaryOp::Not,
+                ..
+            } => true,
+            c_ast::Expression::Unary {
+                op: c_ast::UnaryOp::Plus,
+                exp,
+            } => self.is_boolean_valued(*exp),
+            c_ast::Expression::Binary { op, .. } => !matches!(
+                op,
+                c_ast::BinaryOp::Add
+                    | c_ast::BinaryOp::Subtract
+                    | c_ast::BinaryOp::Multiply
+                    | c_ast::BinaryOp::Divide
+                    | c_ast::BinaryOp::Remainder
+            ),
+            c_ast::Expression::Var(name) => self.var_type(*name) == CType::Bool,
+            c_ast::Expression::Assignment { left, .. } => self.is_boolean_valued(*left),
+            c_ast::Expression::FuncCall { name, .. } => self.return_type(*name) == CType::Bool,
+            _ => false,
+        }
+    }
+
+    fn var_type(&self, name: Symbol) -> CType {
+        self.symbols
+            .get(name)
+            .map(|s| s.tpye.clone())
+            .unwrap_or(CType::Int)
+    }
+
+    fn return_type(&self, name: Symbol) -> CType {
+        match self.symbols.get(name).map(|s| &s.tpye) {
+            Some(CType::FunType { ret, .. }) => (**ret).clone(),
+            _ => CType::Int,
+        }
+    }
+}
+
+/// 循环标签解析填入的跳转标签。
+///
+/// 循环标签解析之后每个循环以及 `break`/`continue` 都带有标签；没有标签说明前面的阶段有 bug，
+/// 报告内部编译器错误。
+fn loop_labels(label: &Option<LoopLabels>) -> Result<&LoopLabels, String> {
+    label
+        .as_ref()
+        .ok_or_else(|| tr!("内部编译器错误 (ICE): 语句没有循环标签，循环标签解析可能没有运行"))
+}
+
+/// 类型在参与运算前是否需要整数提升（零扩展到 int）。
+fn is_promoted(t: &CType) -> bool {
+    conversions::promote(t) != *t
 }
@@ -0,0 +1,458 @@
+// src/backend/tacky_gen_tests.rs
+
+//! TACKY 生成的测试：求值顺序、赋值表达式的值、短路运算的常量折叠、`noreturn`、
+//! 逻辑运算条件的跳转代码、被丢弃的调用结果和 `volatile` 访问生成的指令。
+
+use crate::UniqueNameGenerator;
+use crate::backend::assembly_ast_gen::IntDivOverflow;
+use crate::backend::constexpr_lite;
+use crate::backend::interpreter;
+use crate::backend::tacky_gen::TackyGenerator;
+use crate::backend::tacky_ir::{Instruction, Value};
+use crate::frontend::loop_labeling::LoopLabeling;
+use crate::frontend::parse_program;
+use crate::frontend::resolve_ident::IdentifierResolver;
+use crate::frontend::type_checking::TypeChecker;
+
+#[test]
+fn code_after_noreturn_call_is_unreachable() {
+    let ast = parse_program(
+        "_Noreturn int die(int c);\nint fail(int c) __attribute__((noreturn));\nint f(int x) {\n    if (x) die(x);\n    fail(x);\n    return x;\n}\n",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let (typed, symbols, _) = TypeChecker::new().typecheck_program(&resolved).unwrap();
+    let (program, warnings) = TackyGenerator::new(&mut names, &symbols)
+        .with_return_type_warnings(true)
+        .generate_tacky(&typed)
+        .unwrap();
+    let body = &program.functions[0].body;
+    assert!(
+        matches!(body.last(), Some(Instruction::FunctionCall { name, .. }) if name == "fail"),
+        "{:?}",
+        body
+    );
+    assert!(warnings.is_empty(), "{:?}", warnings);
+}
+
+#[test]
+fn warns_when_control_reaches_end_of_function() {
+    let ast = parse_program(
+        "int f(int x) {\n    if (x) return 1;\n}\nint g(int x) {\n    while (1) {}\n}\nint main(void) {}\n",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let labeled = LoopLabeling::new(&mut names)
+        .label_loops_in_program(&resolved)
+        .unwrap();
+    let (typed, symbols, _) = TypeChecker::new().typecheck_program(&labeled).unwrap();
+    let (_, warnings) = TackyGenerator::new(&mut names, &symbols)
+        .with_return_type_warnings(true)
+        .generate_tacky(&typed)
+        .unwrap();
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert!(warnings[0].message.contains("'f'"), "{:?}", warnings);
+    assert_eq!(warnings[0].code.as_deref(), Some("return-type"));
+}
+
+#[test]
+fn assignment_value_is_the_converted_stored_value() {
+    let ast = parse_program(
+        "\
+static int x;
+int g(void) { x = 10; return 1; }
+int f(void) { _Bool b; return (b = 5) + 1; }
+int main(void) { int y; y = g(); return (x = 3) + g() + f() * 100; }
+",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let labeled = LoopLabeling::new(&mut names)
+        .label_loops_in_program(&resolved)
+        .unwrap();
+    let (typed, symbols, _) = TypeChecker::new().typecheck_program(&labeled).unwrap();
+    let (program, _) = TackyGenerator::new(&mut names, &symbols)
+        .generate_tacky(&typed)
+        .unwrap();
+    // `x = 3` 的值是 3，不会读到 g() 之后的 x
+    assert_eq!(
+        interpreter::run(&program, &symbols, IntDivOverflow::Trap).unwrap(),
+        204
+    );
+    // 值没有被使用的 `y = g()` 仍然直接存入 y
+    let main = program.functions.iter().find(|f| f.name == "main").unwrap();
+    assert!(
+        main.body.iter().any(|ins| matches!(
+            ins,
+            Instruction::FunctionCall { dst: Some(Value::Var(v)), .. } if v.as_str().starts_with("main.y.")
+        )),
+        "{:?}",
+        main.body
+    );
+}
+
+#[test]
+fn declaration_initializers_are_sequenced_with_statements() {
+    let ast = parse_program(
+        "\
+int f(void);
+int g(void);
+int h(void);
+int main(void) {
+    h();
+    int x = f() + g();
+    h();
+    int y = 1;
+    {
+        int y;
+        y = x;
+    }
+    return y;
+}
+",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let labeled = LoopLabeling::new(&mut names)
+        .label_loops_in_program(&resolved)
+        .unwrap();
+    let (typed, symbols, _) = TypeChecker::new().typecheck_program(&labeled).unwrap();
+    let (program, _) = TackyGenerator::new(&mut names, &symbols)
+        .generate_tacky(&typed)
+        .unwrap();
+    let main = &program.functions[0];
+    let position = |pred: &dyn Fn(&Instruction) -> bool| -> Vec<usize> {
+        (0..main.body.len())
+            .filter(|&i| pred(&main.body[i]))
+            .collect()
+    };
+    let calls = |callee: &str| {
+        position(&|ins| matches!(ins, Instruction::FunctionCall { name, .. } if name == callee))
+    };
+    let stores = |prefix: &str| {
+        position(
+            &|ins| matches!(ins, Instruction::Copy { dst: Value::Var(v), .. } if v.as_str().starts_with(prefix)),
+        )
+    };
+    let (h, f, g, x) = (calls("h"), calls("f"), calls("g"), stores("main.x."));
+    assert_eq!((h.len(), x.len()), (2, 1), "{:?}", main.body);
+    // x 在初始化表达式的两个调用之后、下一条语句之前存入
+    assert!(
+        h[0] < f[0] && f[0] < g[0] && g[0] < x[0] && x[0] < h[1],
+        "{:?}",
+        main.body
+    );
+
+    // 没有初始化的内层 y 不会影响外层的 y
+    let y_stores = stores("main.y.");
+    assert_eq!(y_stores.len(), 2, "{:?}", main.body);
+    let Instruction::Copy { dst: inner, .. } = &main.body[y_stores[1]] else {
+        unreachable!()
+    };
+    let Instruction::Return(returned) = main.body.last().unwrap() else {
+        panic!("{:?}", main.body)
+    };
+    assert_ne!(inner.to_string(), returned.to_string(), "{:?}", main.body);
+}
+
+#[test]
+fn logical_operators_with_constant_operands_fold_without_labels() {
+    let ast = parse_program(
+        "int f(void);\nint main(void) {\n    int a = 0 && f();\n    int b = 1 || f();\n    int c = f() && 0;\n    return a + b + c;\n}\n",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let (typed, symbols, _) = TypeChecker::new().typecheck_program(&resolved).unwrap();
+    let (program, _) = TackyGenerator::new(&mut names, &symbols)
+        .generate_tacky(&typed)
+        .unwrap();
+    let body = &program.functions[0].body;
+    assert!(
+        !body.iter().any(|ins| matches!(
+            ins,
+            Instruction::Label(_) | Instruction::Jump(_) | Instruction::JumpIfZero { .. }
+        )),
+        "{:?}",
+        body
+    );
+    // `f() && 0` 的结果已知，但仍然要调用 `f`
+    let calls = body
+        .iter()
+        .filter(|ins| matches!(ins, Instruction::FunctionCall { .. }))
+        .count();
+    assert_eq!(calls, 1, "{:?}", body);
+}
+
+#[test]
+fn ignored_call_results_have_no_destination() {
+    let ast = parse_program(
+        "\
+static int counter;
+int bump(int x) { counter = counter + x; return counter; }
+int square(int x) { return x * x; }
+int main(void) { bump(1); square(2); return bump(3) + square(4); }
+",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let labeled = LoopLabeling::new(&mut names)
+        .label_loops_in_program(&resolved)
+        .unwrap();
+    let (typed, symbols, _) = TypeChecker::new().typecheck_program(&labeled).unwrap();
+    let (mut program, _) = TackyGenerator::new(&mut names, &symbols)
+        .generate_tacky(&typed)
+        .unwrap();
+    let calls = |program: &crate::backend::tacky_ir::Program| -> Vec<(String, bool)> {
+        let main = program.functions.iter().find(|f| f.name == "main").unwrap();
+        main.body
+            .iter()
+            .filter_map(|ins| match ins {
+                Instruction::FunctionCall { name, dst, .. } => {
+                    Some((name.to_string(), dst.is_some()))
+                }
+                _ => None,
+            })
+            .collect()
+    };
+    let expected = [
+        ("bump", false),
+        ("square", false),
+        ("bump", true),
+        ("square", true),
+    ];
+    assert_eq!(calls(&program), expected.map(|(n, d)| (n.to_string(), d)));
+    assert_eq!(
+        interpreter::run(&program, &symbols, IntDivOverflow::Trap).unwrap(),
+        20
+    );
+
+    // 结果不用的纯函数调用被整条删除
+    let folded = constexpr_lite::fold_pure_calls(&mut program, &symbols, IntDivOverflow::Trap);
+    assert_eq!(folded, 2);
+    assert_eq!(
+        calls(&program),
+        [("bump".to_string(), false), ("bump".to_string(), true)]
+    );
+    crate::backend::tacky_ir::verify(&program, &symbols).unwrap();
+    assert_eq!(
+        interpreter::run(&program, &symbols, IntDivOverflow::Trap).unwrap(),
+        20
+    );
+}
+
+#[test]
+fn volatile_accesses_are_kept() {
+    let ast = parse_program(
+        "\
+int delay(int n) {
+    volatile int i = 0;
+    while (i < n)
+        i = i + 1;
+    i;
+    return 0;
+}
+int main(void) { return delay(3); }
+",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let labeled = LoopLabeling::new(&mut names)
+        .label_loops_in_program(&resolved)
+        .unwrap();
+    let (typed, symbols, _) = TypeChecker::new().typecheck_program(&labeled).unwrap();
+    let (mut program, _) = TackyGenerator::new(&mut names, &symbols)
+        .generate_tacky(&typed)
+        .unwrap();
+    let delay = program
+        .functions
+        .iter()
+        .find(|f| f.name == "delay")
+        .unwrap();
+    let volatile = delay
+        .body
+        .iter()
+        .find_map(|ins| match ins {
+            Instruction::Copy {
+                src: Value::Constant(0),
+                dst: Value::Var(name),
+            } => Some(*name),
+            _ => None,
+        })
+        .unwrap();
+    assert!(symbols.is_volatile(volatile));
+    // 值被丢弃的 `i;` 仍然读一次 `i`
+    assert!(
+        delay.body.iter().any(|ins| matches!(
+            ins,
+            Instruction::Copy { src: Value::Var(name), .. } if *name == volatile
+        )),
+        "{:?}",
+        delay.body
+    );
+    // 访问 volatile 变量的函数不在编译期求值
+    let folded = constexpr_lite::fold_pure_calls(&mut program, &symbols, IntDivOverflow::Trap);
+    assert_eq!(folded, 0);
+}
+
+#[test]
+fn arguments_are_copied_only_when_they_name_a_variable() {
+    let ast = parse_program(
+        "\
+int f(int p, int q);
+int g(void);
+int main(void) {
+    _Bool b = 1;
+    int a = 2;
+    return f(b, a) + f(a, g());
+}
+",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let (typed, symbols, _) = TypeChecker::new().typecheck_program(&resolved).unwrap();
+    let (program, _) = TackyGenerator::new(&mut names, &symbols)
+        .generate_tacky(&typed)
+        .unwrap();
+    let body = &program.functions[0].body;
+    let named = |v: &Value| matches!(v, Value::Var(name) if name.as_str().starts_with("main.a.") || name.as_str().starts_with("main.b."));
+    // 具名变量 `a` 先复制一份，后面的参数求值不会影响它
+    let copies = body
+        .iter()
+        .filter(|ins| matches!(ins, Instruction::Copy { src, .. } if named(src)))
+        .count();
+    assert_eq!(copies, 2, "{:?}", body);
+    // `b` 提升后已经是新的临时变量，直接作为参数
+    let extended: Vec<String> = body
+        .iter()
+        .filter_map(|ins| match ins {
+            Instruction::ZeroExtend { dst, .. } => Some(dst.to_string()),
+            _ => None,
+        })
+        .collect();
+    assert!(
+        !body
+            .iter()
+            .any(|ins| matches!(ins, Instruction::Copy { src, .. } if extended.contains(&src.to_string()))),
+        "{:?}",
+        body
+    );
+    let first_call = body
+        .iter()
+        .find_map(|ins| match ins {
+            Instruction::FunctionCall { args, .. } => Some(args),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(first_call[0].to_string(), extended[0], "{:?}", body);
+    for ins in body {
+        if let Instruction::FunctionCall { args, .. } = ins {
+            assert!(!args.iter().any(named), "{:?}", body);
+        }
+    }
+}
+
+#[test]
+fn arguments_are_evaluated_left_to_right() {
+    let ast = parse_program(
+        "\
+static int counter;
+int next(void) { counter = counter + 1; return counter; }
+int digits(int a, int b, int c) { return a * 100 + b * 10 + c; }
+int main(void) {
+    int x = 7;
+    return digits(counter, next(), counter) * 1000 + digits(next(), next(), x);
+}
+",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let (typed, symbols, _) = TypeChecker::new().typecheck_program(&resolved).unwrap();
+    let (program, _) = TackyGenerator::new(&mut names, &symbols)
+        .generate_tacky(&typed)
+        .unwrap();
+    // 第一个参数在 `next()` 修改 counter 之前读取，第三个在之后读取
+    assert_eq!(
+        interpreter::run(&program, &symbols, IntDivOverflow::Trap).unwrap(),
+        11_237
+    );
+}
+
+#[test]
+fn logical_conditions_jump_without_materializing_a_value() {
+    let ast = parse_program(
+        "\
+int main(void) {
+    int a = 1;
+    int b = 2;
+    int r = 0;
+    if (a && b || !a) r = 1;
+    while (a && b) { a = 0; }
+    return r;
+}
+",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let labeled = LoopLabeling::new(&mut names)
+        .label_loops_in_program(&resolved)
+        .unwrap();
+    let (typed, symbols, _) = TypeChecker::new().typecheck_program(&labeled).unwrap();
+    let (program, _) = TackyGenerator::new(&mut names, &symbols)
+        .generate_tacky(&typed)
+        .unwrap();
+    let body = &program.functions[0].body;
+    // 条件直接生成跳转：没有保存 0/1 结果的临时变量，`!a` 变成反向的跳转
+    for ins in body {
+        match ins {
+            Instruction::Copy {
+                dst: Value::Var(name),
+                ..
+            } => assert!(
+                ["main.a.", "main.b.", "main.r."]
+                    .iter()
+                    .any(|p| name.as_str().starts_with(p)),
+                "{:?}",
+                body
+            ),
+            Instruction::Unary { .. } | Instruction::Binary { .. } => panic!("{:?}", body),
+            _ => {}
+        }
+    }
+    let jumps = |pred: fn(&Instruction) -> bool| body.iter().filter(|ins| pred(ins)).count();
+    assert_eq!(
+        jumps(|ins| matches!(ins, Instruction::JumpIfZero { .. })),
+        3,
+        "{:?}",
+        body
+    );
+    assert_eq!(
+        jumps(|ins| matches!(ins, Instruction::JumpIfNotZero { .. })),
+        2,
+        "{:?}",
+        body
+    );
+    assert_eq!(
+        interpreter::run(&program, &symbols, IntDivOverflow::Trap).unwrap(),
+        1
+    );
+}
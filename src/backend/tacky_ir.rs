@@ -1,20 +1,27 @@
 // src/backend/tacky_ir.rs
 
-use crate::common::{AstNode, PrettyPrinter};
+use crate::backend::ir_json;
+use crate::common::{AstNode, PrettyPrinter, Symbol};
+use crate::frontend::type_checking::SymbolTable;
+use crate::i18n::tr;
+use serde::{Serialize, Serializer};
+use std::collections::HashSet;
 use std::fmt;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Program {
     pub functions: Vec<Function>,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Function {
-    pub name: String,
-    pub params: Vec<String>,
+    pub name: Symbol,
+    pub params: Vec<Symbol>,
     pub body: Vec<Instruction>,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum Instruction {
+    #[serde(serialize_with = "ir_json::value")]
     Return(Value),
     Unary {
         op: UnaryOp,
@@ -31,6 +38,7 @@ pub enum Instruction {
         src: Value,
         dst: Value,
     },
+    #[serde(serialize_with = "ir_json::target")]
     Jump(String),
     JumpIfZero {
         condition: Value,
@@ -40,17 +48,31 @@ pub enum Instruction {
         condition: Value,
         target: String,
     },
+    #[serde(serialize_with = "ir_json::name")]
     Label(String),
+    #[serde(rename = "call")]
     FunctionCall {
-        name: String,
+        name: Symbol,
         args: Vec<Value>,
+        /// 返回值被丢弃（例如表达式语句 `f();`）时为 `None`，不需要临时变量
+        dst: Option<Value>,
+    },
+    /// 取 `src` 的低 8 位并零扩展为 `int`（读取 `_Bool` 对象）
+    ZeroExtend {
+        src: Value,
+        dst: Value,
+    },
+    /// 把 `src` 的低 8 位写入 1 字节的 `dst`（写入 `_Bool` 对象）
+    Truncate {
+        src: Value,
         dst: Value,
     },
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Value {
     Constant(i64),
-    Var(String),
+    Var(Symbol),
 }
 #[derive(Debug, Clone)]
 pub enum UnaryOp {
@@ -73,6 +95,50 @@ pub enum BinaryOp {
     LessEqual,
 }
 
+impl UnaryOp {
+    /// 运算符的简短名称，用于生成描述性的临时变量名。
+    pub fn name(&self) -> &'static str {
+        match self {
+            UnaryOp::Complement => "compl",
+            UnaryOp::Negate => "neg",
+            UnaryOp::Not => "not",
+        }
+    }
+}
+
+impl BinaryOp {
+    /// 运算符的简短名称，用于生成描述性的临时变量名。
+    pub fn name(&self) -> &'static str {
+        match self {
+            BinaryOp::Add => "add",
+            BinaryOp::Subtract => "sub",
+            BinaryOp::Multiply => "mul",
+            BinaryOp::Divide => "div",
+            BinaryOp::Remainder => "rem",
+            BinaryOp::EqualEqual => "eq",
+            BinaryOp::BangEqual => "ne",
+            BinaryOp::Greater => "gt",
+            BinaryOp::GreaterEqual => "ge",
+            BinaryOp::Less => "lt",
+            BinaryOp::LessEqual => "le",
+        }
+    }
+}
+
+/// JSON 中运算符写作 [`UnaryOp::name`]。
+impl Serialize for UnaryOp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+/// JSON 中运算符写作 [`BinaryOp::name`]。
+impl Serialize for BinaryOp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -136,8 +202,8 @@ impl AstNode for Function {
     }
 }
 
-impl AstNode for Instruction {
-    fn pretty_print(&self, printer: &mut PrettyPrinter) {
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let line = match self {
             Instruction::Return(val) => {
                 format!("return {}", val)
@@ -171,9 +237,25 @@ impl AstNode for Instruction {
             Instruction::FunctionCall { name, args, dst } => {
                 // 将参数列表格式化成 "arg1, arg2, arg3"
                 let args_str: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
-                format!("{} = call {}, [{}]", dst, name, args_str.join(", "))
+                match dst {
+                    Some(dst) => format!("{} = call {}, [{}]", dst, name, args_str.join(", ")),
+                    None => format!("call {}, [{}]", name, args_str.join(", ")),
+                }
+            }
+            Instruction::ZeroExtend { src, dst } => {
+                format!("{} = zext {}", dst, src)
+            }
+            Instruction::Truncate { src, dst } => {
+                format!("{} = trunc {}", dst, src)
             }
         };
+        write!(f, "{}", line)
+    }
+}
+
+impl AstNode for Instruction {
+    fn pretty_print(&self, printer: &mut PrettyPrinter) {
+        let line = self.to_string();
         // Labels shouldn't be indented like other instructions
         if let Instruction::Label(_) = self {
             printer.unindent();
@@ -184,3 +266,119 @@ impl AstNode for Instruction {
         }
     }
 }
+
+/// 检查 TACKY 程序的结构是否合法，返回遇到的第一个错误。
+///
+/// 违反下列约束说明 TACKY 生成（或之后的某个 IR 变换）有 bug：
+/// -   同一个函数中的标签不重复，每个跳转的目标标签都存在
+/// -   读取的变量是参数、符号表中的局部变量或静态变量，或者是函数中某条指令写入过的临时变量
+/// -   指令的目标操作数是变量
+/// -   `Return` 之后直到下一个标签之前没有指令（这些指令不可达，应已被删除）
+pub fn verify(program: &Program, symbols: &SymbolTable) -> Result<(), String> {
+    program
+        .functions
+        .iter()
+        .try_for_each(|f| verify_function(f, symbols))
+}
+
+fn verify_function(function: &Function, symbols: &SymbolTable) -> Result<(), String> {
+    let mut labels = HashSet::new();
+    let mut written = HashSet::new();
+    for ins in &function.body {
+        if let Instruction::Label(l) = ins
+            && !labels.insert(l.as_str())
+        {
+            return Err(tr!(
+                "内部编译器错误 (ICE): 函数 '{}' 中的 TACKY 标签 '{}' 重复",
+                function.name,
+                l
+            ));
+        }
+        if let Some(Value::Var(name)) = ins.dst() {
+            written.insert(*name);
+        }
+    }
+    let known = |name: Symbol| {
+        function.params.contains(&name) || written.contains(&name) || symbols.get(name).is_some()
+    };
+
+    let mut after_return = false;
+    for (index, ins) in function.body.iter().enumerate() {
+        let check = || -> Result<(), String> {
+            if after_return && !matches!(ins, Instruction::Label(_)) {
+                return Err(tr!("位于 Return 之后，不可达"));
+            }
+            if let Some(target) = ins.jump_target()
+                && !labels.contains(target)
+            {
+                return Err(tr!("跳转目标 '{}' 不存在", target));
+            }
+            if let Some(Value::Constant(_)) = ins.dst() {
+                return Err(tr!("目标操作数是常量"));
+            }
+            for src in ins.sources() {
+                if let Value::Var(name) = src
+                    && !known(*name)
+                {
+                    return Err(tr!("读取了未定义的变量 '{}'", name));
+                }
+            }
+            Ok(())
+        };
+        check().map_err(|reason| {
+            tr!(
+                "内部编译器错误 (ICE): 函数 '{}' 的第 {} 条 TACKY 指令 `{}` 不合法: {}",
+                function.name,
+                index,
+                ins,
+                reason
+            )
+        })?;
+        match ins {
+            Instruction::Return(_) => after_return = true,
+            Instruction::Label(_) => after_return = false,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+impl Instruction {
+    /// 指令写入的操作数。
+    fn dst(&self) -> Option<&Value> {
+        match self {
+            Instruction::Unary { dst, .. }
+            | Instruction::Binary { dst, .. }
+            | Instruction::Copy { dst, .. }
+            | Instruction::ZeroExtend { dst, .. }
+            | Instruction::Truncate { dst, .. } => Some(dst),
+            Instruction::FunctionCall { dst, .. } => dst.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// 指令读取的操作数。
+    fn sources(&self) -> Vec<&Value> {
+        match self {
+            Instruction::Return(v) => vec![v],
+            Instruction::Unary { src, .. }
+            | Instruction::Copy { src, .. }
+            | Instruction::ZeroExtend { src, .. }
+            | Instruction::Truncate { src, .. } => vec![src],
+            Instruction::Binary { src1, src2, .. } => vec![src1, src2],
+            Instruction::JumpIfZero { condition, .. }
+            | Instruction::JumpIfNotZero { condition, .. } => vec![condition],
+            Instruction::FunctionCall { args, .. } => args.iter().collect(),
+            Instruction::Jump(_) | Instruction::Label(_) => Vec::new(),
+        }
+    }
+
+    fn jump_target(&self) -> Option<&str> {
+        match self {
+            Instruction::Jump(target)
+            | Instruction::JumpIfZero { target, .. }
+            | Instruction::JumpIfNotZero { target, .. } => Some(target),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,188 @@
+// src/backend/temp_reuse.rs
+
+//! **临时变量复用**
+//!
+//! TACKY 生成时每个子表达式都会得到一个新的临时变量，而每个临时变量在汇编生成阶段
+//! 都会占用一个独立的栈槽，大表达式会让栈帧迅速膨胀。本模块在单个函数的指令序列上
+//! 计算每个临时变量的活跃区间（第一次出现到最后一次出现），然后用线性扫描把活跃区间
+//! 不重叠的临时变量合并成同一个名字，使它们共享同一个栈槽。
+//!
+//! 临时变量只在一个表达式的求值过程中存活，通常不会跨越循环的回边。为了保守起见，
+//! 如果一个临时变量在循环开始之前定义、在循环内部仍然活跃，它的活跃区间会被延长到
+//! 循环末尾的回跳指令。
+//!
+//! 只有 TACKY 生成器创建的临时变量参与合并，源程序中的变量保持不变。
+
+use std::collections::{HashMap, HashSet};
+
+use crate::backend::tacky_ir::{Instruction, Value};
+use crate::common::Symbol;
+
+/// 合并活跃区间不重叠的临时变量，返回被消除的临时变量数量。
+pub fn reuse_temps(body: &mut [Instruction], temps: &HashSet<Symbol>) -> usize {
+    let mut intervals = live_intervals(body, temps);
+    extend_across_back_edges(body, &mut intervals);
+
+    // 按区间起点排序做线性扫描；名字作为第二关键字，保证结果稳定
+    let mut order: Vec<(Symbol, (usize, usize))> = intervals.into_iter().collect();
+    order.sort_by(|a, b| {
+        a.1.0
+            .cmp(&b.1.0)
+            .then_with(|| a.0.as_str().cmp(b.0.as_str()))
+    });
+
+    // 每个槽位：(使用该槽位的名字, 当前占用者的区间终点)
+    let mut slots: Vec<(Symbol, usize)> = Vec::new();
+    let mut renames: HashMap<Symbol, Symbol> = HashMap::new();
+    for (name, (start, end)) in order {
+        match slots.iter_mut().find(|(_, slot_end)| *slot_end < start) {
+            Some((slot_name, slot_end)) => {
+                *slot_end = end;
+                renames.insert(name, *slot_name);
+            }
+            None => slots.push((name, end)),
+        }
+    }
+
+    for ins in body.iter_mut() {
+        for value in values_mut(ins) {
+            if let Value::Var(name) = value
+                && let Some(new_name) = renames.get(name)
+            {
+                *name = *new_name;
+            }
+        }
+    }
+    renames.len()
+}
+
+/// 每个临时变量第一次和最后一次出现的指令下标。
+fn live_intervals(
+    body: &mut [Instruction],
+    temps: &HashSet<Symbol>,
+) -> HashMap<Symbol, (usize, usize)> {
+    let mut intervals: HashMap<Symbol, (usize, usize)> = HashMap::new();
+    for (i, ins) in body.iter_mut().enumerate() {
+        for value in values_mut(ins) {
+            if let Value::Var(name) = value
+                && temps.contains(name)
+            {
+                intervals
+                    .entry(*name)
+                    .and_modify(|(_, end)| *end = i)
+                    .or_insert((i, i));
+            }
+        }
+    }
+    intervals
+}
+
+/// 对于每条从下标 `j` 跳回下标 `i` 处标签的回边，在循环开始前定义、在循环内仍然活跃
+/// 的临时变量必须在整个循环中保持活跃。
+fn extend_across_back_edges(body: &[Instruction], intervals: &mut HashMap<Symbol, (usize, usize)>) {
+    let labels: HashMap<&str, usize> = body
+        .iter()
+        .enumerate()
+        .filter_map(|(i, ins)| match ins {
+            Instruction::Label(l) => Some((l.as_str(), i)),
+            _ => None,
+        })
+        .collect();
+    let back_edges: Vec<(usize, usize)> = body
+        .iter()
+        .enumerate()
+        .filter_map(|(j, ins)| {
+            let target = match ins {
+                Instruction::Jump(t)
+                | Instruction::JumpIfZero { target: t, .. }
+                | Instruction::JumpIfNotZero { target: t, .. } => t,
+                _ => return None,
+            };
+            labels
+                .get(target.as_str())
+                .filter(|&&i| i < j)
+                .map(|&i| (i, j))
+        })
+        .collect();
+
+    // 延长一个区间可能使它跨入另一个循环，重复直到不再变化
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (start, end) in intervals.values_mut() {
+            for &(loop_start, loop_end) in &back_edges {
+                if *start < loop_start && *end >= loop_start && *end < loop_end {
+                    *end = loop_end;
+                    changed = true;
+                }
+            }
+        }
+    }
+}
+
+fn values_mut(ins: &mut Instruction) -> Vec<&mut Value> {
+    match ins {
+        Instruction::Return(v) => vec![v],
+        Instruction::Unary { src, dst, .. }
+        | Instruction::Copy { src, dst }
+        | Instruction::ZeroExtend { src, dst }
+        | Instruction::Truncate { src, dst } => vec![src, dst],
+        Instruction::Binary {
+            src1, src2, dst, ..
+        } => vec![src1, src2, dst],
+        Instruction::JumpIfZero { condition, .. }
+        | Instruction::JumpIfNotZero { condition, .. } => {
+            vec![condition]
+        }
+        Instruction::FunctionCall { args, dst, .. } => {
+            let mut values: Vec<&mut Value> = args.iter_mut().collect();
+            values.extend(dst.as_mut());
+            values
+        }
+        Instruction::Jump(_) | Instruction::Label(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::UniqueNameGenerator;
+    use crate::backend::tacky_gen::TackyGenerator;
+    use crate::backend::tacky_ir::{Instruction, Value};
+    use crate::common::Symbol;
+    use crate::frontend::parse_program;
+    use crate::frontend::resolve_ident::IdentifierResolver;
+    use crate::frontend::type_checking::TypeChecker;
+
+    #[test]
+    fn temps_with_disjoint_live_ranges_share_a_name() {
+        let distinct_temps = |reuse: bool| {
+            let ast =
+                parse_program("int f(int a) { return (a + 1) * (a + 2) + (a + 3) * (a + 4); }\n");
+            let mut names = UniqueNameGenerator::new();
+            let (resolved, _) = IdentifierResolver::new(&mut names)
+                .resolve_program(&ast)
+                .unwrap();
+            let (typed, symbols, _) = TypeChecker::new().typecheck_program(&resolved).unwrap();
+            let (program, _) = TackyGenerator::new(&mut names, &symbols)
+                .with_temp_reuse(reuse)
+                .generate_tacky(&typed)
+                .unwrap();
+            let temps: HashSet<Symbol> = program.functions[0]
+                .body
+                .iter()
+                .filter_map(|ins| match ins {
+                    Instruction::Binary {
+                        dst: Value::Var(name),
+                        ..
+                    } => Some(*name),
+                    _ => None,
+                })
+                .collect();
+            temps.len()
+        };
+        assert_eq!(distinct_temps(false), 7);
+        assert_eq!(distinct_temps(true), 4);
+    }
+}
@@ -0,0 +1,258 @@
+// src/bench.rs
+
+//! **生成代码基准测试 (Benchmark)**
+//!
+//! 该模块实现 `--bench` 驱动模式：把一组工作负载（例如 `benches/workloads/*.c`）
+//! 分别用本编译器和 `gcc -O0` 编译、运行，并报告编译时间和运行时间的统计数据，
+//! 以便长期跟踪优化工作的效果。
+//!
+//! ## 测量方式
+//!
+//! -   **编译时间**: 本编译器以子进程方式运行 (`ccompiler <file> -c`)，再由 gcc 链接；
+//!     gcc 一侧直接运行 `gcc -O0 <file> -o <exe>`。两边都包含预处理、汇编和链接，口径一致。
+//! -   **运行时间**: 每个可执行文件运行 `runs` 次，取中位数。两边的退出码必须一致，
+//!     否则视为本编译器的错误编译，并直接报错。
+//!
+//! 所有中间文件都写入系统临时目录，结束时自动删除，不会污染工作负载所在目录。
+//! 工作负载所在的目录作为 `-I` 传给两边的编译，以便找到它旁边的头文件。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::grade::include_source_dir;
+use crate::i18n::tr;
+
+/// 一组计时样本。
+#[derive(Debug, Default)]
+struct Samples(Vec<Duration>);
+
+impl Samples {
+    fn push(&mut self, d: Duration) {
+        self.0.push(d);
+    }
+
+    fn min(&self) -> Duration {
+        self.0.iter().min().copied().unwrap_or_default()
+    }
+
+    fn median(&self) -> Duration {
+        let mut sorted = self.0.clone();
+        sorted.sort();
+        sorted.get(sorted.len() / 2).copied().unwrap_or_default()
+    }
+}
+
+/// 单个工作负载的测量结果。
+#[derive(Debug)]
+struct BenchResult {
+    name: String,
+    ours_compile: Samples,
+    gcc_compile: Samples,
+    ours_run: Samples,
+    gcc_run: Samples,
+    exit_code: i32,
+}
+
+/// `--bench` 模式的入口。`path` 可以是单个 `.c` 文件，也可以是包含多个 `.c` 文件的目录。
+pub fn run_benchmarks(path: &Path, runs: usize) -> Result<(), String> {
+    if runs == 0 {
+        return Err(tr!("--bench-runs 必须大于 0"));
+    }
+    let workloads = collect_workloads(path)?;
+    if workloads.is_empty() {
+        return Err(tr!("在 {} 中没有找到任何 .c 工作负载", path.display()));
+    }
+
+    let compiler = std::env::current_exe().map_err(|e| tr!("无法定位编译器自身: {}", e))?;
+    let work_dir = std::env::temp_dir().join(format!("ccompiler-bench-{}", std::process::id()));
+    fs::create_dir_all(&work_dir).map_err(|e| tr!("无法创建临时目录: {}", e))?;
+
+    println!(
+        "{}",
+        tr!(
+            "--- 基准测试: {} 个工作负载, 每个运行 {} 次 ---",
+            workloads.len(),
+            runs
+        )
+    );
+    let mut results = Vec::new();
+    let mut outcome = Ok(());
+    for workload in &workloads {
+        match bench_workload(&compiler, workload, &work_dir, runs) {
+            Ok(r) => results.push(r),
+            Err(e) => {
+                outcome = Err(format!("{}: {}", workload.display(), e));
+                break;
+            }
+        }
+    }
+    // 无论成功与否都清理临时目录
+    let _ = fs::remove_dir_all(&work_dir);
+    outcome?;
+
+    print_report(&results);
+    Ok(())
+}
+
+fn collect_workloads(path: &Path) -> Result<Vec<PathBuf>, String> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let entries = fs::read_dir(path).map_err(|e| tr!("无法读取 {}: {}", path.display(), e))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "c"))
+        .collect();
+    // 按文件名排序，保证报告顺序稳定
+    files.sort();
+    Ok(files)
+}
+
+fn bench_workload(
+    compiler: &Path,
+    workload: &Path,
+    work_dir: &Path,
+    runs: usize,
+) -> Result<BenchResult, String> {
+    let name = workload
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    println!("{}", tr!("   正在测量: {}", name));
+
+    // 把源文件复制到临时目录，本编译器的中间文件都会生成在源文件旁边
+    let source = work_dir.join(format!("{}.c", name));
+    fs::copy(workload, &source).map_err(|e| tr!("无法复制源文件: {}", e))?;
+    let ours_obj = source.with_extension("o");
+    let ours_exe = work_dir.join(format!("{}.ccompiler", name));
+    let gcc_exe = work_dir.join(format!("{}.gcc", name));
+    // 副本旁边没有工作负载的头文件，两边都到原来的目录中查找
+    let include_dir = include_source_dir(workload);
+
+    let mut result = BenchResult {
+        name,
+        ours_compile: Samples::default(),
+        gcc_compile: Samples::default(),
+        ours_run: Samples::default(),
+        gcc_run: Samples::default(),
+        exit_code: 0,
+    };
+
+    for _ in 0..runs {
+        // 本编译器：编译到目标文件，再用 gcc 链接
+        let start = Instant::now();
+        run_quiet(
+            Command::new(compiler)
+                .arg(&source)
+                .arg("-c")
+                .arg(&include_dir),
+            &tr!("ccompiler 编译失败"),
+        )?;
+        run_quiet(
+            Command::new("gcc").arg(&ours_obj).arg("-o").arg(&ours_exe),
+            &tr!("gcc 链接失败"),
+        )?;
+        result.ours_compile.push(start.elapsed());
+
+        // 参照组：gcc -O0
+        let start = Instant::now();
+        run_quiet(
+            Command::new("gcc")
+                .arg("-O0")
+                .arg(&include_dir)
+                .arg(&source)
+                .arg("-o")
+                .arg(&gcc_exe),
+            &tr!("gcc 编译失败"),
+        )?;
+        result.gcc_compile.push(start.elapsed());
+    }
+
+    for _ in 0..runs {
+        let (ours_time, ours_code) = time_executable(&ours_exe)?;
+        let (gcc_time, gcc_code) = time_executable(&gcc_exe)?;
+        if ours_code != gcc_code {
+            return Err(tr!(
+                "退出码不一致: ccompiler 为 {}, gcc 为 {}",
+                ours_code,
+                gcc_code
+            ));
+        }
+        result.ours_run.push(ours_time);
+        result.gcc_run.push(gcc_time);
+        result.exit_code = ours_code;
+    }
+    Ok(result)
+}
+
+/// 运行一个命令并丢弃其输出，只关心是否成功。
+fn run_quiet(cmd: &mut Command, what: &str) -> Result<(), String> {
+    let status = cmd
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("{}: {}", what, e))?;
+    if !status.success() {
+        return Err(what.to_string());
+    }
+    Ok(())
+}
+
+/// 运行可执行文件，返回耗时和退出码。
+fn time_executable(exe: &Path) -> Result<(Duration, i32), String> {
+    let start = Instant::now();
+    let status = Command::new(exe)
+        .stdout(Stdio::null())
+        .status()
+        .map_err(|e| tr!("无法运行 '{}': {}", exe.display(), e))?;
+    let elapsed = start.elapsed();
+    let code = status
+        .code()
+        .ok_or_else(|| tr!("'{}' 被信号终止", exe.display()))?;
+    Ok((elapsed, code))
+}
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+fn ratio(ours: Duration, gcc: Duration) -> f64 {
+    if gcc.is_zero() {
+        0.0
+    } else {
+        ours.as_secs_f64() / gcc.as_secs_f64()
+    }
+}
+
+fn print_report(results: &[BenchResult]) {
+    println!();
+    println!(
+        "{:<12} {:>6} {:>14} {:>14} {:>8} {:>14} {:>14} {:>8}",
+        "workload", "exit", "compile(ms)", "gcc(ms)", "ratio", "run(ms)", "gcc run(ms)", "ratio"
+    );
+    for r in results {
+        println!(
+            "{:<12} {:>6} {:>14.2} {:>14.2} {:>7.2}x {:>14.2} {:>14.2} {:>7.2}x",
+            r.name,
+            r.exit_code,
+            ms(r.ours_compile.median()),
+            ms(r.gcc_compile.median()),
+            ratio(r.ours_compile.median(), r.gcc_compile.median()),
+            ms(r.ours_run.median()),
+            ms(r.gcc_run.median()),
+            ratio(r.ours_run.median(), r.gcc_run.median()),
+        );
+    }
+    println!();
+    println!("{}", tr!("编译时间统计 (ccompiler, 最小值 / 中位数):"));
+    for r in results {
+        println!(
+            "   {:<12} {:.2} / {:.2} ms",
+            r.name,
+            ms(r.ours_compile.min()),
+            ms(r.ours_compile.median())
+        );
+    }
+}
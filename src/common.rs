@@ -1,21 +1,277 @@
 // src/common.rs
 
+use std::collections::HashMap;
+use std::fmt;
 use std::io;
+use std::sync::{LazyLock, Mutex, MutexGuard};
+
+use crate::i18n::tr;
+
+/// 源程序遵循的 C 语言标准（`--std`）。
+///
+/// 目前只影响关键字集合（例如 `_Bool` 需要 C99 及以上），并被转发给预处理器。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, clap::ValueEnum)]
+pub enum CStandard {
+    C89,
+    C99,
+    C11,
+    #[default]
+    C17,
+}
+
+impl fmt::Display for CStandard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CStandard::C89 => write!(f, "c89"),
+            CStandard::C99 => write!(f, "c99"),
+            CStandard::C11 => write!(f, "c11"),
+            CStandard::C17 => write!(f, "c17"),
+        }
+    }
+}
+
+/// 目标平台的数据模型：各基本类型占用的字节数（`-m64` / `-m32`）。
+///
+/// 类型检查（类型大小、常量表达式的取值范围）和后端（栈槽大小、栈上参数的布局）
+/// 都从这里读取大小，而不是直接写死 4 和 8。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataLayout {
+    pub int_size: i64,
+    pub long_size: i64,
+    /// 指针的大小，也是返回地址、保存的帧指针和每个栈上参数占用的大小
+    pub pointer_size: i64,
+}
+
+impl DataLayout {
+    /// x86-64 System V：`int` 4 字节，`long` 和指针 8 字节
+    pub const LP64: DataLayout = DataLayout {
+        int_size: 4,
+        long_size: 8,
+        pointer_size: 8,
+    };
+    /// 32 位目标：`int`、`long` 和指针都是 4 字节
+    pub const ILP32: DataLayout = DataLayout {
+        int_size: 4,
+        long_size: 4,
+        pointer_size: 4,
+    };
+}
+
+impl Default for DataLayout {
+    fn default() -> Self {
+        DataLayout::LP64
+    }
+}
+
+/// 源代码中的位置（行号和列号都从 1 开始）。
+///
+/// 文件和行号取自预处理器输出的行标记，因此对应原始源文件（或者它包含的头文件）中的行，
+/// 而不是 `.i` 文件中的行。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    /// 位置所在的头文件；`None` 表示正在编译的源文件本身
+    pub file: Option<Symbol>,
+}
+
+/// 源文件中的位置显示为 `行:列`，头文件中的位置显示为 `文件:行:列`。
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(file) = self.file {
+            write!(f, "{}:", file)?;
+        }
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// 驻留字符串（标识符和改名后的唯一名字）的句柄。
+///
+/// 所有编译阶段共用一个全局驻留表：相同的字符串总是得到相同的 `Symbol`，所以比较、
+/// 哈希和复制都只是对一个整数操作，AST、符号表和 IR 之间传递名字也不再需要克隆字符串。
+/// 驻留的字符串在进程结束前不会释放。
+///
+/// 驻留表属于整个进程，而不属于某一次编译：编译器中没有贯穿所有阶段的会话对象，`Display`
+/// 和诊断信息需要在任何地方把 `Symbol` 还原成字符串；而且 AST、IR 和各个 pass 都必须能在
+/// 线程之间移动（`Send`），线程局部的表会让移到另一个线程的 `Symbol` 指向错误的字符串。
+/// 驻留表只增不减并由互斥锁保护，并发的编译会话只会共享相同的字符串；`Symbol` 的数值
+/// 因此取决于其他会话，它不实现 `Ord`，需要稳定顺序的地方按字符串排序，输出与数值无关。
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// 返回 `text` 对应的 `Symbol`，第一次出现时把它加入驻留表。
+    pub fn intern(text: &str) -> Symbol {
+        interner().intern(text)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        interner().strings[self.0 as usize]
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl serde::Serialize for Symbol {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(text: &str) -> Self {
+        Symbol::intern(text)
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+#[derive(Default)]
+struct Interner {
+    symbols: HashMap<&'static str, Symbol>,
+    strings: Vec<&'static str>,
+}
+
+impl Interner {
+    fn intern(&mut self, text: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(text) {
+            return symbol;
+        }
+        let symbol = Symbol(self.strings.len() as u32);
+        let text: &'static str = Box::leak(text.into());
+        self.strings.push(text);
+        self.symbols.insert(text, symbol);
+        symbol
+    }
+}
+
+fn interner() -> MutexGuard<'static, Interner> {
+    static INTERNER: LazyLock<Mutex<Interner>> = LazyLock::new(Mutex::default);
+    // 驻留表的每次修改都是完整的，持锁线程 panic 后表仍然可用
+    INTERNER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 编译单个翻译单元时的资源上限。
+///
+/// 超出上限时编译器报告错误并停止，而不是耗尽内存或栈溢出，
+/// 从而可以安全地编译不受信任的输入。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::Args)]
+pub struct ResourceLimits {
+    /// 词法分析产生的 token 数量上限
+    #[arg(long, default_value_t = ResourceLimits::default().max_tokens)]
+    pub max_tokens: usize,
+    /// 语句和表达式的最大嵌套深度
+    #[arg(long, default_value_t = ResourceLimits::default().max_ast_depth)]
+    pub max_ast_depth: usize,
+    /// 每个函数生成的 TACKY 指令数量上限
+    #[arg(long, default_value_t = ResourceLimits::default().max_tacky_instructions)]
+    pub max_tacky_instructions: usize,
+    /// `#include` 的最大嵌套深度；互相包含（且没有头文件保护）的头文件会在此处报错
+    #[arg(long, default_value_t = ResourceLimits::default().max_include_depth)]
+    pub max_include_depth: usize,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        ResourceLimits {
+            max_tokens: 1_000_000,
+            max_ast_depth: 256,
+            max_tacky_instructions: 1_000_000,
+            max_include_depth: 200,
+        }
+    }
+}
 
 pub trait AstNode {
     fn pretty_print(&self, printer: &mut PrettyPrinter);
+
+    /// 返回一个使用默认配置的 `Display` 适配器，例如 `ast.pretty().to_string()`。
+    fn pretty(&self) -> Pretty<'_, Self> {
+        self.pretty_with(PrettyConfig::default())
+    }
+
+    /// 返回一个使用指定配置的 `Display` 适配器。
+    fn pretty_with(&self, config: PrettyConfig) -> Pretty<'_, Self> {
+        Pretty { node: self, config }
+    }
+}
+
+/// 美化打印的配置。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrettyConfig {
+    /// 每级缩进的空格数
+    pub indent_width: usize,
+    /// 每行的最大宽度（含缩进），超出部分以 `...` 截断；`None` 表示不限制
+    pub max_width: Option<usize>,
+    /// 同一个父节点下最多打印的子节点数，其余子节点折叠成一行摘要；`None` 表示全部打印
+    pub elide_over: Option<usize>,
+}
+
+impl Default for PrettyConfig {
+    fn default() -> Self {
+        PrettyConfig {
+            indent_width: 2,
+            max_width: None,
+            elide_over: None,
+        }
+    }
+}
+
+/// `AstNode::pretty` 返回的适配器，通过 `Display` 输出美化打印的结果。
+pub struct Pretty<'a, T: AstNode + ?Sized> {
+    node: &'a T,
+    config: PrettyConfig,
+}
+
+impl<T: AstNode + ?Sized> fmt::Display for Pretty<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer = Vec::new();
+        {
+            let mut printer = PrettyPrinter::with_config(&mut buffer, self.config);
+            self.node.pretty_print(&mut printer);
+        }
+        f.write_str(&String::from_utf8_lossy(&buffer))
+    }
 }
 
 pub struct PrettyPrinter<'a> {
     indent_level: usize,
     writer: &'a mut dyn io::Write,
+    config: PrettyConfig,
+    /// 每一级缩进上（在当前父节点下）已经出现的节点数
+    counts: Vec<usize>,
+    /// 正在折叠的层级以及该层级被折叠的节点数
+    elided: Option<(usize, usize)>,
 }
 
 impl<'a> PrettyPrinter<'a> {
-    pub fn new(writer: &'a mut dyn io::Write) -> Self {
+    pub fn with_config(writer: &'a mut dyn io::Write, config: PrettyConfig) -> Self {
         PrettyPrinter {
             indent_level: 0,
             writer,
+            config,
+            counts: Vec::new(),
+            elided: None,
         }
     }
 
@@ -27,17 +283,62 @@ impl<'a> PrettyPrinter<'a> {
         if self.indent_level > 0 {
             self.indent_level -= 1;
         }
+        // 离开被折叠的层级时输出摘要。这里无法返回错误，与其他调用者一样忽略写入失败
+        let _ = self.flush_elided();
     }
 
-    fn prefix(&self) -> String {
-        "  ".repeat(self.indent_level)
+    fn prefix(&self, level: usize) -> String {
+        " ".repeat(self.config.indent_width * level)
     }
 
     pub fn writeln(&mut self, text: &str) -> io::Result<()> {
-        writeln!(self.writer, "{}{}", self.prefix(), text)
+        let level = self.indent_level;
+        // 同级出现新节点时，更深层级的计数属于上一个节点，清零
+        self.counts.resize(level + 1, 0);
+        self.counts[level] += 1;
+
+        if let Some(limit) = self.config.elide_over
+            && self.elided.is_none()
+            && self.counts[level] > limit
+        {
+            self.elided = Some((level, 0));
+        }
+        if let Some((elided_level, count)) = &mut self.elided {
+            if level == *elided_level {
+                *count += 1;
+            }
+            return Ok(());
+        }
+        self.write_line(level, text)
+    }
+
+    fn write_line(&mut self, level: usize, text: &str) -> io::Result<()> {
+        let mut line = format!("{}{}", self.prefix(level), text);
+        if let Some(width) = self.config.max_width
+            && line.chars().count() > width
+        {
+            line = line.chars().take(width.saturating_sub(3)).collect();
+            line.push_str("...");
+        }
+        writeln!(self.writer, "{}", line)
+    }
+
+    fn flush_elided(&mut self) -> io::Result<()> {
+        match self.elided {
+            Some((level, count)) if self.indent_level < level => {
+                self.elided = None;
+                self.write_line(level, &tr!("... (省略 {} 个节点)", count))
+            }
+            _ => Ok(()),
+        }
     }
+}
 
-    // pub fn write_raw(&mut self, text: &str) -> io::Result<()> {
-    //     write!(self.writer, "{}", text)
-    // }
+impl Drop for PrettyPrinter<'_> {
+    fn drop(&mut self) {
+        // 顶层节点被折叠时，没有后续的 unindent 来输出摘要
+        if let Some((level, count)) = self.elided.take() {
+            let _ = self.write_line(level, &tr!("... (省略 {} 个节点)", count));
+        }
+    }
 }
@@ -0,0 +1,100 @@
+// src/config.rs
+
+//! **项目配置文件 (`ccompiler.toml`)**
+//!
+//! 驱动程序从当前目录开始逐级向上查找 `ccompiler.toml`（或者使用 `--config` 指定的文件，
+//! `--no-config` 则完全不读取），把其中的默认选项转换成命令行参数，放在真正的命令行参数
+//! 之前一起解析。因此只能取一个值的选项（`--std`、`--lang` 等）以命令行为准；
+//! 可重复的选项（`-I`、`-W`、`-D` 等）先使用配置文件中的值，再追加命令行中的值。
+//!
+//! ```toml
+//! std = "c99"
+//! include = ["include"]            # 相对路径相对于配置文件所在的目录
+//! warnings = ["unused-parameter"]  # 对应 -W，也可以写 "error" 或 "error=<名字>"
+//! machine = ["no-red-zone"]        # 对应 -m
+//! define = ["DEBUG=1"]
+//! flags = ["--gc-functions"]       # 其他任意命令行选项
+//! ```
+//!
+//! 取值的合法性由命令行解析检查，错误信息和直接在命令行中写错选项时相同。
+//!
+//! `--grade`、`--difftest` 和 `--watch` 以 `--no-config` 运行每次编译，配置文件中影响编译的
+//! 选项（包括 `flags` 中的）和命令行选项一起由它们转发。
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::i18n::tr;
+
+/// 自动查找的配置文件名。
+pub const FILE_NAME: &str = "ccompiler.toml";
+
+/// 配置文件的内容。每一项都对应一个命令行选项。
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct Config {
+    std: Option<String>,
+    lang: Option<String>,
+    masm: Option<String>,
+    int_div_overflow: Option<String>,
+    include: Vec<PathBuf>,
+    warnings: Vec<String>,
+    machine: Vec<String>,
+    define: Vec<String>,
+    undefine: Vec<String>,
+    flags: Vec<String>,
+}
+
+impl Config {
+    /// 转换成命令行参数。`base_dir` 是配置文件所在的目录。
+    fn to_args(&self, base_dir: &Path) -> Vec<OsString> {
+        let mut args: Vec<OsString> = Vec::new();
+        for (flag, value) in [
+            ("--std", &self.std),
+            ("--lang", &self.lang),
+            ("--masm", &self.masm),
+            ("--int-div-overflow", &self.int_div_overflow),
+        ] {
+            if let Some(value) = value {
+                args.push(flag.into());
+                args.push(value.into());
+            }
+        }
+        for dir in &self.include {
+            let mut arg = OsString::from("-I");
+            arg.push(base_dir.join(dir));
+            args.push(arg);
+        }
+        for (flag, values) in [
+            ("-W", &self.warnings),
+            ("-m", &self.machine),
+            ("-D", &self.define),
+            ("-U", &self.undefine),
+        ] {
+            args.extend(values.iter().map(|v| format!("{}{}", flag, v).into()));
+        }
+        args.extend(self.flags.iter().map(OsString::from));
+        args
+    }
+}
+
+/// 从 `start` 开始逐级向上查找配置文件。
+pub fn find(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// 读取配置文件，返回对应的命令行参数。
+pub fn load(path: &Path) -> Result<Vec<OsString>, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| tr!("无法读取配置文件 '{}': {}", path.display(), e))?;
+    let config: Config =
+        toml::from_str(&content).map_err(|e| tr!("配置文件 '{}' 有误: {}", path.display(), e))?;
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    Ok(config.to_args(base_dir))
+}
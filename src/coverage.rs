@@ -0,0 +1,153 @@
+// src/coverage.rs
+
+//! **语句覆盖率 (`--coverage` 和 `cov-report`)**
+//!
+//! `--coverage` 编译时，[`frontend::coverage`](crate::frontend::coverage) 在每条语句前插入
+//! 一次计数，驱动程序再做两件事：
+//! -   把语句编号到源码位置的对应表写入 `<源文件>.covmap`，每行 `编号 行 列`；
+//! -   生成一小段 C 运行时代码并和程序一起链接，其中定义计数器数组。程序退出时（从 `main`
+//!     返回或调用 `exit`）它把所有计数写入 `<源文件>.cov`，每行 `编号 次数`，覆盖上一次运行的结果。
+//!
+//! `ccompiler cov-report <源文件>` 读取这两个文件，像 `gcov` 一样在源文件每行前面标出
+//! 该行语句的执行次数：`-` 表示这一行没有语句，`#####` 表示有语句但从未执行。
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::common::Span;
+use crate::frontend::coverage::HIT_FUNCTION;
+use crate::i18n::tr;
+
+/// 语句位置表的路径。
+pub fn map_path(source: &Path) -> PathBuf {
+    source.with_extension("covmap")
+}
+
+/// 程序运行后写出的计数文件的路径。
+pub fn counts_path(source: &Path) -> PathBuf {
+    source.with_extension("cov")
+}
+
+/// 链接进程序的运行时代码的路径（编译结束后删除）。
+pub fn runtime_path(source: &Path) -> PathBuf {
+    source.with_extension("cov.c")
+}
+
+/// 写入语句位置表。
+pub fn write_map(path: &Path, spans: &[Span]) -> Result<(), String> {
+    let map: String = spans
+        .iter()
+        .enumerate()
+        .map(|(i, span)| format!("{} {} {}\n", i, span.line, span.column))
+        .collect();
+    fs::write(path, map).map_err(|e| tr!("无法写入文件 '{}': {}", path.display(), e))
+}
+
+/// 生成定义计数器数组和 `__ccov_hit` 的 C 运行时代码，程序退出时它把 `count` 个计数写入
+/// `counts_path`。
+pub fn runtime_source(count: usize, counts_path: &Path) -> String {
+    format!(
+        "\
+#include <stdio.h>
+
+static unsigned long counters[{size}];
+
+int {hit}(int index) {{
+    counters[index]++;
+    return 0;
+}}
+
+__attribute__((destructor)) static void ccompiler_coverage_dump(void) {{
+    FILE *f = fopen(\"{path}\", \"w\");
+    if (!f)
+        return;
+    for (int i = 0; i < {count}; i++)
+        fprintf(f, \"%d %lu\\n\", i, counters[i]);
+    fclose(f);
+}}
+",
+        // 没有语句时数组也不能为空
+        size = count.max(1),
+        hit = HIT_FUNCTION,
+        path = c_string(&counts_path.display().to_string()),
+        count = count,
+    )
+}
+
+fn c_string(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// 读取 `编号 数1 数2 ...` 格式的文件，返回编号到其余数字的映射。
+fn read_table(path: &Path) -> Result<BTreeMap<usize, Vec<usize>>, String> {
+    let text = fs::read_to_string(path).map_err(|_| {
+        tr!(
+            "找不到覆盖率数据 '{}'：先用 --coverage 编译并运行程序",
+            path.display()
+        )
+    })?;
+    text.lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|line| {
+            let numbers: Option<Vec<usize>> =
+                line.split_whitespace().map(|n| n.parse().ok()).collect();
+            match numbers.as_deref() {
+                Some([index, rest @ ..]) => Ok((*index, rest.to_vec())),
+                _ => Err(tr!("覆盖率数据 '{}' 格式错误: {}", path.display(), line)),
+            }
+        })
+        .collect()
+}
+
+/// `cov-report` 子命令：返回标注了执行次数的源文件。
+pub fn report(source: &Path) -> Result<String, String> {
+    let text =
+        fs::read_to_string(source).map_err(|e| tr!("无法读取 {}: {}", source.display(), e))?;
+    let map = read_table(&map_path(source))?;
+    let counts = read_table(&counts_path(source))?;
+    if map.len() != counts.len() {
+        return Err(tr!(
+            "覆盖率数据与 '{}' 不一致：重新编译后需要重新运行程序",
+            source.display()
+        ));
+    }
+
+    // 一行有多条语句时取最大的次数
+    let mut lines: BTreeMap<usize, usize> = BTreeMap::new();
+    for (index, position) in &map {
+        let count = counts
+            .get(index)
+            .and_then(|c| c.first())
+            .copied()
+            .unwrap_or(0);
+        if let Some(&line) = position.first() {
+            let entry = lines.entry(line).or_default();
+            *entry = (*entry).max(count);
+        }
+    }
+
+    let mut out = String::new();
+    for (n, line) in text.lines().enumerate() {
+        let count = match lines.get(&(n + 1)) {
+            None => "-".to_string(),
+            Some(0) => "#####".to_string(),
+            Some(c) => c.to_string(),
+        };
+        out.push_str(&format!("{:>9}:{:>5}:{}\n", count, n + 1, line));
+    }
+    let executed = lines.values().filter(|&&c| c > 0).count();
+    let percent = if lines.is_empty() {
+        100.0
+    } else {
+        executed as f64 * 100.0 / lines.len() as f64
+    };
+    out.push_str(&tr!(
+        "执行过的行: {}% ({} / {})",
+        format!("{:.2}", percent),
+        executed,
+        lines.len()
+    ));
+    out.push('\n');
+    Ok(out)
+}
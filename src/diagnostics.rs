@@ -0,0 +1,276 @@
+// src/diagnostics.rs
+
+//! **诊断信息 (Diagnostics)**
+//!
+//! 前端各阶段在发现错误或警告的地方直接构造 [`Diagnostic`]，带上位置和与输出语言无关的代码：
+//! 错误的代码是类别（`lexical-error`、`syntax-error`、`semantic-error`、`limit-exceeded`、
+//! `internal-error`），警告的代码是控制它的 `-W` 名字。其余阶段仍以字符串报告错误，
+//! 它们成为没有位置和代码的诊断。语法分析从错误中恢复后报告的每个错误都是单独的一条诊断
+//! （[`Errors`]）。
+//!
+//! 给人看的文本由 `Display` 拼出：`行:列: 信息 [-W代码]`（头文件中为 `文件:行:列: `）；
+//! `--diagnostics-format json` 时每条诊断输出为一行 JSON (JSON Lines)：
+//!
+//! ```json
+//! {"file":"a.c","range":{"start":{"line":3,"column":5},"end":{"line":3,"column":5}},"severity":"warning","code":"unused-parameter","message":"...","notes":[]}
+//! ```
+//!
+//! 没有位置的诊断 `range` 为 `null`，没有代码的诊断 `code` 为 `null`。
+//!
+//! `-Werror` / `-Werror=<名字>` 在这里把警告提升为错误（[`promote`]），所以任何编译阶段报告的
+//! 警告都受它控制；提升后的诊断以错误输出，编译在生成代码之前失败（[`check_promoted`]）。
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::common::Span;
+use crate::i18n::tr;
+
+/// 诊断信息的输出格式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum DiagnosticsFormat {
+    /// 给人看的文本
+    #[default]
+    Human,
+    /// 每条诊断一行 JSON，写到标准输出；同时关闭其他所有输出
+    Json,
+}
+
+thread_local! {
+    /// `--diagnostics-format json` 时诊断所属的源文件；`None` 表示输出给人看的文本
+    static JSON_FILE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// 之后的诊断以 JSON 格式输出，`file` 是它们所属的源文件。
+pub fn set_json_output(file: &Path) {
+    JSON_FILE.with(|f| *f.borrow_mut() = Some(file.display().to_string()));
+}
+
+/// 以 JSON 格式输出时返回诊断所属的源文件。
+pub fn json_output() -> Option<String> {
+    JSON_FILE.with(|f| f.borrow().clone())
+}
+
+/// 哪些警告被当作错误。
+#[derive(Debug, Clone, Default)]
+pub struct WarningsAsErrors {
+    /// `-Werror`：所有警告
+    pub all: bool,
+    /// `-Werror=<名字>`：这些代码的警告
+    pub codes: Vec<String>,
+}
+
+thread_local! {
+    static WARNINGS_AS_ERRORS: RefCell<WarningsAsErrors> = RefCell::default();
+    /// 已经被提升为错误的警告数量
+    static PROMOTED: Cell<usize> = const { Cell::new(0) };
+}
+
+pub fn set_warnings_as_errors(policy: WarningsAsErrors) {
+    WARNINGS_AS_ERRORS.with(|p| *p.borrow_mut() = policy);
+}
+
+/// 按 `-Werror` 的设置把警告提升为错误。
+pub fn promote(mut diagnostic: Diagnostic) -> Diagnostic {
+    let promoted = WARNINGS_AS_ERRORS.with(|p| {
+        let p = p.borrow();
+        p.all
+            || diagnostic
+                .code
+                .as_ref()
+                .is_some_and(|c| p.codes.contains(c))
+    });
+    if diagnostic.severity == Severity::Warning && promoted {
+        diagnostic.severity = Severity::Error;
+        diagnostic.promoted = true;
+        PROMOTED.with(|n| n.set(n.get() + 1));
+    }
+    diagnostic
+}
+
+/// 有警告被提升为错误时编译失败。
+pub fn check_promoted() -> Result<(), String> {
+    match PROMOTED.with(Cell::get) {
+        0 => Ok(()),
+        n => Err(tr!("{} 个警告被视为错误 (-Werror)", n)),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    fn name(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// 一条错误或警告。
+///
+/// 由报告它的编译阶段直接构造：位置和代码在那里就已知，不需要从文本中解析。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub span: Option<Span>,
+    /// 警告对应的 `-W` 名字（例如 `unused-parameter`），或者错误的类别（例如 `syntax-error`）；
+    /// 与输出语言无关
+    pub code: Option<String>,
+    pub message: String,
+    pub notes: Vec<String>,
+    /// 由 `-Werror` 从警告提升而来
+    pub promoted: bool,
+}
+
+impl Diagnostic {
+    pub fn error(message: String) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            span: None,
+            code: None,
+            message,
+            notes: Vec::new(),
+            promoted: false,
+        }
+    }
+
+    pub fn warning(message: String) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Diagnostic::error(message)
+        }
+    }
+
+    pub fn with_code(mut self, code: &str) -> Self {
+        self.code = Some(code.to_string());
+        self
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// 一行 JSON，不带换行符。位置在头文件中时 `file` 是头文件，否则是 `source_file`。
+    pub fn to_json(&self, source_file: &str) -> String {
+        let file = match self.span.and_then(|span| span.file) {
+            Some(header) => header.as_str(),
+            None => source_file,
+        };
+        let range = self.span.map(|span| {
+            let position = Position {
+                line: span.line,
+                column: span.column,
+            };
+            Range {
+                start: position,
+                end: position,
+            }
+        });
+        let record = JsonDiagnostic {
+            file,
+            range,
+            severity: self.severity.name(),
+            code: self.code.as_deref(),
+            message: &self.message,
+            notes: &self.notes,
+        };
+        serde_json::to_string(&record).expect("诊断总能序列化为 JSON")
+    }
+}
+
+/// 没有位置和代码的错误，例如无法读取文件。第一行之后的各行成为附注。
+impl From<String> for Diagnostic {
+    fn from(message: String) -> Self {
+        let mut lines = message.lines();
+        let first = lines.next().unwrap_or_default().to_string();
+        Diagnostic {
+            notes: lines.map(str::to_string).collect(),
+            ..Diagnostic::error(first)
+        }
+    }
+}
+
+/// 给人看的文本：`位置: 信息 [-W代码]`，附注各占一行。
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(span) = self.span {
+            write!(f, "{}: ", span)?;
+        }
+        write!(f, "{}", self.message)?;
+        match (&self.code, self.severity, self.promoted) {
+            (Some(code), Severity::Warning, _) => write!(f, " [-W{}]", code)?,
+            (Some(code), Severity::Error, true) => write!(f, " [-Werror={}]", code)?,
+            _ => {}
+        }
+        for note in &self.notes {
+            write!(f, "\n{}", note)?;
+        }
+        Ok(())
+    }
+}
+
+/// 一次编译失败时的所有错误，例如语法分析从错误中恢复后报告的每一个错误。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Errors(pub Vec<Diagnostic>);
+
+impl From<Diagnostic> for Errors {
+    fn from(diagnostic: Diagnostic) -> Self {
+        Errors(vec![diagnostic])
+    }
+}
+
+impl From<Vec<Diagnostic>> for Errors {
+    fn from(diagnostics: Vec<Diagnostic>) -> Self {
+        Errors(diagnostics)
+    }
+}
+
+impl From<String> for Errors {
+    fn from(message: String) -> Self {
+        Errors(vec![Diagnostic::from(message)])
+    }
+}
+
+/// 每个错误占一行（附注另起一行）。
+impl fmt::Display for Errors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, diagnostic) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", diagnostic)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    file: &'a str,
+    range: Option<Range>,
+    severity: &'static str,
+    code: Option<&'a str>,
+    message: &'a str,
+    notes: &'a [String],
+}
+
+#[derive(Serialize)]
+struct Range {
+    start: Position,
+    end: Position,
+}
+
+#[derive(Clone, Copy, Serialize)]
+struct Position {
+    line: usize,
+    column: usize,
+}
@@ -0,0 +1,238 @@
+// src/difftest.rs
+
+//! **与 gcc 的差分测试 (Differential testing)**
+//!
+//! 该模块实现 `--difftest` 驱动模式：把同一份源文件分别用本编译器和 `gcc -O0` 编译，
+//! 以相同的命令行参数和标准输入运行两个可执行文件，比较退出码（或终止信号）和标准输出。
+//! 任何不一致都说明本编译器在某个阶段生成了错误的代码。
+//!
+//! `source_file` 可以是单个 `.c` 文件，也可以是目录（递归查找 `.c` 文件）。
+//! 标准输入来自 `--run-stdin` 指定的文件；没有指定时，使用与源文件同名的 `.in` 文件
+//! （如果存在），否则为空。
+//!
+//! 与 `--bench` 一样，所有中间文件都写入系统临时目录；源文件所在的目录作为 `-I` 传给两边的编译，
+//! 以便找到它旁边的头文件。某个文件无法编译或运行时记录下来，继续比较其余的文件。
+
+use std::fs;
+use std::os::unix::process::ExitStatusExt;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+
+use crate::grade::{collect_sources, include_source_dir, run_with_timeout};
+use crate::i18n::tr;
+
+/// 一个可执行文件的运行结果。
+#[derive(Debug, PartialEq, Eq)]
+struct Outcome {
+    /// 正常退出时的退出码；被信号终止时为 `None`
+    exit_code: Option<i32>,
+    /// 终止进程的信号
+    signal: Option<i32>,
+    stdout: Vec<u8>,
+}
+
+impl Outcome {
+    fn from_status(status: ExitStatus, stdout: Vec<u8>) -> Self {
+        Outcome {
+            exit_code: status.code(),
+            signal: status.signal(),
+            stdout,
+        }
+    }
+
+    fn describe_exit(&self) -> String {
+        match (self.exit_code, self.signal) {
+            (Some(code), _) => code.to_string(),
+            (None, Some(signal)) => tr!("信号 {}", signal),
+            (None, None) => "?".to_string(),
+        }
+    }
+}
+
+/// 运行两边程序时使用的参数和输入。
+pub struct RunInput<'a> {
+    pub args: &'a [String],
+    pub stdin: Option<&'a Path>,
+}
+
+/// `--difftest` 模式的入口。`compiler_args` 会原样传给本编译器的每次编译。
+pub fn run_difftest(path: &Path, compiler_args: &[String], input: &RunInput) -> Result<(), String> {
+    let sources = if path.is_dir() {
+        let mut sources = Vec::new();
+        collect_sources(path, &mut sources)?;
+        sources
+    } else {
+        vec![path.to_path_buf()]
+    };
+    if sources.is_empty() {
+        return Err(tr!("在 {} 中没有找到任何 .c 文件", path.display()));
+    }
+
+    let compiler = std::env::current_exe().map_err(|e| tr!("无法定位编译器自身: {}", e))?;
+    let work_dir = std::env::temp_dir().join(format!("ccompiler-difftest-{}", std::process::id()));
+    fs::create_dir_all(&work_dir).map_err(|e| tr!("无法创建临时目录: {}", e))?;
+
+    println!("{}", tr!("--- 差分测试: {} 个文件 ---", sources.len()));
+    let mut divergent = Vec::new();
+    // 无法完成比较的文件（例如本编译器拒绝了它），不影响其余文件的比较
+    let mut failed = Vec::new();
+    for (index, source) in sources.iter().enumerate() {
+        match difftest_file(&compiler, compiler_args, input, source, &work_dir, index) {
+            Ok(None) => println!("   {:<10} {}", "same", source.display()),
+            Ok(Some(report)) => {
+                println!("   {:<10} {}", "DIFFERENT", source.display());
+                for line in &report {
+                    println!("      {}", line);
+                }
+                divergent.push(source.display().to_string());
+            }
+            Err(e) => {
+                println!("   {:<10} {}", "FAILED", source.display());
+                for line in e.lines() {
+                    println!("      {}", line);
+                }
+                failed.push(source.display().to_string());
+            }
+        }
+    }
+    // 清理临时目录
+    let _ = fs::remove_dir_all(&work_dir);
+
+    if divergent.is_empty() && failed.is_empty() {
+        println!(
+            "{}",
+            tr!(
+                "\n✅ 差分测试完成: {} 个文件的行为与 gcc 一致",
+                sources.len()
+            )
+        );
+        return Ok(());
+    }
+    let mut problems = Vec::new();
+    if !divergent.is_empty() {
+        problems.push(tr!(
+            "{} 个文件的行为与 gcc 不一致: {}",
+            divergent.len(),
+            divergent.join(", ")
+        ));
+    }
+    if !failed.is_empty() {
+        problems.push(tr!(
+            "{} 个文件无法完成比较: {}",
+            failed.len(),
+            failed.join(", ")
+        ));
+    }
+    Err(problems.join("; "))
+}
+
+/// 比较单个文件；一致时返回 `None`，否则返回描述差异的若干行。
+fn difftest_file(
+    compiler: &Path,
+    compiler_args: &[String],
+    input: &RunInput,
+    source: &Path,
+    work_dir: &Path,
+    index: usize,
+) -> Result<Option<Vec<String>>, String> {
+    let stem = source
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stdin = match input.stdin {
+        Some(file) => fs::read(file).map_err(|e| tr!("无法读取 {}: {}", file.display(), e))?,
+        None => fs::read(source.with_extension("in")).unwrap_or_default(),
+    };
+
+    // 本编译器的中间文件生成在源文件旁边，所以先复制到临时目录
+    let copy = work_dir.join(format!("{}-{}.c", index, stem));
+    fs::copy(source, &copy).map_err(|e| tr!("无法复制源文件: {}", e))?;
+    let ours_exe = copy.with_extension("ccompiler");
+    let gcc_exe = copy.with_extension("gcc");
+
+    let include_dir = include_source_dir(source);
+    let output = Command::new(compiler)
+        .arg(&copy)
+        .arg("-c")
+        .arg(&include_dir)
+        .args(compiler_args)
+        .output()
+        .map_err(|e| tr!("无法运行编译器: {}", e))?;
+    if !output.status.success() {
+        return Err(tr!(
+            "ccompiler 编译失败: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    link(
+        Command::new("gcc")
+            .arg(copy.with_extension("o"))
+            .arg("-o")
+            .arg(&ours_exe),
+        &tr!("gcc 链接失败"),
+    )?;
+    // `-I`/`-D`/`-U` 也要传给 gcc，两边才是同一个程序
+    let preprocessor_options = compiler_args
+        .iter()
+        .filter(|arg| ["-I", "-D", "-U"].iter().any(|flag| arg.starts_with(flag)));
+    link(
+        Command::new("gcc")
+            .arg("-O0")
+            .arg(&include_dir)
+            .args(preprocessor_options)
+            .arg(&copy)
+            .arg("-o")
+            .arg(&gcc_exe),
+        &tr!("gcc 编译失败"),
+    )?;
+
+    let ours = run(&ours_exe, input.args, stdin.clone())?;
+    let gcc = run(&gcc_exe, input.args, stdin)?;
+    if ours == gcc {
+        return Ok(None);
+    }
+
+    let mut report = Vec::new();
+    if (ours.exit_code, ours.signal) != (gcc.exit_code, gcc.signal) {
+        report.push(tr!(
+            "退出码不一致: ccompiler 为 {}, gcc 为 {}",
+            ours.describe_exit(),
+            gcc.describe_exit()
+        ));
+    }
+    if ours.stdout != gcc.stdout {
+        report.push(tr!(
+            "标准输出不一致: ccompiler 输出 {} 字节, gcc 输出 {} 字节",
+            ours.stdout.len(),
+            gcc.stdout.len()
+        ));
+        report.push(format!(
+            "ccompiler: {:?}",
+            String::from_utf8_lossy(&ours.stdout)
+        ));
+        report.push(format!(
+            "gcc:       {:?}",
+            String::from_utf8_lossy(&gcc.stdout)
+        ));
+    }
+    Ok(Some(report))
+}
+
+fn link(cmd: &mut Command, what: &str) -> Result<(), String> {
+    let output = cmd.output().map_err(|e| format!("{}: {}", what, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{}: {}",
+            what,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+fn run(exe: &Path, args: &[String], stdin: Vec<u8>) -> Result<Outcome, String> {
+    match run_with_timeout(exe, args, stdin)? {
+        Some((status, stdout)) => Ok(Outcome::from_status(status, stdout)),
+        None => Err(tr!("'{}' 运行超时", exe.display())),
+    }
+}
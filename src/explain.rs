@@ -0,0 +1,203 @@
+// src/explain.rs
+
+//! **编译过程讲解页面 (`--explain`)**
+//!
+//! 把一次编译的各个阶段并排放进一个自包含的 HTML 页面：源代码、token 列表、AST、
+//! TACKY IR 和最终的汇编代码。页面不依赖任何外部资源，可以直接用浏览器打开或者
+//! 作为在线演示的后端输出。
+//!
+//! 鼠标悬停时交叉高亮对应的内容：
+//! -   源代码的一行 ↔ 该行的 token ↔ 在该行声明的顶层声明的 AST（按 `Span` 的行号）
+//! -   TACKY 指令 ↔ 由它展开出的汇编指令（按汇编 AST 中记录的来源下标）
+//! -   同一个函数在 AST、TACKY 和汇编中的标题
+
+use std::fmt::Write;
+
+use crate::backend::code_gen::CodeGenerator;
+use crate::backend::{assembly_ast, tacky_ir};
+use crate::common::AstNode;
+use crate::frontend::c_ast::{self, Declaration};
+use crate::frontend::lexer::Token;
+use crate::i18n::tr;
+
+/// 生成讲解页面所需的各阶段产物。
+pub struct Stages<'a> {
+    pub source: &'a str,
+    pub tokens: &'a [Token],
+    pub ast: &'a c_ast::Program,
+    pub tacky: &'a tacky_ir::Program,
+    pub asm: &'a assembly_ast::Program,
+}
+
+/// 生成完整的 HTML 页面。
+pub fn render(stages: &Stages, code_generator: &CodeGenerator) -> String {
+    let panes = [
+        (tr!("源代码"), source_pane(stages.source)),
+        (tr!("Token"), tokens_pane(stages.tokens)),
+        (tr!("AST"), ast_pane(stages.ast)),
+        (tr!("TACKY IR"), tacky_pane(stages.tacky)),
+        (tr!("汇编"), asm_pane(stages.asm, code_generator)),
+    ];
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(
+        html,
+        "<title>{}</title>",
+        escape(&tr!("ccompiler 编译过程"))
+    );
+    let _ = writeln!(html, "<style>{}</style>\n</head>\n<body>", STYLE);
+    html.push_str("<main>\n");
+    for (title, body) in panes {
+        let _ = writeln!(
+            html,
+            "<section>\n<h2>{}</h2>\n<pre>{}</pre>\n</section>",
+            escape(&title),
+            body
+        );
+    }
+    html.push_str("</main>\n");
+    let _ = writeln!(html, "<script>{}</script>\n</body>\n</html>", SCRIPT);
+    html
+}
+
+fn source_pane(source: &str) -> String {
+    source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            format!(
+                "<div data-line=\"{}\"><span class=\"no\">{:>4}</span> {}</div>",
+                i + 1,
+                i + 1,
+                escape(line)
+            )
+        })
+        .collect()
+}
+
+fn tokens_pane(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| {
+            let position = token.span.to_string();
+            let kind = format!("{:?}", token.type_);
+            format!(
+                "<div data-line=\"{}\"><span class=\"no\">{:>8}</span> {:<16} {}</div>",
+                token.span.line,
+                position,
+                kind,
+                escape(&token.lexeme)
+            )
+        })
+        .collect()
+}
+
+/// 每个顶层声明单独打印，并标记声明所在的行。
+fn ast_pane(ast: &c_ast::Program) -> String {
+    ast.declarations
+        .iter()
+        .map(|decl| {
+            let (name, span) = match decl {
+                Declaration::Fun(f) => (&f.name, f.span),
+                Declaration::Variable(v) => (&v.name, v.span),
+            };
+            format!(
+                "<div data-line=\"{}\" data-fn=\"{}\">{}</div>",
+                span.line,
+                escape(name.as_str()),
+                escape(&ast.arena.with(decl).pretty().to_string())
+            )
+        })
+        .collect()
+}
+
+fn tacky_pane(tacky: &tacky_ir::Program) -> String {
+    let mut out = String::new();
+    for function in &tacky.functions {
+        let params: Vec<&str> = function.params.iter().map(|p| p.as_str()).collect();
+        let _ = write!(
+            out,
+            "<div class=\"fn\" data-fn=\"{0}\">{0}({1}):</div>",
+            escape(function.name.as_str()),
+            escape(&params.join(", "))
+        );
+        for (i, instruction) in function.body.iter().enumerate() {
+            let _ = write!(
+                out,
+                "<div data-tacky=\"{}:{}\">    {}</div>",
+                escape(function.name.as_str()),
+                i,
+                escape(&instruction.to_string())
+            );
+        }
+        // 函数之间空一行
+        out.push('\n');
+    }
+    out
+}
+
+fn asm_pane(asm: &assembly_ast::Program, code_generator: &CodeGenerator) -> String {
+    let mut out = String::new();
+    for function in &asm.functions {
+        for (line, origin) in code_generator.function_listing(function) {
+            let attr = match origin {
+                Some(i) => format!(" data-tacky=\"{}:{}\"", escape(function.name.as_str()), i),
+                // 函数标签所在的行作为函数标题
+                None if line == format!("{}:", function.name) => {
+                    format!(
+                        " class=\"fn\" data-fn=\"{}\"",
+                        escape(function.name.as_str())
+                    )
+                }
+                None => String::new(),
+            };
+            let _ = write!(out, "<div{}>{}</div>", attr, escape(&line));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const STYLE: &str = "
+body { margin: 0; font-family: sans-serif; }
+main { display: grid; grid-template-columns: repeat(5, minmax(22rem, 1fr)); height: 100vh; overflow-x: auto; }
+section { display: flex; flex-direction: column; min-height: 0; border-right: 1px solid #ccc; }
+h2 { margin: 0; padding: 0.4rem 0.6rem; font-size: 1rem; background: #f0f0f0; }
+pre { margin: 0; padding: 0.4rem 0.6rem; overflow: auto; flex: 1; font-size: 0.8rem; }
+pre div { white-space: pre; min-height: 1em; }
+.no { color: #999; }
+.fn { font-weight: bold; }
+.hl { background: #ffe58a; }
+";
+
+/// 悬停时高亮所有具有相同 `data-line`、`data-tacky` 或 `data-fn` 值的元素。
+const SCRIPT: &str = "
+const keys = ['line', 'tacky', 'fn'];
+document.querySelectorAll('pre div').forEach(el => {
+  el.addEventListener('mouseenter', () => {
+    for (const key of keys) {
+      const value = el.dataset[key];
+      if (value === undefined) continue;
+      document.querySelectorAll(`[data-${key}=\"${CSS.escape(value)}\"]`)
+        .forEach(other => other.classList.add('hl'));
+    }
+  });
+  el.addEventListener('mouseleave', () => {
+    document.querySelectorAll('.hl').forEach(other => other.classList.remove('hl'));
+  });
+});
+";
@@ -0,0 +1,86 @@
+// src/features.rs
+
+//! **语言特性清单 (`--features`)**
+//!
+//! 列出本编译器支持和尚不支持的 C 语言特性。书中章节对应的特性带有章节号，
+//! 测试脚本可以根据 `ccompiler --version --features` 的输出（或者直接调用
+//! [`supported_chapters`]）自动选择要运行哪些章节的测试。
+//!
+//! 只有整个编译流程（一直到汇编输出）都实现了的特性才算支持。例如文件作用域变量
+//! 和 `static` 变量能通过语义分析，但代码生成还不输出数据段，所以第 10 章标记为不支持。
+
+use crate::i18n::tr;
+
+/// 一项语言特性。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Feature {
+    /// 稳定的特性名，供脚本匹配
+    pub name: &'static str,
+    /// 书中引入该特性的章节；书外的扩展为 `None`
+    pub chapter: Option<u32>,
+    pub supported: bool,
+}
+
+const fn feature(name: &'static str, chapter: Option<u32>, supported: bool) -> Feature {
+    Feature {
+        name,
+        chapter,
+        supported,
+    }
+}
+
+/// 所有已知特性，书中章节按章节号排列，扩展放在最后。
+pub const FEATURES: &[Feature] = &[
+    feature("return-int", Some(1), true),
+    feature("unary-operators", Some(2), true),
+    feature("binary-operators", Some(3), true),
+    feature("logical-relational", Some(4), true),
+    feature("local-variables", Some(5), true),
+    feature("if-conditional", Some(6), true),
+    feature("compound-statements", Some(7), true),
+    feature("loops", Some(8), true),
+    feature("functions", Some(9), true),
+    feature("file-scope-storage-classes", Some(10), false),
+    feature("long", Some(11), false),
+    feature("unsigned", Some(12), false),
+    feature("double", Some(13), false),
+    feature("pointers", Some(14), false),
+    feature("arrays", Some(15), false),
+    feature("chars-strings", Some(16), false),
+    feature("sizeof-void", Some(17), false),
+    feature("structs", Some(18), false),
+    feature("bool", None, true),
+    feature("noreturn", None, true),
+    feature("compound-assignment", None, false),
+    feature("increment-decrement", None, false),
+    feature("bitwise-operators", None, false),
+    feature("goto", None, false),
+    feature("switch", None, false),
+    feature("offsetof", None, false),
+];
+
+/// 该章节的所有特性都支持的章节号，按升序排列。
+pub fn supported_chapters() -> Vec<u32> {
+    let mut chapters: Vec<u32> = FEATURES.iter().filter_map(|f| f.chapter).collect();
+    chapters.dedup();
+    chapters.retain(|&c| {
+        FEATURES
+            .iter()
+            .filter(|f| f.chapter == Some(c))
+            .all(|f| f.supported)
+    });
+    chapters
+}
+
+/// 打印特性表，最后一行列出完整支持的章节。
+pub fn print_report() {
+    println!("{}", tr!("--- 支持的语言特性 ---"));
+    println!("{:<28} {:>7} {:>9}", "feature", "chapter", "supported");
+    for f in FEATURES {
+        let chapter = f.chapter.map_or("-".to_string(), |c| c.to_string());
+        let supported = if f.supported { "yes" } else { "no" };
+        println!("{:<28} {:>7} {:>9}", f.name, chapter, supported);
+    }
+    let chapters: Vec<String> = supported_chapters().iter().map(|c| c.to_string()).collect();
+    println!("chapters: {}", chapters.join(" "));
+}
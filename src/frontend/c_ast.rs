@@ -1,16 +1,113 @@
 // src/frontend/c_ast.rs
 
-use crate::common::{AstNode, PrettyPrinter};
+use crate::common::{AstNode, PrettyPrinter, Span, Symbol};
 use std::fmt;
+use std::ops::{Index, IndexMut};
 
 #[derive(Debug, Clone)]
 pub struct Program {
     pub declarations: Vec<Declaration>,
+    /// 程序中所有表达式和语句节点的存储
+    pub arena: AstArena,
+}
+
+/// 表达式在 `AstArena` 中的下标。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExprId(u32);
+
+/// 语句在 `AstArena` 中的下标。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StmtId(u32);
+
+/// AST 节点的存储区 (arena)。
+///
+/// 表达式和语句按分配顺序连续存放在两个 `Vec` 中，父节点通过 `ExprId` / `StmtId`
+/// 引用子节点，而不是各自 `Box` 一次。解析大文件时的分配是连续的，释放整棵树只需要
+/// 释放这两个 `Vec`，不会沿着深层嵌套的表达式逐个节点递归 drop。以后需要从子节点
+/// 找到父节点时，也只需要再加一张按下标索引的表。
+///
+/// 节点一经分配就不会移动或删除；各个阶段需要修改 AST 时，直接按下标原地替换节点。
+#[derive(Debug, Clone, Default)]
+pub struct AstArena {
+    exprs: Vec<Expression>,
+    stmts: Vec<Statement>,
+    /// 每个表达式的位置，与 `exprs` 一一对应：二元、赋值和条件表达式是运算符的位置，
+    /// 其余表达式是第一个 token 的位置
+    expr_spans: Vec<Span>,
+    /// 每条语句第一个 token 的位置，与 `stmts` 一一对应
+    stmt_spans: Vec<Span>,
+}
+
+impl AstArena {
+    pub fn new() -> Self {
+        AstArena::default()
+    }
+
+    pub fn alloc_expr(&mut self, exp: Expression, span: Span) -> ExprId {
+        self.exprs.push(exp);
+        self.expr_spans.push(span);
+        ExprId(self.exprs.len() as u32 - 1)
+    }
+
+    pub fn alloc_stmt(&mut self, stmt: Statement, span: Span) -> StmtId {
+        self.stmts.push(stmt);
+        self.stmt_spans.push(span);
+        StmtId(self.stmts.len() as u32 - 1)
+    }
+
+    /// 表达式在源文件中的位置。
+    pub fn expr_span(&self, id: ExprId) -> Span {
+        self.expr_spans[id.0 as usize]
+    }
+
+    /// 语句在源文件中的位置。
+    pub fn stmt_span(&self, id: StmtId) -> Span {
+        self.stmt_spans[id.0 as usize]
+    }
+
+    /// 把节点和存储区放在一起，以便美化打印单个节点，例如 `arena.with(&decl).pretty()`。
+    pub fn with<'a, T>(&'a self, node: &'a T) -> InArena<'a, T> {
+        InArena { arena: self, node }
+    }
+}
+
+impl Index<ExprId> for AstArena {
+    type Output = Expression;
+
+    fn index(&self, id: ExprId) -> &Expression {
+        &self.exprs[id.0 as usize]
+    }
+}
+
+impl IndexMut<ExprId> for AstArena {
+    fn index_mut(&mut self, id: ExprId) -> &mut Expression {
+        &mut self.exprs[id.0 as usize]
+    }
+}
+
+impl Index<StmtId> for AstArena {
+    type Output = Statement;
+
+    fn index(&self, id: StmtId) -> &Statement {
+        &self.stmts[id.0 as usize]
+    }
+}
+
+impl IndexMut<StmtId> for AstArena {
+    fn index_mut(&mut self, id: StmtId) -> &mut Statement {
+        &mut self.stmts[id.0 as usize]
+    }
+}
+
+/// `AstArena::with` 返回的适配器：节点本身不包含子节点，打印时需要从存储区中取出。
+pub struct InArena<'a, T> {
+    arena: &'a AstArena,
+    node: &'a T,
 }
 
 #[derive(Debug, Clone)]
 pub enum BlockItem {
-    S(Statement),
+    S(StmtId),
     D(Declaration),
 }
 
@@ -22,17 +119,38 @@ pub enum Declaration {
 
 #[derive(Debug, Clone)]
 pub struct FunDecl {
-    pub name: String,
-    pub parameters: Vec<String>,
+    pub name: Symbol,
+    pub parameters: Vec<Symbol>,
+    /// 与 `parameters` 一一对应的参数类型
+    pub param_types: Vec<Type>,
+    /// 旧式的 `int f()` 声明：参数未指定（区别于 `int f(void)`）
+    pub unspecified_params: bool,
+    pub ret_type: Type,
     pub body: Option<Block>,
     pub storage_class: Option<StorageClass>,
+    /// 带有 `_Noreturn` 或 `__attribute__((noreturn))`：函数不会返回到调用者
+    pub noreturn: bool,
+    /// 函数名在源文件中的位置
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
 pub struct VarDecl {
-    pub name: String,
-    pub init: Option<Expression>,
+    pub name: Symbol,
+    pub init: Option<ExprId>,
+    pub var_type: Type,
     pub storage_class: Option<StorageClass>,
+    /// 带有 `volatile` 限定符：每次读写都必须真正访问变量，不能被删除、合并或重排
+    pub volatile: bool,
+    /// 变量名在源文件中的位置
+    pub span: Span,
+}
+
+/// 声明中的类型说明符。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    Int,
+    Bool,
 }
 #[derive(Debug, Clone)]
 pub enum StorageClass {
@@ -45,75 +163,122 @@ pub struct Block(pub Vec<BlockItem>);
 
 #[derive(Debug, Clone)]
 pub enum ForInit {
-    InitDecl(VarDecl),
-    InitExp(Option<Expression>),
+    /// `for (int i = 0, j = n; ...)`：声明列表中的每个变量，作用域都是整个循环
+    InitDecl(Vec<VarDecl>),
+    InitExp(Option<ExprId>),
 }
 
 #[derive(Debug, Clone)]
 pub enum Statement {
-    Return(Expression),
-    Expression(Expression),
+    Return(ExprId),
+    Expression(ExprId),
     Null,
     If {
-        condition: Expression,
-        then_stmt: Box<Statement>,
-        else_stmt: Option<Box<Statement>>,
+        condition: ExprId,
+        then_stmt: StmtId,
+        else_stmt: Option<StmtId>,
     },
     Compound(Block),
-    Break(String),
-    Continue(String),
+    /// 标签在循环标签解析阶段填入，指向包含它的最内层循环
+    Break(Option<LoopLabels>),
+    Continue(Option<LoopLabels>),
     While {
-        condition: Expression,
-        body: Box<Statement>,
-        label: Option<String>,
+        condition: ExprId,
+        body: StmtId,
+        label: Option<LoopLabels>,
     },
     DoWhile {
-        body: Box<Statement>,
-        condition: Expression,
-        label: Option<String>,
+        body: StmtId,
+        condition: ExprId,
+        label: Option<LoopLabels>,
     },
     For {
         init: ForInit,
-        condition: Option<Expression>,
-        post: Option<Expression>,
-        body: Box<Statement>,
+        condition: Option<ExprId>,
+        post: Option<ExprId>,
+        body: StmtId,
+        label: Option<LoopLabels>,
+    },
+    /// `name: stmt`，`goto` 的目标。标签名的作用域是整个函数 (C11 6.2.1p3)
+    Labeled {
+        label: Symbol,
+        stmt: StmtId,
+    },
+    /// `case value: stmt`。`label` 是 TACKY 中的跳转目标，在找到所属的 `switch` 之后填入
+    Case {
+        value: ExprId,
+        stmt: StmtId,
+        label: Option<String>,
+    },
+    /// `default: stmt`，`label` 同 [`Statement::Case`]
+    Default {
+        stmt: StmtId,
         label: Option<String>,
     },
 }
-#[derive(Debug, Clone)]
+/// 循环标签解析为一个循环生成的名字和跳转目标。
+///
+/// 三个跳转标签都由循环的名字派生，TACKY 生成直接使用这里的标签，
+/// 不再自己拼接 `break`/`continue` 标签的字符串。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopLabels {
+    /// 循环的唯一名字，例如 `main.loop.0`
+    pub name: String,
+    /// 循环体的第一条指令（`do-while` 和 `for` 跳回这里）
+    pub start_label: String,
+    /// `continue` 的目标
+    pub continue_label: String,
+    /// `break` 的目标，紧跟在整个循环之后
+    pub break_label: String,
+}
+
+impl LoopLabels {
+    pub fn new(name: String) -> Self {
+        LoopLabels {
+            start_label: format!("start.{}", name),
+            continue_label: format!("continue.{}", name),
+            break_label: format!("break.{}", name),
+            name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Expression {
     Constant(i64),
     Unary {
         op: UnaryOp,
-        exp: Box<Expression>,
+        exp: ExprId,
     },
     Binary {
         op: BinaryOp,
-        left: Box<Expression>,
-        right: Box<Expression>,
+        left: ExprId,
+        right: ExprId,
     },
-    Var(String),
+    Var(Symbol),
     Assignment {
-        left: Box<Expression>,
-        right: Box<Expression>,
+        left: ExprId,
+        right: ExprId,
     },
     Conditional {
-        condition: Box<Expression>,
-        left: Box<Expression>,
-        right: Box<Expression>,
+        condition: ExprId,
+        left: ExprId,
+        right: ExprId,
     },
     FuncCall {
-        name: String,
-        args: Vec<Expression>,
+        name: Symbol,
+        args: Vec<ExprId>,
     },
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum UnaryOp {
     Complement,
     Negate,
     Not,
+    /// 一元 `+`：只对操作数做整数提升
+    Plus,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum BinaryOp {
     Add,
     Subtract,
@@ -129,12 +294,49 @@ pub enum BinaryOp {
     Less,
     Greater,
 }
+impl AstArena {
+    /// 求值是否可能产生副作用（赋值、函数调用或者读取 `volatile` 变量）。
+    /// `is_volatile` 判断一个（解析后的）变量名是否带有 `volatile` 限定符。
+    ///
+    /// 不产生副作用的表达式可以在值未被使用时删除，或被安全地重复/重排求值。
+    pub fn has_side_effects(&self, exp: ExprId, is_volatile: &impl Fn(Symbol) -> bool) -> bool {
+        match &self[exp] {
+            Expression::Constant(_) => false,
+            Expression::Var(name) => is_volatile(*name),
+            Expression::Assignment { .. } | Expression::FuncCall { .. } => true,
+            Expression::Unary { exp, .. } => self.has_side_effects(*exp, is_volatile),
+            Expression::Binary { left, right, .. } => {
+                self.has_side_effects(*left, is_volatile)
+                    || self.has_side_effects(*right, is_volatile)
+            }
+            Expression::Conditional {
+                condition,
+                left,
+                right,
+            } => {
+                self.has_side_effects(*condition, is_volatile)
+                    || self.has_side_effects(*left, is_volatile)
+                    || self.has_side_effects(*right, is_volatile)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Int => write!(f, "int"),
+            Type::Bool => write!(f, "_Bool"),
+        }
+    }
+}
 impl fmt::Display for UnaryOp {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             UnaryOp::Complement => write!(f, "~"),
             UnaryOp::Negate => write!(f, "-"),
             UnaryOp::Not => write!(f, "!"),
+            UnaryOp::Plus => write!(f, "+"),
         }
     }
 }
@@ -163,118 +365,130 @@ impl AstNode for Program {
         printer.writeln("Program").unwrap();
         printer.indent();
         for function in &self.declarations {
-            function.pretty_print(printer);
+            self.arena.with(function).pretty_print(printer);
         }
         printer.unindent();
     }
 }
 
-impl AstNode for FunDecl {
+impl AstNode for InArena<'_, FunDecl> {
     fn pretty_print(&self, printer: &mut PrettyPrinter) {
-        let params_str = if self.parameters.is_empty() {
+        let decl = self.node;
+        let params_str = if decl.unspecified_params {
+            String::new()
+        } else if decl.parameters.is_empty() {
             "void".to_string()
         } else {
-            self.parameters.join(", ")
+            decl.parameters
+                .iter()
+                .zip(&decl.param_types)
+                .map(|(name, t)| format!("{} {}", t, name))
+                .collect::<Vec<_>>()
+                .join(", ")
         };
-        let storage_str = match &self.storage_class {
+        let storage_str = match &decl.storage_class {
             Some(StorageClass::Static) => ", storage: static",
             Some(StorageClass::Extern) => ", storage: extern",
             None => "", // 如果没有，就不打印
         };
+        let noreturn_str = if decl.noreturn { ", noreturn" } else { "" };
 
-        if let Some(body) = &self.body {
+        if let Some(body) = &decl.body {
             printer
                 .writeln(&format!(
-                    "FunctionDefinition(name: \"{}\", params: [{}]{})",
-                    self.name, params_str, storage_str
+                    "FunctionDefinition(name: \"{}\", returns: {}, params: [{}]{}{})",
+                    decl.name, decl.ret_type, params_str, storage_str, noreturn_str
                 ))
                 .unwrap();
             printer.indent();
-            body.pretty_print(printer);
+            self.arena.with(body).pretty_print(printer);
             printer.unindent();
         } else {
             printer
                 .writeln(&format!(
-                    "FunctionDeclaration(name: \"{}\", params: [{}]{})",
-                    self.name, params_str, storage_str
+                    "FunctionDeclaration(name: \"{}\", returns: {}, params: [{}]{}{})",
+                    decl.name, decl.ret_type, params_str, storage_str, noreturn_str
                 ))
                 .unwrap();
         }
     }
 }
 
-impl AstNode for VarDecl {
+impl AstNode for InArena<'_, VarDecl> {
     fn pretty_print(&self, printer: &mut PrettyPrinter) {
-        let storage_str = match &self.storage_class {
+        let decl = self.node;
+        let storage_str = match &decl.storage_class {
             Some(StorageClass::Static) => ", storage: static",
             Some(StorageClass::Extern) => ", storage: extern",
             None => "",
         };
 
-        if let Some(init_expr) = &self.init {
+        if let Some(init_expr) = &decl.init {
             // 2. 修改带初始值的打印
             printer
                 .writeln(&format!(
-                    "VarDeclaration(name: \"{}\"{}, with init)",
-                    self.name, storage_str
+                    "VarDeclaration(name: \"{}\", type: {}{}, with init)",
+                    decl.name, decl.var_type, storage_str
                 ))
                 .unwrap();
             printer.indent();
-            init_expr.pretty_print(printer);
+            self.arena.with(init_expr).pretty_print(printer);
             printer.unindent();
         } else {
             // 3. 修改不带初始值的打印
             printer
                 .writeln(&format!(
-                    "VarDeclaration(name: \"{}\"{})",
-                    self.name, storage_str
+                    "VarDeclaration(name: \"{}\", type: {}{})",
+                    decl.name, decl.var_type, storage_str
                 ))
                 .unwrap();
         }
     }
 }
 
-impl AstNode for Declaration {
+impl AstNode for InArena<'_, Declaration> {
     fn pretty_print(&self, printer: &mut PrettyPrinter) {
-        match self {
-            Declaration::Fun(fun_decl) => fun_decl.pretty_print(printer),
-            Declaration::Variable(var_decl) => var_decl.pretty_print(printer),
+        match self.node {
+            Declaration::Fun(fun_decl) => self.arena.with(fun_decl).pretty_print(printer),
+            Declaration::Variable(var_decl) => self.arena.with(var_decl).pretty_print(printer),
         }
     }
 }
 
-impl AstNode for Block {
+impl AstNode for InArena<'_, Block> {
     fn pretty_print(&self, printer: &mut PrettyPrinter) {
         printer.writeln("Block").unwrap();
         printer.indent();
-        for item in &self.0 {
-            item.pretty_print(printer);
+        for item in &self.node.0 {
+            self.arena.with(item).pretty_print(printer);
         }
         printer.unindent();
     }
 }
-impl AstNode for BlockItem {
+impl AstNode for InArena<'_, BlockItem> {
     fn pretty_print(&self, printer: &mut PrettyPrinter) {
-        match self {
-            BlockItem::S(s) => s.pretty_print(printer),
-            BlockItem::D(d) => d.pretty_print(printer),
+        match self.node {
+            BlockItem::S(s) => self.arena.with(s).pretty_print(printer),
+            BlockItem::D(d) => self.arena.with(d).pretty_print(printer),
         }
     }
 }
-impl AstNode for ForInit {
+impl AstNode for InArena<'_, ForInit> {
     fn pretty_print(&self, printer: &mut PrettyPrinter) {
-        match self {
-            ForInit::InitDecl(decl) => {
+        match self.node {
+            ForInit::InitDecl(decls) => {
                 printer.writeln("ForInitDecl").unwrap();
                 printer.indent();
-                decl.pretty_print(printer);
+                for decl in decls {
+                    self.arena.with(decl).pretty_print(printer);
+                }
                 printer.unindent();
             }
             ForInit::InitExp(opt_expr) => {
                 printer.writeln("ForInitExp").unwrap();
                 printer.indent();
                 if let Some(expr) = opt_expr {
-                    expr.pretty_print(printer);
+                    self.arena.with(expr).pretty_print(printer);
                 } else {
                     printer.writeln("EmptyInit").unwrap();
                 }
@@ -283,19 +497,20 @@ impl AstNode for ForInit {
         }
     }
 }
-impl AstNode for Statement {
+impl AstNode for InArena<'_, StmtId> {
     fn pretty_print(&self, printer: &mut PrettyPrinter) {
-        match self {
+        let arena = self.arena;
+        match &arena[*self.node] {
             Statement::Return(expr) => {
                 printer.writeln("Return").unwrap();
                 printer.indent();
-                expr.pretty_print(printer);
+                arena.with(expr).pretty_print(printer);
                 printer.unindent();
             }
             Statement::Expression(e) => {
                 printer.writeln("ExpressionStatement").unwrap();
                 printer.indent();
-                e.pretty_print(printer);
+                arena.with(e).pretty_print(printer);
                 printer.unindent();
             }
             Statement::Null => {
@@ -310,16 +525,16 @@ impl AstNode for Statement {
                 printer.indent();
                 printer.writeln("Condition").unwrap();
                 printer.indent();
-                condition.pretty_print(printer);
+                arena.with(condition).pretty_print(printer);
                 printer.unindent();
                 printer.writeln("Then").unwrap();
                 printer.indent();
-                then_stmt.pretty_print(printer);
+                arena.with(then_stmt).pretty_print(printer);
                 printer.unindent();
                 if let Some(else_s) = else_stmt {
                     printer.writeln("Else").unwrap();
                     printer.indent();
-                    else_s.pretty_print(printer);
+                    arena.with(else_s).pretty_print(printer);
                     printer.unindent();
                 }
                 printer.unindent();
@@ -327,17 +542,19 @@ impl AstNode for Statement {
             Statement::Compound(b) => {
                 printer.writeln("CompoundStatement").unwrap();
                 printer.indent();
-                b.pretty_print(printer);
+                arena.with(b).pretty_print(printer);
                 printer.unindent();
             }
             Statement::Break(label) => {
+                let label_str = label.as_ref().map_or("unlabeled", |l| &l.name);
                 printer
-                    .writeln(&format!("BreakStatement(->{})", label))
+                    .writeln(&format!("BreakStatement(->{})", label_str))
                     .unwrap();
             }
             Statement::Continue(label) => {
+                let label_str = label.as_ref().map_or("unlabeled", |l| &l.name);
                 printer
-                    .writeln(&format!("ContinueStatement(->{})", label))
+                    .writeln(&format!("ContinueStatement(->{})", label_str))
                     .unwrap();
             }
             Statement::While {
@@ -345,18 +562,18 @@ impl AstNode for Statement {
                 body,
                 label,
             } => {
-                let label_str = label.as_deref().unwrap_or("unlabeled");
+                let label_str = label.as_ref().map_or("unlabeled", |l| &l.name);
                 printer
                     .writeln(&format!("WhileStatement(label:{})", label_str))
                     .unwrap();
                 printer.indent();
                 printer.writeln("Condition").unwrap();
                 printer.indent();
-                condition.pretty_print(printer);
+                arena.with(condition).pretty_print(printer);
                 printer.unindent();
                 printer.writeln("Body").unwrap();
                 printer.indent();
-                body.pretty_print(printer);
+                arena.with(body).pretty_print(printer);
                 printer.unindent();
                 printer.unindent();
             }
@@ -365,18 +582,18 @@ impl AstNode for Statement {
                 condition,
                 label,
             } => {
-                let label_str = label.as_deref().unwrap_or("unlabeled");
+                let label_str = label.as_ref().map_or("unlabeled", |l| &l.name);
                 printer
                     .writeln(&format!("DoWhileStatement(label:{})", label_str))
                     .unwrap();
                 printer.indent();
                 printer.writeln("Body").unwrap();
                 printer.indent();
-                body.pretty_print(printer);
+                arena.with(body).pretty_print(printer);
                 printer.unindent();
                 printer.writeln("Condition").unwrap();
                 printer.indent();
-                condition.pretty_print(printer);
+                arena.with(condition).pretty_print(printer);
                 printer.unindent();
                 printer.unindent();
             }
@@ -387,19 +604,19 @@ impl AstNode for Statement {
                 body,
                 label,
             } => {
-                let label_str = label.as_deref().unwrap_or("unlabeled");
+                let label_str = label.as_ref().map_or("unlabeled", |l| &l.name);
                 printer
                     .writeln(&format!("ForStatement(label:{})", label_str))
                     .unwrap();
                 printer.indent();
                 printer.writeln("Init").unwrap();
                 printer.indent();
-                init.pretty_print(printer);
+                arena.with(init).pretty_print(printer);
                 printer.unindent();
                 printer.writeln("Condition").unwrap();
                 printer.indent();
                 if let Some(cond_expr) = condition {
-                    cond_expr.pretty_print(printer);
+                    arena.with(cond_expr).pretty_print(printer);
                 } else {
                     printer.writeln("EmptyCondition").unwrap();
                 }
@@ -407,38 +624,72 @@ impl AstNode for Statement {
                 printer.writeln("Post-Expression").unwrap();
                 printer.indent();
                 if let Some(post_expr) = post {
-                    post_expr.pretty_print(printer);
+                    arena.with(post_expr).pretty_print(printer);
                 } else {
                     printer.writeln("EmptyPostExpression").unwrap();
                 }
                 printer.unindent();
                 printer.writeln("Body").unwrap();
                 printer.indent();
-                body.pretty_print(printer);
+                arena.with(body).pretty_print(printer);
                 printer.unindent();
                 printer.unindent();
             }
+            Statement::Labeled { label, stmt } => {
+                printer
+                    .writeln(&format!("LabeledStatement(label: \"{}\")", label))
+                    .unwrap();
+                printer.indent();
+                arena.with(stmt).pretty_print(printer);
+                printer.unindent();
+            }
+            Statement::Case { value, stmt, label } => {
+                let label_str = label.as_deref().unwrap_or("unlabeled");
+                printer
+                    .writeln(&format!("CaseStatement(label:{})", label_str))
+                    .unwrap();
+                printer.indent();
+                printer.writeln("Value").unwrap();
+                printer.indent();
+                arena.with(value).pretty_print(printer);
+                printer.unindent();
+                printer.writeln("Body").unwrap();
+                printer.indent();
+                arena.with(stmt).pretty_print(printer);
+                printer.unindent();
+                printer.unindent();
+            }
+            Statement::Default { stmt, label } => {
+                let label_str = label.as_deref().unwrap_or("unlabeled");
+                printer
+                    .writeln(&format!("DefaultStatement(label:{})", label_str))
+                    .unwrap();
+                printer.indent();
+                arena.with(stmt).pretty_print(printer);
+                printer.unindent();
+            }
         }
     }
 }
 // Expression 的实现保持不变，它是正确的
-impl AstNode for Expression {
+impl AstNode for InArena<'_, ExprId> {
     fn pretty_print(&self, printer: &mut PrettyPrinter) {
-        match self {
+        let arena = self.arena;
+        match &arena[*self.node] {
             Expression::Constant(value) => {
                 printer.writeln(&format!("Constant({})", value)).unwrap();
             }
             Expression::Unary { op, exp } => {
                 printer.writeln(&format!("Unary(op: '{}')", op)).unwrap();
                 printer.indent();
-                exp.pretty_print(printer);
+                arena.with(exp).pretty_print(printer);
                 printer.unindent();
             }
             Expression::Binary { op, left, right } => {
                 printer.writeln(&format!("Binary(op: '{}')", op)).unwrap();
                 printer.indent();
-                left.pretty_print(printer);
-                right.pretty_print(printer);
+                arena.with(left).pretty_print(printer);
+                arena.with(right).pretty_print(printer);
                 printer.unindent();
             }
             Expression::Var(n) => {
@@ -447,8 +698,8 @@ impl AstNode for Expression {
             Expression::Assignment { left, right } => {
                 printer.writeln("Assignment(op: '=')").unwrap();
                 printer.indent();
-                left.pretty_print(printer);
-                right.pretty_print(printer);
+                arena.with(left).pretty_print(printer);
+                arena.with(right).pretty_print(printer);
                 printer.unindent();
             }
             Expression::Conditional {
@@ -460,15 +711,15 @@ impl AstNode for Expression {
                 printer.indent();
                 printer.writeln("Condition").unwrap();
                 printer.indent();
-                condition.pretty_print(printer);
+                arena.with(condition).pretty_print(printer);
                 printer.unindent();
                 printer.writeln("Then").unwrap();
                 printer.indent();
-                left.pretty_print(printer);
+                arena.with(left).pretty_print(printer);
                 printer.unindent();
                 printer.writeln("Else").unwrap();
                 printer.indent();
-                right.pretty_print(printer);
+                arena.with(right).pretty_print(printer);
                 printer.unindent();
                 printer.unindent();
             }
@@ -483,7 +734,7 @@ impl AstNode for Expression {
                     printer.writeln("NoArguments").unwrap();
                 } else {
                     for arg in args {
-                        arg.pretty_print(printer);
+                        arena.with(arg).pretty_print(printer);
                     }
                 }
                 printer.unindent();
@@ -0,0 +1,134 @@
+// src/frontend/const_eval.rs
+
+//! **编译期常量表达式求值**
+//!
+//! 对只由整数常量和运算符组成的表达式求值，用于静态存储期变量的初始值。
+//! 运算按目标平台 `int` 的语义进行（LP64 和 ILP32 中都是 32 位）：
+//! -   除以零（`/` 和 `%`）是错误，不会被折叠成任意值
+//! -   有符号溢出（包括 `INT_MIN / -1` 和超出 `int` 范围的字面量）按二进制补码回绕，
+//!     并记录下来，由调用者决定是否报告 `-Woverflow` 警告
+//! -   `&&`、`||` 和 `?:` 与运行时一样短路，不会对未被求值的操作数报告错误
+
+use crate::common::DataLayout;
+use crate::frontend::c_ast::{AstArena, BinaryOp, ExprId, Expression, UnaryOp};
+
+/// 常量表达式求值失败的原因。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstEvalError {
+    /// 表达式中包含变量、赋值或函数调用
+    NotConstant,
+    /// 表达式中出现了除以零或对零取余
+    DivisionByZero,
+}
+
+/// 常量表达式的值。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstValue {
+    /// 回绕到 `int` 范围之后的值
+    pub value: i64,
+    /// 求值过程中是否发生了有符号溢出
+    pub overflowed: bool,
+}
+
+/// 以目标平台 `int` 的语义对常量表达式求值。
+pub fn eval_int(
+    expr: ExprId,
+    arena: &AstArena,
+    layout: &DataLayout,
+) -> Result<ConstValue, ConstEvalError> {
+    let mut evaluator = Evaluator {
+        arena,
+        int_bits: layout.int_size * 8,
+        overflowed: false,
+    };
+    let value = evaluator.eval(expr)?;
+    Ok(ConstValue {
+        value: evaluator.wrap(value),
+        overflowed: evaluator.overflowed,
+    })
+}
+
+struct Evaluator<'a> {
+    arena: &'a AstArena,
+    /// `int` 的位数
+    int_bits: i64,
+    /// 求值过程中是否发生了有符号溢出
+    overflowed: bool,
+}
+
+impl Evaluator<'_> {
+    /// 把结果截断到 `int` 范围，超出范围时记录溢出。
+    fn wrap(&mut self, value: i128) -> i64 {
+        let shift = 128 - self.int_bits;
+        let wrapped = (value << shift) >> shift;
+        if wrapped != value {
+            self.overflowed = true;
+        }
+        wrapped as i64
+    }
+
+    /// 求值并截断到 `int` 范围。
+    fn eval_wrapped(&mut self, expr: ExprId) -> Result<i128, ConstEvalError> {
+        let value = self.eval(expr)?;
+        Ok(i128::from(self.wrap(value)))
+    }
+
+    fn eval(&mut self, expr: ExprId) -> Result<i128, ConstEvalError> {
+        let arena = self.arena;
+        match &arena[expr] {
+            Expression::Constant(c) => Ok(i128::from(*c)),
+            Expression::Unary { op, exp } => {
+                let v = self.eval_wrapped(*exp)?;
+                let result = match op {
+                    UnaryOp::Negate => -v,
+                    UnaryOp::Complement => !v,
+                    UnaryOp::Not => (v == 0) as i128,
+                    UnaryOp::Plus => v,
+                };
+                Ok(i128::from(self.wrap(result)))
+            }
+            Expression::Binary { op, left, right } => {
+                let l = self.eval_wrapped(*left)?;
+                // 逻辑运算符短路：右操作数不被求值时，其中的错误也不会被报告
+                match op {
+                    BinaryOp::And if l == 0 => return Ok(0),
+                    BinaryOp::Or if l != 0 => return Ok(1),
+                    _ => {}
+                }
+                let r = self.eval_wrapped(*right)?;
+                let result = match op {
+                    BinaryOp::Add => l + r,
+                    BinaryOp::Subtract => l - r,
+                    BinaryOp::Multiply => l * r,
+                    BinaryOp::Divide | BinaryOp::Remainder if r == 0 => {
+                        return Err(ConstEvalError::DivisionByZero);
+                    }
+                    BinaryOp::Divide => l / r,
+                    BinaryOp::Remainder => l % r,
+                    BinaryOp::And | BinaryOp::Or => (r != 0) as i128,
+                    BinaryOp::EqualEqual => (l == r) as i128,
+                    BinaryOp::BangEqual => (l != r) as i128,
+                    BinaryOp::LessEqual => (l <= r) as i128,
+                    BinaryOp::GreaterEqual => (l >= r) as i128,
+                    BinaryOp::Less => (l < r) as i128,
+                    BinaryOp::Greater => (l > r) as i128,
+                };
+                Ok(i128::from(self.wrap(result)))
+            }
+            Expression::Conditional {
+                condition,
+                left,
+                right,
+            } => {
+                if self.eval_wrapped(*condition)? != 0 {
+                    self.eval(*left)
+                } else {
+                    self.eval(*right)
+                }
+            }
+            Expression::Var(_) | Expression::Assignment { .. } | Expression::FuncCall { .. } => {
+                Err(ConstEvalError::NotConstant)
+            }
+        }
+    }
+}
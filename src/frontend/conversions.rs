@@ -0,0 +1,72 @@
+// src/frontend/conversions.rs
+
+//! **整数提升和寻常算术转换 (C11 6.3.1.1, 6.3.1.8)**
+//!
+//! 类型检查器对每个二元运算符都通过 [`binary_result_type`] 求出操作数转换到的公共类型和
+//! 结果类型，TACKY 生成在读取变量和函数返回值时通过 [`promote`] 决定是否需要零扩展，
+//! 不再在各处各自判断 `_Bool`。
+//!
+//! 目前只有 `int` 和 `_Bool` 两种整数类型，`_Bool` 是无符号类型，但它的所有取值都能用
+//! `int` 表示，整数提升后总是 `int`。以后加入 `long`、`unsigned` 等类型时，只需要扩展这里的
+//! 等级和符号性，以及提升后符号不同的情况。
+
+use crate::frontend::c_ast::BinaryOp;
+use crate::frontend::type_checking::CType;
+
+/// 整数转换等级 (C11 6.3.1.1p1)：`_Bool` 最低。函数类型不是整数类型，返回 `None`。
+pub fn rank(t: &CType) -> Option<u32> {
+    match t {
+        CType::Bool => Some(0),
+        CType::Int => Some(1),
+        CType::FunType { .. } => None,
+    }
+}
+
+/// 整数类型是否有符号。`_Bool` 是无符号类型 (C11 6.2.5p6)。
+pub fn is_signed(t: &CType) -> bool {
+    matches!(t, CType::Int)
+}
+
+/// 整数提升 (C11 6.3.1.1p2)：等级不高于 `int` 的类型，如果 `int` 能表示它的所有取值，
+/// 提升为 `int`。
+pub fn promote(t: &CType) -> CType {
+    match rank(t) {
+        Some(r) if r <= rank(&CType::Int).unwrap() => CType::Int,
+        _ => t.clone(),
+    }
+}
+
+/// 寻常算术转换 (C11 6.3.1.8p1)：两个操作数先各自做整数提升，然后转换到公共类型。
+pub fn usual_arithmetic_conversions(a: &CType, b: &CType) -> CType {
+    let (a, b) = (promote(a), promote(b));
+    if a == b {
+        return a;
+    }
+    // 提升后的类型目前都是有符号的 `int`，符号相同时等级高的一方就是公共类型
+    debug_assert_eq!(
+        is_signed(&a),
+        is_signed(&b),
+        "还不支持有符号和无符号混合的运算"
+    );
+    if rank(&a) >= rank(&b) { a } else { b }
+}
+
+/// 二元运算的结果类型：比较和逻辑运算的结果总是 `int` (C11 6.5.8p6, 6.5.9p3, 6.5.13p3)，
+/// 算术运算的结果是寻常算术转换得到的公共类型。
+pub fn binary_result_type(op: &BinaryOp, left: &CType, right: &CType) -> CType {
+    match op {
+        BinaryOp::Add
+        | BinaryOp::Subtract
+        | BinaryOp::Multiply
+        | BinaryOp::Divide
+        | BinaryOp::Remainder => usual_arithmetic_conversions(left, right),
+        BinaryOp::And
+        | BinaryOp::Or
+        | BinaryOp::EqualEqual
+        | BinaryOp::BangEqual
+        | BinaryOp::LessEqual
+        | BinaryOp::GreaterEqual
+        | BinaryOp::Less
+        | BinaryOp::Greater => CType::Int,
+    }
+}
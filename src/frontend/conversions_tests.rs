@@ -0,0 +1,89 @@
+// src/frontend/conversions_tests.rs
+
+//! 整数提升和寻常算术转换的穷举测试：对所有整数类型和类型对、所有二元运算符检查结果。
+
+use crate::frontend::c_ast::BinaryOp;
+use crate::frontend::conversions::{
+    binary_result_type, is_signed, promote, rank, usual_arithmetic_conversions,
+};
+use crate::frontend::type_checking::CType;
+
+const INTEGER_TYPES: [CType; 2] = [CType::Int, CType::Bool];
+
+const ARITHMETIC: [BinaryOp; 5] = [
+    BinaryOp::Add,
+    BinaryOp::Subtract,
+    BinaryOp::Multiply,
+    BinaryOp::Divide,
+    BinaryOp::Remainder,
+];
+
+const COMPARISON_AND_LOGICAL: [BinaryOp; 8] = [
+    BinaryOp::And,
+    BinaryOp::Or,
+    BinaryOp::EqualEqual,
+    BinaryOp::BangEqual,
+    BinaryOp::LessEqual,
+    BinaryOp::GreaterEqual,
+    BinaryOp::Less,
+    BinaryOp::Greater,
+];
+
+#[test]
+fn ranks_and_signedness() {
+    assert!(rank(&CType::Bool) < rank(&CType::Int));
+    assert!(is_signed(&CType::Int));
+    assert!(!is_signed(&CType::Bool));
+    let function = CType::FunType {
+        params: Vec::new(),
+        ret: Box::new(CType::Int),
+        unspecified_params: false,
+    };
+    assert_eq!(rank(&function), None);
+    assert_eq!(promote(&function), function);
+}
+
+#[test]
+fn integer_promotions() {
+    assert_eq!(promote(&CType::Bool), CType::Int);
+    assert_eq!(promote(&CType::Int), CType::Int);
+    for t in &INTEGER_TYPES {
+        // 提升是幂等的，结果的等级不低于 int
+        assert_eq!(promote(&promote(t)), promote(t));
+        assert!(rank(&promote(t)) >= rank(&CType::Int));
+    }
+}
+
+#[test]
+fn usual_arithmetic_conversions_of_every_pair() {
+    for a in &INTEGER_TYPES {
+        for b in &INTEGER_TYPES {
+            let common = usual_arithmetic_conversions(a, b);
+            assert_eq!(common, CType::Int, "{} 和 {}", a, b);
+            assert_eq!(common, usual_arithmetic_conversions(b, a));
+            assert_eq!(
+                common,
+                usual_arithmetic_conversions(&promote(a), &promote(b))
+            );
+        }
+    }
+}
+
+#[test]
+fn binary_result_types_of_every_operator() {
+    for a in &INTEGER_TYPES {
+        for b in &INTEGER_TYPES {
+            for op in &ARITHMETIC {
+                assert_eq!(
+                    binary_result_type(op, a, b),
+                    usual_arithmetic_conversions(a, b),
+                    "{:?}",
+                    op
+                );
+            }
+            for op in &COMPARISON_AND_LOGICAL {
+                assert_eq!(binary_result_type(op, a, b), CType::Int, "{:?}", op);
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+// src/frontend/coverage.rs
+
+//! **语句覆盖率插桩 (`--coverage`)**
+//!
+//! 在语法分析之后、标识符解析之前改写 AST：源文件中的每条语句（复合语句除外）按出现顺序
+//! 得到一个编号 `i`，语句被原地替换成 `{ __ccov_hit(i); <原来的语句> }`，并在文件作用域
+//! 声明 `int __ccov_hit(int index);`。
+//!
+//! 代码生成还不输出数据段，所以计数器数组和 `__ccov_hit` 都定义在链接进来的 C 运行时代码中，
+//! 程序退出时由它把计数写入文件。插入的只是普通的函数调用，之后的各个阶段不需要知道插桩的
+//! 存在。头文件中的语句不插桩。
+
+use crate::common::{Span, Symbol};
+use crate::frontend::c_ast::{
+    AstArena, Block, BlockItem, Declaration, Expression, FunDecl, Program, Statement, StmtId, Type,
+};
+
+/// 给计数器加一的运行时函数
+pub const HIT_FUNCTION: &str = "__ccov_hit";
+
+/// 插桩整个程序，返回按编号排列的语句位置（即覆盖率数据中的行号表）。
+pub fn instrument(program: &mut Program) -> Vec<Span> {
+    let mut stmts = Vec::new();
+    for decl in &program.declarations {
+        if let Declaration::Fun(f) = decl
+            && let Some(body) = &f.body
+        {
+            collect_block(&program.arena, body, &mut stmts);
+        }
+    }
+
+    let hit = Symbol::intern(HIT_FUNCTION);
+    let spans: Vec<Span> = stmts
+        .iter()
+        .enumerate()
+        .map(|(index, &stmt)| {
+            let span = program.arena.stmt_span(stmt);
+            hit_before(&mut program.arena, stmt, hit, index, span);
+            span
+        })
+        .collect();
+    program.declarations.insert(
+        0,
+        Declaration::Fun(FunDecl {
+            name: hit,
+            parameters: vec![Symbol::intern("index")],
+            param_types: vec![Type::Int],
+            unspecified_params: false,
+            ret_type: Type::Int,
+            body: None,
+            storage_class: None,
+            noreturn: false,
+            span: Span::default(),
+        }),
+    );
+    spans
+}
+
+/// 按源码顺序收集需要插桩的语句。
+fn collect_block(arena: &AstArena, block: &Block, out: &mut Vec<StmtId>) {
+    for item in &block.0 {
+        if let BlockItem::S(s) = item {
+            collect_statement(arena, *s, out);
+        }
+    }
+}
+
+fn collect_statement(arena: &AstArena, stmt: StmtId, out: &mut Vec<StmtId>) {
+    if arena.stmt_span(stmt).file.is_some() {
+        return;
+    }
+    if !matches!(arena[stmt], Statement::Compound(_)) {
+        out.push(stmt);
+    }
+    match &arena[stmt] {
+        Statement::Compound(b) => collect_block(arena, b, out),
+        Statement::If {
+            then_stmt,
+            else_stmt,
+            ..
+        } => {
+            collect_statement(arena, *then_stmt, out);
+            if let Some(s) = else_stmt {
+                collect_statement(arena, *s, out);
+            }
+        }
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. } => collect_statement(arena, *body, out),
+        Statement::Labeled { stmt, .. }
+        | Statement::Case { stmt, .. }
+        | Statement::Default { stmt, .. } => collect_statement(arena, *stmt, out),
+        Statement::Return(_)
+        | Statement::Expression(_)
+        | Statement::Null
+        | Statement::Break(_)
+        | Statement::Continue(_) => {}
+    }
+}
+
+/// 把 `stmt` 替换成先调用 `__ccov_hit(index)`、再执行原来语句的复合语句。
+/// 原来的语句移到新分配的节点中，父节点中的 `StmtId` 保持不变。
+fn hit_before(arena: &mut AstArena, stmt: StmtId, hit: Symbol, index: usize, span: Span) {
+    let original = arena.alloc_stmt(arena[stmt].clone(), span);
+    let index = arena.alloc_expr(Expression::Constant(index as i64), span);
+    let call = arena.alloc_expr(
+        Expression::FuncCall {
+            name: hit,
+            args: vec![index],
+        },
+        span,
+    );
+    let hit = arena.alloc_stmt(Statement::Expression(call), span);
+    arena[stmt] = Statement::Compound(Block(vec![BlockItem::S(hit), BlockItem::S(original)]));
+}
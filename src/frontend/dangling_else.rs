@@ -0,0 +1,84 @@
+// src/frontend/dangling_else.rs
+
+//! **悬空 else (`-Wdangling-else`)**
+//!
+//! `if (a) if (b) s1; else s2;` 中的 `else` 属于最近的、还没有 `else` 的 `if`，也就是内层的
+//! `if (b)` (C11 6.8.4.1p3)，语法分析器在解析内层 `if` 时就贪心地取走了它。缩进常常让人以为
+//! 它属于外层的 `if`，所以这个警告在语法分析之后的 AST 上查找这种写法，建议加上花括号。
+//!
+//! 外层 `if` 没有 `else`、它的分支（可以隔着几层没有花括号的循环）是一个带 `else` 的 `if` 时
+//! 报告警告，位置是外层 `if`。外层 `if` 有自己的 `else` 时没有歧义。
+
+use crate::diagnostics::Diagnostic;
+use crate::frontend::c_ast::{AstArena, Block, BlockItem, Declaration, Program, Statement, StmtId};
+use crate::i18n::tr;
+
+/// 返回程序中所有悬空 `else` 的警告，按源码顺序排列。
+pub fn find_dangling_else(program: &Program) -> Vec<Diagnostic> {
+    let mut warnings = Vec::new();
+    for decl in &program.declarations {
+        if let Declaration::Fun(f) = decl
+            && let Some(body) = &f.body
+        {
+            check_block(&program.arena, body, &mut warnings);
+        }
+    }
+    warnings
+}
+
+fn check_block(arena: &AstArena, block: &Block, warnings: &mut Vec<Diagnostic>) {
+    for item in &block.0 {
+        if let BlockItem::S(s) = item {
+            check_statement(arena, *s, warnings);
+        }
+    }
+}
+
+fn check_statement(arena: &AstArena, stmt: StmtId, warnings: &mut Vec<Diagnostic>) {
+    match &arena[stmt] {
+        Statement::If {
+            then_stmt,
+            else_stmt,
+            ..
+        } => {
+            if else_stmt.is_none() && ends_with_if_else(arena, *then_stmt) {
+                warnings.push(
+                    Diagnostic::warning(tr!("建议加上花括号，明确 else 属于哪个 if"))
+                        .with_code("dangling-else")
+                        .with_span(arena.stmt_span(stmt)),
+                );
+            }
+            check_statement(arena, *then_stmt, warnings);
+            if let Some(s) = else_stmt {
+                check_statement(arena, *s, warnings);
+            }
+        }
+        Statement::Compound(b) => check_block(arena, b, warnings),
+        Statement::While { body, .. }
+        | Statement::DoWhile { body, .. }
+        | Statement::For { body, .. } => check_statement(arena, *body, warnings),
+        Statement::Labeled { stmt, .. }
+        | Statement::Case { stmt, .. }
+        | Statement::Default { stmt, .. } => check_statement(arena, *stmt, warnings),
+        Statement::Return(_)
+        | Statement::Expression(_)
+        | Statement::Null
+        | Statement::Break(_)
+        | Statement::Continue(_) => {}
+    }
+}
+
+/// 没有花括号的语句 `stmt` 是否以带 `else` 的 `if` 结尾，也就是后面的 `else` 本可以
+/// 属于外层的 `if`。
+fn ends_with_if_else(arena: &AstArena, stmt: StmtId) -> bool {
+    match &arena[stmt] {
+        Statement::If {
+            else_stmt: Some(_), ..
+        } => true,
+        // `if (a) while (c) if (b) x; else y;` 同样有歧义
+        Statement::While { body, .. } | Statement::For { body, .. } => {
+            ends_with_if_else(arena, *body)
+        }
+        _ => false,
+    }
+}
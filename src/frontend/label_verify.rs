@@ -0,0 +1,122 @@
+// src/frontend/label_verify.rs
+
+//! **循环标签检查 (`--verify-labels`)**
+//!
+//! 在循环标签解析之后检查 AST，确认标签满足 TACKY 生成所依赖的约束:
+//! -   每个 `while`/`do-while`/`for` 都带有标签，并且整个程序中没有重复的标签
+//! -   每个 `break`/`continue` 都带有标签，并且这个标签就是包含它的最内层循环的标签
+//!
+//! 违反约束说明循环标签解析有 bug。错标的 `break` 会静默地跳出错误的循环，
+//! 这里把它变成指向具体函数和标签的内部编译器错误 (ICE)。
+
+use std::collections::HashSet;
+
+use crate::diagnostics::Diagnostic;
+use crate::frontend::c_ast::{
+    AstArena, Block, BlockItem, Declaration, LoopLabels, Program, Statement, StmtId,
+};
+use crate::i18n::tr;
+
+/// 检查整个程序，返回遇到的第一个违反约束的错误。
+pub fn verify_program(program: &Program) -> Result<(), Diagnostic> {
+    let mut verifier = LabelVerifier {
+        arena: &program.arena,
+        seen: HashSet::new(),
+        enclosing: Vec::new(),
+    };
+    for decl in &program.declarations {
+        if let Declaration::Fun(f) = decl
+            && let Some(body) = &f.body
+        {
+            verifier.verify_block(body).map_err(|reason| {
+                Diagnostic::error(tr!(
+                    "内部编译器错误 (ICE): 函数 '{}' 的循环标签有误: {}",
+                    f.name,
+                    reason
+                ))
+                .with_code("internal-error")
+                .with_span(f.span)
+            })?;
+        }
+    }
+    Ok(())
+}
+
+struct LabelVerifier<'a> {
+    arena: &'a AstArena,
+    /// 已经出现过的循环标签
+    seen: HashSet<&'a str>,
+    /// 包含当前语句的循环的标签，最内层在栈顶
+    enclosing: Vec<&'a LoopLabels>,
+}
+
+impl<'a> LabelVerifier<'a> {
+    fn verify_block(&mut self, block: &'a Block) -> Result<(), String> {
+        for item in &block.0 {
+            if let BlockItem::S(s) = item {
+                self.verify_statement(*s)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn verify_statement(&mut self, stmt: StmtId) -> Result<(), String> {
+        match &self.arena[stmt] {
+            Statement::While { label, body, .. }
+            | Statement::DoWhile { label, body, .. }
+            | Statement::For { label, body, .. } => {
+                let Some(label) = label else {
+                    return Err(tr!("循环语句没有标签"));
+                };
+                if !self.seen.insert(&label.name) {
+                    return Err(tr!("循环标签 '{}' 重复", label.name));
+                }
+                self.enclosing.push(label);
+                self.verify_statement(*body)?;
+                self.enclosing.pop();
+                Ok(())
+            }
+            Statement::Break(label) => self.verify_jump("break", label),
+            Statement::Continue(label) => self.verify_jump("continue", label),
+            Statement::Compound(b) => self.verify_block(b),
+            Statement::If {
+                then_stmt,
+                else_stmt,
+                ..
+            } => {
+                self.verify_statement(*then_stmt)?;
+                match else_stmt {
+                    Some(s) => self.verify_statement(*s),
+                    None => Ok(()),
+                }
+            }
+            Statement::Labeled { stmt, .. }
+            | Statement::Case { stmt, .. }
+            | Statement::Default { stmt, .. } => self.verify_statement(*stmt),
+            Statement::Return(_) | Statement::Expression(_) | Statement::Null => Ok(()),
+        }
+    }
+
+    /// `break`/`continue` 的标签必须是最内层循环的标签。
+    fn verify_jump(&self, keyword: &str, label: &Option<LoopLabels>) -> Result<(), String> {
+        let Some(label) = label else {
+            return Err(tr!("'{}' 没有标签", keyword));
+        };
+        match self.enclosing.last() {
+            None => Err(tr!("'{}' 不在任何循环中", keyword)),
+            Some(&innermost) if innermost == label => Ok(()),
+            Some(&innermost) if self.enclosing.contains(&label) => Err(tr!(
+                "'{}' 的标签 '{}' 属于外层循环，而不是最内层的循环 '{}'",
+                keyword,
+                label.name,
+                innermost.name
+            )),
+            Some(&innermost) => Err(tr!(
+                "'{}' 的标签 '{}' 不属于任何包含它的循环（最内层的循环是 '{}'）",
+                keyword,
+                label.name,
+                innermost.name
+            )),
+        }
+    }
+}
@@ -1,9 +1,17 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::common::{CStandard, ResourceLimits, Span, Symbol};
+use crate::diagnostics::Diagnostic;
+use crate::i18n::tr;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum TokenType {
     Identifier,
     Number,
     // Keywords
     Int,
+    Bool, // _Bool (C99 起)
     Void,
     Return,
     If,
@@ -13,8 +21,13 @@ pub enum TokenType {
     For,
     Continue,
     Break,
+    Case,
+    Default,
     Static,
     Extern,
+    Volatile,
+    Noreturn,  // _Noreturn (C11 起)
+    Attribute, // __attribute__ (GNU 扩展)
     // Single-character tokens
     LeftParen,
     RightParen,
@@ -22,7 +35,7 @@ pub enum TokenType {
     RightBrace,
     Semicolon,
     Bang,       // !
-    Negate,     // -
+    Minus,      // - (一元取负或二元减法，由语法分析器根据位置区分)
     Complement, // ~
     Add,        //+
     Mul,        // *
@@ -43,178 +56,227 @@ pub enum TokenType {
     BangEqual,    // !=
     GreaterEqual, // >=
     LessEqual,    // <=
+    // 语法分析器尚不支持，但必须作为一个整体识别，否则 `a+=b` 会被切成 `+` `=`
+    PlusEqual,     // +=
+    Arrow,         // ->
+    LessLessEqual, // <<=
     // End of File
     Eof,
 }
 
+/// 运算符和标点符号。按最长匹配 (maximal munch) 的规则识别：在当前位置尝试表中所有
+/// 条目，取最长的那个。例如 `a==-b` 是 `a` `==` `-` `b`，`a<=-1` 是 `a` `<=` `-` `1`。
+///
+/// 单独的 `&`、`|` 和 `<<` 不在表中（尚不支持），遇到时报告非法字符。
+pub const PUNCTUATORS: &[(&str, TokenType)] = &[
+    ("<<=", TokenType::LessLessEqual),
+    ("--", TokenType::Decrement),
+    ("&&", TokenType::And),
+    ("||", TokenType::Or),
+    ("==", TokenType::EqualEqual),
+    ("!=", TokenType::BangEqual),
+    (">=", TokenType::GreaterEqual),
+    ("<=", TokenType::LessEqual),
+    ("+=", TokenType::PlusEqual),
+    ("->", TokenType::Arrow),
+    ("(", TokenType::LeftParen),
+    (")", TokenType::RightParen),
+    ("{", TokenType::LeftBrace),
+    ("}", TokenType::RightBrace),
+    (";", TokenType::Semicolon),
+    ("!", TokenType::Bang),
+    ("-", TokenType::Minus),
+    ("~", TokenType::Complement),
+    ("+", TokenType::Add),
+    ("*", TokenType::Mul),
+    ("/", TokenType::Div),
+    ("%", TokenType::Remainder),
+    (">", TokenType::Greater),
+    ("<", TokenType::Less),
+    ("=", TokenType::Assignment),
+    ("?", TokenType::QuestionMark),
+    (":", TokenType::Colon),
+    (",", TokenType::Comma),
+];
+
+/// token 附带的值。标识符的名字被驻留，token 只持有它的 [`Symbol`]。
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TokenValue {
+    Identifier(Symbol),
+    Number(i128),
+    /// 关键字和标点符号由 `type_` 完全确定；文件结尾也没有值
+    Keyword,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Token {
     pub lexeme: String,
     pub type_: TokenType,
-    pub value: Option<String>,
+    pub value: TokenValue,
+    /// token 第一个字符在源文件中的位置
+    pub span: Span,
+}
+
+/// 记录当前位置的字符流。
+///
+/// 除了逐个读取字符外，还负责处理预处理器输出的行标记（`# 12 "main.c"`），
+/// 使位置对应原始源文件（或者被包含的头文件）中的文件和行号。
+struct SourceChars<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+    /// 行标记中出现的第一个真实文件，即正在编译的源文件
+    main_file: Option<Symbol>,
+    /// 当前所在的头文件；在源文件中时为 `None`
+    file: Option<Symbol>,
+}
+
+impl<'a> SourceChars<'a> {
+    fn new(input: &'a str) -> Self {
+        SourceChars {
+            chars: input.chars().peekable(),
+            line: 1,
+            column: 1,
+            main_file: None,
+            file: None,
+        }
+    }
+
+    fn peek(&mut self) -> Option<&char> {
+        self.chars.peek()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// 剩余的输入是否以 `text` 开头（不消耗字符）。
+    fn starts_with(&self, text: &str) -> bool {
+        let mut rest = self.chars.clone();
+        text.chars().all(|c| rest.next() == Some(c))
+    }
+
+    fn span(&self) -> Span {
+        Span {
+            line: self.line,
+            column: self.column,
+            file: self.file,
+        }
+    }
+
+    /// 读取以 `#` 开头的一整行。行标记 `# <行号> "<文件名>" ...` 表示下一行是文件
+    /// `<文件名>` 中的第 `<行号>` 行；其他指令（例如 `#pragma`）直接跳过。
+    fn skip_directive(&mut self) {
+        let mut directive = String::new();
+        while let Some(&c) = self.peek() {
+            if c == '\n' {
+                break;
+            }
+            directive.push(c);
+            self.next();
+        }
+        self.next();
+        let marker = directive.trim_start_matches('#').trim_start();
+        let (line, rest) = marker.split_once(' ').unwrap_or((marker, ""));
+        let Ok(line) = line.parse() else {
+            return;
+        };
+        self.line = line;
+        // 文件名中的 `"` 和 `\` 被转义，但它只用于显示，不需要还原
+        if let Some(start) = rest.find('"')
+            && let Some(end) = rest.rfind('"')
+            && start < end
+        {
+            let name = &rest[start + 1..end];
+            let file = Symbol::intern(name);
+            // `<built-in>`、`<command-line>` 这样的伪文件里没有 token，不会成为源文件
+            if self.main_file.is_none() && !name.starts_with('<') {
+                self.main_file = Some(file);
+            }
+            self.file = (Some(file) != self.main_file).then_some(file);
+        }
+    }
 }
 
 #[derive(Debug)]
-pub struct Lexer {}
+pub struct Lexer {
+    /// 语言标准决定了哪些单词是关键字（例如 `_Bool` 从 C99 开始才是关键字）。
+    standard: CStandard,
+    /// 允许产生的 token 数量上限
+    max_tokens: usize,
+}
+
+/// 词法错误：位于 `span` 处的字符不能组成 token。
+fn lexical_error(span: Span, message: String) -> Diagnostic {
+    Diagnostic::error(message)
+        .with_code("lexical-error")
+        .with_span(span)
+}
 
 impl Lexer {
-    pub fn new() -> Self {
-        Lexer {}
+    pub fn with_standard(standard: CStandard) -> Self {
+        Lexer {
+            standard,
+            max_tokens: ResourceLimits::default().max_tokens,
+        }
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = max_tokens;
+        self
     }
 
-    pub fn lex(&self, input: &str) -> Result<Vec<Token>, String> {
+    pub fn lex(&self, input: &str) -> Result<Vec<Token>, Diagnostic> {
         // 使用 Vec::with_capacity 可以略微提高性能，因为我们大概知道会有多少个 token
         let mut tokens = Vec::with_capacity(input.len() / 2);
-        let mut chars = input.chars().peekable();
+        let mut chars = SourceChars::new(input);
 
         while let Some(&c) = chars.peek() {
-            match c {
-                '(' | ')' | '{' | '}' | ';' | '~' | '+' | '*' | '/' | '%' | ':' | '?' | ',' => {
-                    let type_ = match c {
-                        '(' => TokenType::LeftParen,
-                        ')' => TokenType::RightParen,
-                        '{' => TokenType::LeftBrace,
-                        '}' => TokenType::RightBrace,
-                        ';' => TokenType::Semicolon,
-                        '~' => TokenType::Complement,
-                        '+' => TokenType::Add,
-                        '*' => TokenType::Mul,
-                        '/' => TokenType::Div,
-                        '%' => TokenType::Remainder,
-                        '?' => TokenType::QuestionMark,
-                        ':' => TokenType::Colon,
-                        ',' => TokenType::Comma,
-                        _ => unreachable!(),
-                    };
-                    tokens.push(Token {
-                        lexeme: c.to_string(),
-                        type_,
-                        value: None,
-                    });
-                    chars.next();
-                }
-                '-' => {
-                    chars.next();
-                    if let Some('-') = chars.peek() {
-                        chars.next();
-                        tokens.push(Token {
-                            lexeme: "--".to_string(),
-                            type_: TokenType::Decrement,
-                            value: None,
-                        });
-                    } else {
-                        tokens.push(Token {
-                            lexeme: c.to_string(),
-                            type_: TokenType::Negate,
-                            value: None,
-                        });
-                    }
-                }
-                '!' => {
-                    chars.next();
-                    if let Some('=') = chars.peek() {
-                        chars.next();
-                        tokens.push(Token {
-                            lexeme: "!-".to_string(),
-                            type_: TokenType::BangEqual,
-                            value: None,
-                        });
-                    } else {
-                        tokens.push(Token {
-                            lexeme: c.to_string(),
-                            type_: TokenType::Bang,
-                            value: None,
-                        });
-                    }
-                }
-                '>' => {
-                    chars.next();
-                    if let Some('=') = chars.peek() {
-                        chars.next();
-                        tokens.push(Token {
-                            lexeme: ">=".to_string(),
-                            type_: TokenType::GreaterEqual,
-                            value: None,
-                        });
-                    } else {
-                        tokens.push(Token {
-                            lexeme: c.to_string(),
-                            type_: TokenType::Greater,
-                            value: None,
-                        });
-                    }
-                }
-                '<' => {
-                    chars.next();
-                    if let Some('=') = chars.peek() {
-                        chars.next();
-                        tokens.push(Token {
-                            lexeme: "<=".to_string(),
-                            type_: TokenType::LessEqual,
-                            value: None,
-                        });
-                    } else {
-                        tokens.push(Token {
-                            lexeme: c.to_string(),
-                            type_: TokenType::Less,
-                            value: None,
-                        });
-                    }
-                }
-                '&' => {
-                    chars.next();
-                    if let Some('&') = chars.peek() {
-                        chars.next();
-                        tokens.push(Token {
-                            lexeme: "&&".to_string(),
-                            type_: TokenType::And,
-                            value: None,
-                        });
-                    } else {
-                        return Err(format!("Unexpected character: {}", c));
-                    }
-                }
-                '|' => {
-                    chars.next();
-                    if let Some('|') = chars.peek() {
-                        chars.next();
-                        tokens.push(Token {
-                            lexeme: "||".to_string(),
-                            type_: TokenType::Or,
-                            value: None,
-                        });
-                    } else {
-                        return Err(format!("Unexpected character: {}", c));
-                    }
-                }
-                '=' => {
+            let span = chars.span();
+            if tokens.len() > self.max_tokens {
+                return Err(Diagnostic::error(tr!(
+                    "超出资源限制: token 数量超过上限 {} (--max-tokens)",
+                    self.max_tokens
+                ))
+                .with_code("limit-exceeded"));
+            }
+            if let Some((text, type_)) = PUNCTUATORS
+                .iter()
+                .filter(|(text, _)| chars.starts_with(text))
+                .max_by_key(|(text, _)| text.len())
+            {
+                for _ in 0..text.chars().count() {
                     chars.next();
-                    if let Some('=') = chars.peek() {
-                        chars.next();
-                        tokens.push(Token {
-                            lexeme: "==".to_string(),
-                            type_: TokenType::EqualEqual,
-                            value: None,
-                        });
-                    } else {
-                        tokens.push(Token {
-                            lexeme: c.to_string(),
-                            type_: TokenType::Assignment,
-                            value: None,
-                        });
-                    }
                 }
+                tokens.push(Token {
+                    lexeme: text.to_string(),
+                    type_: type_.clone(),
+                    value: TokenValue::Keyword,
+                    span,
+                });
+                continue;
+            }
+            match c {
                 '0'..='9' => {
                     tokens.push(self.lex_number(&mut chars)?);
                 }
                 'a'..='z' | 'A'..='Z' | '_' => {
                     tokens.push(self.lex_identifier(&mut chars));
                 }
+                // 预处理器输出中只有行首会出现 `#`
+                '#' if span.column == 1 => chars.skip_directive(),
                 c if c.is_whitespace() => {
                     chars.next();
                 }
                 _ => {
-                    return Err(format!("Unexpected character: {}", c));
+                    return Err(lexical_error(span, tr!("Unexpected character: {}", c)));
                 }
             }
         }
@@ -222,18 +284,17 @@ impl Lexer {
         tokens.push(Token {
             lexeme: "".to_string(),
             type_: TokenType::Eof,
-            value: None,
+            value: TokenValue::Keyword,
+            span: chars.span(),
         });
 
         Ok(tokens)
     }
-    fn lex_number(
-        &self,
-        chars: &mut std::iter::Peekable<std::str::Chars>,
-    ) -> Result<Token, String> {
+    fn lex_number(&self, chars: &mut SourceChars) -> Result<Token, Diagnostic> {
+        let span = chars.span();
         let mut number_str = String::new();
         while let Some(&c) = chars.peek() {
-            if c.is_digit(10) {
+            if c.is_ascii_digit() {
                 number_str.push(c);
                 chars.next();
             } else {
@@ -242,24 +303,33 @@ impl Lexer {
         }
 
         // 检查数字后面的字符
-        if let Some(&next_char) = chars.peek() {
-            if next_char.is_alphabetic() {
-                return Err(format!(
+        if let Some(&next_char) = chars.peek()
+            && next_char.is_alphabetic()
+        {
+            return Err(lexical_error(
+                span,
+                tr!(
                     "Identifier cannot start with a number: '{}{}'",
-                    number_str, next_char
-                ));
-            }
+                    number_str,
+                    next_char
+                ),
+            ));
         }
 
+        let value = number_str.parse().map_err(|_| {
+            lexical_error(span, tr!("Integer constant is too large: '{}'", number_str))
+        })?;
         Ok(Token {
-            lexeme: number_str.clone(),
+            lexeme: number_str,
             type_: TokenType::Number,
-            value: Some(number_str),
+            value: TokenValue::Number(value),
+            span,
         })
     }
 
     /// 解析一个标识符或关键字
-    fn lex_identifier(&self, chars: &mut std::iter::Peekable<std::str::Chars>) -> Token {
+    fn lex_identifier(&self, chars: &mut SourceChars) -> Token {
+        let span = chars.span();
         let mut identifier = String::new();
         while let Some(&c) = chars.peek() {
             if c.is_alphanumeric() || c == '_' {
@@ -281,24 +351,27 @@ impl Lexer {
             "for" => TokenType::For,
             "break" => TokenType::Break,
             "continue" => TokenType::Continue,
+            "case" => TokenType::Case,
+            "default" => TokenType::Default,
             "static" => TokenType::Static,
             "extern" => TokenType::Extern,
+            "volatile" => TokenType::Volatile,
+            "_Bool" if self.standard >= CStandard::C99 => TokenType::Bool,
+            "_Noreturn" if self.standard >= CStandard::C11 => TokenType::Noreturn,
+            "__attribute__" => TokenType::Attribute,
             _ => TokenType::Identifier,
         };
 
-        // 根据类型决定如何构造 Token
-        if type_ == TokenType::Identifier {
-            Token {
-                type_,
-                lexeme: identifier.clone(),
-                value: Some(identifier),
-            }
+        let value = if type_ == TokenType::Identifier {
+            TokenValue::Identifier(Symbol::intern(&identifier))
         } else {
-            Token {
-                type_,
-                lexeme: identifier,
-                value: None,
-            }
+            TokenValue::Keyword
+        };
+        Token {
+            type_,
+            lexeme: identifier,
+            value,
+            span,
         }
     }
 }
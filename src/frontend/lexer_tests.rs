@@ -0,0 +1,220 @@
+// src/frontend/lexer_tests.rs
+
+//! 运算符的最长匹配 (maximal munch) 测试。
+//!
+//! 用一个直接按定义实现的参考分词器（每次取 `PUNCTUATORS` 中最长的匹配前缀）作为
+//! 规格，对表中所有运算符两两拼接的结果做穷举比较，并对随机生成的运算符、标识符和
+//! 数字串做性质测试。最后检查标识符和数字 token 携带的值，以及 token 的位置和原文
+//! 能否拼回源代码。
+
+use proptest::prelude::*;
+
+use crate::common::CStandard;
+use crate::frontend::lexer::{Lexer, PUNCTUATORS, Token, TokenType, TokenValue};
+
+fn lex(source: &str) -> Result<Vec<(TokenType, String)>, String> {
+    let tokens = Lexer::with_standard(CStandard::default())
+        .lex(source)
+        .map_err(|e| e.to_string())?;
+    Ok(tokens
+        .into_iter()
+        .filter(|t| t.type_ != TokenType::Eof)
+        .map(|t| (t.type_, t.lexeme))
+        .collect())
+}
+
+/// 参考分词器：空白分隔，字母数字串整体作为一个 token，其余位置取最长的运算符。
+/// 无法识别的字符返回 `None`。
+fn reference(source: &str) -> Option<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if c.is_ascii_alphanumeric() {
+            let end = rest
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .unwrap_or(rest.len());
+            tokens.push(rest[..end].to_string());
+            rest = &rest[end..];
+        } else {
+            let (text, _) = PUNCTUATORS
+                .iter()
+                .filter(|(text, _)| rest.starts_with(text))
+                .max_by_key(|(text, _)| text.len())?;
+            tokens.push(text.to_string());
+            rest = &rest[text.len()..];
+        }
+    }
+    Some(tokens)
+}
+
+fn lexemes(source: &str) -> Option<Vec<String>> {
+    lex(source)
+        .ok()
+        .map(|tokens| tokens.into_iter().map(|(_, lexeme)| lexeme).collect())
+}
+
+#[test]
+fn every_punctuator_is_a_single_token() {
+    for (text, type_) in PUNCTUATORS {
+        assert_eq!(
+            lex(text),
+            Ok(vec![(type_.clone(), text.to_string())]),
+            "{}",
+            text
+        );
+    }
+}
+
+#[test]
+fn adjacent_punctuators_follow_maximal_munch() {
+    for (a, _) in PUNCTUATORS {
+        for (b, _) in PUNCTUATORS {
+            let source = format!("{}{}", a, b);
+            assert_eq!(lexemes(&source), reference(&source), "{}", source);
+        }
+    }
+}
+
+#[test]
+fn operator_sequences() {
+    let cases: [(&str, &[&str]); 7] = [
+        ("a==-b", &["a", "==", "-", "b"]),
+        ("a<=-1", &["a", "<=", "-", "1"]),
+        ("a!=!b", &["a", "!=", "!", "b"]),
+        ("a---b", &["a", "--", "-", "b"]),
+        ("a+=b", &["a", "+=", "b"]),
+        ("p->x", &["p", "->", "x"]),
+        ("x<<=2", &["x", "<<=", "2"]),
+    ];
+    for (source, expected) in cases {
+        let expected: Vec<String> = expected.iter().map(|s| s.to_string()).collect();
+        assert_eq!(lexemes(source), Some(expected), "{}", source);
+    }
+}
+
+/// 运算符字符、标识符、数字和空格组成的随机串。
+fn arb_source() -> impl Strategy<Value = String> {
+    prop::collection::vec(
+        prop_oneof![
+            3 => prop::sample::select(vec![
+                "<", ">", "=", "!", "-", "+", "&", "|", "*", "/", "%", "~", "?", ":",
+            ]),
+            // 数字后紧跟字母是词法错误，所以数字后面总带一个空格
+            1 => prop::sample::select(vec!["a", "b1", "7 ", " "]),
+        ],
+        0..16,
+    )
+    .prop_map(|parts| parts.concat())
+}
+
+proptest! {
+    #[test]
+    fn lexer_matches_reference_maximal_munch(source in arb_source()) {
+        prop_assert_eq!(lexemes(&source), reference(&source), "{}", source);
+    }
+}
+
+#[test]
+fn token_values() {
+    let tokens = Lexer::with_standard(CStandard::default())
+        .lex("x = x + 2147483648; return y;")
+        .unwrap();
+    let values: Vec<_> = tokens.iter().map(|t| t.value).collect();
+    let (TokenValue::Identifier(x), TokenValue::Identifier(y)) = (values[0], values[7]) else {
+        panic!("{:?}", values);
+    };
+    assert_ne!(x, y);
+    assert_eq!(values[2], TokenValue::Identifier(x));
+    assert_eq!(x, "x");
+    assert_eq!(y, "y");
+    assert_eq!(values[4], TokenValue::Number(2147483648));
+    assert_eq!(values[6], TokenValue::Keyword);
+
+    let too_large = "1".repeat(40);
+    let error = Lexer::with_standard(CStandard::default())
+        .lex(&too_large)
+        .unwrap_err();
+    assert_eq!(error.code.as_deref(), Some("lexical-error"));
+    assert!(error.message.contains(&too_large), "{}", error);
+}
+
+/// 把每个 token 的原文放回它的位置，中间用换行和空格补齐。
+fn reassemble(tokens: &[Token]) -> String {
+    let mut out = String::new();
+    let (mut line, mut column) = (1, 1);
+    for token in tokens.iter().filter(|t| t.type_ != TokenType::Eof) {
+        while line < token.span.line {
+            out.push('\n');
+            line += 1;
+            column = 1;
+        }
+        while column < token.span.column {
+            out.push(' ');
+            column += 1;
+        }
+        out.push_str(&token.lexeme);
+        column += token.lexeme.chars().count();
+    }
+    out
+}
+
+/// 去掉每行末尾的空白和末尾的空行：这些内容不影响任何 token。
+fn significant(source: &str) -> String {
+    let lines: Vec<&str> = source.lines().map(str::trim_end).collect();
+    lines.join("\n").trim_end().to_string()
+}
+
+#[test]
+fn tokens_reassemble_into_the_source() {
+    let source = "\
+int main(void) {
+    int a = 1;   
+    for (a = 0; a <= 10; a = a + 1)
+        if (a != 3 && !(a >= 7) || a == -1) return a--;
+
+    return ~a % 2 ? a : a * -a;
+}
+";
+    let tokens = Lexer::with_standard(CStandard::default())
+        .lex(source)
+        .unwrap();
+    assert_eq!(reassemble(&tokens), significant(source));
+}
+
+#[test]
+fn line_markers_move_token_positions() {
+    let source = "\
+# 1 \"main.c\"
+# 1 \"inc.h\" 1
+int x;
+# 40 \"main.c\" 2
+int y;
+";
+    let tokens = Lexer::with_standard(CStandard::default())
+        .lex(source)
+        .unwrap();
+    let positions: Vec<String> = tokens.iter().map(|t| t.span.to_string()).collect();
+    assert_eq!(
+        positions,
+        [
+            "inc.h:1:1",
+            "inc.h:1:5",
+            "inc.h:1:6",
+            "40:1",
+            "40:5",
+            "40:6",
+            "41:1"
+        ]
+    );
+}
+
+proptest! {
+    #[test]
+    fn random_tokens_reassemble_into_the_source(source in arb_source()) {
+        if let Ok(tokens) = Lexer::with_standard(CStandard::default()).lex(&source) {
+            prop_assert_eq!(reassemble(&tokens), significant(&source));
+        }
+    }
+}
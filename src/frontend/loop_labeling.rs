@@ -13,7 +13,8 @@
 //!
 //! 2.  **循环栈管理**:
 //!     -   维护一个 `loop_stack`，用于跟踪当前嵌套的循环层级。
-//!     -   当进入一个新的循环语句时，会生成一个唯一的循环标签（例如，`loop.0`, `loop.1`），并将其压入栈顶。
+//!     -   当进入一个新的循环语句时，会生成一个唯一的循环名字（例如，`main.loop.0`, `main.loop.1`），
+//!         连同由它派生的 start/continue/break 跳转标签（[`LoopLabels`]）一起压入栈顶。
 //!     -   当完成对该循环体的遍历后，将其标签从栈中弹出。
 //!
 //! 3.  **标签关联**:
@@ -25,17 +26,32 @@
 //!     -   捕捉与循环控制相关的语义错误，例如：
 //!         -   在任何循环之外使用 `break` 语句。
 //!         -   在任何循环之外使用 `continue` 语句。
+//!
+//! 5.  **语句标签**:
+//!     -   `name: stmt` 的标签改名为函数内唯一的名字（例如 `main.out.3`），它同时是 TACKY 中的
+//!         跳转标签，不同函数中的同名标签不会冲突。同一个函数中重复的标签是错误 (C11 6.8.1p3)。
+//!     -   还不支持 `switch`，`case` 和 `default` 标签总是报错。
+
+use std::collections::HashMap;
 
+use crate::common::Symbol;
+use crate::diagnostics::Diagnostic;
+use crate::i18n::tr;
 use crate::{
     UniqueNameGenerator,
-    frontend::c_ast::{Block, BlockItem, Declaration, FunDecl, Program, Statement},
+    frontend::c_ast::{
+        AstArena, Block, BlockItem, Declaration, FunDecl, LoopLabels, Program, Statement, StmtId,
+    },
+    frontend::semantic_error,
 };
 
 /// 循环标签解析器的状态机。
 pub struct LoopLabeling<'a> {
     /// 循环标签栈，用于跟踪当前所在的循环。
     /// 每当进入一个循环，就将新生成的唯一循环标签压入此栈。
-    loop_stack: Vec<String>,
+    loop_stack: Vec<LoopLabels>,
+    /// 当前函数中已经出现的语句标签：源码中的名字 -> 唯一的名字
+    statement_labels: HashMap<Symbol, Symbol>,
     /// 用于生成唯一标签名的工具。
     name_gen: &'a mut UniqueNameGenerator,
 }
@@ -45,126 +61,86 @@ impl<'a> LoopLabeling<'a> {
     pub fn new(g: &'a mut UniqueNameGenerator) -> Self {
         LoopLabeling {
             loop_stack: Vec::new(),
+            statement_labels: HashMap::new(),
             name_gen: g,
         }
     }
 
     /// 解析器的主入口点，负责遍历并标记整个程序中的所有循环。
-    pub fn label_loops_in_program(&mut self, ast: &Program) -> Result<Program, String> {
-        let mut decls: Vec<Declaration> = Vec::new();
-        for decl in &ast.declarations {
-            match decl {
-                Declaration::Fun(f) => {
-                    let new_f = self.label_loops_in_function_decl(f)?;
-                    decls.push(Declaration::Fun(new_f));
-                }
-                Declaration::Variable(v) => {
-                    decls.push(Declaration::Variable(v.clone()));
-                }
+    ///
+    /// 标签直接填入存储区中的循环、`break` 和 `continue` 语句节点，声明本身不变。
+    pub fn label_loops_in_program(&mut self, ast: &Program) -> Result<Program, Diagnostic> {
+        let mut program = ast.clone();
+        for decl in &program.declarations {
+            if let Declaration::Fun(f) = decl {
+                self.label_loops_in_function_decl(f, &mut program.arena)?;
             }
         }
-        Ok(Program {
-            declarations: decls,
-        })
+        Ok(program)
     }
 
     /// 遍历函数声明，主要处理其函数体。
-    fn label_loops_in_function_decl(&mut self, f: &FunDecl) -> Result<FunDecl, String> {
-        let new_body = if let Some(b) = &f.body {
-            Some(self.label_loops_in_block(b)?)
-        } else {
-            None
-        };
-
-        Ok(FunDecl {
-            name: f.name.clone(),
-            parameters: f.parameters.clone(),
-            body: new_body,
-            storage_class: f.storage_class.clone(),
-        })
+    fn label_loops_in_function_decl(
+        &mut self,
+        f: &FunDecl,
+        arena: &mut AstArena,
+    ) -> Result<(), Diagnostic> {
+        self.name_gen.enter_function(f.name);
+        self.statement_labels.clear();
+        if let Some(b) = &f.body {
+            self.label_loops_in_block(b, arena)?;
+        }
+        self.name_gen.exit_function();
+        Ok(())
     }
 
     /// 遍历代码块中的每一个条目。
-    fn label_loops_in_block(&mut self, block: &Block) -> Result<Block, String> {
-        let mut new_items = Vec::new();
+    fn label_loops_in_block(
+        &mut self,
+        block: &Block,
+        arena: &mut AstArena,
+    ) -> Result<(), Diagnostic> {
         for item in &block.0 {
-            new_items.push(self.label_loops_in_block_item(item)?);
-        }
-        Ok(Block(new_items))
-    }
-
-    /// 遍历块内条目，区分声明和语句。
-    fn label_loops_in_block_item(&mut self, item: &BlockItem) -> Result<BlockItem, String> {
-        match item {
-            // 声明本身不包含循环控制，因此我们直接克隆它。
+            // 声明本身不包含循环控制，因此只需处理语句。
             // 一个更完备的实现可能需要递归检查初始化表达式，但在这里我们简化处理。
-            BlockItem::D(d) => Ok(BlockItem::D(d.clone())),
-            BlockItem::S(s) => {
-                let new_s = self.label_loops_in_statement(s)?;
-                Ok(BlockItem::S(new_s))
+            if let BlockItem::S(s) = item {
+                self.label_loops_in_statement(*s, arena)?;
             }
         }
+        Ok(())
     }
 
     /// 这是核心的遍历函数，处理各种语句类型。
-    fn label_loops_in_statement(&mut self, stmt: &Statement) -> Result<Statement, String> {
-        match stmt {
+    fn label_loops_in_statement(
+        &mut self,
+        stmt: StmtId,
+        arena: &mut AstArena,
+    ) -> Result<(), Diagnostic> {
+        match arena[stmt].clone() {
             // --- 循环语句处理 ---
-            Statement::While {
-                condition, body, ..
-            } => {
+            Statement::While { body, .. }
+            | Statement::DoWhile { body, .. }
+            | Statement::For { body, .. } => {
                 // 1. 为此循环生成一个新的、唯一的标签。
-                let loop_label = self.name_gen.new_loop_label("loop");
+                let loop_label = LoopLabels::new(self.name_gen.new_loop_label("loop"));
                 // 2. 将标签压入栈中，表示我们进入了一个新的循环层级。
                 self.loop_stack.push(loop_label.clone());
 
                 // 3. 递归地处理循环体。在循环体中遇到的任何 `break` 或 `continue`
                 //    都将使用我们刚刚压入栈的标签。
-                let new_body = self.label_loops_in_statement(body)?;
+                self.label_loops_in_statement(body, arena)?;
 
                 // 4. 循环体处理完毕，将此循环的标签弹出栈。
                 self.loop_stack.pop();
 
-                // 5. 返回一个新的、已填充标签的 `While` 语句节点。
-                Ok(Statement::While {
-                    condition: condition.clone(),
-                    body: Box::new(new_body),
-                    label: Some(loop_label),
-                })
-            }
-
-            Statement::DoWhile {
-                body, condition, ..
-            } => {
-                let loop_label = self.name_gen.new_loop_label("loop");
-                self.loop_stack.push(loop_label.clone());
-                let new_body = self.label_loops_in_statement(body)?;
-                self.loop_stack.pop();
-                Ok(Statement::DoWhile {
-                    body: Box::new(new_body),
-                    condition: condition.clone(),
-                    label: Some(loop_label),
-                })
-            }
-
-            Statement::For {
-                init,
-                condition,
-                post,
-                body,
-                ..
-            } => {
-                let loop_label = self.name_gen.new_loop_label("loop");
-                self.loop_stack.push(loop_label.clone());
-                let new_body = self.label_loops_in_statement(body)?;
-                self.loop_stack.pop();
-                Ok(Statement::For {
-                    init: init.clone(),
-                    condition: condition.clone(),
-                    post: post.clone(),
-                    body: Box::new(new_body),
-                    label: Some(loop_label),
-                })
+                // 5. 把标签填入循环语句节点。
+                if let Statement::While { label, .. }
+                | Statement::DoWhile { label, .. }
+                | Statement::For { label, .. } = &mut arena[stmt]
+                {
+                    *label = Some(loop_label);
+                }
+                Ok(())
             }
 
             // --- Break/Continue 处理 ---
@@ -172,50 +148,77 @@ impl<'a> LoopLabeling<'a> {
                 // 检查循环栈是否为空。如果为空，说明 `break` 不在任何循环内。
                 if let Some(current_loop_label) = self.loop_stack.last() {
                     // 如果不为空，则使用栈顶的标签。
-                    Ok(Statement::Break(current_loop_label.clone()))
+                    arena[stmt] = Statement::Break(Some(current_loop_label.clone()));
+                    Ok(())
                 } else {
-                    Err(
-                        "Semantic Error: 'break' statement not in a loop or switch statement."
-                            .to_string(),
-                    )
+                    Err(semantic_error(
+                        arena.stmt_span(stmt),
+                        tr!("Semantic Error: 'break' statement not in a loop or switch statement."),
+                    ))
                 }
             }
 
             Statement::Continue(_) => {
                 if let Some(current_loop_label) = self.loop_stack.last() {
-                    Ok(Statement::Continue(current_loop_label.clone()))
+                    arena[stmt] = Statement::Continue(Some(current_loop_label.clone()));
+                    Ok(())
                 } else {
-                    Err("Semantic Error: 'continue' statement not in a loop.".to_string())
+                    Err(semantic_error(
+                        arena.stmt_span(stmt),
+                        tr!("Semantic Error: 'continue' statement not in a loop."),
+                    ))
                 }
             }
 
             // --- 其他语句的递归处理 ---
-            Statement::Compound(b) => {
-                let new_b = self.label_loops_in_block(b)?;
-                Ok(Statement::Compound(new_b))
-            }
+            Statement::Compound(b) => self.label_loops_in_block(&b, arena),
 
             Statement::If {
-                condition,
                 then_stmt,
                 else_stmt,
+                ..
             } => {
-                let new_then = self.label_loops_in_statement(then_stmt)?;
-                let new_else = else_stmt
-                    .as_ref()
-                    .map(|s| self.label_loops_in_statement(s))
-                    .transpose()?;
-                Ok(Statement::If {
-                    condition: condition.clone(),
-                    then_stmt: Box::new(new_then),
-                    else_stmt: new_else.map(Box::new),
-                })
+                self.label_loops_in_statement(then_stmt, arena)?;
+                if let Some(s) = else_stmt {
+                    self.label_loops_in_statement(s, arena)?;
+                }
+                Ok(())
+            }
+
+            Statement::Labeled { label, stmt: body } => {
+                if self.statement_labels.contains_key(&label) {
+                    return Err(semantic_error(
+                        arena.stmt_span(stmt),
+                        tr!("Semantic Error: duplicate label '{}'.", label),
+                    ));
+                }
+                let unique = Symbol::intern(&self.name_gen.new_label(label.as_str()));
+                self.statement_labels.insert(label, unique);
+                arena[stmt] = Statement::Labeled {
+                    label: unique,
+                    stmt: body,
+                };
+                self.label_loops_in_statement(body, arena)
             }
 
-            // 对于不包含控制流的简单语句，直接克隆即可。
-            Statement::Return(e) => Ok(Statement::Return(e.clone())),
-            Statement::Expression(e) => Ok(Statement::Expression(e.clone())),
-            Statement::Null => Ok(Statement::Null),
+            // 还不支持 switch，case 和 default 标签总是不在 switch 语句中
+            Statement::Case { .. } => Err(semantic_error(
+                arena.stmt_span(stmt),
+                tr!(
+                    "Semantic Error: '{}' label not within a switch statement.",
+                    "case"
+                ),
+            )),
+            Statement::Default { .. } => Err(semantic_error(
+                arena.stmt_span(stmt),
+                tr!(
+                    "Semantic Error: '{}' label not within a switch statement.",
+                    "default"
+                ),
+            )),
+
+            // 对于不包含控制流的简单语句，无需处理。
+            Statement::Return(_) | Statement::Expression(_) | Statement::Null => Ok(()),
         }
     }
 }
@@ -0,0 +1,111 @@
+// src/frontend/loop_labeling_tests.rs
+
+//! 循环和语句标签的测试：标签在每个函数内唯一，`label_verify` 能发现指向错误循环的
+//! `break`。
+
+use crate::UniqueNameGenerator;
+use crate::backend::assembly_ast_gen::IntDivOverflow;
+use crate::backend::interpreter;
+use crate::backend::tacky_gen::TackyGenerator;
+use crate::common::AstNode;
+use crate::frontend::c_ast::{BlockItem, Declaration, Statement};
+use crate::frontend::label_verify;
+use crate::frontend::loop_labeling::LoopLabeling;
+use crate::frontend::parse_program;
+use crate::frontend::resolve_ident::IdentifierResolver;
+use crate::frontend::type_checking::TypeChecker;
+
+#[test]
+fn statement_labels_are_unique_per_function() {
+    let ast = parse_program(
+        "\
+int f(int x) { out: return x; }
+int main(void) { int r = 1; out: r = r + f(2); done: return r; }
+",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let labeled = LoopLabeling::new(&mut names)
+        .label_loops_in_program(&resolved)
+        .unwrap();
+    let (typed, symbols, _) = TypeChecker::new().typecheck_program(&labeled).unwrap();
+    let (program, _) = TackyGenerator::new(&mut names, &symbols)
+        .generate_tacky(&typed)
+        .unwrap();
+    // 没有 goto 指向的标签在 TACKY 中被删除，检查循环标签解析后的 AST
+    let pretty = labeled.pretty().to_string();
+    for label in ["f.out.", "main.out.", "main.done."] {
+        assert!(
+            pretty.contains(&format!("LabeledStatement(label: \"{}", label)),
+            "{}",
+            pretty
+        );
+    }
+    assert_eq!(
+        interpreter::run(&program, &symbols, IntDivOverflow::Trap).unwrap(),
+        3
+    );
+
+    let label_error = |source: &str| {
+        let ast = parse_program(source);
+        let mut names = UniqueNameGenerator::new();
+        let (resolved, _) = IdentifierResolver::new(&mut names)
+            .resolve_program(&ast)
+            .unwrap();
+        LoopLabeling::new(&mut names)
+            .label_loops_in_program(&resolved)
+            .unwrap_err()
+            .to_string()
+    };
+    let error = label_error("int main(void) { a: ; a: return 0; }");
+    assert!(error.contains("duplicate label 'a'"), "{}", error);
+    let error = label_error("int main(void) { default: return 0; }");
+    assert!(
+        error.contains("'default' label not within a switch"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn label_verifier_rejects_break_labeled_with_outer_loop() {
+    let ast =
+        parse_program("int main(void) {\n    while (1) {\n        for (;;) break;\n    }\n}\n");
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let mut labeled = LoopLabeling::new(&mut names)
+        .label_loops_in_program(&resolved)
+        .unwrap();
+    assert_eq!(label_verify::verify_program(&labeled), Ok(()));
+
+    // 把内层 `for` 中的 `break` 改为指向外层的 `while`
+    let Some(Declaration::Fun(main)) = labeled.declarations.first() else {
+        panic!("意外的声明");
+    };
+    let Some(BlockItem::S(outer)) = main.body.as_ref().and_then(|b| b.0.first()) else {
+        panic!("意外的函数体");
+    };
+    let arena = &mut labeled.arena;
+    let Statement::While { label, body, .. } = arena[*outer].clone() else {
+        panic!("意外的语句");
+    };
+    let Statement::Compound(block) = arena[body].clone() else {
+        panic!("意外的循环体");
+    };
+    let Some(BlockItem::S(inner)) = block.0.first() else {
+        panic!("意外的循环体");
+    };
+    let Statement::For { body: brk, .. } = arena[*inner].clone() else {
+        panic!("意外的语句");
+    };
+    arena[brk] = Statement::Break(label);
+
+    let error = label_verify::verify_program(&labeled)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("ICE") && error.contains("main"), "{}", error);
+}
@@ -1,6 +1,44 @@
 pub mod c_ast;
+pub mod const_eval;
+pub mod conversions;
+pub mod coverage;
+pub mod dangling_else;
+pub mod label_verify;
 pub mod lexer;
 pub mod loop_labeling;
 pub mod parser;
 pub mod resolve_ident;
+pub mod symbol_dump;
+pub mod token_dump;
 pub mod type_checking;
+pub mod unused_statics;
+
+use crate::common::Span;
+use crate::diagnostics::Diagnostic;
+
+/// 标识符解析、循环标记和类型检查报告的语义错误。
+pub fn semantic_error(span: Span, message: String) -> Diagnostic {
+    Diagnostic::error(message)
+        .with_code("semantic-error")
+        .with_span(span)
+}
+
+/// 测试用：词法分析并解析整个程序，两者都必须成功。
+#[cfg(test)]
+pub(crate) fn parse_program(source: &str) -> c_ast::Program {
+    let tokens = lexer::Lexer::with_standard(crate::common::CStandard::default())
+        .lex(source)
+        .unwrap();
+    parser::Parser::new(tokens).parse().unwrap()
+}
+
+#[cfg(test)]
+mod conversions_tests;
+#[cfg(test)]
+mod lexer_tests;
+#[cfg(test)]
+mod loop_labeling_tests;
+#[cfg(test)]
+mod parser_tests;
+#[cfg(test)]
+mod type_checking_tests;
@@ -31,17 +31,25 @@
 //!
 //! ## 错误处理
 //!
-//! -   当 Token 流不符合预期的语法规则时，解析器会返回一个 `Err(String)`。
-//! -   错误信息被格式化为 `"Syntax Error: ..."`，以明确指出错误的性质和位置。
+//! -   当 Token 流不符合预期的语法规则时，解析器在出错的 Token 处构造一条代码为 `syntax-error` 的
+//!     [`Diagnostic`]，信息以 `"Syntax Error: ..."` 开头。
+//! -   解析器采用恐慌模式 (panic-mode) 恢复：在代码块条目或顶层声明中遇到错误时，
+//!     先记录错误，再跳到下一个同步 Token（`;`、`}` 或语句起始关键字）继续解析。
+//!     这样一个缺失的分号不会掩盖文件中后续的所有错误。全部错误在解析结束后一并返回。
+//! -   语句和表达式的嵌套深度受 `max_depth` 限制，超出时报告错误而不是让解析器
+//!     （以及后续递归遍历 AST 的各个阶段）栈溢出。
 
 use std::iter::Peekable;
 use std::vec::IntoIter;
 
+use crate::common::{ResourceLimits, Span, Symbol};
+use crate::diagnostics::Diagnostic;
 use crate::frontend::c_ast::{
-    BinaryOp, Block, BlockItem, Declaration, Expression, ForInit, FunDecl, Program, Statement,
-    StorageClass, UnaryOp, VarDecl,
+    AstArena, BinaryOp, Block, BlockItem, Declaration, ExprId, Expression, ForInit, FunDecl,
+    Program, Statement, StmtId, StorageClass, Type, UnaryOp, VarDecl,
 };
-use crate::frontend::lexer::{Token, TokenType};
+use crate::frontend::lexer::{Token, TokenType, TokenValue};
+use crate::i18n::tr;
 
 /// 语法分析器结构体，持有 Token 流的迭代器。
 #[derive(Debug)]
@@ -49,6 +57,18 @@ pub struct Parser {
     /// 一个可向前查看的 (peekable) Token 迭代器。
     /// `Peekable` 允许我们在不消耗 Token 的情况下查看下一个 Token，这对于语法分析至关重要。
     tokens: Peekable<IntoIter<Token>>,
+    /// 已消耗的 Token 数量，用于在错误恢复时确认解析器有所前进。
+    position: usize,
+    /// 错误恢复过程中记录下来的语法错误。
+    errors: Vec<Diagnostic>,
+    /// 最后一个被消耗的 Token 的位置，输入结束时的错误报告在这里。
+    last_span: Span,
+    /// 当前语句/表达式的嵌套深度。
+    depth: usize,
+    /// 允许的最大嵌套深度。
+    max_depth: usize,
+    /// 解析出的表达式和语句节点
+    arena: AstArena,
 }
 
 impl Parser {
@@ -56,14 +76,59 @@ impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
         Parser {
             tokens: tokens.into_iter().peekable(),
+            position: 0,
+            errors: Vec::new(),
+            last_span: Span::default(),
+            depth: 0,
+            max_depth: ResourceLimits::default().max_ast_depth,
+            arena: AstArena::new(),
         }
     }
 
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// 进入一层嵌套，超出深度上限时报错。
+    fn enter_nested(&mut self) -> Result<(), Diagnostic> {
+        self.depth += 1;
+        if self.depth > self.max_depth {
+            let message = tr!(
+                "超出资源限制: 语句或表达式嵌套深度超过上限 {} (--max-ast-depth)",
+                self.max_depth
+            );
+            return Err(self.syntax_error(message).with_code("limit-exceeded"));
+        }
+        Ok(())
+    }
+
+    /// 在新的一层嵌套中运行 `parse`，无论成功与否都恢复原来的深度。
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, Diagnostic>,
+    ) -> Result<T, Diagnostic> {
+        let depth = self.depth;
+        let result = self.enter_nested().and_then(|()| parse(self));
+        self.depth = depth;
+        result
+    }
+
     // --- 主入口和顶层解析函数 ---
 
     /// 解析器的主入口点。它消耗自身并尝试解析整个 Token 流。
-    pub fn parse(mut self) -> Result<Program, String> {
-        self.parse_program()
+    ///
+    /// 失败时返回恢复过程中遇到的所有错误，每个错误是单独的一条诊断。
+    pub fn parse(mut self) -> Result<Program, Vec<Diagnostic>> {
+        let program = self.parse_program();
+        match program {
+            Ok(program) if self.errors.is_empty() => Ok(program),
+            Ok(_) => Err(self.errors),
+            Err(e) => {
+                self.errors.push(e);
+                Err(self.errors)
+            }
+        }
     }
 
     /// 解析整个程序。
@@ -71,146 +136,239 @@ impl Parser {
     /// 文法规则: `<program> ::= {<function-declaration> | <variable-declaration>}`
     ///
     /// 在我们的C语言子集中，顶层可以包含函数和全局变量的声明。
-    fn parse_program(&mut self) -> Result<Program, String> {
+    fn parse_program(&mut self) -> Result<Program, Diagnostic> {
         let mut decls = Vec::new();
         while !self.match_token(TokenType::Eof) {
-            let decl = self.parse_declaration()?;
-            decls.push(decl);
+            if self.tokens.peek().is_none() {
+                return Err(self.syntax_error(tr!("Syntax Error: Unexpected end of input.")));
+            }
+            let start = self.position;
+            match self.parse_declaration() {
+                Ok(d) => decls.extend(d),
+                Err(e) => {
+                    self.errors.push(e);
+                    self.synchronize();
+                    // 顶层的 `}` 只可能属于一个出错的函数体，直接跳过
+                    if self.position == start || self.check(TokenType::RightBrace) {
+                        self.advance();
+                    }
+                }
+            }
         }
         Ok(Program {
             declarations: decls,
+            arena: std::mem::take(&mut self.arena),
         })
     }
 
     // --- 声明解析 ---
 
-    /// 解析一个声明（变量或函数）。
+    /// 解析一个声明。一个声明可以用逗号分隔声明多个变量或函数，例如 `int a = 1, b, f(void);`，
+    /// 每个声明符展开为一个独立的 `Declaration`，说明符对所有声明符都有效。
+    /// 函数定义只能单独声明。
     ///
-    /// 文法规则: `<declaration> ::= "int" <identifier> (";" | "=" ... | "(" ...)`
-    fn parse_declaration(&mut self) -> Result<Declaration, String> {
+    /// 文法规则: `<declaration> ::= {<specifier>}+ <init-declarator> {"," <init-declarator>} ";"
+    ///                            | {<specifier>}+ <function-declarator> <block>`
+    /// `<init-declarator> ::= <identifier> ["=" <exp>] | <function-declarator>`
+    fn parse_declaration(&mut self) -> Result<Vec<Declaration>, Diagnostic> {
         //收集specifier tokens
         let mut spec_tokens = Vec::new();
-        while let Some(t) = self.tokens.peek().cloned() {
-            if t.type_ == TokenType::Identifier {
-                break;
+        let mut noreturn = false;
+        let mut volatile = false;
+        while self.is_in_specifier() {
+            if self.check(TokenType::Attribute) {
+                noreturn |= self.parse_attributes()?;
+            } else if self.match_token(TokenType::Noreturn) {
+                noreturn = true;
+            } else if self.match_token(TokenType::Volatile) {
+                // 函数返回类型上的限定符没有意义，和 gcc 一样忽略它
+                volatile = true;
             } else {
-                self.tokens.next();
-                spec_tokens.push(t.clone());
+                spec_tokens.push(self.advance().unwrap());
             }
         }
 
-        let storage_class = self.parse_type_and_storage_class(spec_tokens)?;
+        let (decl_type, storage_class) = self.parse_type_and_storage_class(spec_tokens)?;
 
-        let name_token = self.consume(TokenType::Identifier)?;
-        let name = name_token.value.ok_or_else(|| {
-            "Syntax Error: Expected a name for the identifier, but it was missing.".to_string()
-        })?;
-
-        // 通过查看下一个 Token 来判断是函数还是变量。
-        if self.check(TokenType::LeftParen) {
-            // 如果是 '(', 那么这是一个函数声明或定义。
-            self.consume(TokenType::LeftParen)?;
-            let params = self.parse_func_params()?;
-            self.consume(TokenType::RightParen)?;
-            if self.match_token(TokenType::Semicolon) {
-                // 如果是分号，这是一个函数原型声明 (e.g., `int add(int a, int b);`)
-                Ok(Declaration::Fun(FunDecl {
+        let mut decls = Vec::new();
+        loop {
+            let (name, span) = self.consume_identifier()?;
+
+            // 通过查看下一个 Token 来判断是函数还是变量。
+            if self.check(TokenType::LeftParen) {
+                // 如果是 '(', 那么这是一个函数声明或定义。
+                self.consume(TokenType::LeftParen)?;
+                // 空参数列表 `()` 表示参数未指定，`(void)` 才表示没有参数
+                let unspecified_params = self.check(TokenType::RightParen);
+                let (params, param_types) = self.parse_func_params()?;
+                self.consume(TokenType::RightParen)?;
+                // GNU 属性也可以写在参数列表之后：`void f(void) __attribute__((noreturn));`
+                let mut fun_noreturn = noreturn;
+                while self.check(TokenType::Attribute) {
+                    fun_noreturn |= self.parse_attributes()?;
+                }
+                // 第一个声明符后面既不是 `,` 也不是 `;` 时，必须是一个函数体代码块。
+                let body = if decls.is_empty()
+                    && !self.check(TokenType::Comma)
+                    && !self.check(TokenType::Semicolon)
+                {
+                    Some(self.parse_block()?)
+                } else {
+                    None
+                };
+                let is_definition = body.is_some();
+                decls.push(Declaration::Fun(FunDecl {
                     name,
                     parameters: params,
-                    body: None,
-                    storage_class,
-                }))
+                    param_types,
+                    unspecified_params,
+                    ret_type: decl_type,
+                    body,
+                    storage_class: storage_class.clone(),
+                    noreturn: fun_noreturn,
+                    span,
+                }));
+                if is_definition {
+                    return Ok(decls);
+                }
             } else {
-                // 否则，必须是一个函数体代码块。
-                let body = self.parse_block()?;
-                Ok(Declaration::Fun(FunDecl {
+                // 否则，它是一个变量声明。
+                if noreturn {
+                    return Err(self.syntax_error_at(
+                        span,
+                        tr!(
+                            "Syntax Error: 'noreturn' can only be applied to a function declaration, not to '{}'.",
+                            name
+                        ),
+                    ));
+                }
+                let init = if self.match_token(TokenType::Assignment) {
+                    Some(self.parse_exp(0)?)
+                } else {
+                    None
+                };
+                decls.push(Declaration::Variable(VarDecl {
                     name,
-                    parameters: params,
-                    body: Some(body),
-                    storage_class,
-                }))
+                    init,
+                    var_type: decl_type,
+                    storage_class: storage_class.clone(),
+                    volatile,
+                    span,
+                }));
             }
-        } else {
-            // 否则，它是一个变量声明。
-            let init = if self.match_token(TokenType::Assignment) {
-                Some(self.parse_exp(0)?)
-            } else {
-                None
-            };
-            self.consume(TokenType::Semicolon)?;
 
-            Ok(Declaration::Variable(VarDecl {
-                name: name,
-                init: init,
-                storage_class,
-            }))
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
         }
+        self.consume(TokenType::Semicolon)?;
+        Ok(decls)
     }
     //
     fn parse_type_and_storage_class(
         &mut self,
         toknes: Vec<Token>,
-    ) -> Result<Option<StorageClass>, String> {
+    ) -> Result<(Type, Option<StorageClass>), Diagnostic> {
+        let span = toknes.first().map(|t| t.span);
         let mut types = Vec::new();
         let mut storage_classes = Vec::new();
         for t in toknes {
-            if t.type_ == TokenType::Int {
-                types.push(TokenType::Int);
-            } else {
-                storage_classes.push(t.clone());
+            match t.type_ {
+                TokenType::Int => types.push(Type::Int),
+                TokenType::Bool => types.push(Type::Bool),
+                _ => storage_classes.push(t.clone()),
             }
         }
+        let at_specifiers = |parser: &mut Self, message| match span {
+            Some(span) => parser.syntax_error_at(span, message),
+            None => parser.syntax_error(message),
+        };
         if types.len() != 1 {
-            return Err("Syntax Error: Invalid type specifier".to_string());
+            return Err(at_specifiers(
+                self,
+                tr!("Syntax Error: Invalid type specifier"),
+            ));
         }
         if storage_classes.len() > 1 {
-            return Err("Syntax Error: Invalid storage class".to_string());
+            return Err(at_specifiers(
+                self,
+                tr!("Syntax Error: Invalid storage class"),
+            ));
         }
         let ss = self.parse_storage_class(storage_classes)?;
 
-        Ok(ss)
+        Ok((types[0], ss))
     }
-    fn parse_storage_class(&mut self, tokens: Vec<Token>) -> Result<Option<StorageClass>, String> {
-        for t in tokens {
-            match t.type_ {
-                TokenType::Static => {
-                    return Ok(Some(StorageClass::Static));
-                }
-                TokenType::Extern => {
-                    return Ok(Some(StorageClass::Extern));
-                }
-                _ => {
-                    unreachable!()
-                }
+    fn parse_storage_class(
+        &mut self,
+        tokens: Vec<Token>,
+    ) -> Result<Option<StorageClass>, Diagnostic> {
+        match tokens.first().map(|t| &t.type_) {
+            Some(TokenType::Static) => Ok(Some(StorageClass::Static)),
+            Some(TokenType::Extern) => Ok(Some(StorageClass::Extern)),
+            Some(_) => unreachable!(),
+            None => Ok(None),
+        }
+    }
+
+    /// 解析 GNU 属性，返回其中是否包含 `noreturn`。其他属性被忽略。
+    ///
+    /// 文法规则: `<attribute> ::= "__attribute__" "(" "(" [<identifier> {"," <identifier>}] ")" ")"`
+    fn parse_attributes(&mut self) -> Result<bool, Diagnostic> {
+        self.consume(TokenType::Attribute)?;
+        self.consume(TokenType::LeftParen)?;
+        self.consume(TokenType::LeftParen)?;
+        let mut noreturn = false;
+        while !self.check(TokenType::RightParen) {
+            let name = self.consume(TokenType::Identifier)?;
+            noreturn |= matches!(name.lexeme.as_str(), "noreturn" | "__noreturn__");
+            if !self.match_token(TokenType::Comma) {
+                break;
             }
         }
-        Ok(None)
+        self.consume(TokenType::RightParen)?;
+        self.consume(TokenType::RightParen)?;
+        Ok(noreturn)
     }
 
     /// 解析函数参数列表。
     ///
     /// 文法规则: `<param-list> ::= "void" | <param> {"," <param>} | <empty>`
-    /// `<param> ::= "int" <identifier>`
-    fn parse_func_params(&mut self) -> Result<Vec<String>, String> {
+    /// `<param> ::= ("int" | "_Bool") <identifier>`
+    fn parse_func_params(&mut self) -> Result<(Vec<Symbol>, Vec<Type>), Diagnostic> {
         // 处理 `void` 参数或空参数列表 `()` 的情况。
         if self.match_token(TokenType::Void) || self.check(TokenType::RightParen) {
-            return Ok(Vec::new());
+            return Ok((Vec::new(), Vec::new()));
         }
 
         let mut params = Vec::new();
-        // 解析第一个参数。
-        self.consume(TokenType::Int)?;
-        let first_param = self.consume(TokenType::Identifier)?;
-        params.push(first_param.value.unwrap()); // `unwrap` 在这里是安全的，因为标识符 Token 总是有值。
+        let mut types = Vec::new();
+        loop {
+            types.push(self.parse_param_type()?);
+            params.push(self.consume_identifier()?.0);
 
-        // 循环解析后续由逗号分隔的参数。
-        while self.match_token(TokenType::Comma) {
-            self.consume(TokenType::Int)?;
-            let next_param = self.consume(TokenType::Identifier)?;
-            params.push(next_param.value.unwrap());
+            // 循环解析后续由逗号分隔的参数。
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
         }
 
-        Ok(params)
+        Ok((params, types))
+    }
+
+    /// 解析单个参数的类型说明符。
+    fn parse_param_type(&mut self) -> Result<Type, Diagnostic> {
+        if self.match_token(TokenType::Int) {
+            Ok(Type::Int)
+        } else if self.match_token(TokenType::Bool) {
+            Ok(Type::Bool)
+        } else {
+            let found = self.tokens.peek().map(|t| t.type_.clone());
+            Err(self.syntax_error(tr!(
+                "Syntax Error: Expected a parameter type, but got {}.",
+                format!("{:?}", found)
+            )))
+        }
     }
 
     // --- 语句和块解析 ---
@@ -218,57 +376,69 @@ impl Parser {
     /// 解析一个代码块。
     ///
     /// 文法规则: `<block> ::= "{" {<block-item>} "}"`
-    fn parse_block(&mut self) -> Result<Block, String> {
+    fn parse_block(&mut self) -> Result<Block, Diagnostic> {
         self.consume(TokenType::LeftBrace)?;
         let mut items = Vec::new();
-        while !self.check(TokenType::RightBrace) {
-            items.push(self.parse_block_item()?);
+        while !self.check(TokenType::RightBrace) && !self.check(TokenType::Eof) {
+            let start = self.position;
+            match self.parse_block_items() {
+                Ok(parsed) => items.extend(parsed),
+                Err(e) => {
+                    self.errors.push(e);
+                    self.synchronize();
+                    // 保证每次恢复至少前进一个 Token，避免死循环
+                    if self.position == start {
+                        self.advance();
+                    }
+                }
+            }
         }
         self.consume(TokenType::RightBrace)?;
         Ok(Block(items))
     }
 
     /// 解析代码块中的一个条目，它可以是一个声明或一个语句。
+    /// 声明多个变量的声明展开为多个条目。
     ///
     /// 文法规则: `<block-item> ::= <declaration> | <statement>`
-    fn parse_block_item(&mut self) -> Result<BlockItem, String> {
+    fn parse_block_items(&mut self) -> Result<Vec<BlockItem>, Diagnostic> {
         if self.is_in_specifier() {
-            self.parse_declaration().map(BlockItem::D)
+            let decls = self.parse_declaration()?;
+            Ok(decls.into_iter().map(BlockItem::D).collect())
         } else {
-            self.parse_statement().map(BlockItem::S)
+            Ok(vec![BlockItem::S(self.parse_statement()?)])
         }
     }
     fn is_in_specifier(&mut self) -> bool {
-        if self.check(TokenType::Int)
+        self.check(TokenType::Int)
+            || self.check(TokenType::Bool)
             || self.check(TokenType::Static)
             || self.check(TokenType::Extern)
-        {
-            return true;
-        } else {
-            return false;
-        }
+            || self.check(TokenType::Volatile)
+            || self.check(TokenType::Noreturn)
+            || self.check(TokenType::Attribute)
     }
 
     /// 解析 `for` 循环的初始化部分。
     ///
     /// 文法规则: `<for-init> ::= <variable-declaration> | [<exp>] ";"`
-    fn parse_for_init(&mut self) -> Result<ForInit, String> {
-      
+    fn parse_for_init(&mut self) -> Result<ForInit, Diagnostic> {
         if self.is_in_specifier() {
-            // 情况 1: `for (int i = 0; ...)`
-            let decl = self.parse_declaration()?;
-            match decl {
-                Declaration::Variable(var_decl) => {
-                    // if !var_decl.storage_class.is_none() {
-                    //     return Err("Syntax Error: Storage-Class specifiers  is not allowed in a for-loop initializer.".to_string());
-                    // }
-                    Ok(ForInit::InitDecl(var_decl))
-                }
-                Declaration::Fun(_) => Err(
-                    "Syntax Error: Function declaration is not allowed in a for-loop initializer."
-                        .to_string(),
-                ),
-            }
+            // 情况 1: `for (int i = 0, j = n; ...)`
+            let decls = self.parse_declaration()?;
+            let vars = decls
+                .into_iter()
+                .map(|decl| match decl {
+                    Declaration::Variable(var_decl) => Ok(var_decl),
+                    Declaration::Fun(f) => Err(self.syntax_error_at(
+                        f.span,
+                        tr!(
+                            "Syntax Error: Function declaration is not allowed in a for-loop initializer."
+                        ),
+                    )),
+                })
+                .collect::<Result<Vec<_>, Diagnostic>>()?;
+            Ok(ForInit::InitDecl(vars))
         } else if self.match_token(TokenType::Semicolon) {
             // 情况 2: `for (; ...)` (无初始化表达式)
             Ok(ForInit::InitExp(None))
@@ -280,6 +450,33 @@ impl Parser {
         }
     }
 
+    /// 解析 `if`、`while` 和 `do-while` 语句括号中的条件。
+    ///
+    /// C 不允许在这里声明变量（C++ 的 `if (int x = f())`），给出明确的诊断，
+    /// 而不是报告 `int` 不能开始表达式。记录诊断后跳过括号中的内容继续解析语句的其余部分，
+    /// 这样声明本身不会再引出其他语法错误。
+    fn parse_condition(&mut self, statement: &str) -> Result<ExprId, Diagnostic> {
+        self.consume(TokenType::LeftParen)?;
+        if self.is_in_specifier() {
+            let span = self.tokens.peek().map_or(self.last_span, |t| t.span);
+            let error = self.syntax_error_at(
+                span,
+                tr!(
+                    "Syntax Error: A variable cannot be declared in the condition of a '{}' statement (that is C++); declare it before the statement.",
+                    statement
+                ),
+            );
+            self.errors.push(error);
+            self.skip_to_closing_paren();
+            self.consume(TokenType::RightParen)?;
+            // 已经有错误的程序不会进入语义分析，条件用一个占位的常量代替
+            return Ok(self.arena.alloc_expr(Expression::Constant(0), span));
+        }
+        let condition = self.parse_exp(0)?;
+        self.consume(TokenType::RightParen)?;
+        Ok(condition)
+    }
+
     /// 解析一条语句。
     ///
     /// 文法规则:
@@ -292,49 +489,52 @@ impl Parser {
     ///              |  "for" "(" <for-init> [<exp>] ";" [<exp>] ")" <statement>
     ///              |  "break" ";"
     ///              |  "continue" ";"
+    ///              |  <identifier> ":" <statement>
+    ///              |  "case" <exp> ":" <statement>
+    ///              |  "default" ":" <statement>
     ///              |  ";"`
-    fn parse_statement(&mut self) -> Result<Statement, String> {
+    fn parse_statement(&mut self) -> Result<StmtId, Diagnostic> {
+        let span = self.tokens.peek().map(|t| t.span).unwrap_or_default();
+        let stmt = self.nested(Self::parse_statement_inner)?;
+        Ok(self.arena.alloc_stmt(stmt, span))
+    }
+
+    fn parse_statement_inner(&mut self) -> Result<Statement, Diagnostic> {
         if self.match_token(TokenType::Return) {
             let expr = self.parse_exp(0)?;
             self.consume(TokenType::Semicolon)?;
             Ok(Statement::Return(expr))
         } else if self.match_token(TokenType::If) {
-            self.consume(TokenType::LeftParen)?;
-            let condition = self.parse_exp(0)?;
-            self.consume(TokenType::RightParen)?;
+            let condition = self.parse_condition("if")?;
             let then_stmt = self.parse_statement()?;
             let else_stmt = if self.match_token(TokenType::Else) {
-                Some(Box::new(self.parse_statement()?))
+                Some(self.parse_statement()?)
             } else {
                 None
             };
             Ok(Statement::If {
                 condition,
-                then_stmt: Box::new(then_stmt),
+                then_stmt,
                 else_stmt,
             })
         } else if self.check(TokenType::LeftBrace) {
             let block = self.parse_block()?;
             Ok(Statement::Compound(block))
         } else if self.match_token(TokenType::While) {
-            self.consume(TokenType::LeftParen)?;
-            let condition = self.parse_exp(0)?;
-            self.consume(TokenType::RightParen)?;
+            let condition = self.parse_condition("while")?;
             let body = self.parse_statement()?;
             Ok(Statement::While {
                 condition,
-                body: Box::new(body),
+                body,
                 label: None, // 标签在后续阶段处理
             })
         } else if self.match_token(TokenType::Do) {
             let body = self.parse_statement()?;
             self.consume(TokenType::While)?;
-            self.consume(TokenType::LeftParen)?;
-            let condition = self.parse_exp(0)?;
-            self.consume(TokenType::RightParen)?;
+            let condition = self.parse_condition("do-while")?;
             self.consume(TokenType::Semicolon)?;
             Ok(Statement::DoWhile {
-                body: Box::new(body),
+                body,
                 condition,
                 label: None,
             })
@@ -360,20 +560,42 @@ impl Parser {
                 init,
                 condition,
                 post,
-                body: Box::new(body),
+                body,
                 label: None,
             })
         } else if self.match_token(TokenType::Break) {
             self.consume(TokenType::Semicolon)?;
-            Ok(Statement::Break("fakelabel".to_string())) // 标签在后续阶段处理
+            Ok(Statement::Break(None)) // 标签在后续阶段处理
         } else if self.match_token(TokenType::Continue) {
             self.consume(TokenType::Semicolon)?;
-            Ok(Statement::Continue("fakelabel".to_string())) // 标签在后续阶段处理
+            Ok(Statement::Continue(None)) // 标签在后续阶段处理
+        } else if self.match_token(TokenType::Case) {
+            let value = self.parse_exp(0)?;
+            self.consume(TokenType::Colon)?;
+            let stmt = self.parse_statement()?;
+            Ok(Statement::Case {
+                value,
+                stmt,
+                label: None, // 跳转标签在后续阶段处理
+            })
+        } else if self.match_token(TokenType::Default) {
+            self.consume(TokenType::Colon)?;
+            let stmt = self.parse_statement()?;
+            Ok(Statement::Default { stmt, label: None })
         } else if self.match_token(TokenType::Semicolon) {
             Ok(Statement::Null)
         } else {
-            // 如果以上都不是，则它必须是一个表达式语句。
+            // 如果以上都不是，则它必须是一个表达式语句或带标签的语句。
+            let starts_with_identifier = self.check(TokenType::Identifier);
             let expr = self.parse_exp(0)?;
+            // 只有一个标识符的表达式后面跟着 `:` 是语句标签 `name: stmt`
+            if starts_with_identifier
+                && let Expression::Var(label) = self.arena[expr]
+                && self.match_token(TokenType::Colon)
+            {
+                let stmt = self.parse_statement()?;
+                return Ok(Statement::Labeled { label, stmt });
+            }
             self.consume(TokenType::Semicolon)?;
             Ok(Statement::Expression(expr))
         }
@@ -385,16 +607,17 @@ impl Parser {
     ///
     /// `min_prec` 参数指定了当前解析上下文的最小运算符优先级。
     /// 这是 Pratt 解析算法的核心，用于正确处理运算符的结合性和优先级。
-    fn parse_exp(&mut self, min_prec: i32) -> Result<Expression, String> {
+    fn parse_exp(&mut self, min_prec: i32) -> Result<ExprId, Diagnostic> {
+        self.nested(|p| p.parse_exp_inner(min_prec))
+    }
+
+    fn parse_exp_inner(&mut self, min_prec: i32) -> Result<ExprId, Diagnostic> {
         // 表达式总是以前缀部分开始（例如，一个数字、一个变量、一个括号表达式或一个一元运算符）。
         let mut left = self.parse_prefix()?;
 
         // 循环处理中缀运算符。
-        loop {
-            let next_token_type = match self.tokens.peek() {
-                Some(token) => token.type_.clone(),
-                None => break, // Token 流结束
-            };
+        while let Some(token) = self.tokens.peek() {
+            let next_token_type = token.type_.clone();
 
             // 获取该 Token 作为中缀运算符的优先级。
             // 如果它不是一个有效的运算符，或者其优先级低于当前上下文的最小优先级，则停止循环。
@@ -404,10 +627,12 @@ impl Parser {
             };
 
             // 消耗掉运算符 Token。
-            let op_token = self.tokens.next().unwrap();
+            let op_token = self.advance().unwrap();
+            // 左结合的运算符链不会递归，但每次都会把 `left` 包进更深的一层
+            self.enter_nested()?;
 
             // 根据运算符的类型，构建相应的表达式节点。
-            left = match op_token.type_ {
+            let exp = match op_token.type_ {
                 // 特殊情况：三元条件运算符 `?:`
                 TokenType::QuestionMark => {
                     let then_exp = self.parse_exp(0)?; // `then` 分支的优先级最低
@@ -415,19 +640,16 @@ impl Parser {
                     // `else` 分支的优先级与 `?:` 相同，以处理 `a ? b : c ? d : e`
                     let else_exp = self.parse_exp(op_prec)?;
                     Expression::Conditional {
-                        condition: Box::new(left),
-                        left: Box::new(then_exp),
-                        right: Box::new(else_exp),
+                        condition: left,
+                        left: then_exp,
+                        right: else_exp,
                     }
                 }
                 // 特殊情况：赋值运算符 `=` (右结合)
                 TokenType::Assignment => {
                     // 对于右结合运算符，递归调用 `parse_exp` 时传入与当前运算符相同的优先级。
                     let right = self.parse_exp(op_prec)?;
-                    Expression::Assignment {
-                        left: Box::new(left),
-                        right: Box::new(right),
-                    }
+                    Expression::Assignment { left, right }
                 }
                 // 通用情况：所有左结合的二元运算符
                 _ => {
@@ -436,11 +658,12 @@ impl Parser {
                     let right = self.parse_exp(op_prec + 1)?;
                     Expression::Binary {
                         op: bin_op,
-                        left: Box::new(left),
-                        right: Box::new(right),
+                        left,
+                        right,
                     }
                 }
             };
+            left = self.arena.alloc_expr(exp, op_token.span);
         }
 
         Ok(left)
@@ -449,13 +672,28 @@ impl Parser {
     /// 解析函数调用的参数列表。
     ///
     /// 文法规则: `<argument-list> ::= <exp> {"," <exp>} | <empty>`
-    fn parse_argument_list(&mut self) -> Result<Vec<Expression>, String> {
+    ///
+    /// `f(1,)` 和 `f(,1)` 这样的空参数会得到专门的错误信息，而不是笼统的“需要表达式”。
+    fn parse_argument_list(&mut self, callee: Symbol) -> Result<Vec<ExprId>, Diagnostic> {
         if self.check(TokenType::RightParen) {
             return Ok(Vec::new()); // 空参数列表
         }
 
         let mut argument_list = Vec::new();
         loop {
+            if self.check(TokenType::RightParen) {
+                return Err(self.syntax_error(tr!(
+                    "Syntax Error: Trailing comma in the argument list of call to '{}'.",
+                    callee
+                )));
+            }
+            if self.check(TokenType::Comma) {
+                return Err(self.syntax_error(tr!(
+                    "Syntax Error: Missing argument {} in call to '{}'.",
+                    argument_list.len() + 1,
+                    callee
+                )));
+            }
             let e = self.parse_exp(0)?;
             argument_list.push(e);
             if !self.match_token(TokenType::Comma) {
@@ -473,59 +711,103 @@ impl Parser {
     ///            |  <identifier> "(" [<argument-list>] ")"
     ///            |  <unary-op> <prefix>
     ///            |  "(" <exp> ")"`
-    fn parse_prefix(&mut self) -> Result<Expression, String> {
-        let next_token = self.tokens.next().ok_or_else(|| {
-            "Syntax Error: Expected an expression, but found end of input.".to_string()
-        })?;
-
-        match next_token.type_ {
-            TokenType::Number => {
-                let value = next_token
-                    .lexeme
-                    .parse::<i64>()
-                    .map_err(|e| format!("Syntax Error: Invalid number format: {}", e))?;
-                Ok(Expression::Constant(value))
+    fn parse_prefix(&mut self) -> Result<ExprId, Diagnostic> {
+        let Some(next_token) = self.tokens.peek().cloned() else {
+            return Err(self.syntax_error(tr!(
+                "Syntax Error: Expected an expression, but found end of input."
+            )));
+        };
+        // 不是合法前缀时不消耗该 Token（例如 `}`），以便错误恢复能从它开始同步
+        if !matches!(
+            next_token.type_,
+            TokenType::Number
+                | TokenType::Identifier
+                | TokenType::LeftParen
+                | TokenType::Minus
+                | TokenType::Add
+                | TokenType::Complement
+                | TokenType::Bang
+        ) {
+            return Err(self.syntax_error(tr!(
+                "Syntax Error: Expected an expression prefix (like a number, variable, or '('), but found {}.",
+                format!("{:?}", next_token.type_)
+            )));
+        }
+        self.advance();
+
+        // 常量和标识符按 token 携带的值区分，其余前缀 token 按类型区分
+        let exp = match (&next_token.type_, next_token.value) {
+            (_, TokenValue::Number(value)) => {
+                let value = i64::try_from(value).map_err(|e| {
+                    self.syntax_error_at(
+                        next_token.span,
+                        tr!("Syntax Error: Invalid number format: {}", e),
+                    )
+                })?;
+                Expression::Constant(value)
             }
-            TokenType::Identifier => {
-                let name = next_token
-                    .value
-                    .ok_or("Internal Error: Identifier token is missing a name")?;
+            (_, TokenValue::Identifier(symbol)) => {
+                let name = symbol;
+                // 成员偏移要由结构体布局计算，结构体实现之前明确报告不支持，
+                // 而不是把它当成对未声明函数的调用
+                if name == "__builtin_offsetof" {
+                    return Err(self.syntax_error_at(
+                        next_token.span,
+                        tr!(
+                            "Syntax Error: __builtin_offsetof requires struct types, which are not supported yet."
+                        ),
+                    ));
+                }
                 if self.match_token(TokenType::LeftParen) {
                     // 这是一个函数调用
-                    let args = self.parse_argument_list()?;
+                    let args = self.parse_argument_list(name)?;
                     self.consume(TokenType::RightParen)?;
-                    Ok(Expression::FuncCall { name, args })
+                    Expression::FuncCall { name, args }
                 } else {
                     // 这是一个变量
-                    Ok(Expression::Var(name))
+                    Expression::Var(name)
                 }
             }
-            TokenType::LeftParen => {
-                // 这是一个括号表达式
+            (TokenType::LeftParen, _) => {
+                // 这是一个括号表达式，不产生新的节点
                 let exp = self.parse_exp(0)?;
                 self.consume(TokenType::RightParen)?;
-                Ok(exp)
+                return Ok(exp);
             }
             // 处理所有一元前缀运算符
-            TokenType::Negate | TokenType::Complement | TokenType::Bang => {
+            (TokenType::Minus | TokenType::Add | TokenType::Complement | TokenType::Bang, _) => {
                 let op = self.to_unary_op(&next_token.type_)?;
                 let ((), op_prec) = self.get_prefix_precedence(&next_token.type_).unwrap();
                 let right_exp = self.parse_exp(op_prec)?;
-                Ok(Expression::Unary {
-                    op,
-                    exp: Box::new(right_exp),
-                })
+                Expression::Unary { op, exp: right_exp }
             }
-            _ => Err(format!(
-                "Syntax Error: Expected an expression prefix (like a number, variable, or '('), but found {:?}.",
-                next_token.type_
-            )),
-        }
+            _ => unreachable!("已在上面检查过前缀 Token"),
+        };
+        Ok(self.arena.alloc_expr(exp, next_token.span))
     }
 
     // --- 优先级和工具函数 ---
 
     /// 获取中缀（二元）运算符的优先级。返回 `None` 表示该 Token 不是一个有效的中缀运算符。
+    ///
+    /// 优先级与结合性矩阵（数值越大绑定越紧；“右操作数”一列是解析右侧时传给 `parse_exp` 的 `min_prec`）：
+    ///
+    /// | 运算符                  | 优先级 | 结合性 | 右操作数                                  |
+    /// |-------------------------|--------|--------|-------------------------------------------|
+    /// | `=`                     | 10     | 右     | 10                                        |
+    /// | `?:`                    | 15     | 右     | 中间 0（任意表达式）；`:` 之后 15          |
+    /// | `\|\|`                    | 20     | 左     | 21                                        |
+    /// | `&&`                    | 30     | 左     | 31                                        |
+    /// | `==` `!=`               | 40     | 左     | 41                                        |
+    /// | `<` `<=` `>` `>=`       | 50     | 左     | 51                                        |
+    /// | `+` `-`                 | 60     | 左     | 61                                        |
+    /// | `*` `/` `%`             | 70     | 左     | 71                                        |
+    /// | 前缀 `-` `+` `~` `!`    | 80     | —      | 80                                        |
+    ///
+    /// `=` 与 `?:` 的交互遵循 C 文法（而不是 C++）：`?:` 的第三个操作数是条件表达式，
+    /// 不能是赋值表达式，所以 `a = b ? c : d = e` 解析为 `a = ((b ? c : d) = e)`，
+    /// `x ? y = 1 : z = 2` 解析为 `(x ? (y = 1) : z) = 2`；两者随后都会因为赋值的左侧
+    /// 不是左值而在标识符解析阶段被拒绝，与 gcc 一致。`parser_tests.rs` 中的测试固定了这些行为。
     fn get_infix_precedence(&self, typ: &TokenType) -> Option<i32> {
         match typ {
             TokenType::Assignment => Some(10),
@@ -537,7 +819,7 @@ impl Parser {
             | TokenType::GreaterEqual
             | TokenType::Less
             | TokenType::LessEqual => Some(50),
-            TokenType::Add | TokenType::Negate => Some(60), // 在中缀位置，'-' 是减法
+            TokenType::Add | TokenType::Minus => Some(60),
             TokenType::Mul | TokenType::Div | TokenType::Remainder => Some(70),
             _ => None,
         }
@@ -546,16 +828,18 @@ impl Parser {
     /// 获取前缀（一元）运算符的优先级。
     fn get_prefix_precedence(&self, typ: &TokenType) -> Option<((), i32)> {
         match typ {
-            TokenType::Negate | TokenType::Complement | TokenType::Bang => Some(((), 80)),
+            TokenType::Minus | TokenType::Add | TokenType::Complement | TokenType::Bang => {
+                Some(((), 80))
+            }
             _ => None,
         }
     }
 
     /// 将 `TokenType` 转换为 `BinaryOp`。
-    fn to_binary_op(&self, typ: &TokenType) -> Result<BinaryOp, String> {
+    fn to_binary_op(&self, typ: &TokenType) -> Result<BinaryOp, Diagnostic> {
         match typ {
             TokenType::Add => Ok(BinaryOp::Add),
-            TokenType::Negate => Ok(BinaryOp::Subtract), // 在中缀位置，'-' 是减法
+            TokenType::Minus => Ok(BinaryOp::Subtract), // 在中缀位置，'-' 是减法
             TokenType::Mul => Ok(BinaryOp::Multiply),
             TokenType::Div => Ok(BinaryOp::Divide),
             TokenType::Remainder => Ok(BinaryOp::Remainder),
@@ -567,50 +851,147 @@ impl Parser {
             TokenType::GreaterEqual => Ok(BinaryOp::GreaterEqual),
             TokenType::Less => Ok(BinaryOp::Less),
             TokenType::LessEqual => Ok(BinaryOp::LessEqual),
-            _ => Err(format!(
-                "Internal Error: Cannot convert {:?} to a binary operator.",
-                typ
-            )),
+            _ => Err(Diagnostic::error(tr!(
+                "Internal Error: Cannot convert {} to a binary operator.",
+                format!("{:?}", typ)
+            ))
+            .with_code("internal-error")),
         }
     }
 
     /// 将 `TokenType` 转换为 `UnaryOp`。
-    fn to_unary_op(&self, typ: &TokenType) -> Result<UnaryOp, String> {
+    fn to_unary_op(&self, typ: &TokenType) -> Result<UnaryOp, Diagnostic> {
         match typ {
-            TokenType::Negate => Ok(UnaryOp::Negate),
+            TokenType::Minus => Ok(UnaryOp::Negate), // 在前缀位置，'-' 是取负
             TokenType::Complement => Ok(UnaryOp::Complement),
             TokenType::Bang => Ok(UnaryOp::Not),
-            _ => Err(format!(
-                "Internal Error: Cannot convert {:?} to a unary operator.",
-                typ
-            )),
+            TokenType::Add => Ok(UnaryOp::Plus), // 在前缀位置，'+' 是一元加
+            _ => Err(Diagnostic::error(tr!(
+                "Internal Error: Cannot convert {} to a unary operator.",
+                format!("{:?}", typ)
+            ))
+            .with_code("internal-error")),
         }
     }
 
-    /// 消耗一个期望的 Token。如果下一个 Token 不是期望的类型，则返回错误。
-    fn consume(&mut self, expected: TokenType) -> Result<Token, String> {
-        match self.tokens.next() {
-            Some(token) if token.type_ == expected => Ok(token),
-            Some(token) => Err(format!(
-                "Syntax Error: Expected token {:?}, but got {:?}.",
-                expected, token.type_
-            )),
-            None => Err(format!(
-                "Syntax Error: Expected token {:?}, but the input stream ended.",
-                expected
+    /// 消耗一个期望的 Token。如果下一个 Token 不是期望的类型，则返回错误（此时不消耗该 Token）。
+    /// 消耗一个标识符 Token，返回它的名字和位置。
+    fn consume_identifier(&mut self) -> Result<(Symbol, Span), Diagnostic> {
+        let token = self.consume(TokenType::Identifier)?;
+        match token.value {
+            TokenValue::Identifier(symbol) => Ok((symbol, token.span)),
+            _ => Err(self.syntax_error_at(
+                token.span,
+                tr!("Syntax Error: Expected a name for the identifier, but it was missing."),
             )),
         }
     }
 
+    fn consume(&mut self, expected: TokenType) -> Result<Token, Diagnostic> {
+        match self.tokens.peek() {
+            Some(token) if token.type_ == expected => Ok(self.advance().unwrap()),
+            Some(token) => {
+                let message = tr!(
+                    "Syntax Error: Expected token {}, but got {}.",
+                    format!("{:?}", expected),
+                    format!("{:?}", token.type_)
+                );
+                Err(self.syntax_error(message))
+            }
+            None => Err(self.syntax_error(tr!(
+                "Syntax Error: Expected token {}, but the input stream ended.",
+                format!("{:?}", expected)
+            ))),
+        }
+    }
+
+    /// 位于下一个 Token 处的语法错误；输入已经结束时位于最后一个 Token 处。
+    fn syntax_error(&mut self, message: String) -> Diagnostic {
+        let span = self.tokens.peek().map_or(self.last_span, |t| t.span);
+        self.syntax_error_at(span, message)
+    }
+
+    fn syntax_error_at(&self, span: Span, message: String) -> Diagnostic {
+        Diagnostic::error(message)
+            .with_code("syntax-error")
+            .with_span(span)
+    }
+
+    /// 消耗并返回下一个 Token。
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.next();
+        if let Some(token) = &token {
+            self.position += 1;
+            self.last_span = token.span;
+        }
+        token
+    }
+
+    /// 恐慌模式恢复：跳过 Token 直到一个同步点。
+    ///
+    /// 遇到 `;` 时消耗它并停止；遇到 `}`、文件结尾或语句起始关键字时停止但不消耗，
+    /// 由调用者从该处继续解析。
+    fn synchronize(&mut self) {
+        while let Some(token) = self.tokens.peek() {
+            match token.type_ {
+                TokenType::Semicolon => {
+                    self.advance();
+                    return;
+                }
+                TokenType::RightBrace
+                | TokenType::LeftBrace
+                | TokenType::Eof
+                | TokenType::Return
+                | TokenType::If
+                | TokenType::While
+                | TokenType::Do
+                | TokenType::For
+                | TokenType::Break
+                | TokenType::Continue
+                | TokenType::Case
+                | TokenType::Default
+                | TokenType::Int
+                | TokenType::Bool
+                | TokenType::Static
+                | TokenType::Extern
+                | TokenType::Volatile
+                | TokenType::Noreturn
+                | TokenType::Attribute => return,
+                _ => {
+                    self.advance();
+                }
+            }
+        }
+    }
+
+    /// 跳过 Token 直到与已经消耗的 `(` 匹配的 `)`（不消耗它）。遇到 `;`、花括号或文件结尾时
+    /// 停止，不会越过语句的边界。
+    fn skip_to_closing_paren(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.tokens.peek() {
+            match token.type_ {
+                TokenType::RightParen if depth == 0 => return,
+                TokenType::RightParen => depth -= 1,
+                TokenType::LeftParen => depth += 1,
+                TokenType::Semicolon
+                | TokenType::LeftBrace
+                | TokenType::RightBrace
+                | TokenType::Eof => return,
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+
     /// 检查下一个 Token 是否是期望的类型，但不消耗它。
     fn check(&mut self, expected: TokenType) -> bool {
-        self.tokens.peek().map_or(false, |t| t.type_ == expected)
+        self.tokens.peek().is_some_and(|t| t.type_ == expected)
     }
 
     /// 如果下一个 Token 是期望的类型，则消耗它并返回 `true`。否则，不消耗任何东西并返回 `false`。
     fn match_token(&mut self, expected: TokenType) -> bool {
         if self.check(expected) {
-            self.tokens.next();
+            self.advance();
             true
         } else {
             false
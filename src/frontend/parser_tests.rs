@@ -0,0 +1,637 @@
+// src/frontend/parser_tests.rs
+
+//! Pratt 解析器优先级表的性质测试 (property-based tests)。
+//!
+//! 随机生成表达式 AST，分别打印成“每个子表达式都加括号”和“按优先级只加必要括号”
+//! 两种形式，解析两者并断言得到的 AST 与原始 AST 完全一致。
+//! 如果 `get_infix_precedence` 等优先级/结合性规则有误，最小括号形式会被解析成
+//! 不同的树，测试就会失败。
+
+use proptest::prelude::*;
+
+use crate::UniqueNameGenerator;
+use crate::backend::assembly_ast_gen::IntDivOverflow;
+use crate::backend::interpreter;
+use crate::backend::tacky_gen::TackyGenerator;
+use crate::common::{AstNode, CStandard, DataLayout, PrettyConfig, Span, Symbol};
+use crate::diagnostics::Errors;
+use crate::frontend::c_ast::{
+    AstArena, BinaryOp, BlockItem, Declaration, ExprId, Expression, Statement, UnaryOp,
+};
+use crate::frontend::const_eval::{self, ConstEvalError};
+use crate::frontend::dangling_else;
+use crate::frontend::lexer::Lexer;
+use crate::frontend::loop_labeling::LoopLabeling;
+use crate::frontend::parse_program;
+use crate::frontend::parser::Parser;
+use crate::frontend::resolve_ident::IdentifierResolver;
+use crate::frontend::type_checking::TypeChecker;
+
+// 与 parser.rs 中的优先级保持一致
+const PREC_ASSIGN: i32 = 10;
+const PREC_CONDITIONAL: i32 = 15;
+const PREC_UNARY: i32 = 80;
+const PREC_ATOM: i32 = 100;
+
+fn binary_prec(op: &BinaryOp) -> i32 {
+    match op {
+        BinaryOp::Or => 20,
+        BinaryOp::And => 30,
+        BinaryOp::EqualEqual | BinaryOp::BangEqual => 40,
+        BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual => 50,
+        BinaryOp::Add | BinaryOp::Subtract => 60,
+        BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Remainder => 70,
+    }
+}
+
+fn binary_symbol(op: &BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Add => "+",
+        BinaryOp::Subtract => "-",
+        BinaryOp::Multiply => "*",
+        BinaryOp::Divide => "/",
+        BinaryOp::Remainder => "%",
+        BinaryOp::And => "&&",
+        BinaryOp::Or => "||",
+        BinaryOp::EqualEqual => "==",
+        BinaryOp::BangEqual => "!=",
+        BinaryOp::LessEqual => "<=",
+        BinaryOp::GreaterEqual => ">=",
+        BinaryOp::Less => "<",
+        BinaryOp::Greater => ">",
+    }
+}
+
+fn unary_symbol(op: &UnaryOp) -> &'static str {
+    match op {
+        UnaryOp::Negate => "-",
+        UnaryOp::Complement => "~",
+        UnaryOp::Not => "!",
+        UnaryOp::Plus => "+",
+    }
+}
+
+/// 测试用的表达式树：与 `Expression` 一一对应，但子表达式直接内嵌，
+/// 便于随机生成和整体比较。解析结果通过 `to_tree` 从 AST arena 中取出。
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Constant(i64),
+    Var(Symbol),
+    FuncCall {
+        name: Symbol,
+        args: Vec<Expr>,
+    },
+    Unary {
+        op: UnaryOp,
+        exp: Box<Expr>,
+    },
+    Binary {
+        op: BinaryOp,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    Assignment {
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    Conditional {
+        condition: Box<Expr>,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+}
+
+fn to_tree(arena: &AstArena, exp: ExprId) -> Expr {
+    let tree = |e: ExprId| Box::new(to_tree(arena, e));
+    match &arena[exp] {
+        Expression::Constant(i) => Expr::Constant(*i),
+        Expression::Var(name) => Expr::Var(*name),
+        Expression::FuncCall { name, args } => Expr::FuncCall {
+            name: *name,
+            args: args.iter().map(|&a| to_tree(arena, a)).collect(),
+        },
+        Expression::Unary { op, exp } => Expr::Unary {
+            op: op.clone(),
+            exp: tree(*exp),
+        },
+        Expression::Binary { op, left, right } => Expr::Binary {
+            op: op.clone(),
+            left: tree(*left),
+            right: tree(*right),
+        },
+        Expression::Assignment { left, right } => Expr::Assignment {
+            left: tree(*left),
+            right: tree(*right),
+        },
+        Expression::Conditional {
+            condition,
+            left,
+            right,
+        } => Expr::Conditional {
+            condition: tree(*condition),
+            left: tree(*left),
+            right: tree(*right),
+        },
+    }
+}
+
+fn prec(exp: &Expr) -> i32 {
+    match exp {
+        Expr::Constant(_) | Expr::Var(_) | Expr::FuncCall { .. } => PREC_ATOM,
+        Expr::Unary { .. } => PREC_UNARY,
+        Expr::Binary { op, .. } => binary_prec(op),
+        Expr::Conditional { .. } => PREC_CONDITIONAL,
+        Expr::Assignment { .. } => PREC_ASSIGN,
+    }
+}
+
+/// 打印表达式。`minimal` 为 true 时只在优先级要求时加括号，否则每个非原子子表达式都加括号。
+fn print(exp: &Expr, minimal: bool) -> String {
+    // 子表达式的优先级至少为 `min` 时才可以省略括号
+    let child = |e: &Expr, min: i32| {
+        let s = print(e, minimal);
+        if (minimal && prec(e) >= min) || prec(e) == PREC_ATOM {
+            s
+        } else {
+            format!("({})", s)
+        }
+    };
+    match exp {
+        Expr::Constant(i) => i.to_string(),
+        Expr::Var(name) => name.to_string(),
+        Expr::FuncCall { name, args } => {
+            let args: Vec<String> = args.iter().map(|a| print(a, minimal)).collect();
+            format!("{}({})", name, args.join(", "))
+        }
+        // 一元运算符后加空格，避免 `- -a` 被写成 `--a`
+        Expr::Unary { op, exp } => format!("{} {}", unary_symbol(op), child(exp, PREC_UNARY)),
+        // 左结合：右操作数的优先级必须严格更高
+        Expr::Binary { op, left, right } => {
+            let p = binary_prec(op);
+            format!(
+                "{} {} {}",
+                child(left, p),
+                binary_symbol(op),
+                child(right, p + 1)
+            )
+        }
+        // 右结合
+        Expr::Assignment { left, right } => format!(
+            "{} = {}",
+            child(left, PREC_ASSIGN + 1),
+            child(right, PREC_ASSIGN)
+        ),
+        Expr::Conditional {
+            condition,
+            left,
+            right,
+        } => format!(
+            "{} ? {} : {}",
+            child(condition, PREC_CONDITIONAL + 1),
+            child(left, 0),
+            child(right, PREC_CONDITIONAL)
+        ),
+    }
+}
+
+/// 把表达式放进 `return` 语句中解析，返回 AST arena 和解析得到的表达式。
+fn parse_return_expression(source: &str) -> Result<(AstArena, ExprId), String> {
+    let program = format!("int main(void) {{ return {}; }}", source);
+    let tokens = Lexer::with_standard(CStandard::default())
+        .lex(&program)
+        .map_err(|e| e.to_string())?;
+    let ast = Parser::new(tokens)
+        .parse()
+        .map_err(|e| Errors(e).to_string())?;
+    match ast.declarations.into_iter().next() {
+        Some(Declaration::Fun(f)) => match f.body.map(|b| b.0.into_iter().next()) {
+            Some(Some(BlockItem::S(stmt))) => match ast.arena[stmt] {
+                Statement::Return(exp) => Ok((ast.arena, exp)),
+                ref other => Err(format!("意外的语句: {:?}", other)),
+            },
+            other => Err(format!("意外的函数体: {:?}", other)),
+        },
+        other => Err(format!("意外的声明: {:?}", other)),
+    }
+}
+
+fn parse_expression(source: &str) -> Result<Expr, String> {
+    parse_return_expression(source).map(|(arena, exp)| to_tree(&arena, exp))
+}
+
+fn arb_binary_op() -> impl Strategy<Value = BinaryOp> {
+    prop_oneof![
+        Just(BinaryOp::Add),
+        Just(BinaryOp::Subtract),
+        Just(BinaryOp::Multiply),
+        Just(BinaryOp::Divide),
+        Just(BinaryOp::Remainder),
+        Just(BinaryOp::And),
+        Just(BinaryOp::Or),
+        Just(BinaryOp::EqualEqual),
+        Just(BinaryOp::BangEqual),
+        Just(BinaryOp::LessEqual),
+        Just(BinaryOp::GreaterEqual),
+        Just(BinaryOp::Less),
+        Just(BinaryOp::Greater),
+    ]
+}
+
+fn arb_unary_op() -> impl Strategy<Value = UnaryOp> {
+    prop_oneof![
+        Just(UnaryOp::Negate),
+        Just(UnaryOp::Complement),
+        Just(UnaryOp::Not),
+        Just(UnaryOp::Plus),
+    ]
+}
+
+fn arb_expression() -> impl Strategy<Value = Expr> {
+    let leaf = prop_oneof![
+        (0i64..1000).prop_map(Expr::Constant),
+        prop::sample::select(vec!["a", "b", "c"]).prop_map(|v| Expr::Var(v.into())),
+    ];
+    leaf.prop_recursive(6, 64, 3, |inner| {
+        prop_oneof![
+            (arb_unary_op(), inner.clone()).prop_map(|(op, exp)| Expr::Unary {
+                op,
+                exp: Box::new(exp),
+            }),
+            (arb_binary_op(), inner.clone(), inner.clone()).prop_map(|(op, l, r)| {
+                Expr::Binary {
+                    op,
+                    left: Box::new(l),
+                    right: Box::new(r),
+                }
+            }),
+            (inner.clone(), inner.clone()).prop_map(|(l, r)| Expr::Assignment {
+                left: Box::new(l),
+                right: Box::new(r),
+            }),
+            (inner.clone(), inner.clone(), inner.clone()).prop_map(|(c, l, r)| {
+                Expr::Conditional {
+                    condition: Box::new(c),
+                    left: Box::new(l),
+                    right: Box::new(r),
+                }
+            }),
+            prop::collection::vec(inner, 0..3).prop_map(|args| Expr::FuncCall {
+                name: "f".into(),
+                args,
+            }),
+        ]
+    })
+}
+
+proptest! {
+    #[test]
+    fn fully_parenthesized_round_trip(exp in arb_expression()) {
+        let source = print(&exp, false);
+        prop_assert_eq!(parse_expression(&source).map_err(TestCaseError::fail)?, exp, "source: {}", source);
+    }
+
+    #[test]
+    fn minimally_parenthesized_round_trip(exp in arb_expression()) {
+        let source = print(&exp, true);
+        prop_assert_eq!(parse_expression(&source).map_err(TestCaseError::fail)?, exp, "source: {}", source);
+    }
+}
+
+#[test]
+fn subtraction_and_negation() {
+    // `-` 在前缀位置是取负，在中缀位置是减法
+    let parsed = parse_expression("a - -b - c").unwrap();
+    let expected = Expr::Binary {
+        op: BinaryOp::Subtract,
+        left: Box::new(Expr::Binary {
+            op: BinaryOp::Subtract,
+            left: Box::new(Expr::Var("a".into())),
+            right: Box::new(Expr::Unary {
+                op: UnaryOp::Negate,
+                exp: Box::new(Expr::Var("b".into())),
+            }),
+        }),
+        right: Box::new(Expr::Var("c".into())),
+    };
+    assert_eq!(parsed, expected);
+}
+
+#[test]
+fn recovers_after_missing_semicolons() {
+    // 两处缺失的分号都应该被报告，而不是只报告第一处
+    let source = "int main(void) { int a = 1 a = 2; return a }";
+    let tokens = Lexer::with_standard(CStandard::default())
+        .lex(source)
+        .unwrap();
+    let errors = Parser::new(tokens).parse().unwrap_err();
+    // 每个错误都是单独的一条诊断，位于出错的 token 处
+    let positions: Vec<_> = errors
+        .iter()
+        .map(|e| (e.code.as_deref(), e.span.map(|s| (s.line, s.column))))
+        .collect();
+    assert_eq!(
+        positions,
+        [
+            (Some("syntax-error"), Some((1, 28))),
+            (Some("syntax-error"), Some((1, 44))),
+        ],
+        "{}",
+        Errors(errors.clone())
+    );
+}
+
+/// 逐个检查 `parser_corpus.txt` 中的用例，一次报告所有结果不符的用例。
+#[test]
+fn parser_conformance_corpus() {
+    let corpus = include_str!("parser_corpus.txt");
+    let mut cases = 0;
+    let mut failures = Vec::new();
+    for case in corpus.split("\n--- ").skip(1) {
+        let (header, source) = case.split_once('\n').unwrap_or((case, ""));
+        let (expected, name) = header.split_once(": ").expect("用例标题格式错误");
+        let accept = match expected {
+            "accept" => true,
+            "reject" => false,
+            other => panic!("未知的期望结果 '{}'", other),
+        };
+        let result = Lexer::with_standard(CStandard::default())
+            .lex(source)
+            .map_err(|e| vec![e])
+            .and_then(|tokens| Parser::new(tokens).parse());
+        if result.is_ok() != accept {
+            failures.push(format!("{}: {} {:?}", expected, name, result.err()));
+        }
+        cases += 1;
+    }
+    assert!(cases >= 100, "只读到 {} 个用例", cases);
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn pretty_printed_ast_shape() {
+    let ast = parse_program("int main(void) { int a = 1; return a + 2; }");
+    let expected = "\
+Program
+  FunctionDefinition(name: \"main\", returns: int, params: [void])
+    Block
+      VarDeclaration(name: \"a\", type: int, with init)
+        Constant(1)
+      Return
+        Binary(op: '+')
+          Var(name: \"a\")
+          Constant(2)
+";
+    assert_eq!(ast.pretty().to_string(), expected);
+}
+
+#[test]
+fn multiple_declarators_expand_into_declarations() {
+    let ast =
+        parse_program("static int f(void), x = 1, y;\nint main(void) { int a = 1, b, c = a; }");
+    let names: Vec<(&str, bool)> = ast
+        .declarations
+        .iter()
+        .map(|d| match d {
+            Declaration::Fun(f) => (f.name.as_str(), f.storage_class.is_some()),
+            Declaration::Variable(v) => (v.name.as_str(), v.storage_class.is_some()),
+        })
+        .collect();
+    assert_eq!(
+        names,
+        [("f", true), ("x", true), ("y", true), ("main", false)]
+    );
+    let expected = "\
+  FunctionDefinition(name: \"main\", returns: int, params: [void])
+    Block
+      VarDeclaration(name: \"a\", type: int, with init)
+        Constant(1)
+      VarDeclaration(name: \"b\", type: int)
+      VarDeclaration(name: \"c\", type: int, with init)
+        Var(name: \"a\")
+";
+    assert!(
+        ast.pretty().to_string().ends_with(expected),
+        "{}",
+        ast.pretty()
+    );
+}
+
+#[test]
+fn for_loop_declarations_and_declarations_in_conditions() {
+    // for 的声明列表中的变量都在循环的作用域中，循环结束后不可见
+    let ast = parse_program(
+        "int main(void) { int s = 0; for (int i = 0, j = 5; i < j; i = i + 1) s = s + j - i; return s; }",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let labeled = LoopLabeling::new(&mut names)
+        .label_loops_in_program(&resolved)
+        .unwrap();
+    let (typed, symbols, _) = TypeChecker::new().typecheck_program(&labeled).unwrap();
+    let (program, _) = TackyGenerator::new(&mut names, &symbols)
+        .generate_tacky(&typed)
+        .unwrap();
+    assert_eq!(
+        interpreter::run(&program, &symbols, IntDivOverflow::Trap).unwrap(),
+        15
+    );
+    let ast = parse_program("int main(void) { for (int i = 0, j = 1; i; ) ; return j; }");
+    assert!(
+        IdentifierResolver::new(&mut UniqueNameGenerator::new())
+            .resolve_program(&ast)
+            .is_err()
+    );
+
+    for source in [
+        "int main(void) { if (int x = 1) return x; return 0; }",
+        "int main(void) { while (int x = 0) ; return 0; }",
+        "int main(void) { do ; while (int x = 0); return 0; }",
+    ] {
+        let tokens = Lexer::with_standard(CStandard::default())
+            .lex(source)
+            .unwrap();
+        // 只报告这一个错误：声明的其余部分不会再被当作一条声明解析
+        let errors = Parser::new(tokens).parse().unwrap_err();
+        assert_eq!(errors.len(), 1, "{}", Errors(errors.clone()));
+        assert!(
+            errors[0]
+                .message
+                .contains("cannot be declared in the condition"),
+            "{}",
+            errors[0]
+        );
+    }
+}
+
+#[test]
+fn dangling_else_binds_to_the_inner_if() {
+    let ast = parse_program(
+        "\
+int main(void) {
+    int a = 1;
+    if (a)
+        if (0) return 1;
+    else return 2;
+    if (a) { if (0) return 1; } else return 2;
+    if (a) if (0) return 1; else return 2; else return 3;
+    if (a) for (;;) if (0) return 1; else break;
+    return 3;
+}
+",
+    );
+    let expected = "\
+      IfStatement
+        Condition
+          Var(name: \"a\")
+        Then
+          IfStatement
+            Condition
+              Constant(0)
+            Then
+              Return
+                Constant(1)
+            Else
+              Return
+                Constant(2)
+      IfStatement
+";
+    assert!(
+        ast.pretty().to_string().contains(expected),
+        "{}",
+        ast.pretty()
+    );
+    // 只有外层 if 没有 else、分支没有花括号的两处需要警告
+    let warnings = dangling_else::find_dangling_else(&ast);
+    assert_eq!(warnings.len(), 2, "{:?}", warnings);
+    assert!(warnings[0].to_string().starts_with("3:5"), "{:?}", warnings);
+    assert!(warnings[1].to_string().starts_with("8:5"), "{:?}", warnings);
+}
+
+#[test]
+fn pretty_print_elides_long_bodies() {
+    let ast = parse_program("int main(void) { 1; 2; 3; 4; return 0; }");
+    let config = PrettyConfig {
+        indent_width: 1,
+        elide_over: Some(2),
+        ..PrettyConfig::default()
+    };
+    let expected = "\
+Program
+ FunctionDefinition(name: \"main\", returns: int, params: [void])
+  Block
+   ExpressionStatement
+    Constant(1)
+   ExpressionStatement
+    Constant(2)
+   ... (省略 3 个节点)
+";
+    assert_eq!(ast.pretty_with(config).to_string(), expected);
+}
+
+#[test]
+fn rejects_nesting_beyond_depth_limit() {
+    // 左结合的运算符链不经过递归，也必须计入深度
+    let source = format!("int main(void) {{ return {}; }}", ["1"; 20].join(" + "));
+    let tokens = Lexer::with_standard(CStandard::default())
+        .lex(&source)
+        .unwrap();
+    let errors = Parser::new(tokens.clone())
+        .with_max_depth(10)
+        .parse()
+        .unwrap_err();
+    assert!(
+        errors[0].message.contains("--max-ast-depth"),
+        "{}",
+        Errors(errors)
+    );
+    assert!(Parser::new(tokens).with_max_depth(32).parse().is_ok());
+}
+
+#[test]
+fn trailing_comma_in_call_has_specific_error() {
+    let error = parse_expression("f(1,)").unwrap_err();
+    assert!(error.contains("Trailing comma"), "{}", error);
+}
+
+/// 解析后按“每个子表达式都加括号”的形式打印，便于断言树的形状。
+fn parenthesized(source: &str) -> String {
+    print(&parse_expression(source).unwrap(), false)
+}
+
+#[test]
+fn conditional_and_assignment_interaction() {
+    // 与 parser.rs 中 `get_infix_precedence` 的优先级矩阵对应
+    let cases = [
+        ("a = b = c", "a = (b = c)"),
+        ("a ? b : c ? d : e", "a ? b : (c ? d : e)"),
+        ("a ? b ? c : d : e", "a ? (b ? c : d) : e"),
+        ("a = b ? c : d", "a = (b ? c : d)"),
+        // `?:` 的第三个操作数不能是赋值表达式：赋值作用于整个条件表达式
+        ("a = b ? c : d = e", "a = ((b ? c : d) = e)"),
+        ("x ? y = 1 : z = 2", "(x ? (y = 1) : z) = 2"),
+        // 中间操作数可以是任意表达式
+        ("x ? y = 1 : 2", "x ? (y = 1) : 2"),
+        ("a || b ? c : d", "(a || b) ? c : d"),
+        ("a ? b : c || d", "a ? b : (c || d)"),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(parenthesized(source), expected, "source: {}", source);
+    }
+}
+
+#[test]
+fn declaration_spans_follow_line_markers() {
+    // 行标记之后的行号对应原始源文件，而不是预处理输出中的行
+    let ast = parse_program(
+        "# 1 \"<built-in>\"\n# 7 \"main.c\"\nint x;\n\n  int main(void) { return x; }\n",
+    );
+    let spans: Vec<Span> = ast
+        .declarations
+        .iter()
+        .map(|d| match d {
+            Declaration::Fun(f) => f.span,
+            Declaration::Variable(v) => v.span,
+        })
+        .collect();
+    assert_eq!(
+        spans,
+        [
+            Span {
+                line: 7,
+                column: 5,
+                file: None
+            },
+            Span {
+                line: 9,
+                column: 7,
+                file: None
+            }
+        ]
+    );
+}
+
+#[test]
+fn constant_expressions_follow_int_semantics() {
+    let eval = |source: &str| {
+        let (arena, exp) = parse_return_expression(source).unwrap();
+        const_eval::eval_int(exp, &arena, &DataLayout::LP64)
+    };
+    assert_eq!(
+        eval("(3 * 4 - 2) % 7 ? -5 : 1 / 0").map(|c| (c.value, c.overflowed)),
+        Ok((-5, false))
+    );
+    // 短路的操作数不会被求值
+    assert_eq!(eval("0 && 1 / 0").map(|c| c.value), Ok(0));
+    assert_eq!(eval("1 % (2 - 2)"), Err(ConstEvalError::DivisionByZero));
+    assert_eq!(
+        eval("2147483647 + 1").map(|c| (c.value, c.overflowed)),
+        Ok((-2147483648, true))
+    );
+    assert_eq!(
+        eval("-2147483647 - 1").map(|c| (c.value, c.overflowed)),
+        Ok((-2147483648, false))
+    );
+    assert_eq!(eval("x + 1"), Err(ConstEvalError::NotConstant));
+}
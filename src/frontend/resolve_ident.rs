@@ -26,15 +26,23 @@
 //!         -   引用未声明的变量。
 //!         -   在函数参数和函数体顶层作用域之间重复定义变量。
 //!         -   非法地在函数内部定义另一个函数。
+//!
+//! 5.  **警告**:
+//!     -   `-Wunused-parameter`：函数定义中从未被引用的参数。任何引用（包括赋值）都算作使用。
+//!         语言支持类型转换后，`(void)x;` 也会因为引用了 `x` 而消除该警告。
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+use crate::common::Symbol;
+use crate::diagnostics::Diagnostic;
+use crate::i18n::tr;
 use crate::{
     UniqueNameGenerator,
     frontend::c_ast::{
-        Block, BlockItem, Declaration, Expression, ForInit, FunDecl, Program, Statement,
-        StorageClass, VarDecl,
+        AstArena, Block, BlockItem, Declaration, ExprId, Expression, ForInit, FunDecl, Program,
+        Statement, StmtId, StorageClass, VarDecl,
     },
+    frontend::semantic_error,
 };
 #[derive(Debug, PartialEq, Clone)]
 pub enum ScopeKind {
@@ -50,17 +58,25 @@ pub struct IdentifierInfo {
     /// - `false` 表示它是一个局部变量（包括函数参数），仅在当前作用域内有效。
     has_linkage: bool,
     /// 经过名称修饰后的唯一标识符。
-    mangled_name: String,
+    mangled_name: Symbol,
 }
 
 /// 标识符解析器的状态机。
 #[derive(Debug)]
 pub struct IdentifierResolver<'a> {
     /// 环境栈，用于管理作用域。每个 `HashMap` 代表一个作用域的符号表。
-    /// `String` 是原始的标识符名称，`IdentifierInfo` 是其解析后的信息。
-    env_stack: Vec<HashMap<String, IdentifierInfo>>,
+    /// 键是原始的标识符名称，`IdentifierInfo` 是其解析后的信息。
+    env_stack: Vec<HashMap<Symbol, IdentifierInfo>>,
     /// 用于生成唯一变量名的工具。
     name_generator: &'a mut UniqueNameGenerator,
+    /// 被引用过的标识符（修饰后的名称），用于检查未使用的参数。
+    used: HashSet<Symbol>,
+    /// 是否报告未使用的参数 (`-Wunused-parameter`)。
+    warn_unused_parameters: bool,
+    /// 解析过程中产生的警告。
+    warnings: Vec<Diagnostic>,
+    /// 正在解析的程序的表达式和语句节点，标识符在其中原地替换为修饰后的名称。
+    arena: AstArena,
 }
 
 impl<'a> IdentifierResolver<'a> {
@@ -69,13 +85,26 @@ impl<'a> IdentifierResolver<'a> {
         IdentifierResolver {
             env_stack: Vec::new(),
             name_generator,
+            used: HashSet::new(),
+            warn_unused_parameters: false,
+            warnings: Vec::new(),
+            arena: AstArena::new(),
         }
     }
 
-    /// 解析整个程序（即AST的根节点）。
-    pub fn resolve_program(&mut self, ast: &Program) -> Result<Program, String> {
+    pub fn with_unused_parameter_warnings(mut self, enabled: bool) -> Self {
+        self.warn_unused_parameters = enabled;
+        self
+    }
+
+    /// 解析整个程序（即AST的根节点），返回解析后的程序和警告。
+    pub fn resolve_program(
+        &mut self,
+        ast: &Program,
+    ) -> Result<(Program, Vec<Diagnostic>), Diagnostic> {
         // 创建并推入全局作用域
         self.env_stack.push(HashMap::new());
+        self.arena = ast.arena.clone();
 
         let mut resolved_functions: Vec<Declaration> = Vec::new();
         for f in &ast.declarations {
@@ -85,9 +114,11 @@ impl<'a> IdentifierResolver<'a> {
 
         // 完成解析后，弹出全局作用域
         self.env_stack.pop();
-        Ok(Program {
+        let program = Program {
             declarations: resolved_functions,
-        })
+            arena: std::mem::take(&mut self.arena),
+        };
+        Ok((program, std::mem::take(&mut self.warnings)))
     }
 
     /// 解析函数声明或定义。
@@ -95,61 +126,74 @@ impl<'a> IdentifierResolver<'a> {
         &mut self,
         f: &FunDecl,
         scope_kind: ScopeKind,
-    ) -> Result<FunDecl, String> {
+    ) -> Result<FunDecl, Diagnostic> {
         // 检查块作用域内的非法声明
         if scope_kind == ScopeKind::Block {
             if let Some(StorageClass::Static) = f.storage_class {
-                return Err(
-                    "Semantic Error: 'static' function declaration is not allowed inside a block."
-                        .to_string(),
-                );
+                return Err(semantic_error(
+                    f.span,
+                    tr!(
+                        "Semantic Error: 'static' function declaration is not allowed inside a block."
+                    ),
+                ));
             }
             if f.body.is_some() {
-                return Err(format!(
-                    "Semantic Error: Nested function definitions are not allowed (function '{}').",
-                    f.name
+                return Err(semantic_error(
+                    f.span,
+                    tr!(
+                        "Semantic Error: Nested function definitions are not allowed (function '{}').",
+                        f.name
+                    ),
                 ));
             }
         }
 
         // 检查当前作用域的同名标识符冲突，如果无冲突则插入
-        if let Some(info) = self.find_identifier_in_current_scope(&f.name) {
+        if let Some(info) = self.find_identifier_in_current_scope(f.name) {
             // 允许函数重复声明，但不能与变量等其他符号冲突
             if !info.has_linkage {
-                return Err(format!(
-                    "Semantic Error: Redeclaration of '{}' as a different kind of symbol.",
-                    f.name
+                return Err(semantic_error(
+                    f.span,
+                    tr!(
+                        "Semantic Error: Redeclaration of '{}' as a different kind of symbol.",
+                        f.name
+                    ),
                 ));
             }
         } else {
             // 在当前作用域插入函数声明
             self.insert_identifier(
-                f.name.clone(),
+                f.name,
                 IdentifierInfo {
                     has_linkage: true,
-                    mangled_name: f.name.clone(), // 函数名不修饰
+                    mangled_name: f.name, // 函数名不修饰
                 },
             );
         }
 
         // --- 创建函数/原型作用域 ---
         self.env_stack.push(HashMap::new());
+        self.name_generator.enter_function(f.name);
 
         // 解析函数参数
         let mut resolved_params = Vec::new();
-        for p_name in &f.parameters {
+        for &p_name in &f.parameters {
             if self.is_identifier_in_current_scope(p_name) {
-                return Err(format!(
-                    "Semantic Error: Duplicate parameter name '{}' in function '{}'.",
-                    p_name, f.name
+                return Err(semantic_error(
+                    f.span,
+                    tr!(
+                        "Semantic Error: Duplicate parameter name '{}' in function '{}'.",
+                        p_name,
+                        f.name
+                    ),
                 ));
             }
-            let mangled_name = self.name_generator.new_variable_name(p_name.clone());
+            let mangled_name = self.name_generator.new_variable_name(p_name);
             self.insert_identifier(
-                p_name.clone(),
+                p_name,
                 IdentifierInfo {
                     has_linkage: false,
-                    mangled_name: mangled_name.clone(),
+                    mangled_name,
                 },
             );
             resolved_params.push(mangled_name);
@@ -166,21 +210,38 @@ impl<'a> IdentifierResolver<'a> {
         } else {
             None
         };
+        if self.warn_unused_parameters && resolved_body.is_some() {
+            for (name, mangled_name) in f.parameters.iter().zip(&resolved_params) {
+                if !self.used.contains(mangled_name) {
+                    self.warnings.push(
+                        Diagnostic::warning(tr!("函数 '{}' 的参数 '{}' 未被使用", f.name, name))
+                            .with_code("unused-parameter")
+                            .with_span(f.span),
+                    );
+                }
+            }
+        }
 
         // --- 退出函数/原型作用域 ---
+        self.name_generator.exit_function();
         self.env_stack.pop();
 
         Ok(FunDecl {
-            name: f.name.clone(),
+            name: f.name,
             parameters: resolved_params,
+            param_types: f.param_types.clone(),
+            unspecified_params: f.unspecified_params,
+            ret_type: f.ret_type,
             body: resolved_body,
             storage_class: f.storage_class.clone(),
+            noreturn: f.noreturn,
+            span: f.span,
         })
     }
 
     /// 解析代码块（Block）。
     /// 一个块会引入一个新的作用域。
-    fn resolve_block(&mut self, block: &Block) -> Result<Block, String> {
+    fn resolve_block(&mut self, block: &Block) -> Result<Block, Diagnostic> {
         self.env_stack.push(HashMap::new()); // 进入新作用域
         let mut resolved_items: Vec<BlockItem> = Vec::new();
 
@@ -194,15 +255,15 @@ impl<'a> IdentifierResolver<'a> {
     }
 
     /// 解析块内的单个条目（声明或语句）。
-    fn resolve_block_item(&mut self, item: &BlockItem) -> Result<BlockItem, String> {
+    fn resolve_block_item(&mut self, item: &BlockItem) -> Result<BlockItem, Diagnostic> {
         match item {
             BlockItem::D(d) => {
                 let new_d = self.resolve_declaration(d, ScopeKind::Block)?;
                 Ok(BlockItem::D(new_d))
             }
             BlockItem::S(s) => {
-                let new_s = self.resolve_statement(s)?;
-                Ok(BlockItem::S(new_s))
+                self.resolve_statement(*s)?;
+                Ok(BlockItem::S(*s))
             }
         }
     }
@@ -212,7 +273,7 @@ impl<'a> IdentifierResolver<'a> {
         &mut self,
         d: &Declaration,
         scope_kind: ScopeKind,
-    ) -> Result<Declaration, String> {
+    ) -> Result<Declaration, Diagnostic> {
         match d {
             Declaration::Variable(v) => {
                 let new_v = self.resolve_variable_declaration(v, scope_kind)?;
@@ -232,11 +293,11 @@ impl<'a> IdentifierResolver<'a> {
         &mut self,
         v: &VarDecl,
         scope_kind: ScopeKind,
-    ) -> Result<VarDecl, String> {
+    ) -> Result<VarDecl, Diagnostic> {
         match scope_kind {
             ScopeKind::Block => {
                 // 检查当前作用域是否已经有同名声明
-                if let Some(prev_info) = self.find_identifier_in_current_scope(&v.name) {
+                if let Some(prev_info) = self.find_identifier_in_current_scope(v.name) {
                     // 找到了，说明在同一个块里有重复声明，需要检查冲突
                     let current_is_extern = matches!(v.storage_class, Some(StorageClass::Extern));
                     let prev_has_linkage = prev_info.has_linkage;
@@ -246,9 +307,12 @@ impl<'a> IdentifierResolver<'a> {
                     // 1. prev无链接, curr是任何东西 -> 冲突 (e.g., int x; int x; 或 int x; extern int x;)
                     // 2. prev有链接, curr不是extern -> 冲突 (e.g., extern int x; int x;)
                     if !(prev_has_linkage && current_is_extern) {
-                        return Err(format!(
-                            "Semantic Error: Conflicting declarations for '{}' in the same scope",
-                            v.name
+                        return Err(semantic_error(
+                            v.span,
+                            tr!(
+                                "Semantic Error: Conflicting declarations for '{}' in the same scope",
+                                v.name
+                            ),
                         ));
                     }
                     // 如果兼容 (都是 extern)，我们其实什么都不用做，因为符号表里已经有正确的信息了。
@@ -261,48 +325,52 @@ impl<'a> IdentifierResolver<'a> {
                     Some(StorageClass::Extern) => {
                         // 这是一个新的 extern 声明。它有链接性，不重命名。
                         self.insert_identifier(
-                            v.name.clone(),
+                            v.name,
                             IdentifierInfo {
                                 has_linkage: true,
-                                mangled_name: v.name.clone(),
+                                mangled_name: v.name,
                             },
                         );
                         // 保留初始值，让类型检查器来判断其合法性
-                        let new_init = match &v.init {
-                            Some(e) => Some(self.resolve_expression(e)?),
-                            None => None,
-                        };
+                        if let Some(e) = v.init {
+                            self.resolve_expression(e)?;
+                        }
                         Ok(VarDecl {
-                            name: v.name.clone(),
-                            init: new_init,
+                            name: v.name,
+                            init: v.init,
+                            var_type: v.var_type,
                             storage_class: v.storage_class.clone(),
+                            volatile: v.volatile,
+                            span: v.span,
                         })
                     }
                     Some(StorageClass::Static) | None => {
                         // 这是一个新的局部变量（普通或 static）。它无链接，需要重命名。
                         // 它会遮蔽外层同名变量，但这是合法的。
-                        let mangled_name = self.name_generator.new_variable_name(v.name.clone());
+                        let mangled_name = self.name_generator.new_variable_name(v.name);
                         self.insert_identifier(
-                            v.name.clone(),
+                            v.name,
                             IdentifierInfo {
                                 has_linkage: false,
-                                mangled_name: mangled_name.clone(),
+                                mangled_name,
                             },
                         );
-                        let new_init = match &v.init {
-                            Some(e) => Some(self.resolve_expression(e)?),
-                            None => None,
-                        };
+                        if let Some(e) = v.init {
+                            self.resolve_expression(e)?;
+                        }
                         Ok(VarDecl {
                             name: mangled_name,
-                            init: new_init,
+                            init: v.init,
+                            var_type: v.var_type,
                             storage_class: v.storage_class.clone(),
+                            volatile: v.volatile,
+                            span: v.span,
                         })
                     }
                 }
             }
             ScopeKind::File => {
-                if let Some(_prev_info) = self.find_identifier_in_current_scope(&v.name) {
+                if let Some(_prev_info) = self.find_identifier_in_current_scope(v.name) {
                     // 已经存在一个文件作用域的声明了
                     // 在标识符解析阶段，我们假设它们是兼容的。
                     // 真正的链接性冲突检查（static vs extern）留给类型检查阶段。
@@ -310,159 +378,151 @@ impl<'a> IdentifierResolver<'a> {
                 } else {
                     // 这是第一次遇到这个名字的文件作用域声明
                     self.insert_identifier(
-                        v.name.clone(),
+                        v.name,
                         IdentifierInfo {
                             has_linkage: true,
-                            mangled_name: v.name.clone(),
+                            mangled_name: v.name,
                         },
                     );
                 }
 
                 Ok(VarDecl {
-                    name: v.name.clone(),
-                    init: v.init.clone(),
+                    name: v.name,
+                    init: v.init,
+                    var_type: v.var_type,
                     storage_class: v.storage_class.clone(),
+                    volatile: v.volatile,
+                    span: v.span,
                 })
             }
         }
     }
 
-    /// 解析语句。
-    fn resolve_statement(&mut self, stmt: &Statement) -> Result<Statement, String> {
-        match stmt {
-            Statement::Expression(e) => {
-                let new_exp = self.resolve_expression(e)?;
-                Ok(Statement::Expression(new_exp))
-            }
-            Statement::Return(e) => {
-                let new_exp = self.resolve_expression(e)?;
-                Ok(Statement::Return(new_exp))
-            }
+    /// 解析语句，语句节点在存储区中原地更新。
+    fn resolve_statement(&mut self, stmt: StmtId) -> Result<(), Diagnostic> {
+        match self.arena[stmt].clone() {
+            Statement::Expression(e) | Statement::Return(e) => self.resolve_expression(e),
             Statement::If {
                 condition,
                 then_stmt,
                 else_stmt,
             } => {
-                let new_c = self.resolve_expression(condition)?;
-                let new_then = self.resolve_statement(then_stmt)?;
-                let new_else = if let Some(es) = else_stmt {
-                    Some(Box::new(self.resolve_statement(es)?))
-                } else {
-                    None
-                };
-                Ok(Statement::If {
-                    condition: new_c,
-                    then_stmt: Box::new(new_then),
-                    else_stmt: new_else,
-                })
+                self.resolve_expression(condition)?;
+                self.resolve_statement(then_stmt)?;
+                if let Some(es) = else_stmt {
+                    self.resolve_statement(es)?;
+                }
+                Ok(())
             }
             Statement::Compound(b) => {
                 // 复合语句（即用 `{}` 包围的块）会创建一个新的作用域。
-                let new_b = self.resolve_block(b)?;
-                Ok(Statement::Compound(new_b))
+                let new_b = self.resolve_block(&b)?;
+                self.arena[stmt] = Statement::Compound(new_b);
+                Ok(())
             }
             Statement::While {
                 condition, body, ..
             } => {
-                let new_c = self.resolve_expression(condition)?;
-                let new_body = self.resolve_statement(body)?;
-                Ok(Statement::While {
-                    condition: new_c,
-                    body: Box::new(new_body),
-                    label: None, // 标签在后续阶段处理
-                })
+                self.resolve_expression(condition)?;
+                self.resolve_statement(body)
             }
             Statement::DoWhile {
                 body, condition, ..
             } => {
-                let new_body = self.resolve_statement(body)?;
-                let new_c = self.resolve_expression(condition)?;
-                Ok(Statement::DoWhile {
-                    body: Box::new(new_body),
-                    condition: new_c,
-                    label: None,
-                })
+                self.resolve_statement(body)?;
+                self.resolve_expression(condition)
             }
             Statement::For {
                 init,
                 condition,
                 post,
                 body,
-                ..
+                label,
             } => {
-                // `for` 循环的初始化部分可以声明变量，它位于一个新的作用域内。
+                // 作用域规则（C17 6.8.5p5）：初始化部分的声明位于一个新的作用域内，
+                // 它包住条件、post 表达式和循环体；循环体再嵌套一层自己的作用域，
+                // 因此 `for (int i = 0;;) { int i = 1; }` 中内层的 `i` 只是遮蔽外层的 `i`。
+                // 类型检查 (`TypeChecker`) 按同样的结构进出作用域。
                 self.env_stack.push(HashMap::new());
-                let new_init = self.resolve_for_init(init)?;
-                let new_c = match condition {
-                    Some(c) => Some(self.resolve_expression(c)?),
-                    None => None,
-                };
-                let new_post = match post {
-                    Some(p) => Some(self.resolve_expression(p)?),
-                    None => None,
-                };
-                let new_body = self.resolve_statement(body)?;
-                self.env_stack.pop(); // 退出 `for` 循环作用域
+                let new_init = self.resolve_for_init(&init)?;
+                for e in [condition, post].into_iter().flatten() {
+                    self.resolve_expression(e)?;
+                }
+                self.env_stack.push(HashMap::new());
+                self.resolve_statement(body)?;
+                self.env_stack.pop(); // 退出循环体作用域
+                self.env_stack.pop(); // 退出 `for` 初始化作用域
 
-                Ok(Statement::For {
+                self.arena[stmt] = Statement::For {
                     init: new_init,
-                    condition: new_c,
-                    post: new_post,
-                    body: Box::new(new_body),
-                    label: None,
-                })
+                    condition,
+                    post,
+                    body,
+                    label,
+                };
+                Ok(())
+            }
+            // 语句标签有自己的名字空间 (C11 6.2.3p1)，不进入变量的作用域
+            Statement::Labeled { stmt, .. } | Statement::Default { stmt, .. } => {
+                self.resolve_statement(stmt)
+            }
+            Statement::Case { value, stmt, .. } => {
+                self.resolve_expression(value)?;
+                self.resolve_statement(stmt)
             }
-            // 对于简单语句，无需特殊处理，直接返回克隆即可。
-            Statement::Null => Ok(Statement::Null),
-            Statement::Break(n) => Ok(Statement::Break(n.clone())),
-            Statement::Continue(n) => Ok(Statement::Continue(n.clone())),
+            // 简单语句不包含标识符。
+            Statement::Null | Statement::Break(_) | Statement::Continue(_) => Ok(()),
         }
     }
 
     /// 解析 `for` 循环的初始化部分。
-    fn resolve_for_init(&mut self, init: &ForInit) -> Result<ForInit, String> {
+    fn resolve_for_init(&mut self, init: &ForInit) -> Result<ForInit, Diagnostic> {
         match init {
-            ForInit::InitDecl(d) => {
-                let new_d = self.resolve_variable_declaration(d, ScopeKind::Block)?;
-                Ok(ForInit::InitDecl(new_d))
+            // 声明按顺序进入循环的作用域，后面的初始化表达式可以引用前面的变量
+            ForInit::InitDecl(decls) => {
+                let new_decls = decls
+                    .iter()
+                    .map(|d| self.resolve_variable_declaration(d, ScopeKind::Block))
+                    .collect::<Result<Vec<_>, Diagnostic>>()?;
+                Ok(ForInit::InitDecl(new_decls))
             }
-            ForInit::InitExp(Some(e)) => {
-                let new_e = self.resolve_expression(e)?;
-                Ok(ForInit::InitExp(Some(new_e)))
+            ForInit::InitExp(e) => {
+                if let Some(e) = e {
+                    self.resolve_expression(*e)?;
+                }
+                Ok(ForInit::InitExp(*e))
             }
-            ForInit::InitExp(None) => Ok(ForInit::InitExp(None)),
         }
     }
 
-    /// 解析表达式。
-    fn resolve_expression(&mut self, e: &Expression) -> Result<Expression, String> {
-        match e {
+    /// 解析表达式，变量名和函数名在存储区中原地替换为修饰后的名称。
+    fn resolve_expression(&mut self, e: ExprId) -> Result<(), Diagnostic> {
+        match self.arena[e].clone() {
             Expression::Assignment { left, right } => {
                 // 确保赋值操作的左侧是一个有效的左值（l-value）。
                 // 在我们的简化C语言中，只有变量是有效的左值。
-                if !matches!(**left, Expression::Var(_)) {
-                    return Err(
-                        "Semantic Error: Expression is not assignable (not a valid l-value)."
-                            .to_string(),
-                    );
+                if !matches!(self.arena[left], Expression::Var(_)) {
+                    return Err(semantic_error(
+                        self.arena.expr_span(e),
+                        tr!("Semantic Error: Expression is not assignable (not a valid l-value)."),
+                    ));
                 }
-                let new_l = self.resolve_expression(left)?;
-                let new_r = self.resolve_expression(right)?;
-                Ok(Expression::Assignment {
-                    left: Box::new(new_l),
-                    right: Box::new(new_r),
-                })
+                self.resolve_expression(left)?;
+                self.resolve_expression(right)
             }
             Expression::Var(id) => {
                 // 这是解析的核心：查找变量的声明。
                 let (info, _) = self.find_identifier_in_all_scopes(id);
                 if let Some(item) = info {
                     // 查找到后，将AST中的变量名替换为其唯一的、修饰后的名称。
-                    Ok(Expression::Var(item.mangled_name.clone()))
+                    let mangled_name = item.mangled_name;
+                    self.used.insert(mangled_name);
+                    self.arena[e] = Expression::Var(mangled_name);
+                    Ok(())
                 } else {
-                    Err(format!(
-                        "Semantic Error: Use of undeclared identifier '{}'.",
-                        id
+                    Err(semantic_error(
+                        self.arena.expr_span(e),
+                        tr!("Semantic Error: Use of undeclared identifier '{}'.", id),
                     ))
                 }
             }
@@ -472,60 +532,47 @@ impl<'a> IdentifierResolver<'a> {
                 if let Some(r) = info {
                     // 确保被调用的标识符确实是一个函数。
                     if !r.has_linkage {
-                        return Err(format!(
-                            "Semantic Error: Called object '{}' is not a function.",
-                            name
+                        return Err(semantic_error(
+                            self.arena.expr_span(e),
+                            tr!(
+                                "Semantic Error: Called object '{}' is not a function.",
+                                name
+                            ),
                         ));
                     }
-                    let new_name = r.mangled_name.clone();
-                    let mut new_args = Vec::new();
-                    for arg in args {
-                        new_args.push(self.resolve_expression(arg)?);
+                    let new_name = r.mangled_name;
+                    for &arg in &args {
+                        self.resolve_expression(arg)?;
                     }
-                    Ok(Expression::FuncCall {
+                    self.arena[e] = Expression::FuncCall {
                         name: new_name,
-                        args: new_args,
-                    })
+                        args,
+                    };
+                    Ok(())
                 } else {
-                    Err(format!(
-                        "Semantic Error: Call to undeclared function '{}'.",
-                        name
+                    Err(semantic_error(
+                        self.arena.expr_span(e),
+                        tr!("Semantic Error: Call to undeclared function '{}'.", name),
                     ))
                 }
             }
             // 对于其他复合表达式，递归地解析其子表达式。
-            Expression::Binary { op, left, right } => {
-                let new_l = self.resolve_expression(left)?;
-                let new_r = self.resolve_expression(right)?;
-                Ok(Expression::Binary {
-                    op: op.clone(),
-                    left: Box::new(new_l),
-                    right: Box::new(new_r),
-                })
-            }
-            Expression::Unary { op, exp } => {
-                let new_e = self.resolve_expression(exp)?;
-                Ok(Expression::Unary {
-                    op: op.clone(),
-                    exp: Box::new(new_e),
-                })
+            Expression::Binary { left, right, .. } => {
+                self.resolve_expression(left)?;
+                self.resolve_expression(right)
             }
+            Expression::Unary { exp, .. } => self.resolve_expression(exp),
             Expression::Conditional {
                 condition,
                 left,
                 right,
             } => {
-                let new_c = self.resolve_expression(condition)?;
-                let new_l = self.resolve_expression(left)?;
-                let new_r = self.resolve_expression(right)?;
-                Ok(Expression::Conditional {
-                    condition: Box::new(new_c),
-                    left: Box::new(new_l),
-                    right: Box::new(new_r),
-                })
+                self.resolve_expression(condition)?;
+                self.resolve_expression(left)?;
+                self.resolve_expression(right)
             }
             // 常量表达式不需要解析。
-            Expression::Constant(i) => Ok(Expression::Constant(*i)),
+            Expression::Constant(_) => Ok(()),
         }
     }
 
@@ -533,14 +580,14 @@ impl<'a> IdentifierResolver<'a> {
 
     /// 从内到外查找所有作用域中的标识符。
     /// 返回找到的标识符信息以及一个布尔值，该值指示是否在最内层作用域找到。
-    fn find_identifier_in_all_scopes(&self, name: &str) -> (Option<&IdentifierInfo>, bool) {
-        if let Some(current_scope) = self.env_stack.last() {
-            if let Some(info) = current_scope.get(name) {
-                return (Some(info), true); // 在当前作用域找到
-            }
+    fn find_identifier_in_all_scopes(&self, name: Symbol) -> (Option<&IdentifierInfo>, bool) {
+        if let Some(current_scope) = self.env_stack.last()
+            && let Some(info) = current_scope.get(&name)
+        {
+            return (Some(info), true); // 在当前作用域找到
         }
         for scope in self.env_stack.iter().rev().skip(1) {
-            if let Some(info) = scope.get(name) {
+            if let Some(info) = scope.get(&name) {
                 return (Some(info), false); // 在外部作用域找到
             }
         }
@@ -548,19 +595,19 @@ impl<'a> IdentifierResolver<'a> {
     }
 
     /// 仅在当前（最内层）作用域中查找标识符。
-    fn find_identifier_in_current_scope(&self, name: &str) -> Option<&IdentifierInfo> {
-        self.env_stack.last()?.get(name)
+    fn find_identifier_in_current_scope(&self, name: Symbol) -> Option<&IdentifierInfo> {
+        self.env_stack.last()?.get(&name)
     }
 
     /// 检查标识符是否存在于当前作用域。
-    fn is_identifier_in_current_scope(&self, name: &str) -> bool {
+    fn is_identifier_in_current_scope(&self, name: Symbol) -> bool {
         self.env_stack
             .last()
-            .map_or(false, |scope| scope.contains_key(name))
+            .is_some_and(|scope| scope.contains_key(&name))
     }
 
     /// 在当前作用域中插入一个新的标识符。
-    fn insert_identifier(&mut self, name: String, info: IdentifierInfo) {
+    fn insert_identifier(&mut self, name: Symbol, info: IdentifierInfo) {
         if let Some(current_scope) = self.env_stack.last_mut() {
             current_scope.insert(name, info);
         }
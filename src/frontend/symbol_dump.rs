@@ -0,0 +1,112 @@
+// src/frontend/symbol_dump.rs
+
+//! **符号表输出 (`--dump-symbols`)**
+//!
+//! 把类型检查得到的符号表格式化为人类可读的表格 (`table`) 或供工具使用的 JSON (`json`)。
+//! 符号按名字排序，输出在多次运行之间保持稳定。
+//!
+//! 每个符号输出以下字段：
+//! -   **type**：C 类型，函数写作 `int(int, _Bool)`；`int f(void)` 写作 `int(void)`，
+//!     未指定参数的 `int f()` 写作 `int()`
+//! -   **linkage**：`external` / `internal` / `none`（局部变量）
+//! -   **state**：`defined` / `tentative`（暂定定义）/ `declared`（只有声明）
+//! -   **init**：静态变量的初始值；没有编译期初始值时为空（JSON 中为 `null`）
+
+use serde::Serialize;
+
+use crate::frontend::type_checking::{IdentifierAttrs, InitValue, SymbolTable};
+
+/// `--dump-symbols` 的输出格式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SymbolDumpFormat {
+    /// 对齐的文本表格
+    Table,
+    /// JSON 数组，每个符号一个对象
+    Json,
+}
+
+/// 单个符号的可读描述。
+#[derive(Serialize)]
+struct SymbolRow<'a> {
+    name: &'a str,
+    #[serde(rename = "type")]
+    tpye: String,
+    linkage: &'static str,
+    state: &'static str,
+    init: Option<i64>,
+}
+
+fn rows(tables: &SymbolTable) -> Vec<SymbolRow<'_>> {
+    let mut rows: Vec<SymbolRow> = tables
+        .iter()
+        .map(|(name, info)| {
+            let (linkage, state, init) = match &info.identifier_attrs {
+                IdentifierAttrs::FunAttr {
+                    defined, global, ..
+                } => (
+                    linkage(*global),
+                    if *defined { "defined" } else { "declared" },
+                    None,
+                ),
+                IdentifierAttrs::StaticAttr { init_value, global } => {
+                    let (state, init) = match init_value {
+                        InitValue::Initial(v) => ("defined", Some(*v)),
+                        InitValue::Tentative => ("tentative", None),
+                        InitValue::NoInitalizer => ("declared", None),
+                    };
+                    (linkage(*global), state, init)
+                }
+                IdentifierAttrs::LocalAttr => ("none", "defined", None),
+            };
+            SymbolRow {
+                name: name.as_str(),
+                tpye: info.tpye.to_string(),
+                linkage,
+                state,
+                init,
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| a.name.cmp(b.name));
+    rows
+}
+
+fn linkage(global: bool) -> &'static str {
+    if global { "external" } else { "internal" }
+}
+
+/// 按指定格式输出符号表。
+pub fn dump(tables: &SymbolTable, format: SymbolDumpFormat) -> String {
+    match format {
+        SymbolDumpFormat::Table => to_table(tables),
+        SymbolDumpFormat::Json => to_json(tables),
+    }
+}
+
+fn to_table(tables: &SymbolTable) -> String {
+    let rows = rows(tables);
+    let name_width = rows.iter().map(|r| r.name.len()).max().unwrap_or(0).max(4);
+    let type_width = rows.iter().map(|r| r.tpye.len()).max().unwrap_or(0).max(4);
+
+    let mut out = format!(
+        "{:<name_width$}  {:<type_width$}  {:<8}  {:<9}  {}\n",
+        "name", "type", "linkage", "state", "init"
+    );
+    for r in &rows {
+        let init = r
+            .init
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        out.push_str(&format!(
+            "{:<name_width$}  {:<type_width$}  {:<8}  {:<9}  {}\n",
+            r.name, r.tpye, r.linkage, r.state, init
+        ));
+    }
+    out
+}
+
+fn to_json(tables: &SymbolTable) -> String {
+    let mut json = serde_json::to_string_pretty(&rows(tables)).expect("符号表总能序列化为 JSON");
+    json.push('\n');
+    json
+}
@@ -0,0 +1,79 @@
+// src/frontend/token_dump.rs
+
+//! **token 流输出 (`--dump-tokens`)**
+//!
+//! 把词法分析得到的 token 按出现顺序格式化为人类可读的表格 (`table`) 或供工具使用的
+//! JSON (`json`)。每个 token 输出以下字段：
+//! -   **line** / **column**：token 第一个字符在源文件中的位置（经过预处理器行标记修正）
+//! -   **kind**：[`TokenType`] 的名字，例如 `Identifier`、`LessEqual`
+//! -   **lexeme**：token 在源文件中的原文；最后的 `Eof` 为空
+
+use serde::Serialize;
+
+use crate::frontend::lexer::{Token, TokenType};
+
+/// `--dump-tokens` 的输出格式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TokenDumpFormat {
+    /// 对齐的文本表格
+    Table,
+    /// JSON 数组，每个 token 一个对象
+    Json,
+}
+
+fn kind(type_: &TokenType) -> String {
+    format!("{:?}", type_)
+}
+
+/// 按指定格式输出 token 流。
+pub fn dump(tokens: &[Token], format: TokenDumpFormat) -> String {
+    match format {
+        TokenDumpFormat::Table => to_table(tokens),
+        TokenDumpFormat::Json => to_json(tokens),
+    }
+}
+
+fn to_table(tokens: &[Token]) -> String {
+    let positions: Vec<String> = tokens.iter().map(|t| t.span.to_string()).collect();
+    let kinds: Vec<String> = tokens.iter().map(|t| kind(&t.type_)).collect();
+    let position_width = positions.iter().map(String::len).max().unwrap_or(0).max(8);
+    let kind_width = kinds.iter().map(String::len).max().unwrap_or(0).max(4);
+
+    let mut out = format!(
+        "{:<position_width$}  {:<kind_width$}  {}\n",
+        "position", "kind", "lexeme"
+    );
+    for ((position, kind), token) in positions.iter().zip(&kinds).zip(tokens) {
+        let row = format!(
+            "{:<position_width$}  {:<kind_width$}  {}",
+            position, kind, token.lexeme
+        );
+        out.push_str(row.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// JSON 输出中的一个 token。
+#[derive(Serialize)]
+struct TokenRow<'a> {
+    line: usize,
+    column: usize,
+    kind: String,
+    lexeme: &'a str,
+}
+
+fn to_json(tokens: &[Token]) -> String {
+    let rows: Vec<TokenRow> = tokens
+        .iter()
+        .map(|t| TokenRow {
+            line: t.span.line,
+            column: t.span.column,
+            kind: kind(&t.type_),
+            lexeme: &t.lexeme,
+        })
+        .collect();
+    let mut json = serde_json::to_string_pretty(&rows).expect("token 总能序列化为 JSON");
+    json.push('\n');
+    json
+}
@@ -1,9 +1,17 @@
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::fmt;
 
+use crate::common::{DataLayout, Span, Symbol};
+use crate::diagnostics::Diagnostic;
 use crate::frontend::c_ast::{
-    Block, BlockItem, Declaration, Expression, ForInit, FunDecl, Program, Statement, StorageClass,
-    VarDecl,
+    AstArena, Block, BlockItem, Declaration, ExprId, Expression, ForInit, FunDecl, Program,
+    Statement, StmtId, StorageClass, Type, UnaryOp, VarDecl,
 };
+use crate::frontend::const_eval::{self, ConstEvalError};
+use crate::frontend::conversions;
+use crate::frontend::semantic_error;
+use crate::i18n::tr;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum InitValue {
@@ -13,11 +21,19 @@ pub enum InitValue {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::enum_variant_names)]
 pub enum IdentifierAttrs {
-    // 函数属性：是否已定义，是否全局可见
-    FunAttr { defined: bool, global: bool },
+    // 函数属性：是否已定义，是否全局可见，是否有任一声明带有 `_Noreturn`
+    FunAttr {
+        defined: bool,
+        global: bool,
+        noreturn: bool,
+    },
     // 静态存储期变量属性：初始值，是否全局可见
-    StaticAttr { init_value: InitValue, global: bool },
+    StaticAttr {
+        init_value: InitValue,
+        global: bool,
+    },
     // 自动存储期变量（局部变量）
     LocalAttr,
 }
@@ -26,137 +42,435 @@ pub enum IdentifierAttrs {
 pub struct SymbolInfo {
     pub tpye: CType,
     pub identifier_attrs: IdentifierAttrs,
+    /// 最近一次声明的位置；函数参数没有记录位置
+    pub span: Option<Span>,
+    /// 变量带有 `volatile` 限定符
+    pub volatile: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum CType {
     Int,
-    FunType { param_count: usize },
+    /// `_Bool`：占 1 个字节，只能保存 0 或 1，参与运算前提升为 `int`。
+    Bool,
+    FunType {
+        params: Vec<CType>,
+        ret: Box<CType>,
+        /// 由 `int f()` 形式声明：调用时不检查参数数量
+        unspecified_params: bool,
+    },
+}
+
+impl fmt::Display for CType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CType::Int => write!(f, "int"),
+            CType::Bool => write!(f, "_Bool"),
+            CType::FunType {
+                params,
+                ret,
+                unspecified_params,
+            } => {
+                let params: Vec<String> = params.iter().map(|p| p.to_string()).collect();
+                let params = if params.is_empty() && !unspecified_params {
+                    "void".to_string()
+                } else {
+                    params.join(", ")
+                };
+                write!(f, "{}({})", ret, params)
+            }
+        }
+    }
+}
+
+impl CType {
+    pub fn is_function(&self) -> bool {
+        matches!(self, CType::FunType { .. })
+    }
+
+    /// 两个函数类型是否兼容。
+    ///
+    /// 返回值类型必须相同；若其中一方参数未指定，另一方的参数经过默认实参提升后必须不变
+    /// （即不能有 `_Bool` 参数）；否则参数列表必须完全一致。
+    fn is_compatible_fun_type(&self, other: &CType) -> bool {
+        match (self, other) {
+            (
+                CType::FunType {
+                    params: p1,
+                    ret: r1,
+                    unspecified_params: u1,
+                },
+                CType::FunType {
+                    params: p2,
+                    ret: r2,
+                    unspecified_params: u2,
+                },
+            ) => {
+                r1 == r2
+                    && match (u1, u2) {
+                        (true, true) => true,
+                        (true, false) => p2.iter().all(|p| *p == CType::Int),
+                        (false, true) => p1.iter().all(|p| *p == CType::Int),
+                        (false, false) => p1 == p2,
+                    }
+            }
+            _ => false,
+        }
+    }
+
+    /// 对象在目标平台上占用的字节数。
+    pub fn size(&self, layout: &DataLayout) -> i64 {
+        match self {
+            CType::Int => layout.int_size,
+            CType::Bool => 1,
+            CType::FunType { .. } => 0,
+        }
+    }
+
+    /// 把一个整数常量转换为该类型的值（用于静态初始值）。
+    pub fn convert_constant(&self, value: i64) -> i64 {
+        match self {
+            CType::Bool => (value != 0) as i64,
+            _ => value,
+        }
+    }
+}
+
+impl From<Type> for CType {
+    fn from(t: Type) -> Self {
+        match t {
+            Type::Int => CType::Int,
+            Type::Bool => CType::Bool,
+        }
+    }
+}
+
+/// 类型检查阶段产生的符号表：函数、文件作用域变量以及（重命名后唯一的）块作用域变量。
+///
+/// 按名字查找，但遍历时按符号第一次插入的顺序（即在源码中第一次声明的顺序），
+/// 而不是哈希顺序，保证依赖符号表顺序的输出在多次运行之间完全一致。
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    /// 名字 -> 在 `entries` 中的下标
+    index: HashMap<Symbol, usize>,
+    entries: Vec<(Symbol, SymbolInfo)>,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, name: Symbol) -> Option<&SymbolInfo> {
+        self.index.get(&name).map(|&i| &self.entries[i].1)
+    }
+
+    /// 插入或更新一个符号。更新已有的符号不改变它的位置。
+    pub fn insert(&mut self, name: Symbol, info: SymbolInfo) {
+        match self.index.get(&name) {
+            Some(&i) => self.entries[i].1 = info,
+            None => {
+                self.index.insert(name, self.entries.len());
+                self.entries.push((name, info));
+            }
+        }
+    }
+
+    /// 变量是否带有 `volatile` 限定符。不在表中的名字（例如 TACKY 的临时变量）不是。
+    pub fn is_volatile(&self, name: Symbol) -> bool {
+        self.get(name).is_some_and(|s| s.volatile)
+    }
+
+    /// 按插入顺序遍历所有符号。
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &SymbolInfo)> {
+        self.entries.iter().map(|(name, info)| (*name, info))
+    }
+}
+
+impl FromIterator<(Symbol, SymbolInfo)> for SymbolTable {
+    fn from_iter<I: IntoIterator<Item = (Symbol, SymbolInfo)>>(iter: I) -> Self {
+        let mut table = SymbolTable::new();
+        for (name, info) in iter {
+            table.insert(name, info);
+        }
+        table
+    }
+}
+
+impl<const N: usize> From<[(Symbol, SymbolInfo); N]> for SymbolTable {
+    fn from(entries: [(Symbol, SymbolInfo); N]) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+/// 通过类型检查的 AST。
+///
+/// 与输入相比，静态存储期变量的初始值已折叠为转换到变量类型后的常量
+/// （例如 `static _Bool b = 5;` 的初始值变为 `1`），后续阶段可以直接使用。
+pub type TypedProgram = Program;
+
+/// 函数重新声明与之前的声明不兼容时的错误信息，指出两处声明的位置和差异。
+fn incompatible_redeclaration(decl: &FunDecl, fun_type: &CType, old: &SymbolInfo) -> String {
+    let previous = match old.span {
+        Some(span) => tr!("之前在 {} 处的声明", span),
+        None => tr!("之前的声明"),
+    };
+    match (fun_type, &old.tpye) {
+        (
+            CType::FunType {
+                params,
+                unspecified_params: false,
+                ..
+            },
+            CType::FunType {
+                params: old_params,
+                unspecified_params: false,
+                ..
+            },
+        ) if params.len() != old_params.len() => tr!(
+            "函数 '{}' 的声明不兼容：{} 处的声明有 {} 个参数，而{}有 {} 个参数",
+            decl.name,
+            decl.span,
+            params.len(),
+            previous,
+            old_params.len()
+        ),
+        _ => tr!(
+            "函数 '{}' 的声明不兼容：{} 处的声明为 `{}`，而{}为 `{}`",
+            decl.name,
+            decl.span,
+            fun_type,
+            previous,
+            old.tpye
+        ),
+    }
+}
+
+/// 用符号表中记录的初始值原地替换静态存储期变量声明的初始值表达式。
+fn fold_static_initializer(arena: &mut AstArena, decl: &VarDecl, init_value: &InitValue) {
+    if let (Some(init), InitValue::Initial(v)) = (decl.init, init_value) {
+        arena[init] = Expression::Constant(*v);
+    }
+}
+
+/// 具有链接的标识符在翻译单元中第一次被声明的位置和链接性。
+#[derive(Debug, Clone, Copy)]
+struct FirstDeclaration {
+    global: bool,
+    span: Span,
+    block_scope: bool,
 }
 
 #[derive(Debug)]
 pub struct TypeChecker {
     /// 全局符号表：函数和文件作用域变量
-    symbol_tables: HashMap<String, SymbolInfo>,
+    symbol_tables: SymbolTable,
     /// 局部作用域栈：用于块作用域变量和参数
-    scopes: Vec<HashMap<String, SymbolInfo>>,
+    scopes: Vec<HashMap<Symbol, SymbolInfo>>,
+    /// 每个具有链接的标识符第一次被声明时的链接性，包括块作用域中的 `extern` 声明
+    first_declarations: HashMap<Symbol, FirstDeclaration>,
+    /// 已经见过的函数定义的位置，用于报告重复定义
+    function_definitions: HashMap<Symbol, Span>,
+    /// 不影响编译结果的警告
+    warnings: Vec<Diagnostic>,
+    /// 正在检查的函数定义，用于在错误信息中指出位置
+    current_function: Option<Symbol>,
+    /// 是否报告值未被使用且没有副作用的表达式语句 (`-Wunused-value`)
+    warn_unused_value: bool,
+    /// 是否报告常量表达式求值中的有符号溢出 (`-Woverflow`)
+    warn_overflow: bool,
+    /// 目标平台的数据模型，决定常量表达式的取值范围
+    layout: DataLayout,
+    /// 正在检查的程序的表达式和语句节点，静态初始值在其中原地折叠
+    arena: AstArena,
 }
 
 impl TypeChecker {
     pub fn new() -> Self {
         TypeChecker {
-            symbol_tables: HashMap::new(),
+            symbol_tables: SymbolTable::new(),
             scopes: Vec::new(),
+            first_declarations: HashMap::new(),
+            function_definitions: HashMap::new(),
+            warnings: Vec::new(),
+            current_function: None,
+            warn_unused_value: false,
+            warn_overflow: false,
+            layout: DataLayout::default(),
+            arena: AstArena::new(),
         }
     }
 
+    pub fn with_unused_value_warnings(mut self, enabled: bool) -> Self {
+        self.warn_unused_value = enabled;
+        self
+    }
+
+    pub fn with_overflow_warnings(mut self, enabled: bool) -> Self {
+        self.warn_overflow = enabled;
+        self
+    }
+
+    pub fn with_layout(mut self, layout: DataLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// 检查整个程序，返回类型检查后的 AST、符号表和检查过程中产生的警告。
     pub fn typecheck_program(
         mut self,
         ast: &Program,
-    ) -> Result<HashMap<String, SymbolInfo>, String> {
+    ) -> Result<(TypedProgram, SymbolTable, Vec<Diagnostic>), Diagnostic> {
         self.push_scope(); // 全局作用域
+        self.arena = ast.arena.clone();
 
         for decl in &ast.declarations {
             self.typecheck_declaration(decl, true)?; // true 表示文件作用域
         }
 
         self.pop_scope();
-        Ok(self.symbol_tables)
+        let program = Program {
+            declarations: ast.declarations.clone(),
+            arena: self.arena,
+        };
+        Ok((program, self.symbol_tables, self.warnings))
     }
 
     // --- 声明检查 ---
 
-    fn typecheck_declaration(&mut self, d: &Declaration, is_file_scope: bool) -> Result<(), String> {
+    fn typecheck_declaration(
+        &mut self,
+        d: &Declaration,
+        is_file_scope: bool,
+    ) -> Result<(), Diagnostic> {
         match d {
             Declaration::Fun(f) => {
                 // 函数定义（带函数体）只允许在文件作用域。
                 if !is_file_scope && f.body.is_some() {
-                    return Err("函数定义不允许在块作用域内。".to_string());
+                    return Err(semantic_error(f.span, tr!("函数定义不允许在块作用域内。")));
                 }
                 // 函数声明（无论在文件还是块作用域）都针对全局符号表进行检查。
-                self.typecheck_function_declaration(f)
+                self.typecheck_function_declaration(f, is_file_scope)
             }
-            Declaration::Variable(v) => {
-                if is_file_scope {
-                    self.typecheck_file_scope_variable_declaration(v)
-                } else {
-                    self.typecheck_block_scope_variable_declaration(v)
-                }
+            Declaration::Variable(v) if is_file_scope => {
+                self.typecheck_file_scope_variable_declaration(v)
             }
+            Declaration::Variable(v) => self.typecheck_block_scope_variable_declaration(v),
         }
     }
 
-    fn typecheck_function_declaration(&mut self, decl: &FunDecl) -> Result<(), String> {
-        let fun_type = CType::FunType {
-            param_count: decl.parameters.len(),
+    fn typecheck_function_declaration(
+        &mut self,
+        decl: &FunDecl,
+        is_file_scope: bool,
+    ) -> Result<(), Diagnostic> {
+        let mut fun_type = CType::FunType {
+            params: decl.param_types.iter().map(|t| CType::from(*t)).collect(),
+            ret: Box::new(CType::from(decl.ret_type)),
+            unspecified_params: decl.unspecified_params,
         };
         let has_body = decl.body.is_some();
         let mut already_defined = false;
+        let mut noreturn = decl.noreturn;
 
         // 默认是全局可见的，除非显式声明为 static
         let mut global = !matches!(decl.storage_class, Some(StorageClass::Static));
 
-        if let Some(old_decl_info) = self.symbol_tables.get(&decl.name).cloned() {
-            if old_decl_info.tpye != fun_type {
-                return Err(format!("函数 '{}' 的声明不兼容", decl.name));
-            }
-
-            if let IdentifierAttrs::FunAttr {
+        if let Some(old_decl_info) = self.symbol_tables.get(decl.name).cloned() {
+            let IdentifierAttrs::FunAttr {
                 defined,
                 global: old_global,
+                noreturn: old_noreturn,
             } = old_decl_info.identifier_attrs
-            {
-                already_defined = defined;
-                if already_defined && has_body {
-                    return Err(format!("函数 '{}' 被多次定义", decl.name));
-                }
+            else {
+                return Err(semantic_error(
+                    decl.span,
+                    tr!("'{}' 被重新声明为不同类型的符号", decl.name),
+                ));
+            };
+            if !old_decl_info.tpye.is_compatible_fun_type(&fun_type) {
+                return Err(semantic_error(
+                    decl.span,
+                    incompatible_redeclaration(decl, &fun_type, &old_decl_info),
+                ));
+            }
+            // 合成类型：一旦见过带原型的声明，就保留该原型
+            if decl.unspecified_params {
+                fun_type = old_decl_info.tpye.clone();
+            }
 
-                if old_global && matches!(decl.storage_class, Some(StorageClass::Static)) {
-                    return Err("静态函数声明跟在非静态函数声明之后".to_string());
-                }
+            // 只要有一个声明带有 `_Noreturn`，函数就不会返回 (C11 6.7.4p8)
+            noreturn |= old_noreturn;
+            already_defined = defined;
+            if already_defined && has_body {
+                return Err(semantic_error(
+                    decl.span,
+                    tr!(
+                        "函数 '{}' 被多次定义，上一次定义在 {}",
+                        decl.name,
+                        self.function_definitions[&decl.name]
+                    ),
+                ));
+            }
 
-                // 链接性保持不变
+            // 链接性由第一次声明决定：`static` 声明之后不带 `static` 的声明（包括 `extern`）
+            // 沿用内部链接；非静态声明之后的 `static` 声明由 `check_linkage` 报告冲突。
+            if global {
                 global = old_global;
-            } else {
-                return Err(format!("'{}' 被重新声明为不同类型的符号", decl.name));
             }
         }
+        self.check_linkage(decl.name, global, decl.span, !is_file_scope)?;
 
         let attrs = IdentifierAttrs::FunAttr {
             defined: already_defined || has_body,
             global,
+            noreturn,
         };
         self.symbol_tables.insert(
-            decl.name.clone(),
+            decl.name,
             SymbolInfo {
                 tpye: fun_type.clone(),
                 identifier_attrs: attrs,
+                span: Some(decl.span),
+                volatile: false,
             },
         );
 
         if let Some(body_block) = &decl.body {
+            // 参数名只属于各自的声明 (C11 6.2.1p4)，原型和定义中的参数名可以不同，
+            // 函数体中使用的是定义中的参数（标识符解析已经按定义改了名）
+            self.function_definitions.insert(decl.name, decl.span);
             self.push_scope();
+            self.current_function = Some(decl.name);
 
-            for p_name in &decl.parameters {
+            for (&p_name, p_type) in decl.parameters.iter().zip(&decl.param_types) {
                 self.insert_variable(
-                    p_name.clone(),
+                    p_name,
                     SymbolInfo {
-                        tpye: CType::Int,
+                        tpye: CType::from(*p_type),
                         identifier_attrs: IdentifierAttrs::LocalAttr,
+                        span: None,
+                        volatile: false,
                     },
                 )?;
             }
             self.typecheck_block_body(body_block)?;
 
+            self.current_function = None;
             self.pop_scope();
         }
         Ok(())
     }
 
-    fn typecheck_file_scope_variable_declaration(&mut self, decl: &VarDecl) -> Result<(), String> {
-        let mut initial_value = if let Some(init_expr) = &decl.init {
-            let const_val = self.eval_const_expr(init_expr)?;
-            InitValue::Initial(const_val)
+    fn typecheck_file_scope_variable_declaration(
+        &mut self,
+        decl: &VarDecl,
+    ) -> Result<(), Diagnostic> {
+        let var_type = CType::from(decl.var_type);
+        let mut initial_value = if let Some(init_expr) = decl.init {
+            let const_val =
+                self.eval_const_expr(init_expr, decl.span, || tr!("初始值不是常量表达式！"))?;
+            InitValue::Initial(var_type.convert_constant(const_val))
         } else {
             if matches!(decl.storage_class, Some(StorageClass::Extern)) {
                 InitValue::NoInitalizer
@@ -167,9 +481,18 @@ impl TypeChecker {
 
         let mut global = !matches!(decl.storage_class, Some(StorageClass::Static));
 
-        if let Some(old_decl_info) = self.symbol_tables.get(&decl.name).cloned() {
-            if old_decl_info.tpye != CType::Int {
-                return Err(format!("函数 '{}' 被重新声明为变量", decl.name));
+        if let Some(old_decl_info) = self.symbol_tables.get(decl.name).cloned() {
+            if old_decl_info.tpye.is_function() {
+                return Err(semantic_error(
+                    decl.span,
+                    tr!("函数 '{}' 被重新声明为变量", decl.name),
+                ));
+            }
+            if old_decl_info.tpye != var_type || old_decl_info.volatile != decl.volatile {
+                return Err(semantic_error(
+                    decl.span,
+                    tr!("变量 '{}' 的声明类型冲突", decl.name),
+                ));
             }
 
             if let IdentifierAttrs::StaticAttr {
@@ -179,13 +502,11 @@ impl TypeChecker {
             {
                 if matches!(decl.storage_class, Some(StorageClass::Extern)) {
                     global = old_global;
-                } else if old_global != global {
-                    return Err("变量链接冲突".to_string());
                 }
 
                 initial_value = match (old_init, initial_value) {
                     (InitValue::Initial(_), InitValue::Initial(_)) => {
-                        return Err("文件作用域变量定义冲突".to_string());
+                        return Err(semantic_error(decl.span, tr!("文件作用域变量定义冲突")));
                     }
                     (init @ InitValue::Initial(_), _) => init,
                     (_, init @ InitValue::Initial(_)) => init,
@@ -193,112 +514,142 @@ impl TypeChecker {
                     (InitValue::NoInitalizer, InitValue::NoInitalizer) => InitValue::NoInitalizer,
                 };
             } else {
-                return Err(format!("'{}' 被重新声明为不同类型的符号", decl.name));
+                return Err(semantic_error(
+                    decl.span,
+                    tr!("'{}' 被重新声明为不同类型的符号", decl.name),
+                ));
             }
         }
 
+        self.check_linkage(decl.name, global, decl.span, false)?;
+
+        fold_static_initializer(&mut self.arena, decl, &initial_value);
         let attrs = IdentifierAttrs::StaticAttr {
             init_value: initial_value,
             global,
         };
         self.symbol_tables.insert(
-            decl.name.clone(),
+            decl.name,
             SymbolInfo {
-                tpye: CType::Int,
+                tpye: var_type,
                 identifier_attrs: attrs,
+                span: Some(decl.span),
+                volatile: decl.volatile,
             },
         );
 
         Ok(())
     }
 
-    fn typecheck_block_scope_variable_declaration(&mut self, decl: &VarDecl) -> Result<(), String> {
+    fn typecheck_block_scope_variable_declaration(
+        &mut self,
+        decl: &VarDecl,
+    ) -> Result<(), Diagnostic> {
+        let var_type = CType::from(decl.var_type);
         match &decl.storage_class {
             Some(StorageClass::Extern) => {
                 if decl.init.is_some() {
-                    return Err("局部 extern 变量声明带有初始值".to_string());
+                    return Err(semantic_error(
+                        decl.span,
+                        tr!("局部 extern 变量声明带有初始值"),
+                    ));
                 }
 
-                if let Some(old_decl_info) = self.find_identifier(&decl.name) {
-                    if old_decl_info.tpye != CType::Int {
-                        return Err(format!("函数 '{}' 被重新声明为变量", decl.name));
+                if let Some(old_decl_info) = self.find_identifier(decl.name) {
+                    if old_decl_info.tpye.is_function() {
+                        return Err(semantic_error(
+                            decl.span,
+                            tr!("函数 '{}' 被重新声明为变量", decl.name),
+                        ));
+                    }
+                    if old_decl_info.tpye != var_type || old_decl_info.volatile != decl.volatile {
+                        return Err(semantic_error(
+                            decl.span,
+                            tr!("变量 '{}' 的声明类型冲突", decl.name),
+                        ));
                     }
                 } else {
+                    self.check_linkage(decl.name, true, decl.span, true)?;
                     let attrs = IdentifierAttrs::StaticAttr {
                         init_value: InitValue::NoInitalizer,
                         global: true,
                     };
                     self.symbol_tables.insert(
-                        decl.name.clone(),
+                        decl.name,
                         SymbolInfo {
-                            tpye: CType::Int,
+                            tpye: var_type,
                             identifier_attrs: attrs,
+                            span: Some(decl.span),
+                            volatile: decl.volatile,
                         },
                     );
                 }
                 Ok(())
             }
             Some(StorageClass::Static) => {
-                let initial_value = if let Some(init_expr) = &decl.init {
-                    let const_val = self
-                        .eval_const_expr(init_expr)
-                        .map_err(|_| "局部静态变量的初始值不是常量".to_string())?;
-                    InitValue::Initial(const_val)
+                let initial_value = if let Some(init_expr) = decl.init {
+                    let const_val = self.eval_const_expr(init_expr, decl.span, || {
+                        tr!("局部静态变量的初始值不是常量")
+                    })?;
+                    InitValue::Initial(var_type.convert_constant(const_val))
                 } else {
                     InitValue::Initial(0)
                 };
 
+                fold_static_initializer(&mut self.arena, decl, &initial_value);
                 let attrs = IdentifierAttrs::StaticAttr {
                     init_value: initial_value,
                     global: false,
                 };
                 self.insert_variable(
-                    decl.name.clone(),
+                    decl.name,
                     SymbolInfo {
-                        tpye: CType::Int,
+                        tpye: var_type,
                         identifier_attrs: attrs,
+                        span: Some(decl.span),
+                        volatile: decl.volatile,
                     },
-                )
+                )?;
+                Ok(())
             }
             None => {
                 // 自动变量
                 let attrs = IdentifierAttrs::LocalAttr;
                 self.insert_variable(
-                    decl.name.clone(),
+                    decl.name,
                     SymbolInfo {
-                        tpye: CType::Int,
+                        tpye: var_type,
                         identifier_attrs: attrs,
+                        span: Some(decl.span),
+                        volatile: decl.volatile,
                     },
                 )?;
-                if let Some(e) = &decl.init {
-                    self.typecheck_expression(e)?;
-                }
-                Ok(())
+                self.typecheck_optional_expression(decl.init)
             }
         }
     }
 
     // --- 语句和表达式检查 ---
 
-    fn typecheck_block_body(&mut self, block: &Block) -> Result<(), String> {
+    fn typecheck_block_body(&mut self, block: &Block) -> Result<(), Diagnostic> {
         for item in &block.0 {
             self.typecheck_block_item(item)?;
         }
         Ok(())
     }
 
-    fn typecheck_block_item(&mut self, item: &BlockItem) -> Result<(), String> {
+    fn typecheck_block_item(&mut self, item: &BlockItem) -> Result<(), Diagnostic> {
         match item {
             BlockItem::D(d) => self.typecheck_declaration(d, false), // false 表示块作用域
-            BlockItem::S(s) => self.typecheck_statement(s),
+            BlockItem::S(s) => self.typecheck_statement(*s),
         }
     }
 
-    fn typecheck_statement(&mut self, stmt: &Statement) -> Result<(), String> {
-        match stmt {
+    fn typecheck_statement(&mut self, stmt: StmtId) -> Result<(), Diagnostic> {
+        match self.arena[stmt].clone() {
             Statement::Compound(b) => {
                 self.push_scope();
-                self.typecheck_block_body(b)?;
+                self.typecheck_block_body(&b)?;
                 self.pop_scope();
                 Ok(())
             }
@@ -309,20 +660,47 @@ impl TypeChecker {
                 body,
                 ..
             } => {
+                // 与标识符解析相同：初始化作用域包住条件、post 和循环体，循环体另有一层作用域
+                self.push_scope();
+                self.resolve_for_init(&init)?;
+                self.typecheck_optional_expression(condition)?;
+                self.typecheck_optional_expression(post)?;
                 self.push_scope();
-                self.resolve_for_init(init)?;
-                if let Some(c) = condition {
-                    self.typecheck_expression(c)?;
-                }
-                if let Some(p) = post {
-                    self.typecheck_expression(p)?;
-                }
                 self.typecheck_statement(body)?;
                 self.pop_scope();
+                self.pop_scope();
+                Ok(())
+            }
+            Statement::Expression(e) => {
+                let is_volatile = |name| self.symbol_tables.is_volatile(name);
+                if self.warn_unused_value && !self.arena.has_side_effects(e, &is_volatile) {
+                    self.warnings.push(
+                        Diagnostic::warning(tr!(
+                            "函数 '{}' 中的表达式语句没有副作用，其值未被使用",
+                            self.current_function.map_or("?", Symbol::as_str)
+                        ))
+                        .with_code("unused-value")
+                        .with_span(self.arena.stmt_span(stmt)),
+                    );
+                }
+                self.typecheck_expression(e)?;
+                Ok(())
+            }
+            Statement::Return(e) => {
+                if let Some(name) = self.current_function
+                    && self.is_noreturn(name)
+                {
+                    self.warnings.push(
+                        Diagnostic::warning(tr!(
+                            "函数 '{}' 被声明为 noreturn，但包含 return 语句",
+                            name
+                        ))
+                        .with_span(self.arena.stmt_span(stmt)),
+                    );
+                }
+                self.typecheck_expression(e)?;
                 Ok(())
             }
-            Statement::Expression(e) => self.typecheck_expression(e),
-            Statement::Return(e) => self.typecheck_expression(e),
             Statement::If {
                 condition,
                 then_stmt,
@@ -330,74 +708,153 @@ impl TypeChecker {
             } => {
                 self.typecheck_expression(condition)?;
                 self.typecheck_statement(then_stmt)?;
-                if let Some(es) = else_stmt {
-                    self.typecheck_statement(es)?;
+                match else_stmt {
+                    Some(es) => self.typecheck_statement(es),
+                    None => Ok(()),
                 }
+            }
+            Statement::While {
+                condition, body, ..
+            } => {
+                self.typecheck_expression(condition)?;
+                self.typecheck_statement(body)
+            }
+            Statement::DoWhile {
+                body, condition, ..
+            } => {
+                self.typecheck_statement(body)?;
+                self.typecheck_expression(condition)?;
                 Ok(())
             }
-            _ => Ok(()), // while, dowhile, break, continue, null 等语句
+            Statement::Labeled { stmt, .. } | Statement::Default { stmt, .. } => {
+                self.typecheck_statement(stmt)
+            }
+            Statement::Case { value, stmt, .. } => {
+                // case 的值必须是整数常量表达式 (C11 6.8.4.2p3)
+                self.typecheck_expression(value)?;
+                let span = self.arena.stmt_span(stmt);
+                self.eval_const_expr(value, span, || {
+                    tr!("语义错误：case 标签的值不是整数常量表达式。")
+                })?;
+                self.typecheck_statement(stmt)
+            }
+            Statement::Break(_) | Statement::Continue(_) | Statement::Null => Ok(()),
         }
     }
 
-    fn resolve_for_init(&mut self, init: &ForInit) -> Result<(), String> {
+    fn resolve_for_init(&mut self, init: &ForInit) -> Result<(), Diagnostic> {
         match init {
-            ForInit::InitDecl(d) => {
-                if d.storage_class.is_some() {
-                    return Err("for 循环初始值设定项中不允许使用存储类说明符".to_string());
+            ForInit::InitDecl(decls) => {
+                for d in decls {
+                    if d.storage_class.is_some() {
+                        return Err(semantic_error(
+                            d.span,
+                            tr!("for 循环初始值设定项中不允许使用存储类说明符"),
+                        ));
+                    }
+                    self.typecheck_block_scope_variable_declaration(d)?;
                 }
-                self.typecheck_block_scope_variable_declaration(d)
+                Ok(())
             }
-            ForInit::InitExp(Some(e)) => self.typecheck_expression(e),
-            ForInit::InitExp(None) => Ok(()),
+            ForInit::InitExp(e) => self.typecheck_optional_expression(*e),
         }
     }
 
-    fn typecheck_expression(&mut self, e: &Expression) -> Result<(), String> {
+    fn typecheck_optional_expression(&mut self, e: Option<ExprId>) -> Result<(), Diagnostic> {
         match e {
+            Some(e) => self.typecheck_expression(e).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    /// 检查表达式，返回它的类型（`_Bool` 变量的值在参与运算时才提升）。
+    fn typecheck_expression(&mut self, e: ExprId) -> Result<CType, Diagnostic> {
+        match self.arena[e].clone() {
             Expression::Var(id) => match self.find_identifier(id) {
                 Some(info) => {
-                    if info.tpye != CType::Int {
-                        Err(format!("语义错误：函数 '{}' 被用作变量。", id))
+                    if info.tpye.is_function() {
+                        Err(semantic_error(
+                            self.arena.expr_span(e),
+                            tr!("语义错误：函数 '{}' 被用作变量。", id),
+                        ))
                     } else {
-                        Ok(())
+                        Ok(info.tpye.clone())
                     }
                 }
-                None => Err(format!("语义错误：使用了未声明的标识符 '{}'。", id)),
+                None => Err(semantic_error(
+                    self.arena.expr_span(e),
+                    tr!("语义错误：使用了未声明的标识符 '{}'。", id),
+                )),
             },
             Expression::FuncCall { name, args } => match self.find_identifier(name) {
-                Some(info) => match info.tpye {
-                    CType::Int => Err(format!("语义错误：变量 '{}' 被用作函数。", name)),
-                    CType::FunType { param_count } => {
-                        if param_count != args.len() {
-                            Err(format!(
-                                "语义错误：函数 '{}' 调用时参数数量错误。预期 {} 个，实际 {} 个。",
+                Some(info) => match info.tpye.clone() {
+                    CType::Int | CType::Bool => Err(semantic_error(
+                        self.arena.expr_span(e),
+                        tr!("语义错误：变量 '{}' 被用作函数。", name),
+                    )),
+                    CType::FunType {
+                        unspecified_params: true,
+                        ret,
+                        ..
+                    } => {
+                        self.warnings.push(
+                            Diagnostic::warning(tr!(
+                                "通过未指定参数的声明 `{}()` 调用函数，参数数量（{} 个）不会被检查",
                                 name,
-                                param_count,
                                 args.len()
                             ))
+                            .with_span(self.arena.expr_span(e)),
+                        );
+                        self.typecheck_call(&args)?;
+                        Ok(*ret)
+                    }
+                    CType::FunType { params, ret, .. } => {
+                        if params.len() != args.len() {
+                            Err(semantic_error(
+                                self.arena.expr_span(e),
+                                tr!(
+                                    "语义错误：在函数 '{}' 中调用 '{}' 时参数数量错误：'{}' 声明了 {} 个参数，实际传入 {} 个。",
+                                    self.current_function.map_or("?", Symbol::as_str),
+                                    name,
+                                    name,
+                                    params.len(),
+                                    args.len()
+                                ),
+                            ))
                         } else {
-                            for arg in args {
-                                self.typecheck_expression(arg)?;
-                            }
-                            Ok(())
+                            self.typecheck_call(&args)?;
+                            Ok(*ret)
                         }
                     }
                 },
-                None => Err(format!("语义错误：调用了未声明的函数 '{}'。", name)),
+                None => Err(semantic_error(
+                    self.arena.expr_span(e),
+                    tr!("语义错误：调用了未声明的函数 '{}'。", name),
+                )),
             },
+            // 赋值表达式的类型是左边对象的类型
             Expression::Assignment { left, right } => {
-                self.typecheck_expression(left)?;
+                let left_type = self.typecheck_expression(left)?;
                 self.typecheck_expression(right)?;
-                Ok(())
+                Ok(left_type)
             }
-            Expression::Binary { left, right, .. } => {
-                self.typecheck_expression(left)?;
-                self.typecheck_expression(right)?;
-                Ok(())
+            Expression::Binary { op, left, right } => {
+                let left_type = self.typecheck_expression(left)?;
+                let right_type = self.typecheck_expression(right)?;
+                Ok(conversions::binary_result_type(
+                    &op,
+                    &left_type,
+                    &right_type,
+                ))
             }
-            Expression::Unary { exp, .. } => {
-                self.typecheck_expression(exp)?;
-                Ok(())
+            Expression::Unary { op, exp } => {
+                let operand = self.typecheck_expression(exp)?;
+                Ok(match op {
+                    UnaryOp::Not => CType::Int,
+                    UnaryOp::Complement | UnaryOp::Negate | UnaryOp::Plus => {
+                        conversions::promote(&operand)
+                    }
+                })
             }
             Expression::Conditional {
                 condition,
@@ -405,43 +862,142 @@ impl TypeChecker {
                 right,
             } => {
                 self.typecheck_expression(condition)?;
-                self.typecheck_expression(left)?;
-                self.typecheck_expression(right)?;
-                Ok(())
+                let left_type = self.typecheck_expression(left)?;
+                let right_type = self.typecheck_expression(right)?;
+                Ok(conversions::usual_arithmetic_conversions(
+                    &left_type,
+                    &right_type,
+                ))
             }
-            Expression::Constant(_) => Ok(()),
+            Expression::Constant(_) => Ok(CType::Int),
         }
     }
 
+    fn typecheck_call(&mut self, args: &[ExprId]) -> Result<(), Diagnostic> {
+        for &arg in args {
+            self.typecheck_expression(arg)?;
+        }
+        Ok(())
+    }
+
     // --- 辅助函数 ---
 
-    fn eval_const_expr(&self, expr: &Expression) -> Result<i64, String> {
-        match expr {
-            Expression::Constant(i) => Ok(*i),
-            _ => Err("初始值不是常量表达式！".to_string()),
+    /// 对静态存储期变量的初始值求值，必要时报告 `-Woverflow` 警告。
+    ///
+    /// `not_constant` 是初始值不是常量表达式时的错误信息。
+    fn eval_const_expr(
+        &mut self,
+        expr: ExprId,
+        span: Span,
+        not_constant: impl FnOnce() -> String,
+    ) -> Result<i64, Diagnostic> {
+        match const_eval::eval_int(expr, &self.arena, &self.layout) {
+            Ok(c) => {
+                if c.overflowed && self.warn_overflow {
+                    self.warnings.push(
+                        Diagnostic::warning(tr!(
+                            "常量表达式求值时发生整数溢出，结果为 {}",
+                            c.value
+                        ))
+                        .with_code("overflow")
+                        .with_span(span),
+                    );
+                }
+                Ok(c.value)
+            }
+            Err(ConstEvalError::NotConstant) => Err(semantic_error(span, not_constant())),
+            Err(ConstEvalError::DivisionByZero) => Err(semantic_error(
+                span,
+                tr!("语义错误：常量表达式中出现除以零。"),
+            )),
+        }
+    }
+
+    /// 检查标识符的链接性是否与它在翻译单元中的第一次声明一致，并记录第一次声明。
+    ///
+    /// 同一个标识符在翻译单元中既有内部链接又有外部链接时行为未定义 (C11 6.2.2p7)，
+    /// 例如块作用域中的 `extern int f(void);` 之后出现文件作用域的 `static int f(void);`。
+    fn check_linkage(
+        &mut self,
+        name: Symbol,
+        global: bool,
+        span: Span,
+        block_scope: bool,
+    ) -> Result<(), Diagnostic> {
+        let linkage = |global| if global { tr!("外部") } else { tr!("内部") };
+        match self.first_declarations.get(&name) {
+            Some(first) if first.global != global => Err(semantic_error(
+                span,
+                tr!(
+                    "'{}' 的链接冲突：{} 处的声明具有{}链接，而 {} 处的第一次声明{}具有{}链接 (C11 6.2.2p7)",
+                    name,
+                    span,
+                    linkage(global),
+                    first.span,
+                    if first.block_scope {
+                        tr!("（块作用域）")
+                    } else {
+                        String::new()
+                    },
+                    linkage(first.global)
+                ),
+            )),
+            Some(_) => Ok(()),
+            None => {
+                self.first_declarations.insert(
+                    name,
+                    FirstDeclaration {
+                        global,
+                        span,
+                        block_scope,
+                    },
+                );
+                Ok(())
+            }
         }
     }
 
-    fn find_identifier(&self, name: &str) -> Option<SymbolInfo> {
+    fn is_noreturn(&self, name: Symbol) -> bool {
+        matches!(
+            self.symbol_tables.get(name).map(|s| &s.identifier_attrs),
+            Some(IdentifierAttrs::FunAttr { noreturn: true, .. })
+        )
+    }
+
+    fn find_identifier(&self, name: Symbol) -> Option<SymbolInfo> {
         for scope in self.scopes.iter().rev() {
-            if let Some(info) = scope.get(name) {
+            if let Some(info) = scope.get(&name) {
                 return Some(info.clone());
             }
         }
         self.symbol_tables.get(name).cloned()
     }
 
-    fn insert_variable(&mut self, name: String, info: SymbolInfo) -> Result<(), String> {
+    /// 在当前块作用域中插入变量。
+    ///
+    /// 标识符解析之后块作用域变量的名字都是唯一的，所以它们同时被记录到全局符号表中，
+    /// 供后续阶段（TACKY 生成、汇编生成）查询变量的类型和存储期。
+    fn insert_variable(&mut self, name: Symbol, info: SymbolInfo) -> Result<(), Diagnostic> {
         let current_scope = self
             .scopes
             .last_mut()
             .expect("没有作用域时无法插入变量。这是一个编译器错误。");
 
-        if current_scope.contains_key(&name) {
-            Err(format!("语义错误：在同一作用域中重定义了变量 '{}'。", name))
-        } else {
-            current_scope.insert(name, info);
-            Ok(())
+        match current_scope.entry(name) {
+            Entry::Occupied(e) => {
+                let error =
+                    Diagnostic::error(tr!("语义错误：在同一作用域中重定义了变量 '{}'。", e.key()))
+                        .with_code("semantic-error");
+                Err(match info.span {
+                    Some(span) => error.with_span(span),
+                    None => error,
+                })
+            }
+            Entry::Vacant(e) => {
+                self.symbol_tables.insert(*e.key(), info.clone());
+                e.insert(info);
+                Ok(())
+            }
         }
     }
 
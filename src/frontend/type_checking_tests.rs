@@ -0,0 +1,147 @@
+// src/frontend/type_checking_tests.rs
+
+//! 类型检查的测试：错误信息的位置、链接属性冲突、原型与定义的参数名、没有原型的调用、
+//! 静态变量的初始值，以及符号表的顺序。
+
+use crate::UniqueNameGenerator;
+use crate::backend::assembly_ast_gen::IntDivOverflow;
+use crate::backend::interpreter;
+use crate::backend::tacky_gen::TackyGenerator;
+use crate::frontend::loop_labeling::LoopLabeling;
+use crate::frontend::parse_program;
+use crate::frontend::resolve_ident::IdentifierResolver;
+use crate::frontend::type_checking::{IdentifierAttrs, InitValue, TypeChecker};
+
+/// 解析、标识符解析并类型检查整个程序，返回类型检查的错误。
+fn typecheck_error(source: &str) -> String {
+    let ast = parse_program(source);
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    TypeChecker::new()
+        .typecheck_program(&resolved)
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn wrong_argument_count_reports_call_location() {
+    let error =
+        typecheck_error("int add(int a, int b);\nint main(void) {\n    return 1 + add(1);\n}\n");
+    assert!(error.contains("3:16: "), "{}", error);
+    // 调用位于头文件中时还带上文件名
+    let error = typecheck_error(
+        "# 1 \"main.c\"\nint add(int a, int b);\n# 1 \"calls.h\" 1\nint f(void) { return add(1, 2, 3); }\n",
+    );
+    assert!(error.contains("calls.h:1:22: "), "{}", error);
+}
+
+#[test]
+fn linkage_conflicts_with_block_scope_first_declaration() {
+    let error = typecheck_error(
+        "int main(void) {\n    extern int f(void);\n    return f();\n}\nstatic int f(void) { return 1; }\n",
+    );
+    assert!(
+        error.contains("5:12") && error.contains("2:16"),
+        "{}",
+        error
+    );
+    let error = typecheck_error("int main(void) { extern int x; return x; }\nstatic int x;\n");
+    assert!(error.contains("6.2.2p7"), "{}", error);
+}
+
+#[test]
+fn prototypes_may_rename_parameters_but_definitions_are_unique() {
+    let ast = parse_program(
+        "\
+int f(int a);
+int f(int b) { return b + 1; }
+int f(int c);
+int main(void) { int g(int p, int q); return f(2) * 10 + g(3, 4); }
+int g(int m, int n) { return m - n; }
+",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let labeled = LoopLabeling::new(&mut names)
+        .label_loops_in_program(&resolved)
+        .unwrap();
+    let (typed, symbols, _) = TypeChecker::new().typecheck_program(&labeled).unwrap();
+    let (program, _) = TackyGenerator::new(&mut names, &symbols)
+        .generate_tacky(&typed)
+        .unwrap();
+    // 函数体使用定义中的参数名
+    let f = program.functions.iter().find(|f| f.name == "f").unwrap();
+    assert!(f.params[0].as_str().starts_with("f.b."), "{:?}", f.params);
+    assert_eq!(
+        interpreter::run(&program, &symbols, IntDivOverflow::Trap).unwrap(),
+        29
+    );
+
+    let error =
+        typecheck_error("int f(int a) { return a; }\nint f(int a);\nint f(int b) { return b; }\n");
+    assert!(
+        error.contains("3:5") && error.contains("'f'") && error.contains("1:5"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn symbol_table_iterates_in_declaration_order() {
+    let ast = parse_program(
+        "static int zeta = 1;\nint alpha(void);\nint mid;\nint main(void) { return alpha(); }\nint alpha(void) { return zeta; }\n",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let (_, symbols, _) = TypeChecker::new().typecheck_program(&resolved).unwrap();
+    let order: Vec<&str> = symbols.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(order, ["zeta", "alpha", "mid", "main"]);
+}
+
+#[test]
+fn static_bool_initializers_are_converted() {
+    let ast = parse_program("static _Bool flag = 256;\nstatic _Bool off = 0;\n_Bool neg = -1;\n");
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let (_, symbols, _) = TypeChecker::new().typecheck_program(&resolved).unwrap();
+    let init = |name: &str| match &symbols.get(name.into()).unwrap().identifier_attrs {
+        IdentifierAttrs::StaticAttr { init_value, .. } => init_value.clone(),
+        attrs => panic!("{:?}", attrs),
+    };
+    // 256 的低 8 位是 0，但转换为 `_Bool` 看的是整个值是否非零
+    assert_eq!(init("flag"), InitValue::Initial(1));
+    assert_eq!(init("off"), InitValue::Initial(0));
+    assert_eq!(init("neg"), InitValue::Initial(1));
+}
+
+#[test]
+fn only_calls_without_a_prototype_warn() {
+    let ast = parse_program(
+        "int f();\nint g(void);\nint h(int a);\nint main(void) {\n    return f(1, 2) + g() + h(3) + f();\n}\n",
+    );
+    let mut names = UniqueNameGenerator::new();
+    let (resolved, _) = IdentifierResolver::new(&mut names)
+        .resolve_program(&ast)
+        .unwrap();
+    let (_, _, warnings) = TypeChecker::new().typecheck_program(&resolved).unwrap();
+    // 两次通过 `int f();` 的调用各有一个警告，有原型的 `g` 和 `h` 没有
+    let spans: Vec<String> = warnings
+        .iter()
+        .map(|w| w.span.unwrap().to_string())
+        .collect();
+    assert_eq!(spans, ["5:12", "5:35"], "{:?}", warnings);
+    assert!(warnings[0].message.contains("f()"), "{:?}", warnings);
+    assert!(warnings[0].message.contains('2'), "{:?}", warnings);
+
+    // `int g(void)` 不接受参数
+    let error = typecheck_error("int g(void);\nint main(void) { return g(1); }\n");
+    assert!(error.contains("2:25"), "{}", error);
+}
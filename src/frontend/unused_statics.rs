@@ -0,0 +1,210 @@
+// src/frontend/unused_statics.rs
+
+//! **未使用的内部链接符号 (`-Wunused-static`, `--drop-unused-statics`)**
+//!
+//! 在类型检查后的 AST 上构建引用图：每个函数定义指向它的函数体中引用的所有函数和变量。
+//! 从外部可见（非 `static`）的函数定义出发，不可达的 `static` 函数定义和文件作用域
+//! `static` 变量就是未使用的——它们只能在本翻译单元内被引用，而本翻译单元中没有任何
+//! 可能被执行的代码会用到它们。只互相调用的一组 `static` 函数同样被视为未使用。
+//!
+//! 找到的符号可以报告为警告，也可以从 AST 中删除，使它们不再参与代码生成。
+
+use std::collections::{HashMap, HashSet};
+
+use crate::common::{Span, Symbol};
+use crate::diagnostics::Diagnostic;
+use crate::frontend::c_ast::{
+    AstArena, Block, BlockItem, Declaration, ExprId, Expression, ForInit, Statement, StmtId,
+    StorageClass,
+};
+use crate::frontend::type_checking::{IdentifierAttrs, SymbolTable, TypedProgram};
+use crate::i18n::tr;
+
+/// 一个未使用的 `static` 函数或变量。
+#[derive(Debug)]
+pub struct UnusedStatic {
+    pub name: Symbol,
+    pub is_function: bool,
+    pub span: Span,
+}
+
+impl UnusedStatic {
+    /// `-Wunused-static` 警告信息。
+    pub fn warning(&self) -> Diagnostic {
+        let message = if self.is_function {
+            tr!("static 函数 '{}' 已定义但从未被使用", self.name)
+        } else {
+            tr!("static 变量 '{}' 已定义但从未被使用", self.name)
+        };
+        Diagnostic::warning(message)
+            .with_code("unused-static")
+            .with_span(self.span)
+    }
+}
+
+/// 找出程序中未使用的 `static` 函数定义和文件作用域 `static` 变量，按声明顺序返回。
+pub fn find_unused_statics(program: &TypedProgram, symbols: &SymbolTable) -> Vec<UnusedStatic> {
+    // 引用图：函数名 -> 函数体中引用的标识符
+    let mut references: HashMap<Symbol, HashSet<Symbol>> = HashMap::new();
+    let mut roots = Vec::new();
+    for decl in &program.declarations {
+        if let Declaration::Fun(f) = decl
+            && let Some(body) = &f.body
+        {
+            collect_block(&program.arena, body, references.entry(f.name).or_default());
+            if has_external_linkage(f.name, symbols) {
+                roots.push(f.name);
+            }
+        }
+    }
+
+    let mut used: HashSet<Symbol> = HashSet::new();
+    while let Some(name) = roots.pop() {
+        if !used.insert(name) {
+            continue;
+        }
+        if let Some(referenced) = references.get(&name) {
+            roots.extend(referenced.iter().copied());
+        }
+    }
+
+    let mut unused: Vec<UnusedStatic> = Vec::new();
+    for decl in &program.declarations {
+        let (name, is_function, span) = match decl {
+            Declaration::Fun(f) if f.body.is_some() => (f.name, true, f.span),
+            Declaration::Variable(v) => (v.name, false, v.span),
+            Declaration::Fun(_) => continue,
+        };
+        let already_reported = unused.iter().any(|u| u.name == name);
+        if !has_external_linkage(name, symbols) && !used.contains(&name) && !already_reported {
+            unused.push(UnusedStatic {
+                name,
+                is_function,
+                span,
+            });
+        }
+    }
+    unused
+}
+
+/// 从程序中删除 `unused` 中列出的符号的所有文件作用域声明。
+pub fn remove_unused_statics(program: &mut TypedProgram, unused: &[UnusedStatic]) {
+    let names: HashSet<Symbol> = unused.iter().map(|u| u.name).collect();
+    program.declarations.retain(|decl| {
+        let name = match decl {
+            Declaration::Fun(f) => f.name,
+            Declaration::Variable(v) => v.name,
+        };
+        !names.contains(&name)
+    });
+}
+
+fn has_external_linkage(name: Symbol, symbols: &SymbolTable) -> bool {
+    match symbols.get(name).map(|s| &s.identifier_attrs) {
+        Some(IdentifierAttrs::FunAttr { global, .. })
+        | Some(IdentifierAttrs::StaticAttr { global, .. }) => *global,
+        _ => true,
+    }
+}
+
+fn collect_block(arena: &AstArena, block: &Block, out: &mut HashSet<Symbol>) {
+    for item in &block.0 {
+        match item {
+            BlockItem::S(s) => collect_statement(arena, *s, out),
+            BlockItem::D(Declaration::Variable(v)) => {
+                // 块作用域的 static 变量只在定义它的函数中可见，它的初始值是常量
+                if !matches!(v.storage_class, Some(StorageClass::Static))
+                    && let Some(init) = v.init
+                {
+                    collect_expression(arena, init, out);
+                }
+            }
+            BlockItem::D(Declaration::Fun(_)) => {}
+        }
+    }
+}
+
+fn collect_statement(arena: &AstArena, stmt: StmtId, out: &mut HashSet<Symbol>) {
+    match &arena[stmt] {
+        Statement::Return(e) | Statement::Expression(e) => collect_expression(arena, *e, out),
+        Statement::If {
+            condition,
+            then_stmt,
+            else_stmt,
+        } => {
+            collect_expression(arena, *condition, out);
+            collect_statement(arena, *then_stmt, out);
+            if let Some(s) = else_stmt {
+                collect_statement(arena, *s, out);
+            }
+        }
+        Statement::Compound(b) => collect_block(arena, b, out),
+        Statement::While {
+            condition, body, ..
+        }
+        | Statement::DoWhile {
+            body, condition, ..
+        } => {
+            collect_expression(arena, *condition, out);
+            collect_statement(arena, *body, out);
+        }
+        Statement::For {
+            init,
+            condition,
+            post,
+            body,
+            ..
+        } => {
+            match init {
+                ForInit::InitDecl(decls) => {
+                    for e in decls.iter().filter_map(|v| v.init.as_ref()) {
+                        collect_expression(arena, *e, out);
+                    }
+                }
+                ForInit::InitExp(e) => {
+                    if let Some(e) = e {
+                        collect_expression(arena, *e, out);
+                    }
+                }
+            }
+            for e in [condition, post].into_iter().flatten() {
+                collect_expression(arena, *e, out);
+            }
+            collect_statement(arena, *body, out);
+        }
+        // case 的值是常量表达式，不引用任何函数或变量
+        Statement::Labeled { stmt, .. }
+        | Statement::Case { stmt, .. }
+        | Statement::Default { stmt, .. } => collect_statement(arena, *stmt, out),
+        Statement::Break(_) | Statement::Continue(_) | Statement::Null => {}
+    }
+}
+
+fn collect_expression(arena: &AstArena, exp: ExprId, out: &mut HashSet<Symbol>) {
+    match &arena[exp] {
+        Expression::Constant(_) => {}
+        Expression::Var(name) => {
+            out.insert(*name);
+        }
+        Expression::FuncCall { name, args } => {
+            out.insert(*name);
+            for arg in args {
+                collect_expression(arena, *arg, out);
+            }
+        }
+        Expression::Unary { exp, .. } => collect_expression(arena, *exp, out),
+        Expression::Binary { left, right, .. } | Expression::Assignment { left, right } => {
+            collect_expression(arena, *left, out);
+            collect_expression(arena, *right, out);
+        }
+        Expression::Conditional {
+            condition,
+            left,
+            right,
+        } => {
+            collect_expression(arena, *condition, out);
+            collect_expression(arena, *left, out);
+            collect_expression(arena, *right, out);
+        }
+    }
+}
@@ -0,0 +1,335 @@
+// src/grade.rs
+
+//! **批量评分 (Grading)**
+//!
+//! 该模块实现 `--grade` 驱动模式：编译目录（含子目录）下的每个 `.c` 文件，运行编译
+//! 成功的程序，并把每个文件的结果写成一份 JSON 报告，供课程教师批量检查学生作业。
+//!
+//! ## 输入和期望输出
+//!
+//! 对于 `foo.c`，如果同一目录下存在：
+//! -   `foo.in`：作为程序的标准输入；否则标准输入为空
+//! -   `foo.expected`：程序的标准输出必须与它完全一致，否则状态为 `wrong_output`
+//!
+//! ## 状态
+//!
+//! `pass`、`compile_error`、`link_error`、`runtime_error`（被信号终止）、`timeout`、
+//! `wrong_output`。程序的退出码只被记录，不影响状态。
+//!
+//! 与 `--bench` 一样，本编译器以子进程方式运行 (`ccompiler <file> -c`)，所有中间文件
+//! 都写入系统临时目录，不会污染被评分的目录。源文件被复制到临时目录中编译，所以编译时
+//! 加上 `-I<源文件所在目录>`，`#include "x.h"` 仍然能找到源文件旁边的头文件。
+//!
+//! ## 诊断信息
+//!
+//! 编译时加上 `--diagnostics-format json`，报告中的 `diagnostics` 直接保存编译器输出的
+//! JSON 记录（格式见 [`crate::diagnostics`]），源文件的 `file` 是它相对于被评分目录的路径。
+//! 其他工具（gcc 预处理器、链接器）的每一行输出成为一条没有位置的错误，链接错误的代码是
+//! `link-error`。
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Serialize, Serializer};
+use serde_json::{Value, json};
+
+use crate::i18n::tr;
+
+/// 每个程序允许运行的最长时间，超时的程序会被杀死。
+const RUN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 整个目录的评分报告。
+#[derive(Serialize)]
+struct Report<'a> {
+    directory: String,
+    total: usize,
+    passed: usize,
+    files: &'a [GradeResult],
+}
+
+/// 单个源文件的评分结果。
+#[derive(Debug, Serialize)]
+struct GradeResult {
+    /// 相对于被评分目录的路径
+    file: String,
+    status: &'static str,
+    /// 编译器报告的错误和警告，以及链接错误
+    diagnostics: Vec<Value>,
+    exit_code: Option<i32>,
+    /// 没有 `.expected` 文件时为 `None`
+    output_matched: Option<bool>,
+    #[serde(rename = "compile_ms", serialize_with = "ms")]
+    compile_time: Duration,
+    #[serde(rename = "run_ms", serialize_with = "optional_ms")]
+    run_time: Option<Duration>,
+}
+
+/// `--grade` 模式的入口。`compiler_args` 会原样传给每次编译（例如 `--lang`、`-W`）。
+pub fn run_grading(dir: &Path, report_path: &Path, compiler_args: &[String]) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Err(tr!("--grade 需要一个目录: {}", dir.display()));
+    }
+    let mut sources = Vec::new();
+    collect_sources(dir, &mut sources)?;
+    if sources.is_empty() {
+        return Err(tr!("在 {} 中没有找到任何 .c 文件", dir.display()));
+    }
+
+    let compiler = std::env::current_exe().map_err(|e| tr!("无法定位编译器自身: {}", e))?;
+    let work_dir = std::env::temp_dir().join(format!("ccompiler-grade-{}", std::process::id()));
+    fs::create_dir_all(&work_dir).map_err(|e| tr!("无法创建临时目录: {}", e))?;
+
+    println!("{}", tr!("--- 批量评分: {} 个文件 ---", sources.len()));
+    let mut results = Vec::new();
+    let mut outcome = Ok(());
+    for (index, source) in sources.iter().enumerate() {
+        match grade_file(&compiler, compiler_args, dir, source, &work_dir, index) {
+            Ok(r) => {
+                println!("   {:<16} {}", r.status, r.file);
+                results.push(r);
+            }
+            Err(e) => {
+                outcome = Err(format!("{}: {}", source.display(), e));
+                break;
+            }
+        }
+    }
+    // 无论成功与否都清理临时目录
+    let _ = fs::remove_dir_all(&work_dir);
+    outcome?;
+
+    fs::write(report_path, to_json(dir, &results))
+        .map_err(|e| tr!("无法写入文件 '{}': {}", report_path.display(), e))?;
+    let passed = results.iter().filter(|r| r.status == "pass").count();
+    println!(
+        "{}",
+        tr!(
+            "\n✅ 评分完成: {}/{} 通过，报告已写入 {}",
+            passed,
+            results.len(),
+            report_path.display()
+        )
+    );
+    Ok(())
+}
+
+/// 递归收集目录下的 `.c` 文件，按路径排序，保证报告顺序稳定。
+pub(crate) fn collect_sources(dir: &Path, out: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| tr!("无法读取 {}: {}", dir.display(), e))?;
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_sources(&path, out)?;
+        } else if path.extension().is_some_and(|ext| ext == "c") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn grade_file(
+    compiler: &Path,
+    compiler_args: &[String],
+    dir: &Path,
+    source: &Path,
+    work_dir: &Path,
+    index: usize,
+) -> Result<GradeResult, String> {
+    let file = source
+        .strip_prefix(dir)
+        .unwrap_or(source)
+        .to_string_lossy()
+        .into_owned();
+    let stem = source
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    // 不同子目录中可能有同名文件，临时文件名带上序号
+    let copy = work_dir.join(format!("{}-{}.c", index, stem));
+    fs::copy(source, &copy).map_err(|e| tr!("无法复制源文件: {}", e))?;
+    let obj = copy.with_extension("o");
+    let exe = copy.with_extension("");
+
+    let mut result = GradeResult {
+        file,
+        status: "pass",
+        diagnostics: Vec::new(),
+        exit_code: None,
+        output_matched: None,
+        compile_time: Duration::ZERO,
+        run_time: None,
+    };
+
+    let start = Instant::now();
+    let output = Command::new(compiler)
+        .arg(&copy)
+        .arg("-c")
+        .arg(include_source_dir(source))
+        .args(["--diagnostics-format", "json"])
+        .args(compiler_args)
+        .output()
+        .map_err(|e| tr!("无法运行编译器: {}", e))?;
+    result.compile_time = start.elapsed();
+    result.diagnostics = diagnostics(&result.file, &copy, &output.stdout, &output.stderr);
+    if !output.status.success() {
+        result.status = "compile_error";
+        return Ok(result);
+    }
+
+    let linked = Command::new("gcc")
+        .arg(&obj)
+        .arg("-o")
+        .arg(&exe)
+        .output()
+        .map_err(|e| tr!("无法执行 gcc: {}", e))?;
+    if !linked.status.success() {
+        result.status = "link_error";
+        let stderr = String::from_utf8_lossy(&linked.stderr);
+        result.diagnostics.extend(
+            nonempty_lines(&stderr).map(|line| tool_error(&result.file, Some("link-error"), line)),
+        );
+        return Ok(result);
+    }
+
+    let input = fs::read(source.with_extension("in")).unwrap_or_default();
+    let start = Instant::now();
+    let Some((status, stdout)) = run_with_timeout(&exe, &[], input)? else {
+        result.status = "timeout";
+        return Ok(result);
+    };
+    result.run_time = Some(start.elapsed());
+    result.exit_code = status.code();
+    if result.exit_code.is_none() {
+        result.status = "runtime_error";
+        return Ok(result);
+    }
+    if let Ok(expected) = fs::read(source.with_extension("expected")) {
+        let matched = expected == stdout;
+        result.output_matched = Some(matched);
+        if !matched {
+            result.status = "wrong_output";
+        }
+    }
+    Ok(result)
+}
+
+/// 让复制到临时目录的源文件仍能包含原来目录中的头文件的 `-I` 选项。
+pub(crate) fn include_source_dir(source: &Path) -> String {
+    let dir = source
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    format!("-I{}", dir.display())
+}
+
+/// 从编译器的输出中提取诊断信息：标准输出中的每一行 JSON 记录，以及标准错误中其他工具
+/// 的输出。编译的是临时目录中的副本，指向它的 `file` 换成 `file` 参数。
+fn diagnostics(file: &str, copy: &Path, stdout: &[u8], stderr: &[u8]) -> Vec<Value> {
+    let copy = copy.display().to_string();
+    let stdout = String::from_utf8_lossy(stdout);
+    let stderr = String::from_utf8_lossy(stderr);
+    let mut records: Vec<Value> = nonempty_lines(&stdout)
+        .map(|line| {
+            let mut record =
+                serde_json::from_str(line).unwrap_or_else(|_| tool_error(file, None, line));
+            if record["file"] == copy.as_str() {
+                record["file"] = json!(file);
+            }
+            record
+        })
+        .collect();
+    records.extend(nonempty_lines(&stderr).map(|line| tool_error(file, None, line)));
+    records
+}
+
+/// 其他工具输出的一行，作为一条没有位置的错误。
+fn tool_error(file: &str, code: Option<&str>, message: &str) -> Value {
+    json!({
+        "file": file,
+        "range": null,
+        "severity": "error",
+        "code": code,
+        "message": message,
+        "notes": [],
+    })
+}
+
+fn nonempty_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines().map(str::trim).filter(|line| !line.is_empty())
+}
+
+/// 以 `args` 为命令行参数运行可执行文件并收集标准输出；超时返回 `None`。
+pub(crate) fn run_with_timeout(
+    exe: &Path,
+    args: &[String],
+    input: Vec<u8>,
+) -> Result<Option<(ExitStatus, Vec<u8>)>, String> {
+    let mut child = Command::new(exe)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| tr!("无法运行 '{}': {}", exe.display(), e))?;
+
+    // 在单独的线程中读写管道，避免程序输出填满管道后阻塞
+    let mut stdin = child.stdin.take().expect("stdin 已设置为管道");
+    let writer = thread::spawn(move || {
+        let _ = stdin.write_all(&input);
+    });
+    let mut stdout = child.stdout.take().expect("stdout 已设置为管道");
+    let reader = thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stdout.read_to_end(&mut buf);
+        buf
+    });
+
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child
+            .try_wait()
+            .map_err(|e| tr!("无法运行 '{}': {}", exe.display(), e))?
+        {
+            break Some(status);
+        }
+        if start.elapsed() > RUN_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    let _ = writer.join();
+    let stdout = reader.join().unwrap_or_default();
+    Ok(status.map(|s| (s, stdout)))
+}
+
+/// 时间在报告中以毫秒为单位，保留两位小数。
+fn ms<S: Serializer>(d: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64((d.as_secs_f64() * 100_000.0).round() / 100.0)
+}
+
+fn optional_ms<S: Serializer>(d: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+    match d {
+        Some(d) => ms(d, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn to_json(dir: &Path, results: &[GradeResult]) -> String {
+    let report = Report {
+        directory: dir.to_string_lossy().into_owned(),
+        total: results.len(),
+        passed: results.iter().filter(|r| r.status == "pass").count(),
+        files: results,
+    };
+    let mut json = serde_json::to_string_pretty(&report).expect("评分报告总能序列化为 JSON");
+    json.push('\n');
+    json
+}
@@ -0,0 +1,861 @@
+// src/i18n.rs
+
+//! **诊断信息的语言 (`--lang`)**
+//!
+//! 驱动程序的进度输出、错误和警告都通过 [`tr!`] 生成。`tr!` 的模板是消息在源码中的
+//! 原文（中文或英文），[`CATALOG`] 为每条消息同时记录中文和英文版本；输出时按当前语言
+//! 从表中取出对应的模板，再把参数依次填入 `{}` 占位符。
+//!
+//! 语言由驱动程序在启动时通过 [`set_lang`] 设置（`--lang`，默认根据 `LC_ALL`/
+//! `LC_MESSAGES`/`LANG` 推断）。没有设置语言时（例如单元测试中）消息保持源码中的原文。
+//!
+//! 语言是线程局部的：一次编译在一个线程中完成（`--interpret` 在单独的线程上执行程序时
+//! 通过 [`lang`] 把语言带过去），因此多个线程可以同时以不同的语言编译，互不影响。编译器中的其他全局状态：
+//! -   线程局部的：驱动程序的 `--quiet` 开关，以及 [`crate::diagnostics`] 中的诊断输出格式
+//!     (`--diagnostics-format json` 时的源文件)、`-Werror` 的设置和被提升的警告数量
+//! -   整个进程共享的：[`crate::common::Symbol`] 的驻留表（只增不减，由互斥锁保护）
+//!
+//! 其余状态都属于各个 pass 的实例。
+
+use std::cell::Cell;
+use std::fmt;
+
+/// 诊断信息使用的语言。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Lang {
+    En,
+    Zh,
+}
+
+impl Lang {
+    /// 根据 POSIX locale 环境变量推断语言：以 `zh` 开头时使用中文，否则使用英文。
+    pub fn from_locale() -> Lang {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty());
+        match locale {
+            Some(value) if value.starts_with("zh") => Lang::Zh,
+            _ => Lang::En,
+        }
+    }
+}
+
+thread_local! {
+    static LANG: Cell<Option<Lang>> = const { Cell::new(None) };
+}
+
+/// 设置当前线程（即当前编译会话）的诊断语言。
+pub fn set_lang(lang: Lang) {
+    LANG.with(|l| l.set(Some(lang)));
+}
+
+/// 当前线程的诊断语言；`None` 表示没有设置过（消息保持中文原文）。
+/// 编译会话把工作交给新线程时用它把语言带过去。
+pub fn lang() -> Option<Lang> {
+    LANG.with(Cell::get)
+}
+
+/// 按当前语言生成消息：查表取得模板，然后依次用 `args` 替换 `{}` 占位符。
+///
+/// 模板中只允许使用 `{}` 占位符，`{{` 和 `}}` 分别表示字面的 `{` 和 `}`。
+pub fn render(template: &'static str, args: &[&dyn fmt::Display]) -> String {
+    let template = match lang() {
+        Some(lang) => translate(template, lang),
+        None => template,
+    };
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('}')) => {
+                chars.next();
+                match args.next() {
+                    Some(arg) => out.push_str(&arg.to_string()),
+                    None => debug_assert!(false, "消息模板的参数不足: {}", template),
+                }
+            }
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// 在消息表中查找模板（中文或英文原文均可），返回指定语言的版本。
+/// 表中没有的模板原样返回。
+pub(crate) fn translate(template: &'static str, lang: Lang) -> &'static str {
+    CATALOG
+        .iter()
+        .find(|(zh, en)| *zh == template || *en == template)
+        .map(|&(zh, en)| match lang {
+            Lang::Zh => zh,
+            Lang::En => en,
+        })
+        .unwrap_or(template)
+}
+
+/// 生成一条按当前语言翻译的消息，用法与 `format!` 相同，但模板只支持 `{}` 占位符。
+macro_rules! tr {
+    ($template:literal $(, $arg:expr)* $(,)?) => {
+        $crate::i18n::render($template, &[$(&$arg as &dyn ::std::fmt::Display),*])
+    };
+}
+pub(crate) use tr;
+
+/// 消息表：`(中文, English)`。
+pub const CATALOG: &[(&str, &str)] = &[
+    // --- 驱动程序 ---
+    ("--- 自动清理 ---", "--- Cleanup ---"),
+    (
+        "   警告: 清理临时文件 {} 失败: {}",
+        "   warning: failed to remove temporary file {}: {}",
+    ),
+    ("   ✅ 已清理: {}", "   ✅ Removed: {}"),
+    ("\n❌ 编译失败: {}", "\n❌ Compilation failed: {}"),
+    ("输入文件不存在: {}", "input file does not exist: {}"),
+    (
+        "无法读取配置文件 '{}': {}",
+        "failed to read the configuration file '{}': {}",
+    ),
+    (
+        "配置文件 '{}' 有误: {}",
+        "invalid configuration file '{}': {}",
+    ),
+    (
+        "无法生成 man 手册页: {}",
+        "failed to generate the man page: {}",
+    ),
+    (
+        "无法写入标准输出: {}",
+        "failed to write to standard output: {}",
+    ),
+    (
+        "   警告: 输入文件 '{}' 可能不是一个C源文件 (.c)",
+        "   warning: input file '{}' may not be a C source file (.c)",
+    ),
+    ("\n--- 开始编译: {} ---", "\n--- Compiling: {} ---"),
+    (
+        "\n--lex: 词法分析完成，程序停止。",
+        "\n--lex: lexing finished, stopping.",
+    ),
+    (
+        "\n--parse: 语法分析完成，程序停止。",
+        "\n--parse: parsing finished, stopping.",
+    ),
+    (
+        "\n--validate: 语义分析完成, 程序停止。",
+        "\n--validate: semantic analysis finished, stopping.",
+    ),
+    (
+        "\n--tacky: IR 生成完成, 程序停止。",
+        "\n--tacky: IR generation finished, stopping.",
+    ),
+    (
+        "\n--codegen: 汇编 AST 生成完成, 程序停止。",
+        "\n--codegen: assembly AST generation finished, stopping.",
+    ),
+    (
+        "\n--emit-asm-stdout: 汇编代码如下:",
+        "\n--emit-asm-stdout: generated assembly:",
+    ),
+    ("\n-S: 保留汇编文件。", "\n-S: keeping the assembly file."),
+    (
+        "\n✅ 编译完成，生成目标文件: {}",
+        "\n✅ Compilation finished, object file: {}",
+    ),
+    (
+        "\n✅ 编译完成，生成可执行文件: {}",
+        "\n✅ Compilation finished, executable: {}",
+    ),
+    (
+        "\n✅ 编译并运行成功！",
+        "\n✅ Compiled and ran successfully!",
+    ),
+    ("(1) 预处理: {} -> {}", "(1) Preprocessing: {} -> {}"),
+    ("无法执行 gcc: {}", "failed to run gcc: {}"),
+    ("gcc 预处理失败", "gcc preprocessing failed"),
+    ("(1) 词法分析: {}", "(1) Lexing: {}"),
+    (
+        "   ✅ 预处理与词法分析完成，生成 {} 个 token。",
+        "   ✅ Preprocessing and lexing finished, {} tokens.",
+    ),
+    (
+        "(2) 语法分析 (输入 {} 个 token)...",
+        "(2) Parsing ({} tokens)...",
+    ),
+    (
+        "   ✅ 语法分析完成。打印 AST:",
+        "   ✅ Parsing finished. AST:",
+    ),
+    (
+        "(3.1) 语义分析：标识符解析...",
+        "(3.1) Semantic analysis: identifier resolution...",
+    ),
+    ("   警告: {}", "   warning: {}"),
+    ("警告: {}", "warning: {}"),
+    (
+        "   ✅ 标识符解析完成, 打印解析后的 AST:",
+        "   ✅ Identifier resolution finished. Resolved AST:",
+    ),
+    (
+        "(3.2) 语义分析：循环标记...",
+        "(3.2) Semantic analysis: loop labeling...",
+    ),
+    (
+        "   ✅ 循环标记完成, 打印标记后的 AST:",
+        "   ✅ Loop labeling finished. Labeled AST:",
+    ),
+    ("(3.3) 类型检查：...", "(3.3) Type checking..."),
+    (
+        "   ✅ 类型检查完成, 打印类型检查后的 AST:",
+        "   ✅ Type checking finished. Typed AST:",
+    ),
+    (
+        "(3.4) 查找未使用的 static 符号...",
+        "(3.4) Looking for unused static symbols...",
+    ),
+    (
+        "   ✅ 已删除 {} 个未使用的 static 符号: {}",
+        "   ✅ Removed {} unused static symbol(s): {}",
+    ),
+    ("(4) Tacky IR 生成...", "(4) Generating TACKY IR..."),
+    (
+        "   ✅ IR 生成完成。打印 Tacky IR:",
+        "   ✅ IR generation finished. TACKY IR:",
+    ),
+    (
+        "编译期求值纯函数调用",
+        "Evaluating pure function calls at compile time",
+    ),
+    (
+        "   ✅ 在编译期求值了 {} 个调用",
+        "   ✅ Evaluated {} call(s) at compile time",
+    ),
+    ("删除不可达函数", "Removing unreachable functions"),
+    ("重排基本块", "Reordering basic blocks"),
+    ("(4.{}) {}...", "(4.{}) {}..."),
+    (
+        "   ✅ 删除了 {} 条跳转指令",
+        "   ✅ Removed {} jump instruction(s)",
+    ),
+    (
+        "   ✅ 没有不可达的函数。",
+        "   ✅ No unreachable functions.",
+    ),
+    (
+        "   ✅ 已删除 {} 个函数: {}",
+        "   ✅ Removed {} function(s): {}",
+    ),
+    ("   警告: {}，跳过。", "   warning: {}, skipping."),
+    ("无法写入文件 '{}': {}", "cannot write file '{}': {}"),
+    ("   ✅ 已写入: {}", "   ✅ Wrote: {}"),
+    ("(5) 汇编 AST 生成...", "(5) Generating assembly AST..."),
+    (
+        "   ✅ 汇编 AST 生成完成。打印汇编 AST:",
+        "   ✅ Assembly AST generation finished. Assembly AST:",
+    ),
+    ("(6) 汇编代码发射 -> {}", "(6) Emitting assembly -> {}"),
+    ("   ✅ 汇编代码已生成。", "   ✅ Assembly emitted."),
+    ("(7a) 仅汇编: {} -> {}", "(7a) Assembling only: {} -> {}"),
+    ("gcc 汇编失败", "gcc assembling failed"),
+    ("   ✅ 汇编成功。", "   ✅ Assembled successfully."),
+    (
+        "(7b) 汇编与链接: {} -> {}",
+        "(7b) Assembling and linking: {} -> {}",
+    ),
+    ("gcc 汇编或链接失败", "gcc assembling or linking failed"),
+    (
+        "   ✅ 汇编与链接成功。",
+        "   ✅ Assembled and linked successfully.",
+    ),
+    (
+        "(8) 运行生成的可执行文件: {}",
+        "(8) Running the generated executable: {}",
+    ),
+    (
+        "无法运行生成的文件 '{}': {}",
+        "failed to run the generated file '{}': {}",
+    ),
+    (
+        "   ✅ 程序执行完毕，返回值为: {}",
+        "   ✅ Program exited with status: {}",
+    ),
+    (
+        "程序被信号终止，没有返回码。",
+        "the program was terminated by a signal and has no exit status.",
+    ),
+    // --- 基准测试 ---
+    (
+        "--bench-runs 必须大于 0",
+        "--bench-runs must be greater than 0",
+    ),
+    (
+        "在 {} 中没有找到任何 .c 工作负载",
+        "no .c workloads found in {}",
+    ),
+    (
+        "无法定位编译器自身: {}",
+        "cannot locate the compiler executable: {}",
+    ),
+    ("无法读取标准输入: {}", "failed to read standard input: {}"),
+    (
+        "无法创建临时目录: {}",
+        "cannot create temporary directory: {}",
+    ),
+    (
+        "--- 基准测试: {} 个工作负载, 每个运行 {} 次 ---",
+        "--- Benchmark: {} workload(s), {} run(s) each ---",
+    ),
+    ("无法读取 {}: {}", "cannot read {}: {}"),
+    (
+        "(2a) 插入语句覆盖率计数器...",
+        "(2a) Inserting statement coverage counters...",
+    ),
+    (
+        "   ✅ 插入了 {} 个计数器，语句位置表写入 {}",
+        "   ✅ Inserted {} counter(s), statement map written to {}",
+    ),
+    (
+        "找不到覆盖率数据 '{}'：先用 --coverage 编译并运行程序",
+        "coverage data '{}' not found: compile with --coverage and run the program first",
+    ),
+    (
+        "覆盖率数据 '{}' 格式错误: {}",
+        "malformed coverage data '{}': {}",
+    ),
+    (
+        "覆盖率数据与 '{}' 不一致：重新编译后需要重新运行程序",
+        "coverage data does not match '{}': run the program again after recompiling",
+    ),
+    ("执行过的行: {}% ({} / {})", "Lines executed: {}% ({} / {})"),
+    ("   正在测量: {}", "   Measuring: {}"),
+    ("无法复制源文件: {}", "cannot copy source file: {}"),
+    ("ccompiler 编译失败", "ccompiler compilation failed"),
+    ("gcc 链接失败", "gcc linking failed"),
+    ("gcc 编译失败", "gcc compilation failed"),
+    (
+        "退出码不一致: ccompiler 为 {}, gcc 为 {}",
+        "exit codes differ: ccompiler returned {}, gcc returned {}",
+    ),
+    ("无法运行 '{}': {}", "cannot run '{}': {}"),
+    ("'{}' 被信号终止", "'{}' was terminated by a signal"),
+    (
+        "编译时间统计 (ccompiler, 最小值 / 中位数):",
+        "Compile time (ccompiler, min / median):",
+    ),
+    // --- 批量评分 ---
+    (
+        "--grade 需要一个目录: {}",
+        "--grade requires a directory: {}",
+    ),
+    ("在 {} 中没有找到任何 .c 文件", "no .c files found in {}"),
+    ("--- 批量评分: {} 个文件 ---", "--- Grading: {} file(s) ---"),
+    (
+        "\n✅ 评分完成: {}/{} 通过，报告已写入 {}",
+        "\n✅ Grading finished: {}/{} passed, report written to {}",
+    ),
+    ("无法运行编译器: {}", "cannot run the compiler: {}"),
+    // --- TACKY 解释器 ---
+    ("(5) 解释执行 TACKY IR...", "(5) Interpreting TACKY IR..."),
+    (
+        "\n--interpret: main 返回 {}",
+        "\n--interpret: main returned {}",
+    ),
+    (
+        "--interpret 不支持调用外部函数 '{}'",
+        "--interpret does not support calling the external function '{}'",
+    ),
+    (
+        "运行时错误: 执行的指令数超过上限",
+        "runtime error: instruction limit exceeded",
+    ),
+    (
+        "运行时错误: 函数调用深度超过 {} 层（无限递归？）",
+        "runtime error: call depth exceeded {} (infinite recursion?)",
+    ),
+    (
+        "运行时错误: 函数 '{}' 需要 {} 个参数，但调用时传入了 {} 个",
+        "runtime error: function '{}' takes {} argument(s) but was called with {}",
+    ),
+    (
+        "运行时错误: 跳转目标 '{}' 不存在",
+        "runtime error: jump target '{}' does not exist",
+    ),
+    (
+        "运行时错误: 控制流到达函数 '{}' 的末尾",
+        "runtime error: control reached the end of function '{}'",
+    ),
+    ("运行时错误: 除以零", "runtime error: division by zero"),
+    (
+        "无法创建解释器线程: {}",
+        "failed to create the interpreter thread: {}",
+    ),
+    (
+        "运行时错误: 有符号除法溢出 (INT_MIN / -1)",
+        "runtime error: signed division overflow (INT_MIN / -1)",
+    ),
+    // --- 讲解页面 ---
+    ("源代码", "Source"),
+    ("Token", "Tokens"),
+    ("AST", "AST"),
+    ("TACKY IR", "TACKY IR"),
+    ("汇编", "Assembly"),
+    ("ccompiler 编译过程", "How ccompiler compiles this program"),
+    (
+        "\n--explain: 讲解页面已生成, 程序停止。",
+        "\n--explain: explanation page written, stopping.",
+    ),
+    // --- 差分测试 ---
+    (
+        "--- 差分测试: {} 个文件 ---",
+        "--- Differential testing: {} file(s) ---",
+    ),
+    (
+        "\n✅ 差分测试完成: {} 个文件的行为与 gcc 一致",
+        "\n✅ Differential testing finished: {} file(s) behave the same as gcc",
+    ),
+    (
+        "{} 个文件的行为与 gcc 不一致: {}",
+        "{} file(s) behave differently from gcc: {}",
+    ),
+    (
+        "{} 个文件无法完成比较: {}",
+        "{} file(s) could not be compared: {}",
+    ),
+    ("ccompiler 编译失败: {}", "ccompiler compilation failed: {}"),
+    (
+        "标准输出不一致: ccompiler 输出 {} 字节, gcc 输出 {} 字节",
+        "stdout differs: ccompiler wrote {} bytes, gcc wrote {} bytes",
+    ),
+    ("信号 {}", "signal {}"),
+    ("'{}' 运行超时", "'{}' timed out"),
+    // --- 诊断信息 ---
+    ("错误: {}", "error: {}"),
+    (
+        "{} 个警告被视为错误 (-Werror)",
+        "{} warning(s) treated as errors (-Werror)",
+    ),
+    // --- 监视模式 ---
+    (
+        "--- 监视 {}，文件修改后自动重新编译（按 Ctrl-C 退出）---",
+        "--- Watching {}; recompiling on change (press Ctrl-C to exit) ---",
+    ),
+    ("--- 编译: {} ---", "--- Compiling: {} ---"),
+    (
+        "❌ 编译失败，等待文件修改...",
+        "❌ Compilation failed, waiting for changes...",
+    ),
+    ("✅ 编译成功", "✅ Compiled"),
+    ("   程序返回 {}", "   program exited with {}"),
+    (
+        "   程序被信号 {} 终止",
+        "   program was terminated by signal {}",
+    ),
+    // --- AST 打印 ---
+    ("... (省略 {} 个节点)", "... ({} nodes elided)"),
+    // --- 词法分析 ---
+    (
+        "超出资源限制: token 数量超过上限 {} (--max-tokens)",
+        "resource limit exceeded: more than {} tokens (--max-tokens)",
+    ),
+    ("词法错误：意外的字符: {}", "Unexpected character: {}"),
+    (
+        "词法错误：标识符不能以数字开头: '{}{}'",
+        "Identifier cannot start with a number: '{}{}'",
+    ),
+    (
+        "词法错误：整数常量太大: '{}'",
+        "Integer constant is too large: '{}'",
+    ),
+    // --- 语法分析 ---
+    (
+        "超出资源限制: 语句或表达式嵌套深度超过上限 {} (--max-ast-depth)",
+        "resource limit exceeded: statement or expression nesting deeper than {} (--max-ast-depth)",
+    ),
+    (
+        "语法错误：输入意外结束。",
+        "Syntax Error: Unexpected end of input.",
+    ),
+    (
+        "语法错误：缺少标识符的名字。",
+        "Syntax Error: Expected a name for the identifier, but it was missing.",
+    ),
+    (
+        "语法错误：无效的类型说明符",
+        "Syntax Error: Invalid type specifier",
+    ),
+    (
+        "语法错误：'noreturn' 只能用于函数声明，不能用于 '{}'。",
+        "Syntax Error: 'noreturn' can only be applied to a function declaration, not to '{}'.",
+    ),
+    (
+        "语法错误：无效的存储类说明符",
+        "Syntax Error: Invalid storage class",
+    ),
+    (
+        "语法错误：应为参数类型，实际为 {}。",
+        "Syntax Error: Expected a parameter type, but got {}.",
+    ),
+    (
+        "语法错误：for 循环的初始化部分不允许函数声明。",
+        "Syntax Error: Function declaration is not allowed in a for-loop initializer.",
+    ),
+    (
+        "语法错误：'{}' 语句的条件中不能声明变量（这是 C++ 的写法），请在语句之前声明。",
+        "Syntax Error: A variable cannot be declared in the condition of a '{}' statement (that is C++); declare it before the statement.",
+    ),
+    (
+        "语法错误：调用 '{}' 的参数列表末尾多了一个逗号。",
+        "Syntax Error: Trailing comma in the argument list of call to '{}'.",
+    ),
+    (
+        "语法错误：缺少第 {} 个参数（调用 '{}'）。",
+        "Syntax Error: Missing argument {} in call to '{}'.",
+    ),
+    (
+        "语法错误：应为表达式，但输入已结束。",
+        "Syntax Error: Expected an expression, but found end of input.",
+    ),
+    (
+        "语法错误：应为表达式的开头（例如数字、变量或 '('），实际为 {}。",
+        "Syntax Error: Expected an expression prefix (like a number, variable, or '('), but found {}.",
+    ),
+    (
+        "语法错误：无效的数字格式: {}",
+        "Syntax Error: Invalid number format: {}",
+    ),
+    (
+        "语法错误：__builtin_offsetof 需要结构体类型，目前尚不支持。",
+        "Syntax Error: __builtin_offsetof requires struct types, which are not supported yet.",
+    ),
+    (
+        "内部错误：无法把 {} 转换为二元运算符。",
+        "Internal Error: Cannot convert {} to a binary operator.",
+    ),
+    (
+        "内部错误：无法把 {} 转换为一元运算符。",
+        "Internal Error: Cannot convert {} to a unary operator.",
+    ),
+    (
+        "语法错误：应为 {}，实际为 {}。",
+        "Syntax Error: Expected token {}, but got {}.",
+    ),
+    (
+        "语法错误：应为 {}，但输入已结束。",
+        "Syntax Error: Expected token {}, but the input stream ended.",
+    ),
+    // --- 语义分析 ---
+    (
+        "语义错误：'break' 语句不在循环或 switch 语句中。",
+        "Semantic Error: 'break' statement not in a loop or switch statement.",
+    ),
+    (
+        "语义错误：'continue' 语句不在循环中。",
+        "Semantic Error: 'continue' statement not in a loop.",
+    ),
+    (
+        "语义错误：case 标签的值不是整数常量表达式。",
+        "Semantic Error: case label does not reduce to an integer constant.",
+    ),
+    (
+        "语义错误：标签 '{}' 重复定义。",
+        "Semantic Error: duplicate label '{}'.",
+    ),
+    (
+        "语义错误：'{}' 标签不在 switch 语句中。",
+        "Semantic Error: '{}' label not within a switch statement.",
+    ),
+    (
+        "语义错误：块作用域内不允许 'static' 函数声明。",
+        "Semantic Error: 'static' function declaration is not allowed inside a block.",
+    ),
+    (
+        "语义错误：不允许嵌套的函数定义（函数 '{}'）。",
+        "Semantic Error: Nested function definitions are not allowed (function '{}').",
+    ),
+    (
+        "语义错误：'{}' 被重新声明为不同种类的符号。",
+        "Semantic Error: Redeclaration of '{}' as a different kind of symbol.",
+    ),
+    (
+        "语义错误：参数名 '{}' 在函数 '{}' 中重复。",
+        "Semantic Error: Duplicate parameter name '{}' in function '{}'.",
+    ),
+    (
+        "函数 '{}' 的参数 '{}' 未被使用",
+        "function '{}': parameter '{}' is never used",
+    ),
+    (
+        "语义错误：同一作用域中 '{}' 的声明冲突",
+        "Semantic Error: Conflicting declarations for '{}' in the same scope",
+    ),
+    (
+        "语义错误：表达式不可赋值（不是有效的左值）。",
+        "Semantic Error: Expression is not assignable (not a valid l-value).",
+    ),
+    (
+        "语义错误：使用了未声明的标识符 '{}'。",
+        "Semantic Error: Use of undeclared identifier '{}'.",
+    ),
+    (
+        "语义错误：被调用的对象 '{}' 不是函数。",
+        "Semantic Error: Called object '{}' is not a function.",
+    ),
+    (
+        "语义错误：调用了未声明的函数 '{}'。",
+        "Semantic Error: Call to undeclared function '{}'.",
+    ),
+    // --- 类型检查 ---
+    ("之前在 {} 处的声明", "the previous declaration at {}"),
+    ("之前的声明", "the previous declaration"),
+    (
+        "函数 '{}' 的声明不兼容：{} 处的声明有 {} 个参数，而{}有 {} 个参数",
+        "conflicting declarations of function '{}': the declaration at {} has {} parameter(s), but {} has {}",
+    ),
+    (
+        "函数 '{}' 的声明不兼容：{} 处的声明为 `{}`，而{}为 `{}`",
+        "conflicting declarations of function '{}': the declaration at {} is `{}`, but {} is `{}`",
+    ),
+    (
+        "函数定义不允许在块作用域内。",
+        "function definitions are not allowed at block scope.",
+    ),
+    (
+        "'{}' 被重新声明为不同类型的符号",
+        "'{}' redeclared as a different kind of symbol",
+    ),
+    (
+        "函数 '{}' 被声明为 noreturn，但包含 return 语句",
+        "function '{}' is declared noreturn but has a return statement",
+    ),
+    (
+        "建议加上花括号，明确 else 属于哪个 if",
+        "suggest explicit braces to avoid ambiguous 'else'",
+    ),
+    (
+        "控制流到达非 void 函数 '{}' 的末尾",
+        "control reaches end of non-void function '{}'",
+    ),
+    (
+        "函数 '{}' 被多次定义，上一次定义在 {}",
+        "function '{}' is defined more than once; previous definition at {}",
+    ),
+    (
+        "'{}' 的链接冲突：{} 处的声明具有{}链接，而 {} 处的第一次声明{}具有{}链接 (C11 6.2.2p7)",
+        "conflicting linkage for '{}': the declaration at {} has {} linkage, but the first declaration at {}{} has {} linkage (C11 6.2.2p7)",
+    ),
+    ("外部", "external"),
+    ("内部", "internal"),
+    ("（块作用域）", " (block scope)"),
+    (
+        "函数 '{}' 被重新声明为变量",
+        "function '{}' redeclared as a variable",
+    ),
+    (
+        "变量 '{}' 的声明类型冲突",
+        "conflicting types for variable '{}'",
+    ),
+    (
+        "文件作用域变量定义冲突",
+        "conflicting definitions of file-scope variable",
+    ),
+    (
+        "局部 extern 变量声明带有初始值",
+        "block-scope extern variable declaration has an initializer",
+    ),
+    (
+        "局部静态变量的初始值不是常量",
+        "initializer of a block-scope static variable is not a constant",
+    ),
+    (
+        "函数 '{}' 中的表达式语句没有副作用，其值未被使用",
+        "expression statement in function '{}' has no effect and its value is unused",
+    ),
+    (
+        "for 循环初始值设定项中不允许使用存储类说明符",
+        "storage class specifiers are not allowed in a for-loop initializer",
+    ),
+    (
+        "语义错误：函数 '{}' 被用作变量。",
+        "Semantic Error: function '{}' used as a variable.",
+    ),
+    (
+        "语义错误：变量 '{}' 被用作函数。",
+        "Semantic Error: variable '{}' used as a function.",
+    ),
+    (
+        "通过未指定参数的声明 `{}()` 调用函数，参数数量（{} 个）不会被检查",
+        "calling a function through the unprototyped declaration `{}()`: the number of arguments ({}) is not checked",
+    ),
+    (
+        "语义错误：在函数 '{}' 中调用 '{}' 时参数数量错误：'{}' 声明了 {} 个参数，实际传入 {} 个。",
+        "Semantic Error: in function '{}', call to '{}' has the wrong number of arguments: '{}' declares {} parameter(s), but {} were passed.",
+    ),
+    (
+        "常量表达式求值时发生整数溢出，结果为 {}",
+        "integer overflow in constant expression, result is {}",
+    ),
+    (
+        "语义错误：常量表达式中出现除以零。",
+        "Semantic Error: division by zero in constant expression.",
+    ),
+    (
+        "初始值不是常量表达式！",
+        "initializer is not a constant expression!",
+    ),
+    (
+        "语义错误：在同一作用域中重定义了变量 '{}'。",
+        "Semantic Error: redefinition of variable '{}' in the same scope.",
+    ),
+    (
+        "static 函数 '{}' 已定义但从未被使用",
+        "static function '{}' defined but not used",
+    ),
+    (
+        "static 变量 '{}' 已定义但从未被使用",
+        "static variable '{}' defined but not used",
+    ),
+    // --- 后端 ---
+    (
+        "-m32: 32 位目标的代码生成尚未实现（可以配合 --validate、--tacky 或 --interpret 使用）",
+        "-m32: code generation for 32-bit targets is not implemented yet (use it with --validate, --tacky or --interpret)",
+    ),
+    (
+        "内部编译器错误 (ICE): 函数 '{}' 的第 {} 条指令 `{}` 违反机器约束: {}",
+        "internal compiler error (ICE): in function '{}', instruction {} `{}` violates a machine constraint: {}",
+    ),
+    (
+        "内部编译器错误 (ICE): 函数 '{}' 中的 TACKY 标签 '{}' 重复",
+        "internal compiler error (ICE): in function '{}', duplicate TACKY label '{}'",
+    ),
+    (
+        "内部编译器错误 (ICE): 函数 '{}' 的第 {} 条 TACKY 指令 `{}` 不合法: {}",
+        "internal compiler error (ICE): in function '{}', TACKY instruction {} `{}` is malformed: {}",
+    ),
+    (
+        "位于 Return 之后，不可达",
+        "it follows a Return and is unreachable",
+    ),
+    ("跳转目标 '{}' 不存在", "jump target '{}' does not exist"),
+    ("目标操作数是常量", "the destination operand is a constant"),
+    (
+        "读取了未定义的变量 '{}'",
+        "it reads the undefined variable '{}'",
+    ),
+    (
+        "内部编译器错误 (ICE): 函数 '{}' 的循环标签有误: {}",
+        "internal compiler error (ICE): wrong loop labels in function '{}': {}",
+    ),
+    ("循环语句没有标签", "loop statement has no label"),
+    (
+        "内部编译器错误 (ICE): case 或 default 语句没有跳转标签，它可能不在 switch 语句中",
+        "internal compiler error (ICE): case or default statement has no jump label; it may not be inside a switch statement",
+    ),
+    (
+        "内部编译器错误 (ICE): 语句没有循环标签，循环标签解析可能没有运行",
+        "internal compiler error (ICE): statement has no loop label; did loop labeling run?",
+    ),
+    (
+        "内部编译器错误 (ICE): 短路运算符没有按短路求值生成代码",
+        "internal compiler error (ICE): a short-circuit operator was not lowered with short-circuit evaluation",
+    ),
+    ("循环标签 '{}' 重复", "duplicate loop label '{}'"),
+    ("'{}' 不在任何循环中", "'{}' is not inside any loop"),
+    ("'{}' 没有标签", "'{}' has no label"),
+    (
+        "'{}' 的标签 '{}' 属于外层循环，而不是最内层的循环 '{}'",
+        "'{}' is labeled '{}', an outer loop, instead of the innermost loop '{}'",
+    ),
+    (
+        "'{}' 的标签 '{}' 不属于任何包含它的循环（最内层的循环是 '{}'）",
+        "'{}' is labeled '{}', which is not an enclosing loop (the innermost loop is '{}')",
+    ),
+    ("   ✅ 循环标签检查通过。", "   ✅ Loop labels verified."),
+    (
+        "内部编译器错误 (ICE): 函数 '{}' 的栈帧违反约束: {}",
+        "internal compiler error (ICE): the stack frame of function '{}' violates a constraint: {}",
+    ),
+    (
+        "省略了栈帧，却通过 %rbp 访问栈",
+        "the frame pointer was omitted, but the stack is accessed through %rbp",
+    ),
+    (
+        "局部变量区 {} 字节超出了 {} 字节的红区",
+        "the {}-byte local area exceeds the {}-byte red zone",
+    ),
+    (
+        "使用红区的函数不能调用其他函数或移动 %rsp",
+        "a function that uses the red zone must not call other functions or move %rsp",
+    ),
+    (
+        "读取了被前面的 call 破坏的寄存器 {}",
+        "reads register {}, which was clobbered by a preceding call",
+    ),
+    (
+        "movzbl 的源操作数不能是立即数",
+        "the source operand of movzbl cannot be an immediate",
+    ),
+    (
+        "cmp 的第二个操作数不能是立即数",
+        "the second operand of cmp cannot be an immediate",
+    ),
+    (
+        "idiv 的操作数不能是立即数",
+        "the operand of idiv cannot be an immediate",
+    ),
+    (
+        "setcc 的操作数必须可按字节寻址",
+        "the operand of setcc must be byte-addressable",
+    ),
+    (
+        "栈调整量 {} 不是 8 的正整数倍",
+        "stack adjustment {} is not a positive multiple of 8",
+    ),
+    ("残留伪寄存器 '{}'", "leftover pseudo register '{}'"),
+    (
+        "立即数 {} 无法编码为 {} 字节操作数",
+        "immediate {} cannot be encoded as a {}-byte operand",
+    ),
+    (
+        "栈偏移 {} 未按 {} 字节对齐",
+        "stack offset {} is not aligned to {} bytes",
+    ),
+    (
+        "不允许两个内存操作数",
+        "two memory operands are not allowed",
+    ),
+    (
+        "目标操作数不能是立即数",
+        "the destination operand cannot be an immediate",
+    ),
+    (
+        "整数常量 {} 超出 32 位立即数的范围 [{}, {}]",
+        "integer constant {} is out of range for a 32-bit immediate [{}, {}]",
+    ),
+    (
+        "--gc-functions 需要以 main 作为根，但程序中没有定义 main",
+        "--gc-functions needs main as its root, but the program does not define main",
+    ),
+    ("无法创建文件: {}", "cannot create file: {}"),
+    ("--- 代码生成统计 ---", "--- Code generation statistics ---"),
+    (
+        "--- 支持的语言特性 ---",
+        "--- Supported language features ---",
+    ),
+    ("--- 栈使用报告 ---", "--- Stack usage report ---"),
+    ("无界 (递归)", "unbounded (recursion)"),
+    ("{} (不含外部函数)", "{} (excluding external functions)"),
+    (
+        "超出资源限制: 函数 '{}' 生成了 {} 条 TACKY 指令，超过上限 {} (--max-tacky-instructions)",
+        "resource limit exceeded: function '{}' generated {} TACKY instructions, more than {} (--max-tacky-instructions)",
+    ),
+    (
+        "不支持对非变量赋值。",
+        "Assignment to non-variable is not supported.",
+    ),
+];
@@ -0,0 +1,108 @@
+// src/i18n_tests.rs
+
+//! 消息表的一致性测试：源码中每个 `tr!` 模板都必须在 `CATALOG` 中有中英文两个版本，
+//! 且两个版本的占位符数量相同。
+
+use std::fs;
+use std::path::Path;
+
+use crate::i18n::{CATALOG, Lang, translate};
+
+fn placeholders(template: &str) -> usize {
+    template
+        .replace("{{", "")
+        .replace("}}", "")
+        .matches("{}")
+        .count()
+}
+
+/// 收集 `dir` 下所有 Rust 源文件中 `tr!(` 后面的第一个字符串字面量。
+fn collect_templates(dir: &Path, out: &mut Vec<(String, String)>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_templates(&path, out);
+            continue;
+        }
+        if path.extension().is_none_or(|e| e != "rs")
+            || path.ends_with("i18n.rs")
+            || path.ends_with("i18n_tests.rs")
+        {
+            continue;
+        }
+        let source = fs::read_to_string(&path).unwrap();
+        for (start, _) in source.match_indices("tr!(") {
+            // 跳过 `include_str!(` 之类以 `tr!(` 结尾的宏
+            if source[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_')
+            {
+                continue;
+            }
+            let rest = source[start + 4..].trim_start();
+            let Some(rest) = rest.strip_prefix('"') else {
+                continue;
+            };
+            let mut literal = String::new();
+            let mut chars = rest.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => match chars.next() {
+                        Some('n') => literal.push('\n'),
+                        Some(other) => literal.push(other),
+                        None => break,
+                    },
+                    _ => literal.push(c),
+                }
+            }
+            out.push((path.display().to_string(), literal));
+        }
+    }
+}
+
+#[test]
+fn every_template_is_in_the_catalog() {
+    let mut templates = Vec::new();
+    collect_templates(
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+        &mut templates,
+    );
+    assert!(!templates.is_empty());
+    for (file, template) in templates {
+        assert!(
+            CATALOG
+                .iter()
+                .any(|(zh, en)| *zh == template || *en == template),
+            "{} 中的消息没有加入 CATALOG: {:?}",
+            file,
+            template
+        );
+    }
+}
+
+#[test]
+fn catalog_entries_are_consistent() {
+    for (i, (zh, en)) in CATALOG.iter().enumerate() {
+        assert_eq!(
+            placeholders(zh),
+            placeholders(en),
+            "占位符数量不一致: {:?}",
+            zh
+        );
+        for (other_zh, other_en) in &CATALOG[i + 1..] {
+            assert_ne!(zh, other_zh, "重复的中文消息");
+            assert_ne!(en, other_en, "重复的英文消息");
+        }
+    }
+}
+
+#[test]
+fn translates_from_either_language() {
+    let en = "Syntax Error: Invalid type specifier";
+    let zh = "语法错误：无效的类型说明符";
+    assert_eq!(translate(en, Lang::Zh), zh);
+    assert_eq!(translate(zh, Lang::En), en);
+    assert_eq!(translate("不在表中的消息", Lang::En), "不在表中的消息");
+}
@@ -0,0 +1,132 @@
+// src/watch.rs
+
+//! **监视模式 (`--watch`)**
+//!
+//! 编译一次源文件，然后监视它以及它包含的头文件（由 `gcc -MM` 得到），任何一个文件被修改后
+//! 都重新编译，直到按下 Ctrl-C。每次编译都以 `-q` 运行本编译器的子进程，所以只打印诊断信息，
+//! 不打印各阶段的进度。加上 `--watch-run` 时，每次编译成功后运行生成的程序并报告退出码。
+//!
+//! 监视通过定期比较文件的修改时间实现，不依赖平台相关的文件系统通知。
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::os::unix::process::ExitStatusExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::difftest::RunInput;
+use crate::i18n::tr;
+
+/// 两次检查修改时间之间的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// 发现修改后再等一会儿，编辑器分几步写入文件时只重新编译一次
+const SETTLE_DELAY: Duration = Duration::from_millis(50);
+
+/// `--watch` 模式的入口。`compiler_args` 会原样传给每次编译；`run` 为 `Some` 时运行生成的程序。
+pub fn run_watch(
+    source: &Path,
+    compiler_args: &[String],
+    run: Option<&RunInput>,
+) -> Result<(), String> {
+    if !source.is_file() {
+        return Err(tr!("输入文件不存在: {}", source.display()));
+    }
+    let compiler = std::env::current_exe().map_err(|e| tr!("无法定位编译器自身: {}", e))?;
+    println!(
+        "{}",
+        tr!(
+            "--- 监视 {}，文件修改后自动重新编译（按 Ctrl-C 退出）---",
+            source.display()
+        )
+    );
+    loop {
+        // 在编译之前记录修改时间，编译期间发生的修改也会触发下一次编译
+        let watched = modification_times(&dependencies(source, compiler_args));
+        compile_and_run(&compiler, source, compiler_args, run)?;
+        while modification_times(&watched.keys().cloned().collect::<Vec<_>>()) == watched {
+            thread::sleep(POLL_INTERVAL);
+        }
+        thread::sleep(SETTLE_DELAY);
+    }
+}
+
+/// 编译一次，成功且需要时运行生成的程序。编译失败不是错误，等待下一次修改即可。
+fn compile_and_run(
+    compiler: &Path,
+    source: &Path,
+    compiler_args: &[String],
+    run: Option<&RunInput>,
+) -> Result<(), String> {
+    println!("\n{}", tr!("--- 编译: {} ---", source.display()));
+    let status = Command::new(compiler)
+        .arg(source)
+        .args(compiler_args)
+        .args(["-q", "--no-run"])
+        .status()
+        .map_err(|e| tr!("无法运行编译器: {}", e))?;
+    if !status.success() {
+        println!("{}", tr!("❌ 编译失败，等待文件修改..."));
+        return Ok(());
+    }
+    println!("{}", tr!("✅ 编译成功"));
+    let Some(input) = run else {
+        return Ok(());
+    };
+    let stdin = match input.stdin {
+        Some(path) => fs::File::open(path)
+            .map(Stdio::from)
+            .map_err(|e| tr!("无法读取 {}: {}", path.display(), e))?,
+        None => Stdio::null(),
+    };
+    let exe = source.with_extension("");
+    let status = Command::new(crate::runnable_path(&exe))
+        .args(input.args)
+        .stdin(stdin)
+        .status()
+        .map_err(|e| tr!("无法运行生成的文件 '{}': {}", exe.display(), e))?;
+    match (status.code(), status.signal()) {
+        (Some(code), _) => println!("{}", tr!("   程序返回 {}", code)),
+        (None, Some(signal)) => println!("{}", tr!("   程序被信号 {} 终止", signal)),
+        (None, None) => {}
+    }
+    Ok(())
+}
+
+/// 源文件和它包含的（非系统）头文件。`gcc -MM` 失败时（例如源文件有预处理错误）只监视源文件。
+fn dependencies(source: &Path, compiler_args: &[String]) -> Vec<PathBuf> {
+    let preprocessor_options = compiler_args
+        .iter()
+        .filter(|arg| ["-I", "-D", "-U"].iter().any(|flag| arg.starts_with(flag)));
+    let output = Command::new("gcc")
+        .arg("-MM")
+        .args(preprocessor_options)
+        .arg(source)
+        .stderr(Stdio::null())
+        .output();
+    let mut files = vec![source.to_path_buf()];
+    if let Ok(output) = output
+        && output.status.success()
+    {
+        // 输出形如 `main.o: main.c a.h \` 加上续行
+        let rule = String::from_utf8_lossy(&output.stdout);
+        let (_, prerequisites) = rule.split_once(':').unwrap_or_default();
+        files.extend(
+            prerequisites
+                .split_whitespace()
+                .filter(|f| *f != "\\")
+                .map(PathBuf::from)
+                .filter(|f| f != source),
+        );
+    }
+    files
+}
+
+/// 每个文件的修改时间；读取失败（例如文件正在被替换）时为 `None`。
+fn modification_times(files: &[PathBuf]) -> BTreeMap<PathBuf, Option<SystemTime>> {
+    files
+        .iter()
+        .map(|f| (f.clone(), fs::metadata(f).and_then(|m| m.modified()).ok()))
+        .collect()
+}
@@ -0,0 +1,7 @@
+int main(void) {
+  /* An extern declaration inside a block refers to the file-scope variable */
+  extern int i;
+  return i;
+}
+
+int i = 0;
@@ -0,0 +1,23 @@
+// tests/bool_conversion.rs
+
+//! `_Bool` 的转换：任何非零值（例如 256，它的低 8 位是 0）存入 `_Bool` 后都是 1。
+//! 初始化、赋值、参数和返回值都要转换，并且结果与 gcc 一致。静态变量的初始值在类型检查
+//! 时转换（见 `type_checking_tests.rs`）；代码生成还不支持静态变量，这里不涉及。
+
+mod common;
+
+use common::{WorkDir, difftest, difftest_result, exit_code, fixture};
+
+#[test]
+fn nonzero_values_become_one() {
+    let work_dir = WorkDir::new("bool");
+    let source = work_dir.copy_fixture("bool_conversion/bool.c");
+    assert_eq!(exit_code(&source, &[]), Some(1 + 2 + 8 + 16 + 32 + 64));
+}
+
+#[test]
+fn conversions_match_gcc() {
+    let output = difftest(&fixture("bool_conversion/bool.c"), &[]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(difftest_result(&output, "same", "bool.c"), "{:?}", output);
+}
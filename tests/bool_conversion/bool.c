@@ -0,0 +1,19 @@
+_Bool to_bool(int x) {
+    return x;
+}
+
+int count(_Bool b) {
+    return b;
+}
+
+int main(void) {
+    _Bool b = 256;
+    _Bool n = -1;
+    _Bool z = 0;
+    _Bool a;
+    a = 512;
+    _Bool c = 1;
+    c = c + 1;
+    /* 每个转换的结果占一位：除了 z 以外都必须是 1 */
+    return b + n * 2 + z * 4 + a * 8 + to_bool(1024) * 16 + count(256) * 32 + c * 64;
+}
@@ -0,0 +1,28 @@
+// tests/call_arguments.rs
+
+//! 调用参数先全部求值到临时变量中，再移入参数寄存器：参数中嵌套的调用不会覆盖前面
+//! 已经求值的参数，结果与 gcc 一致。
+
+mod common;
+
+use common::{WorkDir, difftest, difftest_result};
+
+const SOURCE: &str = "\
+int add(int a, int b) { return a + b; }
+int digits(int a, int b, int c) { return a * 100 + b * 10 + c; }
+int pick(_Bool first, int a, int b) { return first ? a : b; }
+int main(void) {
+    int x = 1;
+    _Bool b = 5;
+    return digits(add(x, 1), add(add(x, 2), 1), b) + pick(b, add(x, x), 9);
+}
+";
+
+#[test]
+fn nested_calls_do_not_clobber_earlier_arguments() {
+    let work_dir = WorkDir::new("call-args");
+    let source = work_dir.write("calls.c", SOURCE);
+    let output = difftest(&source, &[]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(difftest_result(&output, "same", "calls.c"), "{:?}", output);
+}
@@ -0,0 +1,26 @@
+// tests/cli_subcommands.rs
+
+//! `ccompiler completions <shell>` 和 `ccompiler man` 根据命令行定义生成输出，
+//! 不需要源文件。
+
+use std::process::Command;
+
+fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_ccompiler"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?} 失败", args);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn completions_and_man_page_list_the_flags() {
+    let bash = run(&["completions", "bash"]);
+    assert!(bash.contains("_ccompiler()"), "{}", bash);
+    assert!(bash.contains("--nqcc-compat"), "{}", bash);
+
+    let man = run(&["man"]);
+    assert!(man.contains(".TH ccompiler 1"), "{}", man);
+    assert!(man.contains("nqcc\\-compat"), "{}", man);
+}
@@ -0,0 +1,193 @@
+// tests/common/mod.rs
+
+//! 集成测试共用的辅助函数：临时工作目录、调用编译器、用 gcc 链接以及运行生成的程序。
+//!
+//! 每个测试文件通过 `mod common;` 引入，只用到其中的一部分。
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::Write;
+use std::os::unix::process::ExitStatusExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// 测试的临时工作目录 `<临时目录>/ccompiler-<名字>-<pid>`，离开作用域时（包括测试失败时）删除。
+pub struct WorkDir(PathBuf);
+
+impl WorkDir {
+    pub fn new(name: &str) -> WorkDir {
+        let path = std::env::temp_dir().join(format!("ccompiler-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        WorkDir(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    pub fn join(&self, name: impl AsRef<Path>) -> PathBuf {
+        self.0.join(name)
+    }
+
+    /// 把 `contents` 写入工作目录中的 `name`，返回它的路径。
+    pub fn write(&self, name: &str, contents: &str) -> PathBuf {
+        let path = self.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    /// 把 fixture `tests/<fixture>` 复制到工作目录中（只保留文件名），返回副本的路径。
+    pub fn copy_fixture(&self, fixture_path: &str) -> PathBuf {
+        let source = fixture(fixture_path);
+        let path = self.join(source.file_name().unwrap());
+        fs::copy(&source, &path).unwrap();
+        path
+    }
+}
+
+impl Drop for WorkDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// `tests/` 下的 fixture 的路径。
+pub fn fixture(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(path)
+}
+
+/// 调用编译器的命令，已经带上 `--lang en --no-config`，诊断信息与环境和配置文件无关。
+pub fn ccompiler() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_ccompiler"));
+    command.args(["--lang", "en", "--no-config"]);
+    command
+}
+
+/// 以 `-q -c` 和 `args` 把 `source` 编译成目标文件，返回目标文件的路径。
+pub fn compile(source: &Path, args: &[&str]) -> PathBuf {
+    let output = ccompiler()
+        .args(["-q", "-c"])
+        .args(args)
+        .arg(source)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "编译 {} 失败: {:?}",
+        source.display(),
+        output
+    );
+    source.with_extension("o")
+}
+
+/// 用 gcc 把 `objects` 链接成 `exe`。
+pub fn link(objects: &[&Path], exe: &Path) {
+    let status = Command::new("gcc")
+        .args(objects)
+        .arg("-o")
+        .arg(exe)
+        .status()
+        .unwrap();
+    assert!(status.success(), "链接 {} 失败", exe.display());
+}
+
+/// 编译 `source`（见 [`compile`]），用 gcc 把它和 `extra` 链接成与源文件同名的可执行文件，
+/// 返回可执行文件的路径。
+pub fn compile_and_link(source: &Path, args: &[&str], extra: &[&Path]) -> PathBuf {
+    let object = compile(source, args);
+    let exe = source.with_extension("");
+    link(&[&[object.as_path()][..], extra].concat(), &exe);
+    exe
+}
+
+/// 运行生成的程序的结果。
+#[derive(Debug)]
+pub struct Run {
+    pub code: Option<i32>,
+    /// 程序被信号终止时的信号编号
+    pub signal: Option<i32>,
+    pub stdout: String,
+}
+
+/// 以 `args` 为命令行参数、`stdin` 为标准输入运行 `exe`。
+pub fn run(exe: &Path, args: &[&str], stdin: &str) -> Run {
+    let mut child = Command::new(exe)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    Run {
+        code: output.status.code(),
+        signal: output.status.signal(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+    }
+}
+
+/// 编译、链接并不带参数地运行 `source`，返回程序的退出码。
+pub fn exit_code(source: &Path, args: &[&str]) -> Option<i32> {
+    run(&compile_and_link(source, args, &[]), &[], "").code
+}
+
+/// 以 `--difftest` 和 `args` 比较 `path`（文件或目录），返回编译器的输出。
+pub fn difftest(path: &Path, args: &[&str]) -> Output {
+    ccompiler()
+        .arg("--difftest")
+        .args(args)
+        .arg(path)
+        .output()
+        .unwrap()
+}
+
+/// `--difftest` 的输出中 `file` 的结果是否为 `status`（`same`、`FAILED` 等）。
+pub fn difftest_result(output: &Output, status: &str, file: &str) -> bool {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .any(|l| l.trim_start().starts_with(status) && l.ends_with(file))
+}
+
+/// 以 `--grade` 和 `args` 评分目录 `dir`，返回 JSON 报告。
+pub fn grade(dir: &Path, args: &[&str]) -> serde_json::Value {
+    // 同一个测试文件中的测试并行运行，每次评分使用单独的目录存放报告
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let work_dir = WorkDir::new(&format!("grade-{}", NEXT.fetch_add(1, Ordering::Relaxed)));
+    let report = work_dir.join("report.json");
+    let output = ccompiler()
+        .arg("--grade")
+        .arg(dir)
+        .arg("--grade-report")
+        .arg(&report)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    read_report(&report)
+}
+
+/// 读取 `--grade` 写出的 JSON 报告。
+pub fn read_report(path: &Path) -> serde_json::Value {
+    let json = fs::read_to_string(path).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+/// 报告中文件 `name` 的记录。
+pub fn graded_file<'a>(report: &'a serde_json::Value, name: &str) -> &'a serde_json::Value {
+    report["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["file"] == name)
+        .unwrap_or_else(|| panic!("报告中没有 {}: {}", name, report))
+}
@@ -0,0 +1,82 @@
+// tests/config_file.rs
+
+//! `ccompiler.toml`：从当前目录向上查找，其中的选项先于命令行参数解析，
+//! 只能取一个值的选项以命令行为准。
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use common::{WorkDir, graded_file, read_report, run};
+
+/// 在 `dir` 中编译 `main.c`。不使用 [`common::ccompiler`]，因为它带有 `--no-config`。
+fn compile(dir: &Path, args: &[&str]) -> bool {
+    Command::new(env!("CARGO_BIN_EXE_ccompiler"))
+        .current_dir(dir)
+        .args(args)
+        .arg("main.c")
+        .output()
+        .unwrap()
+        .status
+        .success()
+}
+
+#[test]
+fn config_file_supplies_defaults_and_command_line_wins() {
+    let work_dir = WorkDir::new("config");
+    let project = work_dir.join("src");
+    fs::create_dir_all(&project).unwrap();
+    fs::create_dir_all(work_dir.join("include")).unwrap();
+    fs::write(work_dir.join("include/base.h"), "#define BASE 5\n").unwrap();
+    fs::write(
+        project.join("main.c"),
+        "#include \"base.h\"\nint main(void) {\n    _Bool b = 1;\n    return BASE + RET + b;\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        work_dir.join("ccompiler.toml"),
+        "std = \"c89\"\ninclude = [\"include\"]\ndefine = [\"RET=10\"]\nflags = [\"-q\"]\n",
+    )
+    .unwrap();
+
+    // c89 中没有 `_Bool`
+    assert!(!compile(&project, &[]));
+    assert!(compile(&project, &["--std", "c17"]));
+    assert_eq!(run(&project.join("main"), &[], "").code, Some(16));
+
+    // 不读取配置文件时找不到 base.h
+    assert!(!compile(&project, &["--no-config", "--std", "c17"]));
+}
+
+#[test]
+fn config_file_options_reach_graded_compilations() {
+    let work_dir = WorkDir::new("config-grade");
+    let project = work_dir.join("src");
+    fs::create_dir_all(&project).unwrap();
+    fs::write(
+        project.join("int_min.c"),
+        "int main(void) {\n    int min = -2147483647 - 1;\n    return min / -1 == min ? 3 : 4;\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        work_dir.join("ccompiler.toml"),
+        "int-div-overflow = \"wrap\"\n",
+    )
+    .unwrap();
+
+    // 每次编译都使用 `--no-config`，配置文件中的选项必须由评分模式转发
+    let report = work_dir.join("report.json");
+    let output = Command::new(env!("CARGO_BIN_EXE_ccompiler"))
+        .current_dir(work_dir.path())
+        .args(["--lang", "en", "--grade", "src", "--grade-report"])
+        .arg(&report)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let report = read_report(&report);
+    let int_min = graded_file(&report, "int_min.c");
+    assert_eq!(int_min["status"], "pass", "{}", report);
+    assert_eq!(int_min["exit_code"], 3, "{}", report);
+}
@@ -0,0 +1,64 @@
+// tests/coverage.rs
+
+//! `--coverage`：程序退出时写出每条语句的执行次数，`cov-report` 把次数标注到源文件的每一行。
+
+mod common;
+
+use std::process::Command;
+
+use common::{WorkDir, ccompiler};
+
+const SOURCE: &str = "\
+int f(int n) {
+    int s = 0;
+    for (int i = 0; i < n; i = i + 1)
+        s = s + i;
+    if (s > 100)
+        return 1;
+    return s;
+}
+
+int main(void) {
+    return f(4);
+}
+";
+
+#[test]
+fn statement_counts_are_reported_per_line() {
+    let work_dir = WorkDir::new("coverage");
+    let source = work_dir.write("main.c", SOURCE);
+
+    let status = ccompiler()
+        .args(["-q", "--no-run", "--coverage"])
+        .arg(&source)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert!(work_dir.join("main.covmap").exists());
+    assert!(!work_dir.join("main.cov.c").exists());
+
+    // 从别的目录运行，计数仍然写到源文件旁边
+    let status = Command::new(work_dir.join("main"))
+        .current_dir(std::env::temp_dir())
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(6));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ccompiler"))
+        .env("LC_ALL", "C")
+        .args(["cov-report"])
+        .arg(&source)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let report = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines[1], "        -:    2:    int s = 0;");
+    assert_eq!(
+        lines[2],
+        "        1:    3:    for (int i = 0; i < n; i = i + 1)"
+    );
+    assert_eq!(lines[3], "        4:    4:        s = s + i;");
+    assert_eq!(lines[5], "    #####:    6:        return 1;");
+    assert_eq!(lines[12], "Lines executed: 83.33% (5 / 6)");
+}
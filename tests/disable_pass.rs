@@ -0,0 +1,82 @@
+// tests/disable_pass.rs
+
+//! `--disable-pass <名字>`：关闭的遍不再运行，其余的遍照常按顺序运行；未知的名字是命令行错误。
+
+mod common;
+
+use std::process::{Command, Output};
+
+use common::{WorkDir, ccompiler};
+
+const SOURCE: &str = "\
+int square(int x) { return x * x; }
+int unused(void) { return 1; }
+int add(int a, int b) { return a + b; }
+int main(void) {
+    int a = square(4);
+    if (a)
+        return add(a, 1);
+    return 0;
+}
+";
+
+#[test]
+fn disabled_passes_do_not_run() {
+    let work_dir = WorkDir::new("disable-pass");
+    let source = work_dir.write("main.c", SOURCE);
+
+    let compile =
+        |args: &[&str]| -> Output { ccompiler().args(args).arg(&source).output().unwrap() };
+    let passes = |output: &Output| -> Vec<String> {
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|l| l.starts_with("(4."))
+            .map(str::to_string)
+            .collect()
+    };
+
+    let optimize = [
+        "-fconstexpr-lite",
+        "-fblock-layout",
+        "--gc-functions",
+        "--tacky",
+    ];
+    let output = compile(&optimize);
+    assert!(output.status.success());
+    assert_eq!(
+        passes(&output),
+        [
+            "(4.1) Evaluating pure function calls at compile time...",
+            "(4.2) Removing unreachable functions...",
+            "(4.3) Reordering basic blocks...",
+        ]
+    );
+
+    let output = compile(&[&optimize[..], &["--disable-pass", "constexpr-lite"]].concat());
+    assert!(output.status.success());
+    assert_eq!(
+        passes(&output),
+        [
+            "(4.1) Removing unreachable functions...",
+            "(4.2) Reordering basic blocks...",
+        ]
+    );
+
+    // 关闭内置的遍不改变程序的结果
+    let output = compile(&[
+        "-q",
+        "--no-run",
+        "--disable-pass",
+        "reuse-temps",
+        "--disable-pass",
+        "params-in-registers",
+    ]);
+    assert!(output.status.success());
+    let status = Command::new(work_dir.join("main")).status().unwrap();
+    assert_eq!(status.code(), Some(17));
+
+    let output = compile(&["--disable-pass", "no-such-pass"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("unknown pass 'no-such-pass'"), "{}", stderr);
+}
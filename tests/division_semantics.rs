@@ -0,0 +1,66 @@
+// tests/division_semantics.rs
+
+//! 有符号除法和取余的语义：向零截断，以及 `--int-div-overflow` 对 `INT_MIN / -1`、
+//! `INT_MIN % -1` 的两种处理方式。
+
+mod common;
+
+use common::{Run, WorkDir, compile_and_link, fixture, grade, graded_file, run};
+
+/// Linux 上整数除法异常对应的信号
+const SIGFPE: i32 = 8;
+
+/// 用给定的 `--int-div-overflow` 模式编译并链接 fixture，运行并返回结果。
+fn compile_and_run(name: &str, mode: &str) -> Run {
+    let work_dir = WorkDir::new(&format!("division-{}-{}", name, mode));
+    let source = work_dir.copy_fixture(&format!("division_semantics/{}.c", name));
+    let exe = compile_and_link(&source, &["--int-div-overflow", mode], &[]);
+    run(&exe, &[], "")
+}
+
+#[test]
+fn division_truncates_toward_zero() {
+    for mode in ["trap", "wrap"] {
+        assert_eq!(
+            compile_and_run("truncation", mode).code,
+            Some(0),
+            "{}",
+            mode
+        );
+    }
+}
+
+#[test]
+fn int_min_by_minus_one_traps_by_default() {
+    assert_eq!(compile_and_run("int_min", "trap").signal, Some(SIGFPE));
+}
+
+#[test]
+fn int_min_by_minus_one_wraps_on_request() {
+    assert_eq!(compile_and_run("int_min", "wrap").code, Some(0));
+}
+
+#[test]
+fn grading_uses_the_requested_division_mode() {
+    let fixture_dir = fixture("division_semantics");
+    let trap = grade(&fixture_dir, &[]);
+    assert_eq!(
+        graded_file(&trap, "int_min.c")["status"],
+        "runtime_error",
+        "{}",
+        trap
+    );
+    let wrap = grade(&fixture_dir, &["--int-div-overflow", "wrap"]);
+    assert_eq!(
+        graded_file(&wrap, "int_min.c")["status"],
+        "pass",
+        "{}",
+        wrap
+    );
+    assert_eq!(
+        graded_file(&wrap, "truncation.c")["status"],
+        "pass",
+        "{}",
+        wrap
+    );
+}
@@ -0,0 +1,16 @@
+int quotient(int a, int b) {
+    return a / b;
+}
+
+int rem(int a, int b) {
+    return a % b;
+}
+
+int main(void) {
+    int min = -2147483647 - 1;
+    if (rem(min, -1) != 0) return 1;
+    if (quotient(min, -1) != min) return 2;
+    if (min % -1 != 0) return 3;
+    if (min / -1 != min) return 4;
+    return 0;
+}
@@ -0,0 +1,17 @@
+int check(int a, int b, int q, int r) {
+    return a / b == q && a % b == r && q * b + r == a;
+}
+
+int main(void) {
+    int min = -2147483647 - 1;
+    if (!check(7, 2, 3, 1)) return 1;
+    if (!check(-7, 2, -3, -1)) return 2;
+    if (!check(7, -2, -3, 1)) return 3;
+    if (!check(-7, -2, 3, -1)) return 4;
+    if (!check(min, 1, min, 0)) return 5;
+    if (!check(min, 2, -1073741824, 0)) return 6;
+    if (!check(min, 3, -715827882, -2)) return 7;
+    if (!check(5, -1, -5, 0)) return 8;
+    if (!check(0, -3, 0, 0)) return 9;
+    return 0;
+}
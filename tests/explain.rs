@@ -0,0 +1,54 @@
+// tests/explain.rs
+
+//! `--explain <文件>`：生成一个自包含的 HTML 页面，把源代码、token、AST、TACKY 和汇编
+//! 按源代码行和 TACKY 指令互相关联。
+
+mod common;
+
+use std::fs;
+
+use common::{WorkDir, ccompiler};
+
+const SOURCE: &str =
+    "int add(int a, int b) {\n    return a + b;\n}\nint main(void) { return add(1, 2) < 4; }\n";
+
+#[test]
+fn explain_writes_a_self_contained_page() {
+    let work_dir = WorkDir::new("explain");
+    let source = work_dir.write("main.c", SOURCE);
+    let page = work_dir.join("out.html");
+
+    let output = ccompiler()
+        .args(["-q", "--explain"])
+        .arg(&page)
+        .arg(&source)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let html = fs::read_to_string(&page).unwrap();
+
+    // 样式和脚本都内联在页面中，不引用任何外部资源
+    assert!(html.starts_with("<!DOCTYPE html>"), "{}", html);
+    assert_eq!(html.matches("<style>").count(), 1);
+    assert_eq!(html.matches("<script>").count(), 1);
+    assert!(
+        !html.contains("src=") && !html.contains("href="),
+        "{}",
+        html
+    );
+
+    for title in ["Source", "Tokens", "AST", "TACKY IR", "Assembly"] {
+        assert!(html.contains(&format!("<h2>{}</h2>", title)), "{}", title);
+    }
+    // 源代码按行编号并做了 HTML 转义
+    assert!(html.contains("<div data-line=\"2\">"));
+    assert!(html.contains("return add(1, 2) &lt; 4;"));
+    // 每个函数在 TACKY 和汇编中各有一个标题，汇编指令链接回生成它的 TACKY 指令
+    assert!(html.contains("data-fn=\"add\""));
+    assert!(html.contains("data-fn=\"main\""));
+    assert!(html.contains("<div data-tacky=\"main:1\">    main.tmp1 = main.tmp0 &lt; 4</div>"));
+    assert!(html.contains("data-tacky=\"main:1\">    setl %al<"));
+
+    // 只生成页面，不生成可执行文件
+    assert!(!work_dir.join("main").exists());
+}
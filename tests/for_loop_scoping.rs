@@ -0,0 +1,15 @@
+// tests/for_loop_scoping.rs
+
+//! `for` 循环的作用域和 `continue`：初始化作用域包住条件、post 表达式和循环体，
+//! 循环体有自己的嵌套作用域，`continue` 跳到 post 表达式。
+
+mod common;
+
+use common::{WorkDir, exit_code};
+
+#[test]
+fn continue_runs_post_expression_with_shadowed_loop_variable() {
+    let work_dir = WorkDir::new("for");
+    let source = work_dir.copy_fixture("for_loop_scoping/main.c");
+    assert_eq!(exit_code(&source, &[]), Some(57));
+}
@@ -0,0 +1,20 @@
+/* 从不调用：只要求能通过语义分析。循环体中的 i 遮蔽初始化中的 i。 */
+int spin(void) {
+    for (int i = 0;;) {
+        int i = 1;
+        continue;
+    }
+    return 0;
+}
+
+int main(void) {
+    int count = 0;
+    for (int i = 0; i < 5; i = i + 1) {
+        int i = 100; /* 遮蔽初始化中的 i，条件和 post 表达式仍使用外层的 i */
+        count = count + i / 100;
+        continue; /* 跳到 post 表达式，而不是跳过它 */
+        count = count + 1000;
+    }
+    int i = 7; /* for 的初始化作用域已经结束，可以再次声明 i */
+    return count * 10 + i;
+}
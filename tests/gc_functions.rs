@@ -0,0 +1,51 @@
+// tests/gc_functions.rs
+
+//! `--gc-functions`：从 `main` 和显式 `extern` 的函数出发不可达的函数不出现在汇编中，
+//! 只通过其他函数间接可达的函数保留。
+
+mod common;
+
+use common::{WorkDir, ccompiler, exit_code};
+
+const SOURCE: &str = "\
+int leaf(int x) { return x + 1; }
+int middle(int x) { return leaf(x) * 2; }
+int orphan(int x) { return leaf(x); }
+static int dead(void) { return orphan(1); }
+extern int api(void) { return 7; }
+int main(void) { return middle(20); }
+";
+
+#[test]
+fn unreachable_functions_are_not_emitted() {
+    let work_dir = WorkDir::new("gc");
+    let source = work_dir.write("main.c", SOURCE);
+
+    let functions = |args: &[&str]| -> Vec<String> {
+        let output = ccompiler()
+            .args(["-q", "--emit-asm-stdout"])
+            .args(args)
+            .arg(&source)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|l| l.strip_suffix(':'))
+            .filter(|l| !l.starts_with('.'))
+            .map(str::to_string)
+            .collect()
+    };
+    assert_eq!(
+        functions(&[]),
+        ["leaf", "middle", "orphan", "dead", "api", "main"]
+    );
+    // `leaf` 只通过 `middle` 可达；`dead` 调用 `orphan` 不会让它们变得可达
+    assert_eq!(
+        functions(&["--gc-functions"]),
+        ["leaf", "middle", "api", "main"]
+    );
+
+    // 删除之后程序仍然可以链接和运行
+    assert_eq!(exit_code(&source, &["--gc-functions"]), Some(42));
+}
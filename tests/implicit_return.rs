@@ -0,0 +1,38 @@
+// tests/implicit_return.rs
+
+//! 控制流到达函数末尾：`main` 隐式返回 0 且不产生警告，其他函数同样返回 0，
+//! 开启 `-Wreturn-type` 时给出警告。
+
+mod common;
+
+use common::{WorkDir, ccompiler, run};
+
+#[test]
+fn empty_main_returns_zero_and_only_other_functions_warn() {
+    let work_dir = WorkDir::new("implicit-return");
+    let compile = |source: &str| -> String {
+        let path = work_dir.write("main.c", source);
+        let output = ccompiler()
+            .args(["-q", "--no-run", "-Wreturn-type"])
+            .arg(&path)
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(output.status.success(), "{}", stderr);
+        assert_eq!(run(&work_dir.join("main"), &[], "").code, Some(0));
+        stderr
+    };
+
+    let stderr = compile("int main(void) {}\n");
+    assert!(!stderr.contains("-Wreturn-type"), "{}", stderr);
+
+    let stderr = compile(
+        "int f(int x) {\n    if (x)\n        return 7;\n}\n\nint main(void) {\n    f(0);\n}\n",
+    );
+    assert_eq!(stderr.matches("[-Wreturn-type]").count(), 1, "{}", stderr);
+    assert!(
+        stderr.contains("control reaches end of non-void function 'f'"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,81 @@
+// tests/interpreter.rs
+
+//! `--interpret`：直接执行 TACKY IR 得到的退出码与本地编译运行的结果一致。
+//! 复用其他集成测试的 fixture。
+
+mod common;
+
+use std::path::Path;
+use std::process::Output;
+
+use common::{WorkDir, ccompiler};
+
+/// 用 `--interpret` 运行 `source`，返回编译器的输出。
+fn interpret(source: &Path, extra_args: &[&str]) -> Output {
+    ccompiler()
+        .args(["-q", "--interpret"])
+        .args(extra_args)
+        .arg(source)
+        .output()
+        .unwrap()
+}
+
+/// 用 `--interpret` 运行 fixture，返回退出码。
+fn interpret_fixture(fixture: &str, extra_args: &[&str]) -> Option<i32> {
+    let work_dir = WorkDir::new(&format!("interpret-{}", fixture.replace('/', "-")));
+    let source = work_dir.copy_fixture(fixture);
+    interpret(&source, extra_args).status.code()
+}
+
+#[test]
+fn interpreter_matches_native_exit_codes() {
+    assert_eq!(interpret_fixture("for_loop_scoping/main.c", &[]), Some(57));
+    assert_eq!(
+        interpret_fixture("division_semantics/truncation.c", &[]),
+        Some(0)
+    );
+    assert_eq!(
+        interpret_fixture(
+            "division_semantics/int_min.c",
+            &["--int-div-overflow", "wrap"]
+        ),
+        Some(0)
+    );
+}
+
+#[test]
+fn interpreter_reports_division_overflow_as_error() {
+    assert_eq!(
+        interpret_fixture("division_semantics/int_min.c", &[]),
+        Some(1)
+    );
+}
+
+#[test]
+fn deep_recursion_is_limited_by_call_depth() {
+    let work_dir = WorkDir::new("interpret-recursion");
+
+    // 接近上限的递归可以正常执行
+    let source = work_dir.write(
+        "deep.c",
+        "int d(int n) { return n == 0 ? 0 : 1 + d(n - 1); }\n\
+         int main(void) { return d(9990) - 9990 + 7; }\n",
+    );
+    let output = interpret(&source, &[]);
+    assert_eq!(output.status.code(), Some(7), "{:?}", output);
+
+    // 无限递归报告为运行时错误，而不是让解释器栈溢出
+    let source = work_dir.write(
+        "infinite.c",
+        "int f(int n) { return f(n); }\nint main(void) { return f(1); }\n",
+    );
+    let output = interpret(&source, &[]);
+    assert_eq!(output.status.code(), Some(1), "{:?}", output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("runtime error: call depth exceeded 10000"),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("Compilation failed"), "{}", stderr);
+}
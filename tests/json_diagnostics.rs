@@ -0,0 +1,80 @@
+// tests/json_diagnostics.rs
+
+//! `--diagnostics-format json`：标准输出中只有诊断信息，每条一行 JSON。
+
+mod common;
+
+use std::process::Output;
+
+use common::{WorkDir, ccompiler};
+use serde_json::{Value, json};
+
+fn compile(work_dir: &WorkDir, lang: &str, name: &str, source: &str) -> Output {
+    let path = work_dir.write(name, source);
+    // 后出现的 `--lang` 生效
+    ccompiler()
+        .args([
+            "--lang",
+            lang,
+            "--diagnostics-format",
+            "json",
+            "-Wunused-parameter",
+        ])
+        .arg(&path)
+        .output()
+        .unwrap()
+}
+
+fn records(output: &Output) -> Vec<Value> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|e| panic!("{}: {}", e, line)))
+        .collect()
+}
+
+#[test]
+fn warnings_and_errors_are_json_lines() {
+    let work_dir = WorkDir::new("json-diag");
+
+    let output = compile(
+        &work_dir,
+        "en",
+        "warn.c",
+        "int f(int unused) {\n    return 1;\n}\nint main(void) { return f(2); }\n",
+    );
+    assert!(output.status.success());
+    let warnings = records(&output);
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert!(warnings[0]["file"].as_str().unwrap().ends_with("warn.c"));
+    assert_eq!(warnings[0]["severity"], "warning");
+    assert_eq!(warnings[0]["code"], "unused-parameter");
+    assert_eq!(
+        warnings[0]["range"]["start"],
+        json!({"line": 1, "column": 5})
+    );
+    assert!(
+        !warnings[0]["message"].as_str().unwrap().contains("[-W"),
+        "{:?}",
+        warnings
+    );
+
+    // 语言不影响代码；恢复后报告的每个语法错误都是单独的一条记录，并且带有位置
+    for lang in ["en", "zh"] {
+        let output = compile(
+            &work_dir,
+            lang,
+            "error.c",
+            "int main(void) {\n    int a = 1 +;\n    return a +;\n}\n",
+        );
+        assert_eq!(output.status.code(), Some(1));
+        assert!(output.stderr.is_empty());
+        let errors = records(&output);
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        for (error, line) in errors.iter().zip([2, 3]) {
+            assert_eq!(error["severity"], "error");
+            assert_eq!(error["code"], "syntax-error");
+            assert_eq!(error["range"]["start"]["line"], line, "{:?}", error);
+            assert_eq!(error["notes"], json!([]));
+        }
+    }
+}
@@ -0,0 +1,66 @@
+// tests/libc_calls.rs
+
+//! 调用 libc 函数：源文件自己给出 `putchar`/`getchar`/`exit` 的原型，
+//! 这些函数在本编译单元中没有定义，生成的 `call` 要经过 PLT 才能和共享的 libc 链接。
+//! （`exit` 声明为返回 `int`，因为目前还不支持 `void` 返回类型。）
+
+mod common;
+
+use std::fs;
+use std::process::Command;
+
+use common::{WorkDir, run};
+
+const SOURCE: &str = "\
+int putchar(int c);
+int getchar(void);
+int exit(int status);
+
+int main(void) {
+    int c = getchar();
+    while (c != -1) {
+        if (c == 113)
+            exit(7);
+        putchar(c + 1);
+        c = getchar();
+    }
+    putchar(10);
+    return 0;
+}
+";
+
+#[test]
+fn putchar_getchar_and_exit() {
+    let work_dir = WorkDir::new("libc");
+    let source = work_dir.write("io.c", SOURCE);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ccompiler"))
+        .arg("--nqcc-compat")
+        .arg("-S")
+        .arg(&source)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "编译失败");
+    let asm = fs::read_to_string(work_dir.join("io.s")).unwrap();
+    for name in ["putchar", "getchar", "exit"] {
+        assert!(
+            asm.contains(&format!("call {}@PLT", name)),
+            "{} 应通过 PLT 调用",
+            name
+        );
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_ccompiler"))
+        .arg("--nqcc-compat")
+        .arg(&source)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "编译失败");
+
+    let exe = work_dir.join("io");
+    let result = run(&exe, &[], "HAL");
+    assert_eq!((result.code, result.stdout.as_str()), (Some(0), "IBM\n"));
+    // exit 会刷新 stdout 的缓冲区。
+    let result = run(&exe, &[], "abqz");
+    assert_eq!((result.code, result.stdout.as_str()), (Some(7), "bc"));
+}
@@ -0,0 +1,39 @@
+// tests/local_includes.rs
+
+//! `--grade` 和 `--difftest` 在临时目录中编译源文件的副本，`#include "value.h"` 仍然要找到
+//! 源文件旁边的头文件。`broken.c` 有语法错误，它不能让其余的文件被跳过。
+
+mod common;
+
+use common::{difftest, difftest_result, fixture, grade, graded_file};
+
+#[test]
+fn graded_files_include_headers_next_to_them() {
+    let report = grade(&fixture("local_includes"), &[]);
+    let main = graded_file(&report, "main.c");
+    assert_eq!(main["status"], "pass", "{}", report);
+    assert_eq!(main["exit_code"], 42, "{}", report);
+
+    // 诊断信息是编译器输出的结构化记录，`file` 指向被评分的文件而不是临时副本
+    let broken = graded_file(&report, "broken.c");
+    assert_eq!(broken["status"], "compile_error", "{}", report);
+    let diagnostic = &broken["diagnostics"][0];
+    assert_eq!(diagnostic["file"], "broken.c", "{}", report);
+    assert_eq!(diagnostic["code"], "syntax-error", "{}", report);
+    assert_eq!(diagnostic["range"]["start"]["line"], 3, "{}", report);
+}
+
+#[test]
+fn difftest_reports_failures_and_compares_the_rest() {
+    let output = difftest(&fixture("local_includes"), &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{}{}", stdout, stderr);
+    assert!(difftest_result(&output, "FAILED", "broken.c"), "{}", stdout);
+    assert!(difftest_result(&output, "same", "main.c"), "{}", stdout);
+    assert!(
+        stderr.contains("1 file(s) could not be compared") && stderr.contains("broken.c"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,50 @@
+// tests/macro_options.rs
+
+//! `-D`/`-U` 按命令行中出现的顺序传给预处理器，后出现的生效；`--difftest` 把它们同样
+//! 传给 gcc 参考编译。
+
+mod common;
+
+use common::{WorkDir, difftest, difftest_result, exit_code};
+
+const SOURCE: &str = "\
+#ifndef VALUE
+#define VALUE 1
+#endif
+int main(void) {
+#ifdef EXTRA
+    return VALUE + EXTRA;
+#else
+    return VALUE;
+#endif
+}
+";
+
+#[test]
+fn defines_and_undefines_apply_in_order() {
+    let work_dir = WorkDir::new("macros");
+    let source = work_dir.write("main.c", SOURCE);
+
+    let cases: [(&[&str], i32); 6] = [
+        (&[], 1),
+        (&["-DVALUE=7"], 7),
+        (&["-DVALUE=7", "-DEXTRA=30"], 37),
+        // `-DNAME` 不带值时定义为 1
+        (&["-DEXTRA"], 2),
+        (&["-DVALUE=7", "-UVALUE"], 1),
+        (&["-UVALUE", "-DVALUE=7"], 7),
+    ];
+    for (args, expected) in cases {
+        assert_eq!(exit_code(&source, args), Some(expected), "{:?}", args);
+    }
+
+    // 没有 `-D` 时 gcc 参考编译会失败，所以结果相同说明两边都收到了宏定义
+    let needs_define = work_dir.write("needs_define.c", "int main(void) { return VALUE; }\n");
+    let output = difftest(&needs_define, &["-DVALUE=9"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(
+        difftest_result(&output, "same", "needs_define.c"),
+        "{:?}",
+        output
+    );
+}
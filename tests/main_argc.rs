@@ -0,0 +1,17 @@
+// tests/main_argc.rs
+
+//! `int main(int argc)`：argc 由启动代码放在 %edi 中，和普通的 int 参数一样读取。
+//! （`char **argv` 需要指针类型，目前尚不支持。）
+
+mod common;
+
+use common::{WorkDir, compile_and_link, run};
+
+#[test]
+fn main_returns_argc() {
+    let work_dir = WorkDir::new("argc");
+    let source = work_dir.write("main.c", "int main(int argc) {\n    return argc;\n}\n");
+    let exe = compile_and_link(&source, &[], &[]);
+    assert_eq!(run(&exe, &[], "").code, Some(1));
+    assert_eq!(run(&exe, &["a", "b", "c"], "").code, Some(4));
+}
@@ -0,0 +1,36 @@
+// tests/nqcc_compat.rs
+
+//! `--nqcc-compat`：官方测试套件期望编译器不输出任何进度信息，`-S` 只生成汇编文件，
+//! 链接后不运行生成的程序。
+
+mod common;
+
+use std::process::Command;
+
+use common::WorkDir;
+
+#[test]
+fn compat_mode_is_silent_and_does_not_run_the_program() {
+    let work_dir = WorkDir::new("nqcc");
+    let source = work_dir.write("main.c", "int main(void) { return 3; }\n");
+
+    let compile = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_ccompiler"))
+            .arg("--nqcc-compat")
+            .args(args)
+            .arg(&source)
+            .output()
+            .unwrap()
+    };
+
+    let output = compile(&["-S"]);
+    assert!(output.status.success(), "编译失败");
+    assert!(output.stdout.is_empty(), "兼容模式不应输出进度信息");
+    assert!(work_dir.join("main.s").exists());
+    assert!(!work_dir.join("main").exists(), "-S 不应链接");
+
+    let output = compile(&[]);
+    assert!(output.status.success(), "编译失败");
+    assert!(output.stdout.is_empty(), "兼容模式不应运行生成的程序");
+    assert!(work_dir.join("main").exists());
+}
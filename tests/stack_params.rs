@@ -0,0 +1,27 @@
+// tests/stack_params.rs
+
+//! 超过 6 个参数的函数：第 7 个及以后的参数通过栈传递。`main.c` 由本编译器编译，
+//! `check.c` 由 gcc 编译，两边互相调用 7 到 10 个参数的函数，检查参数的值和调用时
+//! 16 字节的栈对齐（栈参数个数为奇数时调用者需要填充）。
+
+mod common;
+
+use common::{WorkDir, compile_and_link, fixture, run};
+
+#[test]
+fn stack_parameters_interoperate_with_gcc() {
+    let work_dir = WorkDir::new("stack-params");
+    let source = work_dir.copy_fixture("stack_params/main.c");
+    let check = fixture("stack_params/check.c");
+
+    // 参数保存在寄存器中和栈帧中、有没有红区时，栈参数的偏移和填充都必须正确
+    for options in [
+        &[][..],
+        &["--disable-pass", "params-in-registers"],
+        &["-mno-red-zone"],
+    ] {
+        let exe = compile_and_link(&source, options, &[&check]);
+        // 返回值是检查出的错误个数
+        assert_eq!(run(&exe, &[], "").code, Some(0), "{:?}", options);
+    }
+}
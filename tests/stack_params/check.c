@@ -0,0 +1,30 @@
+// 用 gcc 编译：检查参数的值和调用时 16 字节的栈对齐 (System V AMD64 ABI 3.2.2)。
+
+int sum10(int a, int b, int c, int d, int e, int f, int g, int h, int i, int j);
+
+static int misaligned(void) {
+    // 不内联时，进入函数后压入 %rbp，帧地址必须 16 字节对齐
+    return ((unsigned long)__builtin_frame_address(0) % 16) != 0;
+}
+
+int check_args(int a, int b, int c, int d, int e, int f, int g, int h, int i, int j) {
+    int errors = misaligned();
+    int values[] = {a, b, c, d, e, f, g, h, i, j};
+    for (int k = 0; k < 10; k++)
+        if (values[k] != k + 1)
+            errors++;
+    return errors;
+}
+
+int check_odd(int a, int b, int c, int d, int e, int f, int g, int h, int i) {
+    int errors = misaligned();
+    int values[] = {a, b, c, d, e, f, g, h, i};
+    for (int k = 0; k < 9; k++)
+        if (values[k] != k + 1)
+            errors++;
+    return errors;
+}
+
+int call_from_c(void) {
+    return sum10(1, 2, 3, 4, 5, 6, 7, 8, 9, 10);
+}
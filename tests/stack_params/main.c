@@ -0,0 +1,38 @@
+// 7 到 10 个参数的函数：第 7 个及以后的参数通过栈传递。
+// check.c 用 gcc 编译，检查从这里传过去的参数和调用时的栈对齐，并反过来调用这里的函数。
+
+int check_args(int a, int b, int c, int d, int e, int f, int g, int h, int i, int j);
+int check_odd(int a, int b, int c, int d, int e, int f, int g, int h, int i);
+int call_from_c(void);
+
+int sum7(int a, int b, int c, int d, int e, int f, int g) {
+    return a + 2 * b + 3 * c + 4 * d + 5 * e + 6 * f + 7 * g;
+}
+
+int sum8(int a, int b, int c, int d, int e, int f, int g, int h) {
+    return sum7(a, b, c, d, e, f, g) * 2 - h;
+}
+
+int sum9(int a, int b, int c, int d, int e, int f, int g, int h, int i) {
+    int odd_frame = i; // 让栈帧中的局部变量个数为奇数
+    return sum8(i, h, g, f, e, d, c, b) + a;
+}
+
+int sum10(int a, int b, int c, int d, int e, int f, int g, int h, int i, int j) {
+    int local = a - j;
+    return sum9(b, c, d, e, f, g, h, i, j) + local;
+}
+
+int main(void) {
+    // 栈上的参数个数为奇数时调用者需要额外的 8 字节填充
+    int errors = check_args(1, 2, 3, 4, 5, 6, 7, 8, 9, 10);
+    int x = 0;
+    errors = errors + check_odd(1, 2, 3, 4, 5, 6, 7, 8, 9);
+    if (sum7(1, 1, 1, 1, 1, 1, 1) != 28)
+        errors = errors + 1;
+    if (sum10(1, 2, 3, 4, 5, 6, 7, 8, 9, 10) != 326)
+        errors = errors + 1;
+    if (call_from_c() != 326)
+        errors = errors + 1;
+    return errors;
+}
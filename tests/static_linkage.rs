@@ -0,0 +1,29 @@
+// tests/static_linkage.rs
+
+//! 两个目标文件各自定义同名的 `static` 函数 `helper`，链接后必须各自调用自己的版本。
+
+mod common;
+
+use std::fs;
+
+use common::{WorkDir, compile, link, run};
+
+#[test]
+fn static_helpers_with_same_name_link() {
+    let work_dir = WorkDir::new("static");
+    let mut objects = Vec::new();
+    for name in ["main.c", "other.c"] {
+        let source = work_dir.copy_fixture(&format!("static_linkage/{}", name));
+        objects.push(compile(&source, &["-S"]));
+        let asm = fs::read_to_string(source.with_extension("s")).unwrap();
+        assert!(
+            !asm.contains(".globl helper"),
+            "static 函数不应导出:\n{}",
+            asm
+        );
+    }
+
+    let exe = work_dir.join("static_linkage");
+    link(&[&objects[0], &objects[1]], &exe);
+    assert_eq!(run(&exe, &[], "").code, Some(42));
+}
@@ -0,0 +1,10 @@
+int other(void);
+
+static int helper(int x) {
+    return x + 1;
+}
+
+int main(void) {
+    // main.c 的 helper 返回 x + 1，other.c 的 helper 返回 x * 10
+    return helper(1) + other();
+}
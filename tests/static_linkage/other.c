@@ -0,0 +1,9 @@
+static int helper(int x);
+
+int other(void) {
+    return helper(4);
+}
+
+int helper(int x) {
+    return x * 10;
+}
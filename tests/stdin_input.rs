@@ -0,0 +1,69 @@
+// tests/stdin_input.rs
+
+//! 源文件为 `-` 时从标准输入读取程序：中间文件放在临时目录中，输出像 gcc 一样写到当前
+//! 目录（可执行文件 `a.out`，`-c` 时为 `a.o`）。
+
+mod common;
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Output, Stdio};
+
+use common::{WorkDir, ccompiler, run};
+
+const SOURCE: &str = "int main(void) { return 6 * 7; }\n";
+
+fn compile_stdin(work_dir: &Path, source: &str, args: &[&str]) -> (Output, u32) {
+    let mut child = ccompiler()
+        .args(["-q", "--no-run"])
+        .args(args)
+        .arg("-")
+        .current_dir(work_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let pid = child.id();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(source.as_bytes())
+        .unwrap();
+    (child.wait_with_output().unwrap(), pid)
+}
+
+#[test]
+fn program_from_stdin_is_written_to_a_out() {
+    let work_dir = WorkDir::new("stdin-test");
+
+    let (output, pid) = compile_stdin(work_dir.path(), SOURCE, &[]);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(run(&work_dir.join("a.out"), &[], "").code, Some(42));
+    // 中间文件所在的临时目录在编译结束后删除，当前目录中只有输出
+    assert!(
+        !std::env::temp_dir()
+            .join(format!("ccompiler-stdin-{}", pid))
+            .exists()
+    );
+    let entries: Vec<String> = fs::read_dir(work_dir.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(entries, ["a.out"]);
+
+    let (output, _) = compile_stdin(work_dir.path(), SOURCE, &["-c"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(work_dir.join("a.o").exists());
+
+    // 错误照常报告
+    let (output, _) = compile_stdin(work_dir.path(), "int main(void) { return 1 +; }\n", &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("1:28: "),
+        "{:?}",
+        output
+    );
+}
@@ -0,0 +1,154 @@
+// tests/warnings.rs
+
+//! 可选的警告：用 `-W<名字>` 开启时有问题的代码得到一条带代码的警告，没有开启或者代码
+//! 没有问题时没有警告；`-Werror=<名字>` 把它提升为错误，编译失败。
+
+mod common;
+
+use common::{WorkDir, ccompiler};
+use serde_json::Value;
+
+/// 以 `--diagnostics-format json` 编译 `source`，返回退出码和所有诊断记录。
+fn compile(name: &str, source: &str, args: &[&str]) -> (Option<i32>, Vec<Value>) {
+    let work_dir = WorkDir::new(&format!("warnings-{}", name));
+    let path = work_dir.write(&format!("{}.c", name), source);
+    let output = ccompiler()
+        .args(["--diagnostics-format", "json"])
+        .args(args)
+        .arg(&path)
+        .output()
+        .unwrap();
+    let records = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    (output.status.code(), records)
+}
+
+/// 代码为 `code` 的诊断的 (严重程度, 行号)。
+fn with_code(records: &[Value], code: &str) -> Vec<(String, u64)> {
+    records
+        .iter()
+        .filter(|r| r["code"] == code)
+        .map(|r| {
+            (
+                r["severity"].as_str().unwrap().to_string(),
+                r["range"]["start"]["line"].as_u64().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn unused_parameter() {
+    const UNUSED: &str = "\
+int f(int unused, int used) { return used; }
+int g(int declared_only);
+int main(void) { return f(1, 2); }
+";
+    let (status, records) = compile("unused-param", UNUSED, &["-Wunused-parameter"]);
+    assert_eq!(status, Some(0), "{:?}", records);
+    assert_eq!(
+        with_code(&records, "unused-parameter"),
+        [("warning".to_string(), 1)]
+    );
+    assert!(
+        records[0]["message"].as_str().unwrap().contains("'unused'"),
+        "{:?}",
+        records
+    );
+
+    // 默认不开启
+    let (status, records) = compile("unused-param-off", UNUSED, &[]);
+    assert_eq!(status, Some(0));
+    assert!(records.is_empty(), "{:?}", records);
+
+    // 所有参数都被使用
+    let (_, records) = compile(
+        "used-params",
+        "int f(int a, int b) { return a - b; }\nint main(void) { return f(2, 2); }\n",
+        &["-Wunused-parameter"],
+    );
+    assert!(records.is_empty(), "{:?}", records);
+
+    let (status, records) = compile(
+        "unused-param-werror",
+        UNUSED,
+        &["-Wunused-parameter", "-Werror=unused-parameter"],
+    );
+    assert_eq!(status, Some(1));
+    assert_eq!(
+        with_code(&records, "unused-parameter"),
+        [("error".to_string(), 1)]
+    );
+}
+
+#[test]
+fn unused_value() {
+    const DISCARDED: &str = "\
+int f(void) { return 1; }
+int main(void) {
+    int a = 1;
+    volatile int v = 2;
+    a + 2;
+    a = 3;
+    f();
+    a + f();
+    v;
+    return a;
+}
+";
+    // 只有没有副作用的 `a + 2;` 有警告；赋值、调用和 volatile 读取都有副作用
+    let (status, records) = compile("unused-value", DISCARDED, &["-Wunused-value"]);
+    assert_eq!(status, Some(0), "{:?}", records);
+    assert_eq!(
+        with_code(&records, "unused-value"),
+        [("warning".to_string(), 5)]
+    );
+
+    let (_, records) = compile("unused-value-off", DISCARDED, &[]);
+    assert!(records.is_empty(), "{:?}", records);
+
+    let (status, records) = compile(
+        "unused-value-werror",
+        DISCARDED,
+        &["-Wunused-value", "-Werror=unused-value"],
+    );
+    assert_eq!(status, Some(1));
+    assert_eq!(
+        with_code(&records, "unused-value"),
+        [("error".to_string(), 5)]
+    );
+}
+
+#[test]
+fn unused_static() {
+    const STATICS: &str = "\
+static int helper(void) { return 1; }
+static int unused_helper(void) { return 2; }
+static int unused_counter;
+int exported(void) { return 3; }
+int main(void) { return helper(); }
+";
+    // 只有从未被引用的 static 函数和变量有警告；没有 static 的函数可能被其他文件使用
+    let (status, records) = compile("unused-static", STATICS, &["-Wunused-static"]);
+    assert_eq!(status, Some(0), "{:?}", records);
+    assert_eq!(
+        with_code(&records, "unused-static"),
+        [("warning".to_string(), 2), ("warning".to_string(), 3)]
+    );
+
+    let (_, records) = compile("unused-static-off", STATICS, &[]);
+    assert!(records.is_empty(), "{:?}", records);
+
+    let (status, records) = compile(
+        "unused-static-werror",
+        STATICS,
+        &["-Wunused-static", "-Werror=unused-static"],
+    );
+    assert_eq!(status, Some(1));
+    assert_eq!(
+        with_code(&records, "unused-static"),
+        [("error".to_string(), 2), ("error".to_string(), 3)]
+    );
+}
@@ -0,0 +1,65 @@
+// tests/watch_mode.rs
+
+//! `--watch --watch-run`：修改源文件包含的头文件后自动重新编译并运行。
+
+mod common;
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use common::{WorkDir, ccompiler};
+
+/// 测试失败时也要结束监视进程，否则它一直占着输出管道，`cargo test` 不会退出。
+struct KillOnDrop(Child);
+
+impl Drop for KillOnDrop {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+#[test]
+fn recompiles_when_an_included_header_changes() {
+    let work_dir = WorkDir::new("watch");
+    let source = work_dir.write(
+        "main.c",
+        "#include \"value.h\"\nint main(void) { return VALUE; }\n",
+    );
+    let header = work_dir.write("value.h", "#define VALUE 3\n");
+
+    let mut child = KillOnDrop(
+        ccompiler()
+            .args(["--watch", "--watch-run"])
+            .arg(&source)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap(),
+    );
+    let (tx, rx) = mpsc::channel();
+    let stdout = child.0.stdout.take().unwrap();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            if tx.send(line.unwrap()).is_err() {
+                break;
+            }
+        }
+    });
+    let wait_for = |expected: &str| loop {
+        match rx.recv_timeout(Duration::from_secs(20)) {
+            Ok(line) if line.contains(expected) => break,
+            Ok(_) => {}
+            Err(e) => panic!("没有等到 '{}': {}", expected, e),
+        }
+    };
+
+    wait_for("program exited with 3");
+    fs::write(&header, "#define VALUE 4\n").unwrap();
+    wait_for("program exited with 4");
+
+    drop(child);
+}
@@ -0,0 +1,72 @@
+// tests/werror.rs
+
+//! `-Werror` 和 `-Werror=<名字>`：被提升的警告作为错误输出，编译失败且不生成可执行文件；
+//! 没有被提升的警告照常输出。
+
+mod common;
+
+use std::fs;
+use std::process::Output;
+
+use common::{WorkDir, ccompiler};
+
+const SOURCE: &str = "\
+int f(int unused, int a) {
+    if (a)
+        return 1;
+}
+
+int main(void) {
+    return f(1, 1);
+}
+";
+
+#[test]
+fn promoted_warnings_fail_the_compilation() {
+    let work_dir = WorkDir::new("werror");
+    let source = work_dir.write("main.c", SOURCE);
+    let executable = work_dir.join("main");
+
+    let compile = |args: &[&str]| -> Output {
+        let _ = fs::remove_file(&executable);
+        ccompiler()
+            .args(["-q", "--no-run"])
+            .args(args)
+            .arg(&source)
+            .output()
+            .unwrap()
+    };
+
+    let output = compile(&["-Wunused-parameter", "-Werror=return-type"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(output.status.code(), Some(1), "{}", stderr);
+    assert!(
+        stderr.contains(
+            "warning: 1:5: function 'f': parameter 'unused' is never used [-Wunused-parameter]"
+        ),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains(
+            "error: 1:5: control reaches end of non-void function 'f' [-Werror=return-type]"
+        ),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("1 warning(s) treated as errors"),
+        "{}",
+        stderr
+    );
+    assert!(!executable.exists());
+
+    let output = compile(&["-Werror", "-Wunused-parameter"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!executable.exists());
+
+    // 没有开启任何警告时 -Werror 不影响编译
+    let output = compile(&["-Werror"]);
+    assert!(output.status.success());
+    assert!(executable.exists());
+}